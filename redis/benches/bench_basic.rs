@@ -273,5 +273,49 @@ fn bench_decode(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(bench, bench_query, bench_encode, bench_decode);
+fn bench_decode_blobs_to_vec(b: &mut Bencher, input: &[u8]) {
+    b.iter(|| {
+        let value = redis::parse_redis_value(input).unwrap();
+        redis::from_owned_redis_value::<Vec<bytes::Bytes>>(value).unwrap()
+    });
+}
+
+fn bench_decode_blobs_to_bytes(b: &mut Bencher, input: &bytes::Bytes) {
+    b.iter(|| {
+        let value = redis::parse_redis_bytes_value(input).unwrap();
+        redis::from_bytes_value::<Vec<bytes::Bytes>>(value).unwrap()
+    });
+}
+
+fn bench_decode_blobs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode_blobs");
+    for (count, size) in [(1, 1024 * 1024), (64, 64 * 1024), (1024, 128)] {
+        let value = Value::Array(vec![Value::BulkString(vec![b'a'; size]); count]);
+        let mut input = Vec::new();
+        support::encode_value(&value, &mut input).unwrap();
+        let input = bytes::Bytes::from(input);
+        assert_eq!(
+            Value::from(redis::parse_redis_bytes_value(&input).unwrap()),
+            value
+        );
+
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group
+            .bench_function(format!("vec_{count}x{size}"), |b| {
+                bench_decode_blobs_to_vec(b, &input)
+            })
+            .bench_function(format!("bytes_{count}x{size}"), |b| {
+                bench_decode_blobs_to_bytes(b, &input)
+            });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    bench,
+    bench_query,
+    bench_encode,
+    bench_decode,
+    bench_decode_blobs
+);
 criterion_main!(bench);
@@ -0,0 +1,703 @@
+//! Client-side caching for async connections.
+//!
+//! [`CachedConnection`] enables [`CLIENT TRACKING`](https://redis.io/docs/manual/client-side-caching/)
+//! on the wrapped connection, and caches the responses of read-only commands. The server notifies
+//! the connection about modified keys with `invalidate` push messages, and the cached responses
+//! that read these keys are evicted. Tracking requires the RESP3 protocol.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::FutureExt;
+
+use super::{ConnectionLike, MultiplexedConnection};
+use crate::cmd::{cmd, Arg, Cmd};
+use crate::push_manager::PushListener;
+use crate::types::{
+    ErrorKind, ProtocolVersion, PushKind, RedisError, RedisFuture, RedisResult, Value,
+};
+use crate::PushManager;
+
+/// The way the server tracks the keys that the client should be notified about.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum TrackingMode {
+    /// The server remembers the keys that were read by the connection, and notifies it only when
+    /// one of these keys is modified.
+    #[default]
+    Default,
+    /// The server notifies the connection about every modified key that starts with one of the
+    /// prefixes, and doesn't need to remember which keys were read. Only keys that match one of
+    /// the prefixes are cached. No prefixes means that all keys match.
+    Broadcast {
+        /// The prefixes of the keys that are tracked.
+        prefixes: Vec<String>,
+    },
+}
+
+/// Configuration of the client-side cache of a [`CachedConnection`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use redis::aio::{CacheConfig, TrackingMode};
+/// use std::time::Duration;
+///
+/// let config = CacheConfig::default()
+///     .tracking_mode(TrackingMode::Broadcast {
+///         prefixes: vec!["user:".to_string()],
+///     })
+///     .max_entries(1000)
+///     .ttl(Duration::from_secs(60));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CacheConfig {
+    tracking_mode: TrackingMode,
+    max_entries: usize,
+    ttl: Option<Duration>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            tracking_mode: TrackingMode::Default,
+            max_entries: 10_000,
+            ttl: None,
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Sets the tracking mode that is requested from the server. Defaults to [`TrackingMode::Default`].
+    pub fn tracking_mode(mut self, mode: TrackingMode) -> Self {
+        self.tracking_mode = mode;
+        self
+    }
+
+    /// Sets the maximal number of cached responses. When the cache is full, the least recently
+    /// used response is evicted. Defaults to 10,000.
+    pub fn max_entries(mut self, n: usize) -> Self {
+        self.max_entries = n;
+        self
+    }
+
+    /// Sets the duration after which cached responses expire, even if they weren't invalidated.
+    /// By default responses expire only when they are invalidated or evicted.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+}
+
+/// Statistics of the client-side cache of a [`CachedConnection`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStatistics {
+    /// The number of requests that were answered from the cache.
+    pub hits: u64,
+    /// The number of cacheable requests that were sent to the server.
+    pub misses: u64,
+    /// The number of cached responses that were removed because their keys were modified, or
+    /// because the connection was lost.
+    pub invalidations: u64,
+    /// The number of cached responses that were removed because the cache was full, or because
+    /// they expired.
+    pub evictions: u64,
+}
+
+/// An async connection that can be wrapped by a [`CachedConnection`].
+pub trait CacheableConnection: ConnectionLike + Send {
+    /// Returns the `PushManager` that receives the push messages of the connection.
+    fn push_manager(&self) -> PushManager;
+
+    /// Returns the protocol that is used by the connection.
+    fn protocol(&self) -> ProtocolVersion;
+}
+
+/// An async connection with a client-side cache.
+///
+/// The responses of read-only commands such as `GET`, `HGETALL` or `SMEMBERS` are cached, and
+/// are evicted when the server reports that their keys were modified. Other commands and
+/// pipelines are always sent to the server. The cache is shared between clones of the connection.
+///
+/// When the wrapped connection reports that it was disconnected, all cached responses are
+/// dropped, and tracking is enabled again with the next request. This allows wrapping a
+/// [`ConnectionManager`](super::ConnectionManager), which reconnects automatically.
+///
+/// Invalidations are received through the [`PushManager`] of the wrapped connection, so the
+/// push manager shouldn't be replaced after the connection was wrapped. Clones of a connection
+/// share their push manager, and each of them can be wrapped by its own `CachedConnection`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use redis::AsyncCommands;
+/// use redis::aio::{CacheConfig, CachedConnection};
+///
+/// # async fn do_something() -> redis::RedisResult<()> {
+/// let client = redis::Client::open("redis://127.0.0.1/?protocol=resp3")?;
+/// let con = client.get_multiplexed_async_connection().await?;
+/// let mut con = CachedConnection::new(con, CacheConfig::default()).await?;
+/// let _: Option<String> = con.get("key").await?;
+/// // answered from the cache, unless `key` was modified in the meantime.
+/// let _: Option<String> = con.get("key").await?;
+/// assert_eq!(con.statistics().hits, 1);
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+pub struct CachedConnection<C = MultiplexedConnection> {
+    connection: C,
+    cache: Arc<ClientCache>,
+    // The push manager only holds a weak reference to the listener, so it's kept alive here.
+    _push_listener: PushListener,
+}
+
+impl<C> CachedConnection<C>
+where
+    C: CacheableConnection,
+{
+    /// Wraps `connection` and enables tracking on it.
+    pub async fn new(connection: C, config: CacheConfig) -> RedisResult<Self> {
+        if connection.protocol() == ProtocolVersion::RESP2 {
+            return Err(RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "RESP3 is required for client-side caching",
+            )));
+        }
+        let cache = Arc::new(ClientCache::new(config));
+        let listener_cache = cache.clone();
+        let push_listener: PushListener =
+            Arc::new(move |kind, data| listener_cache.handle_push(kind, data));
+        connection.push_manager().add_listener(&push_listener);
+        let mut connection = Self {
+            connection,
+            cache,
+            _push_listener: push_listener,
+        };
+        connection.enable_tracking().await?;
+        Ok(connection)
+    }
+
+    /// Returns the statistics of the cache.
+    pub fn statistics(&self) -> CacheStatistics {
+        self.cache.state.lock().unwrap().stats
+    }
+
+    /// Drops all the cached responses.
+    pub fn clear_cache(&self) {
+        self.cache.state.lock().unwrap().clear();
+    }
+
+    /// Returns a reference to the wrapped connection.
+    pub fn get_ref(&self) -> &C {
+        &self.connection
+    }
+
+    async fn enable_tracking(&mut self) -> RedisResult<()> {
+        if !self.cache.start_tracking() {
+            return Ok(());
+        }
+        let result = self
+            .connection
+            .req_packed_command(&self.cache.tracking_cmd())
+            .await;
+        match result {
+            Ok(_) => {
+                // Responses that were read before tracking was enabled might never be invalidated.
+                self.cache.state.lock().unwrap().clear();
+                Ok(())
+            }
+            Err(err) => {
+                self.cache.state.lock().unwrap().tracking_enabled = false;
+                Err(err)
+            }
+        }
+    }
+
+    async fn send_packed_command(&mut self, cmd: &Cmd) -> RedisResult<Value> {
+        self.enable_tracking().await?;
+        let keys = match self.cache.cacheable_keys(cmd) {
+            Some(keys) => keys,
+            None => return self.connection.req_packed_command(cmd).await,
+        };
+        let packed_command = cmd.get_packed_command();
+        if let Some(value) = self.cache.get(&packed_command) {
+            return Ok(value);
+        }
+        let generation = self.cache.state.lock().unwrap().generation;
+        let value = self.connection.req_packed_command(cmd).await?;
+        self.cache
+            .insert(packed_command, keys, value.clone(), generation);
+        Ok(value)
+    }
+}
+
+impl<C> ConnectionLike for CachedConnection<C>
+where
+    C: CacheableConnection,
+{
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        (async move { self.send_packed_command(cmd).await }).boxed()
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a crate::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        (async move {
+            self.enable_tracking().await?;
+            self.connection
+                .req_packed_commands(cmd, offset, count)
+                .await
+        })
+        .boxed()
+    }
+
+    fn get_db(&self) -> i64 {
+        self.connection.get_db()
+    }
+}
+
+struct CacheEntry {
+    value: Value,
+    keys: Vec<Vec<u8>>,
+    expires_at: Option<Instant>,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    // packed command -> cached response
+    entries: HashMap<Vec<u8>, CacheEntry>,
+    // key -> packed commands that read the key
+    commands_by_key: HashMap<Vec<u8>, HashSet<Vec<u8>>>,
+    // last use -> packed command
+    lru: BTreeMap<u64, Vec<u8>>,
+    tick: u64,
+    // Incremented whenever entries are invalidated, so that responses that were requested before
+    // an invalidation won't be cached after it.
+    generation: u64,
+    tracking_enabled: bool,
+    stats: CacheStatistics,
+}
+
+impl CacheState {
+    fn remove(&mut self, command: &[u8]) -> bool {
+        let entry = match self.entries.remove(command) {
+            Some(entry) => entry,
+            None => return false,
+        };
+        self.lru.remove(&entry.last_used);
+        for key in entry.keys {
+            if let Some(commands) = self.commands_by_key.get_mut(&key) {
+                commands.remove(command);
+                if commands.is_empty() {
+                    self.commands_by_key.remove(&key);
+                }
+            }
+        }
+        true
+    }
+
+    fn invalidate_key(&mut self, key: &[u8]) {
+        if let Some(commands) = self.commands_by_key.remove(key) {
+            for command in commands {
+                if self.remove(&command) {
+                    self.stats.invalidations += 1;
+                }
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.stats.invalidations += self.entries.len() as u64;
+        self.entries.clear();
+        self.commands_by_key.clear();
+        self.lru.clear();
+        self.generation += 1;
+    }
+}
+
+struct ClientCache {
+    config: CacheConfig,
+    state: Mutex<CacheState>,
+}
+
+impl ClientCache {
+    fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    fn tracking_cmd(&self) -> Cmd {
+        let mut tracking = cmd("CLIENT");
+        tracking.arg("TRACKING").arg("ON");
+        if let TrackingMode::Broadcast { prefixes } = &self.config.tracking_mode {
+            tracking.arg("BCAST");
+            for prefix in prefixes {
+                tracking.arg("PREFIX").arg(prefix);
+            }
+        }
+        tracking
+    }
+
+    /// Returns true if tracking should be enabled by the caller.
+    fn start_tracking(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.tracking_enabled {
+            return false;
+        }
+        state.tracking_enabled = true;
+        true
+    }
+
+    /// Returns the keys that are read by `cmd`, or `None` if the response of `cmd` can't be cached.
+    fn cacheable_keys(&self, cmd: &Cmd) -> Option<Vec<Vec<u8>>> {
+        let mut args = cmd.args_iter().map(|arg| match arg {
+            Arg::Simple(arg) => Some(arg),
+            Arg::Cursor => None,
+        });
+        let name = args.next()??.to_ascii_uppercase();
+        let args = args.collect::<Option<Vec<_>>>()?;
+        let keys: Vec<Vec<u8>> = match &name[..] {
+            b"MGET" | b"EXISTS" => args.iter().map(|arg| arg.to_vec()).collect(),
+            b"GET" | b"GETRANGE" | b"SUBSTR" | b"STRLEN" | b"GETBIT" | b"BITCOUNT" | b"BITPOS"
+            | b"HGET" | b"HMGET" | b"HGETALL" | b"HEXISTS" | b"HKEYS" | b"HVALS" | b"HLEN"
+            | b"HSTRLEN" | b"LINDEX" | b"LLEN" | b"LPOS" | b"LRANGE" | b"SCARD" | b"SISMEMBER"
+            | b"SMISMEMBER" | b"SMEMBERS" | b"ZCARD" | b"ZCOUNT" | b"ZLEXCOUNT" | b"ZMSCORE"
+            | b"ZRANGE" | b"ZRANGEBYLEX" | b"ZRANGEBYSCORE" | b"ZRANK" | b"ZREVRANGE"
+            | b"ZREVRANGEBYLEX" | b"ZREVRANGEBYSCORE" | b"ZREVRANK" | b"ZSCORE" | b"TYPE"
+            | b"XLEN" | b"XRANGE" | b"XREVRANGE" => vec![args.first()?.to_vec()],
+            _ => return None,
+        };
+        if keys.is_empty() {
+            return None;
+        }
+        if let TrackingMode::Broadcast { prefixes } = &self.config.tracking_mode {
+            let is_tracked = |key: &Vec<u8>| {
+                prefixes.is_empty()
+                    || prefixes
+                        .iter()
+                        .any(|prefix| key.starts_with(prefix.as_bytes()))
+            };
+            if !keys.iter().all(is_tracked) {
+                return None;
+            }
+        }
+        Some(keys)
+    }
+
+    fn get(&self, command: &[u8]) -> Option<Value> {
+        let mut state = self.state.lock().unwrap();
+        let expired = match state.entries.get(command) {
+            Some(entry) => entry
+                .expires_at
+                .map_or(false, |expires_at| expires_at <= Instant::now()),
+            None => {
+                state.stats.misses += 1;
+                return None;
+            }
+        };
+        if expired {
+            state.remove(command);
+            state.stats.evictions += 1;
+            state.stats.misses += 1;
+            return None;
+        }
+        state.tick += 1;
+        let tick = state.tick;
+        let state = &mut *state;
+        // The entry was found above.
+        let entry = state.entries.get_mut(command).unwrap();
+        state.lru.remove(&entry.last_used);
+        state.lru.insert(tick, command.to_vec());
+        entry.last_used = tick;
+        state.stats.hits += 1;
+        Some(entry.value.clone())
+    }
+
+    fn insert(&self, command: Vec<u8>, keys: Vec<Vec<u8>>, value: Value, generation: u64) {
+        if self.config.max_entries == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        if state.generation != generation || !state.tracking_enabled {
+            return;
+        }
+        state.remove(&command);
+        while state.entries.len() >= self.config.max_entries {
+            let least_recently_used = match state.lru.values().next() {
+                Some(command) => command.clone(),
+                None => break,
+            };
+            state.remove(&least_recently_used);
+            state.stats.evictions += 1;
+        }
+        state.tick += 1;
+        let tick = state.tick;
+        for key in &keys {
+            state
+                .commands_by_key
+                .entry(key.clone())
+                .or_default()
+                .insert(command.clone());
+        }
+        state.lru.insert(tick, command.clone());
+        state.entries.insert(
+            command,
+            CacheEntry {
+                value,
+                keys,
+                expires_at: self.config.ttl.map(|ttl| Instant::now() + ttl),
+                last_used: tick,
+            },
+        );
+    }
+
+    fn handle_push(&self, kind: &PushKind, data: &[Value]) {
+        match kind {
+            PushKind::Invalidate => {
+                let mut state = self.state.lock().unwrap();
+                match data.first() {
+                    Some(Value::Array(keys)) => {
+                        for key in keys {
+                            if let Value::BulkString(key) = key {
+                                state.invalidate_key(key);
+                            }
+                        }
+                        state.generation += 1;
+                    }
+                    // A nil invalidation is sent when the whole database is flushed.
+                    _ => state.clear(),
+                }
+            }
+            PushKind::Disconnection => {
+                let mut state = self.state.lock().unwrap();
+                state.clear();
+                state.tracking_enabled = false;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracked_cache(config: CacheConfig) -> ClientCache {
+        let cache = ClientCache::new(config);
+        assert!(cache.start_tracking());
+        cache
+    }
+
+    fn get_cmd(key: &str) -> (Vec<u8>, Vec<Vec<u8>>) {
+        (
+            cmd("GET").arg(key).get_packed_command(),
+            vec![key.as_bytes().to_vec()],
+        )
+    }
+
+    fn invalidate(cache: &ClientCache, key: &str) {
+        cache.handle_push(
+            &PushKind::Invalidate,
+            &[Value::Array(vec![Value::BulkString(
+                key.as_bytes().to_vec(),
+            )])],
+        );
+    }
+
+    #[derive(Clone, Default)]
+    struct MockConnection {
+        push_manager: PushManager,
+        gets: Arc<Mutex<i64>>,
+    }
+
+    impl ConnectionLike for MockConnection {
+        fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+            let value = match cmd.args_iter().next() {
+                Some(Arg::Simple(b"GET")) => {
+                    let mut gets = self.gets.lock().unwrap();
+                    *gets += 1;
+                    Value::Int(*gets)
+                }
+                _ => Value::Okay,
+            };
+            (async move { Ok(value) }).boxed()
+        }
+
+        fn req_packed_commands<'a>(
+            &'a mut self,
+            _cmd: &'a crate::Pipeline,
+            _offset: usize,
+            _count: usize,
+        ) -> RedisFuture<'a, Vec<Value>> {
+            (async move { Ok(vec![]) }).boxed()
+        }
+
+        fn get_db(&self) -> i64 {
+            0
+        }
+    }
+
+    impl CacheableConnection for MockConnection {
+        fn push_manager(&self) -> PushManager {
+            self.push_manager.clone()
+        }
+
+        fn protocol(&self) -> ProtocolVersion {
+            ProtocolVersion::RESP3
+        }
+    }
+
+    #[tokio::test]
+    async fn test_caches_of_cloned_connections_are_invalidated() {
+        let connection = MockConnection::default();
+        let mut first = CachedConnection::new(connection.clone(), CacheConfig::default())
+            .await
+            .unwrap();
+        let mut second = CachedConnection::new(connection.clone(), CacheConfig::default())
+            .await
+            .unwrap();
+        for cached in [&mut first, &mut second] {
+            for _ in 0..2 {
+                cached
+                    .req_packed_command(cmd("GET").arg("foo"))
+                    .await
+                    .unwrap();
+            }
+            assert_eq!(cached.statistics().hits, 1);
+        }
+
+        connection.push_manager.try_send_raw(&Value::Push {
+            kind: PushKind::Invalidate,
+            data: vec![Value::Array(vec![Value::BulkString(b"foo".to_vec())])],
+        });
+
+        for cached in [&mut first, &mut second] {
+            assert_eq!(cached.statistics().invalidations, 1);
+            cached
+                .req_packed_command(cmd("GET").arg("foo"))
+                .await
+                .unwrap();
+            assert_eq!(cached.statistics().misses, 2);
+        }
+    }
+
+    #[test]
+    fn test_cacheable_keys() {
+        let cache = ClientCache::new(CacheConfig::default());
+        assert_eq!(
+            cache.cacheable_keys(cmd("GET").arg("foo")),
+            Some(vec![b"foo".to_vec()])
+        );
+        assert_eq!(
+            cache.cacheable_keys(cmd("mget").arg("foo").arg("bar")),
+            Some(vec![b"foo".to_vec(), b"bar".to_vec()])
+        );
+        assert_eq!(cache.cacheable_keys(cmd("SET").arg("foo").arg("bar")), None);
+        assert_eq!(cache.cacheable_keys(&cmd("MGET")), None);
+        assert_eq!(
+            cache.cacheable_keys(cmd("HSCAN").arg("foo").cursor_arg(0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_broadcast_mode_only_caches_tracked_prefixes() {
+        let cache = ClientCache::new(CacheConfig::default().tracking_mode(
+            TrackingMode::Broadcast {
+                prefixes: vec!["user:".to_string()],
+            },
+        ));
+        assert_eq!(
+            cache.tracking_cmd().get_packed_command(),
+            cmd("CLIENT")
+                .arg("TRACKING")
+                .arg("ON")
+                .arg("BCAST")
+                .arg("PREFIX")
+                .arg("user:")
+                .get_packed_command()
+        );
+        assert!(cache.cacheable_keys(cmd("GET").arg("user:1")).is_some());
+        assert!(cache
+            .cacheable_keys(cmd("MGET").arg("user:1").arg("order:1"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_invalidation_evicts_entries_of_key() {
+        let cache = tracked_cache(CacheConfig::default());
+        let (foo, foo_keys) = get_cmd("foo");
+        let (bar, bar_keys) = get_cmd("bar");
+        cache.insert(foo.clone(), foo_keys, Value::Int(1), 0);
+        cache.insert(bar.clone(), bar_keys, Value::Int(2), 0);
+
+        invalidate(&cache, "foo");
+
+        assert_eq!(cache.get(&foo), None);
+        assert_eq!(cache.get(&bar), Some(Value::Int(2)));
+        let stats = cache.state.lock().unwrap().stats;
+        assert_eq!(stats.invalidations, 1);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+    }
+
+    #[test]
+    fn test_responses_requested_before_invalidation_are_not_cached() {
+        let cache = tracked_cache(CacheConfig::default());
+        let (foo, foo_keys) = get_cmd("foo");
+        let generation = cache.state.lock().unwrap().generation;
+
+        invalidate(&cache, "foo");
+        cache.insert(foo.clone(), foo_keys, Value::Int(1), generation);
+
+        assert_eq!(cache.get(&foo), None);
+    }
+
+    #[test]
+    fn test_least_recently_used_entry_is_evicted() {
+        let cache = tracked_cache(CacheConfig::default().max_entries(2));
+        let (foo, foo_keys) = get_cmd("foo");
+        let (bar, bar_keys) = get_cmd("bar");
+        let (baz, baz_keys) = get_cmd("baz");
+        cache.insert(foo.clone(), foo_keys, Value::Int(1), 0);
+        cache.insert(bar.clone(), bar_keys, Value::Int(2), 0);
+        assert!(cache.get(&foo).is_some());
+
+        cache.insert(baz.clone(), baz_keys, Value::Int(3), 0);
+
+        assert!(cache.get(&foo).is_some());
+        assert!(cache.get(&bar).is_none());
+        assert!(cache.get(&baz).is_some());
+        assert_eq!(cache.state.lock().unwrap().stats.evictions, 1);
+    }
+
+    #[test]
+    fn test_expired_entries_are_evicted() {
+        let cache = tracked_cache(CacheConfig::default().ttl(Duration::from_millis(0)));
+        let (foo, foo_keys) = get_cmd("foo");
+        cache.insert(foo.clone(), foo_keys, Value::Int(1), 0);
+
+        assert_eq!(cache.get(&foo), None);
+        assert_eq!(cache.state.lock().unwrap().stats.evictions, 1);
+    }
+
+    #[test]
+    fn test_disconnection_clears_cache_and_requires_tracking() {
+        let cache = tracked_cache(CacheConfig::default());
+        let (foo, foo_keys) = get_cmd("foo");
+        cache.insert(foo.clone(), foo_keys.clone(), Value::Int(1), 0);
+
+        cache.handle_push(&PushKind::Disconnection, &[]);
+
+        assert_eq!(cache.get(&foo), None);
+        let generation = cache.state.lock().unwrap().generation;
+        cache.insert(foo.clone(), foo_keys, Value::Int(1), generation);
+        assert_eq!(cache.get(&foo), None);
+        assert!(cache.start_tracking());
+    }
+}
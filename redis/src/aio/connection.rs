@@ -5,6 +5,8 @@ use super::async_std;
 use super::ConnectionLike;
 use super::{setup_connection, AsyncStream, RedisRuntime};
 use crate::cmd::{cmd, Cmd};
+#[cfg(any(feature = "tls-native-tls", feature = "tls-rustls"))]
+use crate::connection::tls_server_name;
 use crate::connection::{
     resp2_is_pub_sub_state_cleared, resp3_is_pub_sub_state_cleared, ConnectionAddr, ConnectionInfo,
     Msg, RedisConnectionInfo,
@@ -469,9 +471,10 @@ pub(crate) async fn connect_simple<T: RedisRuntime>(
             insecure,
             ref tls_params,
         } => {
+            let server_name = tls_server_name(host, tls_params);
             if let Some(socket_addr) = _socket_addr {
                 return Ok::<_, RedisError>((
-                    <T>::connect_tcp_tls(host, socket_addr, insecure, tls_params).await?,
+                    <T>::connect_tcp_tls(server_name, socket_addr, insecure, tls_params).await?,
                     Some(socket_addr.ip()),
                 ));
             }
@@ -479,7 +482,8 @@ pub(crate) async fn connect_simple<T: RedisRuntime>(
             select_ok(socket_addrs.map(|socket_addr| {
                 Box::pin(async move {
                     Ok::<_, RedisError>((
-                        <T>::connect_tcp_tls(host, socket_addr, insecure, tls_params).await?,
+                        <T>::connect_tcp_tls(server_name, socket_addr, insecure, tls_params)
+                            .await?,
                         Some(socket_addr.ip()),
                     ))
                 })
@@ -3,8 +3,8 @@ use crate::cmd::Cmd;
 use crate::push_manager::PushManager;
 use crate::types::{RedisError, RedisResult, Value};
 use crate::{
-    aio::{ConnectionLike, MultiplexedConnection, Runtime},
-    Client,
+    aio::{CacheableConnection, ConnectionLike, MultiplexedConnection, Runtime},
+    Client, ProtocolVersion,
 };
 #[cfg(all(not(feature = "tokio-comp"), feature = "async-std-comp"))]
 use ::async_std::net::ToSocketAddrs;
@@ -62,10 +62,10 @@ pub struct ConnectionManager {
 }
 
 /// A `RedisResult` that can be cloned because `RedisError` is behind an `Arc`.
-type CloneableRedisResult<T> = Result<T, Arc<RedisError>>;
+pub(crate) type CloneableRedisResult<T> = Result<T, Arc<RedisError>>;
 
 /// Type alias for a shared boxed future that will resolve to a `CloneableRedisResult`.
-type SharedRedisFuture<T> = Shared<BoxFuture<'static, CloneableRedisResult<T>>>;
+pub(crate) type SharedRedisFuture<T> = Shared<BoxFuture<'static, CloneableRedisResult<T>>>;
 
 /// Handle a command result. If the connection was dropped, reconnect.
 macro_rules! reconnect_if_dropped {
@@ -283,6 +283,16 @@ impl ConnectionManager {
     }
 }
 
+impl CacheableConnection for ConnectionManager {
+    fn push_manager(&self) -> PushManager {
+        self.get_push_manager()
+    }
+
+    fn protocol(&self) -> ProtocolVersion {
+        self.client.connection_info().redis.protocol
+    }
+}
+
 impl ConnectionLike for ConnectionManager {
     fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
         (async move { self.send_packed_command(cmd).await }).boxed()
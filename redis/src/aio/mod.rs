@@ -1,12 +1,14 @@
 //! Adds async IO support to redis.
-use crate::cmd::{cmd, Cmd};
+use crate::cmd::{cmd, pipe, Cmd};
 use crate::connection::get_resp3_hello_command_error;
 use crate::connection::RedisConnectionInfo;
-use crate::types::{ErrorKind, ProtocolVersion, RedisFuture, RedisResult, Value};
+use crate::pipeline::Pipeline;
+use crate::types::{ErrorKind, ProtocolVersion, RedisFuture, RedisResult, ToRedisArgs, Value};
 use ::tokio::io::{AsyncRead, AsyncWrite};
 use async_trait::async_trait;
 use futures_util::Future;
 use std::net::SocketAddr;
+use std::num::NonZeroUsize;
 #[cfg(unix)]
 use std::path::Path;
 use std::pin::Pin;
@@ -78,6 +80,20 @@ pub trait ConnectionLike {
         count: usize,
     ) -> RedisFuture<'a, Vec<Value>>;
 
+    /// Like `req_packed_commands`, but returns the result of every command, so that an error
+    /// reply doesn't hide the replies of the other commands. Connections that can't tell the
+    /// replies apart return the first error for the whole pipeline, like `req_packed_commands`.
+    #[doc(hidden)]
+    fn req_packed_commands_with_errors<'a>(
+        &'a mut self,
+        cmd: &'a crate::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<RedisResult<Value>>> {
+        let values = self.req_packed_commands(cmd, offset, count);
+        Box::pin(async move { Ok(values.await?.into_iter().map(Ok).collect()) })
+    }
+
     /// Returns the database this connection is bound to.  Note that this
     /// information might be unreliable because it's initially cached and
     /// also might be incorrect if the connection like object is not
@@ -85,6 +101,111 @@ pub trait ConnectionLike {
     fn get_db(&self) -> i64;
 }
 
+/// Async version of [`transaction`](crate::transaction). The keys are watched, and `func` is
+/// called with the connection and a fresh pipeline in atomic mode, until the transaction isn't
+/// aborted by a modification of one of the watched keys. `func` should query the pipeline with
+/// the connection and return the result, which is `None` if `EXEC` was aborted. The future that
+/// is returned by `func` may only borrow the connection, so other values that it uses should be
+/// moved or cloned into it.
+///
+/// `WATCH` applies to the whole connection, so the connection should not be used concurrently
+/// by other tasks during the transaction. A clone of a [`MultiplexedConnection`] or a
+/// [`ConnectionManager`] shares its connection with the original, so a dedicated connection
+/// should be created for transactions, for example with
+/// [`Client::get_multiplexed_async_connection`](crate::Client::get_multiplexed_async_connection).
+///
+/// ```rust,no_run
+/// use futures::FutureExt;
+/// use redis::AsyncCommands;
+/// # async fn do_something() -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://127.0.0.1/").unwrap();
+/// # let mut con = client.get_multiplexed_async_connection().await.unwrap();
+/// let key = "the_key";
+/// let (new_val,): (isize,) = redis::aio::transaction_async(&mut con, &[key], |con, mut pipe| {
+///     async move {
+///         let old_val: isize = con.get(key).await?;
+///         pipe.set(key, old_val + 1).ignore().get(key).query_async(con).await
+///     }
+///     .boxed()
+/// })
+/// .await?;
+/// println!("The incremented number is: {}", new_val);
+/// # Ok(()) }
+/// ```
+///
+/// [`ConnectionManager`]: struct.ConnectionManager.html
+pub async fn transaction_async<C, K, T, F>(con: &mut C, keys: &[K], func: F) -> RedisResult<T>
+where
+    C: ConnectionLike,
+    K: ToRedisArgs,
+    F: for<'a> FnMut(&'a mut C, Pipeline) -> RedisFuture<'a, Option<T>>,
+{
+    run_transaction(con, keys, None, func).await
+}
+
+/// Like [`transaction_async`], but gives up after `func` was called `max_attempts` times
+/// without the transaction succeeding, and returns an error of kind
+/// [`ErrorKind::ExecAbortError`].
+pub async fn transaction_async_with_max_attempts<C, K, T, F>(
+    con: &mut C,
+    keys: &[K],
+    max_attempts: NonZeroUsize,
+    func: F,
+) -> RedisResult<T>
+where
+    C: ConnectionLike,
+    K: ToRedisArgs,
+    F: for<'a> FnMut(&'a mut C, Pipeline) -> RedisFuture<'a, Option<T>>,
+{
+    run_transaction(con, keys, Some(max_attempts), func).await
+}
+
+async fn run_transaction<C, K, T, F>(
+    con: &mut C,
+    keys: &[K],
+    max_attempts: Option<NonZeroUsize>,
+    mut func: F,
+) -> RedisResult<T>
+where
+    C: ConnectionLike,
+    K: ToRedisArgs,
+    F: for<'a> FnMut(&'a mut C, Pipeline) -> RedisFuture<'a, Option<T>>,
+{
+    let mut attempts = 0;
+    loop {
+        if max_attempts.map_or(false, |max_attempts| attempts >= max_attempts.get()) {
+            // the watch is already removed if the transaction was executed, this is in case
+            // `func` returned without executing it.
+            cmd("UNWATCH").query_async::<_, ()>(con).await?;
+            fail!((
+                ErrorKind::ExecAbortError,
+                "Transaction aborted too many times",
+                format!("the watched keys were modified in each of the {attempts} attempts"),
+            ));
+        }
+        attempts += 1;
+
+        cmd("WATCH").arg(keys).query_async::<_, ()>(con).await?;
+        let mut p = pipe();
+        p.atomic();
+        match func(con, p).await {
+            Ok(Some(response)) => {
+                // make sure no watch is left in the connection, even if
+                // someone forgot to use the pipeline.
+                cmd("UNWATCH").query_async::<_, ()>(con).await?;
+                return Ok(response);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                // The connection might be used again after the error, so the watch is removed
+                // if the connection is still usable.
+                let _ = cmd("UNWATCH").query_async::<_, ()>(con).await;
+                return Err(err);
+            }
+        }
+    }
+}
+
 // Initial setup for every connection.
 async fn setup_connection<C>(connection_info: &RedisConnectionInfo, con: &mut C) -> RedisResult<()>
 where
@@ -180,6 +301,10 @@ mod connection_manager;
 #[cfg(feature = "connection-manager")]
 #[cfg_attr(docsrs, doc(cfg(feature = "connection-manager")))]
 pub use connection_manager::*;
+mod cache;
+pub use cache::*;
+mod pool;
+pub use pool::*;
 mod runtime;
 use crate::commands::resp3_hello;
 pub(super) use runtime::*;
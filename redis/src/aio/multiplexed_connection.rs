@@ -1,21 +1,24 @@
-use super::{ConnectionLike, Runtime};
+use super::{CacheableConnection, ConnectionLike, Runtime};
 use crate::aio::setup_connection;
 use crate::cmd::Cmd;
+use crate::instrumentation::{Instrumenter, RequestRecorder};
+use crate::parser::ResponseFrame;
 #[cfg(any(feature = "tokio-comp", feature = "async-std-comp"))]
-use crate::parser::ValueCodec;
+use crate::parser::StreamingValueCodec;
 use crate::push_manager::PushManager;
-use crate::types::{RedisError, RedisFuture, RedisResult, Value};
+use crate::types::{BytesValue, ErrorKind, RedisError, RedisFuture, RedisResult, Value};
 use crate::{cmd, ConnectionInfo, ProtocolVersion, PushKind};
 use ::tokio::{
     io::{AsyncRead, AsyncWrite},
     sync::{mpsc, oneshot},
 };
 use arc_swap::ArcSwap;
+use bytes::Bytes;
 use futures_util::{
     future::{Future, FutureExt},
     ready,
     sink::Sink,
-    stream::{self, Stream, StreamExt, TryStreamExt as _},
+    stream::{self, Stream, StreamExt},
 };
 use pin_project_lite::pin_project;
 use std::collections::VecDeque;
@@ -23,15 +26,22 @@ use std::fmt;
 use std::fmt::Debug;
 use std::io;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::task::{self, Poll};
 use std::time::Duration;
+use tokio::io::ReadBuf;
 #[cfg(any(feature = "tokio-comp", feature = "async-std-comp"))]
 use tokio_util::codec::Decoder;
+use tokio_util::sync::PollSender;
 
 // Senders which the result of a single request are sent through
 type PipelineOutput = oneshot::Sender<RedisResult<Value>>;
 
+// The number of chunks of a streamed bulk string that are buffered before the connection stops
+// reading from the socket.
+const BULK_STREAM_BUFFER_SIZE: usize = 16;
+
 enum ResponseAggregate {
     SingleCommand,
     Pipeline {
@@ -40,20 +50,70 @@ enum ResponseAggregate {
         buffer: Vec<Value>,
         first_err: Option<RedisError>,
     },
+    // A pipeline whose replies are sent to `results` one by one, errors included, so that an
+    // error only fails its own command. The output receives `Value::Nil` once all of them arrived.
+    PipelineResults {
+        expected_response_count: usize,
+        buffer: Vec<RedisResult<Value>>,
+        results: Option<oneshot::Sender<Vec<RedisResult<Value>>>>,
+    },
+    // A single command whose bulk string reply is forwarded in chunks. The output receives
+    // `Value::Int` with the length of the bulk string once its chunks start to arrive, and any
+    // other reply as a `Value::Nil` or an error.
+    BulkStream {
+        chunks: PollSender<Bytes>,
+        started: bool,
+    },
+    // A single command whose reply is decoded into a `BytesValue` and sent to `value`. The output
+    // receives `Value::Nil` once it was sent.
+    BytesValue {
+        value: Option<oneshot::Sender<RedisResult<BytesValue>>>,
+    },
 }
 
 impl ResponseAggregate {
-    fn new(pipeline_response_count: Option<usize>) -> Self {
-        match pipeline_response_count {
-            Some(response_count) => ResponseAggregate::Pipeline {
+    fn new(expected: ExpectedResponse) -> Self {
+        match expected {
+            ExpectedResponse::Pipeline(response_count) => ResponseAggregate::Pipeline {
                 expected_response_count: response_count,
                 current_response_count: 0,
                 buffer: Vec::new(),
                 first_err: None,
             },
-            None => ResponseAggregate::SingleCommand,
+            ExpectedResponse::PipelineResults(response_count, results) => {
+                ResponseAggregate::PipelineResults {
+                    expected_response_count: response_count,
+                    buffer: Vec::new(),
+                    results: Some(results),
+                }
+            }
+            ExpectedResponse::SingleCommand => ResponseAggregate::SingleCommand,
+            ExpectedResponse::BulkStream(chunks) => ResponseAggregate::BulkStream {
+                chunks: PollSender::new(chunks),
+                started: false,
+            },
+            ExpectedResponse::BytesValue(value) => {
+                ResponseAggregate::BytesValue { value: Some(value) }
+            }
         }
     }
+
+    fn is_bulk_stream(&self) -> bool {
+        matches!(self, ResponseAggregate::BulkStream { .. })
+    }
+
+    fn is_bytes_value(&self) -> bool {
+        matches!(self, ResponseAggregate::BytesValue { .. })
+    }
+}
+
+// The responses that are expected for a message.
+enum ExpectedResponse {
+    SingleCommand,
+    Pipeline(usize),
+    PipelineResults(usize, oneshot::Sender<Vec<RedisResult<Value>>>),
+    BulkStream(mpsc::Sender<Bytes>),
+    BytesValue(oneshot::Sender<RedisResult<BytesValue>>),
 }
 
 struct InFlight {
@@ -65,8 +125,7 @@ struct InFlight {
 struct PipelineMessage<S> {
     input: S,
     output: PipelineOutput,
-    // If `None`, this is a single request, not a pipeline of multiple requests.
-    pipeline_response_count: Option<usize>,
+    expected: ExpectedResponse,
 }
 
 /// Wrapper around a `Stream + Sink` where each item sent through the `Sink` results in one or more
@@ -104,35 +163,172 @@ pin_project! {
         in_flight: VecDeque<InFlight>,
         error: Option<RedisError>,
         push_manager: Arc<ArcSwap<PushManager>>,
+        // Tells the codec whether the next reply should be streamed.
+        stream_next: Arc<AtomicBool>,
+        // Tells the codec whether the next reply should be decoded into a `BytesValue`.
+        bytes_next: Arc<AtomicBool>,
     }
 }
 
 impl<T> PipelineSink<T>
 where
-    T: Stream<Item = RedisResult<Value>> + 'static,
+    T: Stream<Item = RedisResult<ResponseFrame>> + 'static,
 {
-    fn new<SinkItem>(sink_stream: T, push_manager: Arc<ArcSwap<PushManager>>) -> Self
+    fn new<SinkItem>(
+        sink_stream: T,
+        push_manager: Arc<ArcSwap<PushManager>>,
+        stream_next: Arc<AtomicBool>,
+        bytes_next: Arc<AtomicBool>,
+    ) -> Self
     where
-        T: Sink<SinkItem, Error = RedisError> + Stream<Item = RedisResult<Value>> + 'static,
+        T: Sink<SinkItem, Error = RedisError> + Stream<Item = RedisResult<ResponseFrame>> + 'static,
     {
         PipelineSink {
             sink_stream,
             in_flight: VecDeque::new(),
             error: None,
             push_manager,
+            stream_next,
+            bytes_next,
         }
     }
 
     // Read messages from the stream and send them back to the caller
     fn poll_read(mut self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Result<(), ()>> {
         loop {
+            ready!(self.as_mut().poll_bulk_stream_capacity(cx));
             let item = match ready!(self.as_mut().project().sink_stream.poll_next(cx)) {
                 Some(result) => result,
                 // The redis response stream is not going to produce any more items so we `Err`
                 // to break out of the `forward` combinator and stop handling requests
-                None => return Poll::Ready(Err(())),
+                None => {
+                    self.as_mut().fail_bulk_stream();
+                    return Poll::Ready(Err(()));
+                }
             };
-            self.as_mut().send_result(item);
+            match item {
+                Ok(ResponseFrame::Value(result)) => self.as_mut().send_result(result),
+                Ok(ResponseFrame::BulkStart(len)) => self.as_mut().start_bulk_stream(len),
+                Ok(ResponseFrame::BulkChunk(chunk)) => self.as_mut().send_bulk_chunk(chunk),
+                Ok(ResponseFrame::BulkEnd) => self.as_mut().finish_bulk_stream(),
+                Ok(ResponseFrame::BytesValue(result)) => self.as_mut().send_bytes_result(result),
+                Err(err) => {
+                    self.as_mut().fail_bulk_stream();
+                    self.as_mut().send_result(Err(err))
+                }
+            }
+        }
+    }
+
+    // Waits until a streamed bulk string has room for another chunk, so that the socket is only
+    // read as fast as the chunks are consumed. Also tells the codec whether the next reply should
+    // be streamed or decoded into a `BytesValue`.
+    fn poll_bulk_stream_capacity(self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<()> {
+        let self_ = self.project();
+        let front = self_.in_flight.front_mut();
+        self_.stream_next.store(
+            front
+                .as_ref()
+                .map_or(false, |entry| entry.response_aggregate.is_bulk_stream()),
+            Ordering::Release,
+        );
+        self_.bytes_next.store(
+            front
+                .as_ref()
+                .map_or(false, |entry| entry.response_aggregate.is_bytes_value()),
+            Ordering::Release,
+        );
+        if let Some(InFlight {
+            response_aggregate:
+                ResponseAggregate::BulkStream {
+                    chunks,
+                    started: true,
+                },
+            ..
+        }) = front
+        {
+            // An error means that the receiver was dropped, in which case the remaining chunks
+            // are read and discarded.
+            let _ = ready!(chunks.poll_reserve(cx));
+        }
+        Poll::Ready(())
+    }
+
+    fn start_bulk_stream(self: Pin<&mut Self>, len: usize) {
+        let self_ = self.project();
+        match self_.in_flight.front_mut() {
+            Some(InFlight {
+                output,
+                response_aggregate: ResponseAggregate::BulkStream { started, .. },
+            }) => {
+                *started = true;
+                let (replaced, _) = oneshot::channel();
+                let output = std::mem::replace(output, replaced);
+                output.send(Ok(Value::Int(len as i64))).ok();
+            }
+            _ => {
+                self_.error.get_or_insert_with(|| {
+                    RedisError::from((ErrorKind::ClientError, "Unexpected bulk string chunks"))
+                });
+            }
+        }
+    }
+
+    fn send_bulk_chunk(self: Pin<&mut Self>, chunk: Bytes) {
+        if let Some(InFlight {
+            response_aggregate: ResponseAggregate::BulkStream { chunks, .. },
+            ..
+        }) = self.project().in_flight.front_mut()
+        {
+            // Fails if the receiver was dropped, in which case the chunk is discarded.
+            let _ = chunks.send_item(chunk);
+        }
+    }
+
+    fn finish_bulk_stream(self: Pin<&mut Self>) {
+        let self_ = self.project();
+        if let Some(InFlight {
+            response_aggregate: ResponseAggregate::BulkStream { .. },
+            ..
+        }) = self_.in_flight.front()
+        {
+            // Dropping the sender ends the stream of chunks.
+            self_.in_flight.pop_front();
+        }
+    }
+
+    // Ends a streamed bulk string that was cut off by a connection error. The receiver notices
+    // that chunks are missing.
+    fn fail_bulk_stream(self: Pin<&mut Self>) {
+        let self_ = self.project();
+        if let Some(InFlight {
+            response_aggregate: ResponseAggregate::BulkStream { started: true, .. },
+            ..
+        }) = self_.in_flight.front()
+        {
+            self_.in_flight.pop_front();
+        }
+    }
+
+    fn send_bytes_result(self: Pin<&mut Self>, result: RedisResult<BytesValue>) {
+        let is_reply = !matches!(result, Ok(BytesValue::Push { .. }))
+            && self
+                .in_flight
+                .front()
+                .map_or(false, |entry| entry.response_aggregate.is_bytes_value());
+        if !is_reply {
+            // Push messages are handled like any other value.
+            return self.send_result(result.map(Value::from));
+        }
+        if let Some(InFlight {
+            output,
+            response_aggregate: ResponseAggregate::BytesValue { value },
+        }) = self.project().in_flight.pop_front()
+        {
+            if let Some(value) = value {
+                value.send(result).ok();
+            }
+            output.send(Ok(Value::Nil)).ok();
         }
     }
 
@@ -163,6 +359,24 @@ where
             ResponseAggregate::SingleCommand => {
                 entry.output.send(result).ok();
             }
+            ResponseAggregate::BulkStream { .. } => {
+                let result = match result {
+                    Ok(Value::Nil) => Ok(Value::Nil),
+                    Ok(value) => Err(RedisError::from((
+                        ErrorKind::TypeError,
+                        "Response was of incompatible type",
+                        format!("Not a bulk string (response was {value:?})"),
+                    ))),
+                    Err(err) => Err(err),
+                };
+                entry.output.send(result).ok();
+            }
+            ResponseAggregate::BytesValue { value } => {
+                if let Some(value) = value.take() {
+                    value.send(result.map(BytesValue::from)).ok();
+                }
+                entry.output.send(Ok(Value::Nil)).ok();
+            }
             ResponseAggregate::Pipeline {
                 expected_response_count,
                 current_response_count,
@@ -197,13 +411,29 @@ where
                 // and sender
                 entry.output.send(response).ok();
             }
+            ResponseAggregate::PipelineResults {
+                expected_response_count,
+                buffer,
+                results,
+            } => {
+                buffer.push(result);
+                if buffer.len() < *expected_response_count {
+                    self_.in_flight.push_front(entry);
+                    return;
+                }
+
+                if let Some(results) = results.take() {
+                    results.send(std::mem::take(buffer)).ok();
+                }
+                entry.output.send(Ok(Value::Nil)).ok();
+            }
         }
     }
 }
 
 impl<SinkItem, T> Sink<PipelineMessage<SinkItem>> for PipelineSink<T>
 where
-    T: Sink<SinkItem, Error = RedisError> + Stream<Item = RedisResult<Value>> + 'static,
+    T: Sink<SinkItem, Error = RedisError> + Stream<Item = RedisResult<ResponseFrame>> + 'static,
 {
     type Error = ();
 
@@ -226,7 +456,7 @@ where
         PipelineMessage {
             input,
             output,
-            pipeline_response_count,
+            expected,
         }: PipelineMessage<SinkItem>,
     ) -> Result<(), Self::Error> {
         // If there is nothing to receive our output we do not need to send the message as it is
@@ -245,7 +475,7 @@ where
 
         match self_.sink_stream.start_send(input) {
             Ok(()) => {
-                let response_aggregate = ResponseAggregate::new(pipeline_response_count);
+                let response_aggregate = ResponseAggregate::new(expected);
                 let entry = InFlight {
                     output,
                     response_aggregate,
@@ -296,9 +526,13 @@ impl<SinkItem> Pipeline<SinkItem>
 where
     SinkItem: Send + 'static,
 {
-    fn new<T>(sink_stream: T) -> (Self, impl Future<Output = ()>)
+    fn new<T>(
+        sink_stream: T,
+        stream_next: Arc<AtomicBool>,
+        bytes_next: Arc<AtomicBool>,
+    ) -> (Self, impl Future<Output = ()>)
     where
-        T: Sink<SinkItem, Error = RedisError> + Stream<Item = RedisResult<Value>> + 'static,
+        T: Sink<SinkItem, Error = RedisError> + Stream<Item = RedisResult<ResponseFrame>> + 'static,
         T: Send + 'static,
         T::Item: Send,
         T::Error: Send,
@@ -308,7 +542,12 @@ where
         let (sender, mut receiver) = mpsc::channel(BUFFER_SIZE);
         let push_manager: Arc<ArcSwap<PushManager>> =
             Arc::new(ArcSwap::new(Arc::new(PushManager::default())));
-        let sink = PipelineSink::new::<SinkItem>(sink_stream, push_manager.clone());
+        let sink = PipelineSink::new::<SinkItem>(
+            sink_stream,
+            push_manager.clone(),
+            stream_next,
+            bytes_next,
+        );
         let f = stream::poll_fn(move |cx| receiver.poll_recv(cx))
             .map(Ok)
             .forward(sink)
@@ -328,14 +567,14 @@ where
         item: SinkItem,
         timeout: Duration,
     ) -> Result<Value, Option<RedisError>> {
-        self.send_recv(item, None, timeout).await
+        self.send_recv(item, ExpectedResponse::SingleCommand, timeout)
+            .await
     }
 
     async fn send_recv(
         &mut self,
         input: SinkItem,
-        // If `None`, this is a single request, not a pipeline of multiple requests.
-        pipeline_response_count: Option<usize>,
+        expected: ExpectedResponse,
         timeout: Duration,
     ) -> Result<Value, Option<RedisError>> {
         let (sender, receiver) = oneshot::channel();
@@ -343,7 +582,7 @@ where
         self.sender
             .send(PipelineMessage {
                 input,
-                pipeline_response_count,
+                expected,
                 output: sender,
             })
             .await
@@ -360,7 +599,7 @@ where
     }
 
     /// Sets `PushManager` of Pipeline
-    async fn set_push_manager(&mut self, push_manager: PushManager) {
+    fn set_push_manager(&mut self, push_manager: PushManager) {
         self.push_manager.store(Arc::new(push_manager));
     }
 }
@@ -374,6 +613,8 @@ pub struct MultiplexedConnection {
     response_timeout: Duration,
     protocol: ProtocolVersion,
     push_manager: PushManager,
+    instrumenter: Option<Instrumenter>,
+    asking: bool,
 }
 
 impl Debug for MultiplexedConnection {
@@ -418,19 +659,22 @@ impl MultiplexedConnection {
         compile_error!("tokio-comp or async-std-comp features required for aio feature");
 
         let redis_connection_info = &connection_info.redis;
-        let codec = ValueCodec::default()
-            .framed(stream)
-            .and_then(|msg| async move { msg });
-        let (mut pipeline, driver) = Pipeline::new(codec);
+        let stream_next = Arc::new(AtomicBool::new(false));
+        let bytes_next = Arc::new(AtomicBool::new(false));
+        let codec =
+            StreamingValueCodec::new(stream_next.clone(), bytes_next.clone()).framed(stream);
+        let (mut pipeline, driver) = Pipeline::new(codec, stream_next, bytes_next);
         let driver = boxed(driver);
         let pm = PushManager::default();
-        pipeline.set_push_manager(pm.clone()).await;
+        pipeline.set_push_manager(pm.clone());
         let mut con = MultiplexedConnection {
             pipeline,
             db: connection_info.redis.db,
             response_timeout,
             push_manager: pm,
             protocol: redis_connection_info.protocol,
+            instrumenter: None,
+            asking: false,
         };
         let driver = {
             let auth = setup_connection(&connection_info.redis, &mut con);
@@ -461,9 +705,20 @@ impl MultiplexedConnection {
     /// Sends an already encoded (packed) command into the TCP socket and
     /// reads the single response from it.
     pub async fn send_packed_command(&mut self, cmd: &Cmd) -> RedisResult<Value> {
+        if self.asking {
+            let mut pipeline = crate::pipe();
+            pipeline.add_command(cmd.clone());
+            let mut values = self.send_packed_commands(&pipeline, 0, 1).await?;
+            return Ok(values.pop().unwrap_or(Value::Nil));
+        }
+        let packed = cmd.get_packed_command();
+        let recorder = self
+            .instrumenter
+            .as_ref()
+            .map(|instrumenter| instrumenter.command(cmd, packed.len()));
         let result = self
             .pipeline
-            .send_single(cmd.get_packed_command(), self.response_timeout)
+            .send_single(packed, self.response_timeout)
             .await
             .map_err(|err| {
                 err.unwrap_or_else(|| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))
@@ -479,6 +734,9 @@ impl MultiplexedConnection {
                 }
             }
         }
+        if let Some(recorder) = recorder {
+            recorder.finish(self.db, &result);
+        }
         result
     }
 
@@ -491,18 +749,133 @@ impl MultiplexedConnection {
         offset: usize,
         count: usize,
     ) -> RedisResult<Vec<Value>> {
+        let asking = self.asking.then(|| AskingPipeline::new(cmd));
+        let (cmd, offset, count) = match &asking {
+            Some(asking) => asking.replies(offset, count),
+            None => (cmd, offset, count),
+        };
+        let value = self
+            .send_pipeline(cmd, ExpectedResponse::Pipeline(offset + count))
+            .await?;
+        let values = match value {
+            Value::Array(mut values) => {
+                values.drain(..offset);
+                values
+            }
+            _ => vec![value],
+        };
+        Ok(match asking {
+            Some(asking) => asking.remove_asking_replies(values),
+            None => values,
+        })
+    }
+
+    // Like `send_packed_commands`, but an error reply only fails its own command.
+    async fn send_packed_commands_with_errors(
+        &mut self,
+        cmd: &crate::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisResult<Vec<RedisResult<Value>>> {
+        let asking = self.asking.then(|| AskingPipeline::new(cmd));
+        let (cmd, offset, count) = match &asking {
+            Some(asking) => asking.replies(offset, count),
+            None => (cmd, offset, count),
+        };
+        let (sender, receiver) = oneshot::channel();
+        self.send_pipeline(
+            cmd,
+            ExpectedResponse::PipelineResults(offset + count, sender),
+        )
+        .await?;
+        let mut results = receiver
+            .await
+            .map_err(|_| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))?;
+        results.drain(..offset);
+        Ok(match asking {
+            Some(asking) => asking.remove_asking_replies(results),
+            None => results,
+        })
+    }
+
+    async fn send_pipeline(
+        &mut self,
+        cmd: &crate::Pipeline,
+        expected: ExpectedResponse,
+    ) -> RedisResult<Value> {
+        let packed = cmd.get_packed_pipeline();
+        let recorder = self.instrumenter.as_ref().map(|instrumenter| {
+            instrumenter.pipeline(cmd.is_atomic(), cmd.cmd_iter().count(), packed.len())
+        });
+        let result = self
+            .pipeline
+            .send_recv(packed, expected, self.response_timeout)
+            .await
+            .map_err(|err| {
+                err.unwrap_or_else(|| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))
+            });
+
+        if self.protocol != ProtocolVersion::RESP2 {
+            if let Err(e) = &result {
+                if e.is_connection_dropped() {
+                    // Notify the PushManager that the connection was lost
+                    self.push_manager.try_send_raw(&Value::Push {
+                        kind: PushKind::Disconnection,
+                        data: vec![],
+                    });
+                }
+            }
+        }
+        if let Some(recorder) = recorder {
+            recorder.finish(self.db, &result);
+        }
+        result
+    }
+
+    /// Sends a command whose reply is a bulk string, and returns the reply as a
+    /// [`BulkStringStream`] that yields it chunk by chunk instead of buffering it
+    /// completely.  Returns `None` if the reply is nil.
+    ///
+    /// Replies are read in order, so while the stream is being consumed no other
+    /// replies are received on this connection and requests sent through clones of
+    /// it are delayed.  Dropping the stream discards its remaining chunks.  With
+    /// instrumentation, the request is reported once the whole value was read, or when
+    /// the stream is dropped before that.
+    ///
+    /// ```rust,no_run
+    /// # async fn do_something(con: &mut redis::aio::MultiplexedConnection) -> redis::RedisResult<()> {
+    /// use futures::StreamExt;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// let mut out = tokio::io::sink();
+    /// if let Some(mut blob) = con.query_bulk_string_stream(redis::cmd("GET").arg("blob")).await? {
+    ///     while let Some(chunk) = blob.next().await {
+    ///         out.write_all(&chunk?).await?;
+    ///     }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn query_bulk_string_stream(
+        &mut self,
+        cmd: &Cmd,
+    ) -> RedisResult<Option<BulkStringStream>> {
+        let packed = cmd.get_packed_command();
+        let recorder = self
+            .instrumenter
+            .as_ref()
+            .map(|instrumenter| instrumenter.command(cmd, packed.len()));
+        let (sender, receiver) = mpsc::channel(BULK_STREAM_BUFFER_SIZE);
         let result = self
             .pipeline
             .send_recv(
-                cmd.get_packed_pipeline(),
-                Some(offset + count),
+                packed,
+                ExpectedResponse::BulkStream(sender),
                 self.response_timeout,
             )
             .await
             .map_err(|err| {
                 err.unwrap_or_else(|| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))
             });
-
         if self.protocol != ProtocolVersion::RESP2 {
             if let Err(e) = &result {
                 if e.is_connection_dropped() {
@@ -514,20 +887,267 @@ impl MultiplexedConnection {
                 }
             }
         }
-        let value = result?;
-        match value {
-            Value::Array(mut values) => {
-                values.drain(..offset);
-                Ok(values)
+        match result {
+            Ok(Value::Int(len)) => {
+                let mut stream = BulkStringStream {
+                    remaining: len as usize,
+                    len: len as usize,
+                    chunks: receiver,
+                    chunk: Bytes::new(),
+                    recorder: recorder.map(|recorder| (recorder, self.db)),
+                };
+                if stream.remaining == 0 {
+                    stream.finish_recorder(&Ok(()));
+                }
+                Ok(Some(stream))
+            }
+            result => {
+                if let Some(recorder) = recorder {
+                    recorder.finish(self.db, &result);
+                }
+                result.map(|_| None)
             }
-            _ => Ok(vec![value]),
         }
     }
 
+    /// Sends a command and returns its reply as a [`BytesValue`], whose bulk strings are slices
+    /// of the buffer the reply was read into instead of copies.  This avoids copying large blobs,
+    /// and the reply can be converted with [`from_bytes_value`](crate::from_bytes_value).
+    ///
+    /// ```rust,no_run
+    /// # async fn do_something(con: &mut redis::aio::MultiplexedConnection) -> redis::RedisResult<()> {
+    /// let reply = con.query_bytes_value(redis::cmd("GET").arg("blob")).await?;
+    /// let blob: Option<bytes::Bytes> = redis::from_bytes_value(reply)?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn query_bytes_value(&mut self, cmd: &Cmd) -> RedisResult<BytesValue> {
+        if self.asking {
+            let mut pipeline = crate::pipe();
+            pipeline.add_command(cmd.clone());
+            let mut values = self.send_packed_commands(&pipeline, 0, 1).await?;
+            return Ok(values.pop().unwrap_or(Value::Nil).into());
+        }
+        let packed = cmd.get_packed_command();
+        let recorder = self
+            .instrumenter
+            .as_ref()
+            .map(|instrumenter| instrumenter.command(cmd, packed.len()));
+        let (sender, receiver) = oneshot::channel();
+        let result = self
+            .pipeline
+            .send_recv(
+                packed,
+                ExpectedResponse::BytesValue(sender),
+                self.response_timeout,
+            )
+            .await
+            .map_err(|err| {
+                err.unwrap_or_else(|| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))
+            });
+        let result = match result {
+            // The reply was sent before the output.
+            Ok(_) => receiver.await.unwrap_or_else(|_| {
+                Err(RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))
+            }),
+            Err(err) => Err(err),
+        };
+        if self.protocol != ProtocolVersion::RESP2 {
+            if let Err(e) = &result {
+                if e.is_connection_dropped() {
+                    // Notify the PushManager that the connection was lost
+                    self.push_manager.try_send_raw(&Value::Push {
+                        kind: PushKind::Disconnection,
+                        data: vec![],
+                    });
+                }
+            }
+        }
+        if let Some(recorder) = recorder {
+            recorder.finish(self.db, &result);
+        }
+        result
+    }
+
     /// Sets `PushManager` of connection
     pub async fn set_push_manager(&mut self, push_manager: PushManager) {
+        self.replace_push_manager(push_manager);
+    }
+
+    pub(crate) fn replace_push_manager(&mut self, push_manager: PushManager) {
         self.push_manager = push_manager.clone();
-        self.pipeline.set_push_manager(push_manager).await;
+        self.pipeline.set_push_manager(push_manager);
+    }
+
+    pub(crate) fn set_instrumenter(&mut self, instrumenter: Option<Instrumenter>) {
+        self.instrumenter = instrumenter;
+    }
+
+    /// Makes the connection send `ASKING` before its commands, so that they can access the slot
+    /// that its node is importing.
+    #[cfg(feature = "cluster-async")]
+    pub(crate) fn set_asking(&mut self, asking: bool) {
+        self.asking = asking;
+    }
+}
+
+// The pipeline that a connection in asking mode sends instead of `pipeline`. `ASKING` only
+// applies to the next command, unless it's sent before `MULTI`, in which case it applies to the
+// whole transaction.
+struct AskingPipeline {
+    pipeline: crate::Pipeline,
+    atomic: bool,
+}
+
+impl AskingPipeline {
+    fn new(pipeline: &crate::Pipeline) -> Self {
+        let mut asking = crate::pipe();
+        if pipeline.is_atomic() {
+            asking.cmd("ASKING").cmd("MULTI");
+            for cmd in pipeline.cmd_iter() {
+                asking.add_command(cmd.clone());
+            }
+            asking.cmd("EXEC");
+        } else {
+            for cmd in pipeline.cmd_iter() {
+                asking.cmd("ASKING").add_command(cmd.clone());
+            }
+        }
+        Self {
+            pipeline: asking,
+            atomic: pipeline.is_atomic(),
+        }
+    }
+
+    /// Returns the pipeline to send, with the offset and count of the replies that are read
+    /// instead of the given ones.
+    fn replies(&self, offset: usize, count: usize) -> (&crate::Pipeline, usize, usize) {
+        if self.atomic {
+            (&self.pipeline, offset + 1, count)
+        } else {
+            (&self.pipeline, offset * 2, count * 2)
+        }
+    }
+
+    /// Removes the replies of the `ASKING` commands from the replies that were read.
+    fn remove_asking_replies<T>(&self, replies: Vec<T>) -> Vec<T> {
+        if self.atomic {
+            replies
+        } else {
+            replies.into_iter().skip(1).step_by(2).collect()
+        }
+    }
+}
+
+/// A bulk string reply that is read from a [`MultiplexedConnection`] chunk by chunk.
+///
+/// Returned by [`MultiplexedConnection::query_bulk_string_stream`].  It can be consumed
+/// either as a [`Stream`] of [`Bytes`] or through [`AsyncRead`], which makes it easy to pipe
+/// large values into files or HTTP bodies with bounded memory.  If the connection fails before
+/// the whole value was read, the stream ends with an error.
+pub struct BulkStringStream {
+    len: usize,
+    // The number of bytes that were not received yet.
+    remaining: usize,
+    chunks: mpsc::Receiver<Bytes>,
+    // The unread rest of the chunk that `poll_read` is in the middle of.
+    chunk: Bytes,
+    // Finished once the whole value was received, so that the transfer is measured as well.
+    recorder: Option<(RequestRecorder, i64)>,
+}
+
+impl BulkStringStream {
+    /// Returns the total length of the bulk string in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the bulk string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn poll_chunk(&mut self, cx: &mut task::Context) -> Poll<Option<RedisResult<Bytes>>> {
+        if !self.chunk.is_empty() {
+            return Poll::Ready(Some(Ok(std::mem::take(&mut self.chunk))));
+        }
+        match ready!(self.chunks.poll_recv(cx)) {
+            Some(chunk) => {
+                self.remaining -= chunk.len();
+                let result = Ok(chunk);
+                if self.remaining == 0 {
+                    self.finish_recorder(&result);
+                }
+                Poll::Ready(Some(result))
+            }
+            None if self.remaining > 0 => {
+                self.remaining = 0;
+                let result = Err(RedisError::from(io::Error::from(
+                    io::ErrorKind::UnexpectedEof,
+                )));
+                self.finish_recorder(&result);
+                Poll::Ready(Some(result))
+            }
+            None => Poll::Ready(None),
+        }
+    }
+
+    fn finish_recorder<T>(&mut self, result: &RedisResult<T>) {
+        if let Some((recorder, db)) = self.recorder.take() {
+            recorder.finish(db, result);
+        }
+    }
+}
+
+impl Drop for BulkStringStream {
+    fn drop(&mut self) {
+        // The rest of the value is discarded, which isn't an error of the request.
+        self.finish_recorder(&Ok(()));
+    }
+}
+
+impl Debug for BulkStringStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BulkStringStream")
+            .field("len", &self.len)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+impl Stream for BulkStringStream {
+    type Item = RedisResult<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_chunk(cx)
+    }
+}
+
+impl AsyncRead for BulkStringStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let self_ = self.get_mut();
+        let mut chunk = match ready!(self_.poll_chunk(cx)) {
+            Some(Ok(chunk)) => chunk,
+            Some(Err(err)) => return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, err))),
+            None => return Poll::Ready(Ok(())),
+        };
+        let read = chunk.len().min(buf.remaining());
+        buf.put_slice(&chunk.split_to(read));
+        self_.chunk = chunk;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl CacheableConnection for MultiplexedConnection {
+    fn push_manager(&self) -> PushManager {
+        self.get_push_manager()
+    }
+
+    fn protocol(&self) -> ProtocolVersion {
+        self.protocol
     }
 }
 
@@ -545,6 +1165,19 @@ impl ConnectionLike for MultiplexedConnection {
         (async move { self.send_packed_commands(cmd, offset, count).await }).boxed()
     }
 
+    fn req_packed_commands_with_errors<'a>(
+        &'a mut self,
+        cmd: &'a crate::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<RedisResult<Value>>> {
+        (async move {
+            self.send_packed_commands_with_errors(cmd, offset, count)
+                .await
+        })
+        .boxed()
+    }
+
     fn get_db(&self) -> i64 {
         self.db
     }
@@ -0,0 +1,385 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use futures_util::FutureExt;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::{boxed_sleep, ConnectionLike, MultiplexedConnection, Runtime};
+use crate::cmd::{cmd, Cmd};
+use crate::types::{ErrorKind, RedisError, RedisFuture, RedisResult, Value};
+use crate::Client;
+
+/// Configuration of a [`ConnectionPool`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use redis::aio::ConnectionPoolConfig;
+/// use std::time::Duration;
+///
+/// let config = ConnectionPoolConfig::default()
+///     .min_idle(2)
+///     .max_size(16)
+///     .idle_timeout(Duration::from_secs(300))
+///     .checkout_timeout(Duration::from_secs(1));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConnectionPoolConfig {
+    min_idle: usize,
+    max_size: usize,
+    idle_timeout: Option<Duration>,
+    checkout_timeout: Option<Duration>,
+    connection_timeout: Duration,
+    response_timeout: Duration,
+    health_check: bool,
+}
+
+impl Default for ConnectionPoolConfig {
+    fn default() -> Self {
+        Self {
+            min_idle: 0,
+            max_size: 10,
+            idle_timeout: None,
+            checkout_timeout: None,
+            connection_timeout: Duration::MAX,
+            response_timeout: Duration::MAX,
+            health_check: true,
+        }
+    }
+}
+
+impl ConnectionPoolConfig {
+    /// Sets the number of connections that are opened when the pool is created, and that are
+    /// kept open regardless of the idle timeout. Defaults to 0.
+    pub fn min_idle(mut self, min_idle: usize) -> Self {
+        self.min_idle = min_idle;
+        self
+    }
+
+    /// Sets the maximal number of open connections. Checkouts wait while all of them are checked
+    /// out. Defaults to 10.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Sets the duration after which an unused connection is closed. The pool checks for expired
+    /// connections once per idle timeout, so a connection might stay open for up to twice the
+    /// idle timeout. By default unused connections are kept open.
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    /// Sets the maximal duration of a checkout, which includes waiting for a connection to become
+    /// available, checking its health and opening a new connection. By default checkouts wait
+    /// indefinitely.
+    pub fn checkout_timeout(mut self, checkout_timeout: Duration) -> Self {
+        self.checkout_timeout = Some(checkout_timeout);
+        self
+    }
+
+    /// Sets the timeout for opening a new connection.
+    pub fn connection_timeout(mut self, connection_timeout: Duration) -> Self {
+        self.connection_timeout = connection_timeout;
+        self
+    }
+
+    /// Sets the response timeout of the connections.
+    pub fn response_timeout(mut self, response_timeout: Duration) -> Self {
+        self.response_timeout = response_timeout;
+        self
+    }
+
+    /// Sets whether an idle connection is checked with `PING` before it's checked out. Broken
+    /// connections are replaced with new ones. Defaults to `true`.
+    pub fn health_check(mut self, health_check: bool) -> Self {
+        self.health_check = health_check;
+        self
+    }
+}
+
+/// The number of connections of a [`ConnectionPool`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolState {
+    /// The number of open connections, including the ones that are checked out.
+    pub connections: usize,
+    /// The number of open connections that aren't checked out.
+    pub idle_connections: usize,
+}
+
+struct IdleConnection {
+    connection: MultiplexedConnection,
+    idle_since: Instant,
+}
+
+struct PoolInner {
+    client: Client,
+    config: ConnectionPoolConfig,
+    // One permit for every connection that can be checked out.
+    checkouts: Arc<Semaphore>,
+    idle: Mutex<VecDeque<IdleConnection>>,
+}
+
+impl PoolInner {
+    /// Removes the connections that were idle for longer than the idle timeout, while there are
+    /// more than `min_idle` idle connections.
+    fn remove_expired(&self, idle: &mut VecDeque<IdleConnection>) {
+        let idle_timeout = match self.config.idle_timeout {
+            Some(idle_timeout) => idle_timeout,
+            None => return,
+        };
+        // The least recently used connections are at the front.
+        while idle.len() > self.config.min_idle
+            && idle
+                .front()
+                .map_or(false, |conn| conn.idle_since.elapsed() >= idle_timeout)
+        {
+            idle.pop_front();
+        }
+    }
+
+    fn pop_idle(&self) -> Option<MultiplexedConnection> {
+        let mut idle = self.idle.lock().unwrap();
+        self.remove_expired(&mut idle);
+        idle.pop_back().map(|conn| conn.connection)
+    }
+
+    async fn connect(&self) -> RedisResult<MultiplexedConnection> {
+        self.client
+            .get_multiplexed_async_connection_with_timeouts(
+                self.config.response_timeout,
+                self.config.connection_timeout,
+            )
+            .await
+    }
+}
+
+/// A pool of async connections to a single server.
+///
+/// Every checked out [`PooledConnection`] is used exclusively by its holder until it's dropped,
+/// and then it's returned to the pool. This means that large values and blocking commands such as
+/// `BLPOP` or `XREAD BLOCK` on one connection don't delay the requests that are sent on other
+/// connections of the pool.
+///
+/// The pool can be cloned, and the clones share the same connections.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use redis::AsyncCommands;
+/// use redis::aio::{ConnectionPool, ConnectionPoolConfig};
+///
+/// # async fn do_something() -> redis::RedisResult<()> {
+/// let client = redis::Client::open("redis://127.0.0.1/")?;
+/// let pool = ConnectionPool::new(client, ConnectionPoolConfig::default().max_size(4)).await?;
+/// let mut con = pool.get().await?;
+/// let _: Option<(String, String)> = con.blpop("queue", 1.0).await?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+pub struct ConnectionPool {
+    inner: Arc<PoolInner>,
+}
+
+impl ConnectionPool {
+    /// Creates a pool, and opens `min_idle` connections.
+    pub async fn new(client: Client, config: ConnectionPoolConfig) -> RedisResult<Self> {
+        if config.max_size == 0 || config.min_idle > config.max_size {
+            return Err(RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "The maximal size of the pool must be positive, and at least the minimal number of idle connections",
+            )));
+        }
+        let pool = Self {
+            inner: Arc::new(PoolInner {
+                client,
+                checkouts: Arc::new(Semaphore::new(config.max_size)),
+                config,
+                idle: Mutex::new(VecDeque::new()),
+            }),
+        };
+        for _ in 0..pool.inner.config.min_idle {
+            let connection = pool.inner.connect().await?;
+            pool.inner.idle.lock().unwrap().push_back(IdleConnection {
+                connection,
+                idle_since: Instant::now(),
+            });
+        }
+        if let Some(idle_timeout) = pool.inner.config.idle_timeout {
+            Runtime::locate().spawn(remove_expired_periodically(
+                Arc::downgrade(&pool.inner),
+                idle_timeout,
+            ));
+        }
+        Ok(pool)
+    }
+
+    /// Checks out a connection. Waits for a connection to become available if all of them are
+    /// checked out, and opens a new connection if no idle connection is available. Fails if all
+    /// of this takes longer than the checkout timeout.
+    pub async fn get(&self) -> RedisResult<PooledConnection> {
+        match self.inner.config.checkout_timeout {
+            Some(checkout_timeout) => Runtime::locate()
+                .timeout(checkout_timeout, self.checkout())
+                .await
+                .map_err(RedisError::from)?,
+            None => self.checkout().await,
+        }
+    }
+
+    async fn checkout(&self) -> RedisResult<PooledConnection> {
+        let permit = self
+            .inner
+            .checkouts
+            .clone()
+            .acquire_owned()
+            .await
+            // The semaphore is never closed.
+            .unwrap();
+
+        while let Some(mut connection) = self.inner.pop_idle() {
+            if !self.inner.config.health_check || is_healthy(&mut connection).await {
+                return Ok(self.checked_out(connection, permit));
+            }
+        }
+        let connection = self.inner.connect().await?;
+        Ok(self.checked_out(connection, permit))
+    }
+
+    /// Returns the number of connections in the pool.
+    pub fn state(&self) -> PoolState {
+        let mut idle = self.inner.idle.lock().unwrap();
+        self.inner.remove_expired(&mut idle);
+        let checked_out = self.inner.config.max_size - self.inner.checkouts.available_permits();
+        PoolState {
+            connections: idle.len() + checked_out,
+            idle_connections: idle.len(),
+        }
+    }
+
+    fn checked_out(
+        &self,
+        connection: MultiplexedConnection,
+        permit: OwnedSemaphorePermit,
+    ) -> PooledConnection {
+        PooledConnection {
+            connection: Some(connection),
+            pool: self.inner.clone(),
+            broken: false,
+            request_in_flight: false,
+            _permit: permit,
+        }
+    }
+}
+
+// Closes the expired connections of an unused pool. Stops once the pool and its connections were
+// dropped.
+async fn remove_expired_periodically(pool: Weak<PoolInner>, interval: Duration) {
+    loop {
+        boxed_sleep(interval).await;
+        let pool = match pool.upgrade() {
+            Some(pool) => pool,
+            None => return,
+        };
+        let mut idle = pool.idle.lock().unwrap();
+        pool.remove_expired(&mut idle);
+    }
+}
+
+async fn is_healthy(connection: &mut MultiplexedConnection) -> bool {
+    cmd("PING").query_async::<_, ()>(connection).await.is_ok()
+}
+
+/// A connection that is checked out of a [`ConnectionPool`].
+///
+/// The connection is returned to the pool when it's dropped, unless it failed with an error that
+/// means it can't be used anymore, or a request was dropped before it completed, since the
+/// dropped command, e.g. `BLPOP`, might still block the connection.
+///
+/// Only requests can be sent through the connection, so that connections with subscriptions or a
+/// replaced push manager aren't returned to the pool.
+pub struct PooledConnection {
+    connection: Option<MultiplexedConnection>,
+    pool: Arc<PoolInner>,
+    broken: bool,
+    request_in_flight: bool,
+    // Released only after the connection was returned to the pool.
+    _permit: OwnedSemaphorePermit,
+}
+
+impl PooledConnection {
+    /// Closes the connection instead of returning it to the pool when it's dropped. This is
+    /// useful after changing the state of the connection, for example with `SELECT` or
+    /// `CLIENT TRACKING`.
+    pub fn discard(&mut self) {
+        self.broken = true;
+    }
+
+    fn connection(&mut self) -> &mut MultiplexedConnection {
+        // The connection is only taken on drop.
+        self.connection.as_mut().unwrap()
+    }
+
+    fn check_result<T>(&mut self, result: &RedisResult<T>) {
+        if let Err(err) = result {
+            if err.is_unrecoverable_error() {
+                self.broken = true;
+            }
+        }
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if self.broken || self.request_in_flight {
+            return;
+        }
+        if let Some(connection) = self.connection.take() {
+            self.pool.idle.lock().unwrap().push_back(IdleConnection {
+                connection,
+                idle_since: Instant::now(),
+            });
+        }
+    }
+}
+
+impl ConnectionLike for PooledConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        (async move {
+            // Stays set if the request is dropped before it completes.
+            self.request_in_flight = true;
+            let result = self.connection().req_packed_command(cmd).await;
+            self.request_in_flight = false;
+            self.check_result(&result);
+            result
+        })
+        .boxed()
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a crate::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        (async move {
+            // Stays set if the request is dropped before it completes.
+            self.request_in_flight = true;
+            let result = self
+                .connection()
+                .req_packed_commands(cmd, offset, count)
+                .await;
+            self.request_in_flight = false;
+            self.check_result(&result);
+            result
+        })
+        .boxed()
+    }
+
+    fn get_db(&self) -> i64 {
+        // The connection is only taken on drop.
+        self.connection.as_ref().unwrap().get_db()
+    }
+}
@@ -1,6 +1,6 @@
 use std::{io, time::Duration};
 
-use futures_util::Future;
+use futures_util::{future::BoxFuture, Future};
 
 #[cfg(feature = "async-std-comp")]
 use super::async_std;
@@ -45,7 +45,7 @@ impl Runtime {
     }
 
     #[allow(dead_code)]
-    pub(super) fn spawn(&self, f: impl Future<Output = ()> + Send + 'static) {
+    pub(crate) fn spawn(&self, f: impl Future<Output = ()> + Send + 'static) {
         match self {
             #[cfg(feature = "tokio-comp")]
             Runtime::Tokio => tokio::Tokio::spawn(f),
@@ -72,6 +72,14 @@ impl Runtime {
     }
 }
 
+pub(crate) fn boxed_sleep(duration: Duration) -> BoxFuture<'static, ()> {
+    #[cfg(feature = "tokio-comp")]
+    return Box::pin(::tokio::time::sleep(duration));
+
+    #[cfg(all(not(feature = "tokio-comp"), feature = "async-std-comp"))]
+    return Box::pin(::async_std::task::sleep(duration));
+}
+
 #[derive(Debug)]
 pub(crate) struct Elapsed(());
 
@@ -0,0 +1,629 @@
+//! Defines types to use with the RedisBloom commands of [`BloomCommands`]: Bloom
+//! filters (`BF.*`), Cuckoo filters (`CF.*`), Count-Min sketches (`CMS.*`),
+//! Top-K (`TOPK.*`) and t-digest (`TDIGEST.*`).
+//!
+//! [`BloomCommands`]: ../trait.BloomCommands.html
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use redis::BloomCommands;
+//! use redis::bloom::{BfInfo, BfReserveOptions};
+//! # fn do_something() -> redis::RedisResult<()> {
+//! let client = redis::Client::open("redis://127.0.0.1/")?;
+//! let mut con = client.get_connection()?;
+//!
+//! let options = BfReserveOptions::default().non_scaling();
+//! let _: () = con.bf_reserve("visitors", 0.01, 100_000, &options)?;
+//! let added: bool = con.bf_add("visitors", "alice")?;
+//! let seen: Vec<bool> = con.bf_mexists("visitors", &["alice", "bob"])?;
+//! let info: BfInfo = con.bf_info("visitors")?;
+//! # Ok(()) }
+//! ```
+
+use crate::types::{
+    ErrorKind, FromRedisValue, HashMap, RedisResult, RedisWrite, ToRedisArgs, Value,
+};
+
+macro_rules! invalid_type_error {
+    ($v:expr, $det:expr) => {{
+        fail!((
+            ErrorKind::TypeError,
+            "Response was of incompatible type",
+            format!("{:?} (response was {:?})", $det, $v)
+        ));
+    }};
+}
+
+/// Builder options for [`bf_reserve`] command.
+///
+/// [`bf_reserve`]: ../trait.BloomCommands.html#method.bf_reserve
+#[derive(Default, Debug, Clone)]
+pub struct BfReserveOptions {
+    expansion: Option<u32>,
+    non_scaling: bool,
+}
+
+impl BfReserveOptions {
+    /// Set `EXPANSION <expansion>` cmd arg, the capacity ratio of each new sub-filter.
+    pub fn expansion(mut self, expansion: u32) -> Self {
+        self.expansion = Some(expansion);
+        self
+    }
+
+    /// Set `NONSCALING` cmd arg, so that the filter fails to add items once full.
+    pub fn non_scaling(mut self) -> Self {
+        self.non_scaling = true;
+        self
+    }
+}
+
+impl ToRedisArgs for BfReserveOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(expansion) = self.expansion {
+            out.write_arg(b"EXPANSION");
+            out.write_arg_fmt(expansion);
+        }
+        if self.non_scaling {
+            out.write_arg(b"NONSCALING");
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// Builder options for [`bf_insert`] command.
+///
+/// The capacity, error rate and expansion only apply when the filter is created by the command.
+///
+/// [`bf_insert`]: ../trait.BloomCommands.html#method.bf_insert
+#[derive(Default, Debug, Clone)]
+pub struct BfInsertOptions {
+    capacity: Option<u64>,
+    error_rate: Option<f64>,
+    expansion: Option<u32>,
+    no_create: bool,
+    non_scaling: bool,
+}
+
+impl BfInsertOptions {
+    /// Set `CAPACITY <capacity>` cmd arg.
+    pub fn capacity(mut self, capacity: u64) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Set `ERROR <rate>` cmd arg, the false positive rate of the filter.
+    pub fn error_rate(mut self, rate: f64) -> Self {
+        self.error_rate = Some(rate);
+        self
+    }
+
+    /// Set `EXPANSION <expansion>` cmd arg.
+    pub fn expansion(mut self, expansion: u32) -> Self {
+        self.expansion = Some(expansion);
+        self
+    }
+
+    /// Set `NOCREATE` cmd arg, failing if the filter doesn't exist.
+    pub fn no_create(mut self) -> Self {
+        self.no_create = true;
+        self
+    }
+
+    /// Set `NONSCALING` cmd arg.
+    pub fn non_scaling(mut self) -> Self {
+        self.non_scaling = true;
+        self
+    }
+}
+
+impl ToRedisArgs for BfInsertOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(capacity) = self.capacity {
+            out.write_arg(b"CAPACITY");
+            out.write_arg_fmt(capacity);
+        }
+        if let Some(rate) = self.error_rate {
+            out.write_arg(b"ERROR");
+            out.write_arg_fmt(rate);
+        }
+        if let Some(expansion) = self.expansion {
+            out.write_arg(b"EXPANSION");
+            out.write_arg_fmt(expansion);
+        }
+        if self.no_create {
+            out.write_arg(b"NOCREATE");
+        }
+        if self.non_scaling {
+            out.write_arg(b"NONSCALING");
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// Builder options for [`cf_reserve`] command.
+///
+/// [`cf_reserve`]: ../trait.BloomCommands.html#method.cf_reserve
+#[derive(Default, Debug, Clone)]
+pub struct CfReserveOptions {
+    bucket_size: Option<u32>,
+    max_iterations: Option<u32>,
+    expansion: Option<u32>,
+}
+
+impl CfReserveOptions {
+    /// Set `BUCKETSIZE <size>` cmd arg, the number of items in each bucket.
+    pub fn bucket_size(mut self, size: u32) -> Self {
+        self.bucket_size = Some(size);
+        self
+    }
+
+    /// Set `MAXITERATIONS <iterations>` cmd arg, the number of swaps attempted
+    /// before the filter is considered full.
+    pub fn max_iterations(mut self, iterations: u32) -> Self {
+        self.max_iterations = Some(iterations);
+        self
+    }
+
+    /// Set `EXPANSION <expansion>` cmd arg, the capacity ratio of each new sub-filter.
+    pub fn expansion(mut self, expansion: u32) -> Self {
+        self.expansion = Some(expansion);
+        self
+    }
+}
+
+impl ToRedisArgs for CfReserveOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(size) = self.bucket_size {
+            out.write_arg(b"BUCKETSIZE");
+            out.write_arg_fmt(size);
+        }
+        if let Some(iterations) = self.max_iterations {
+            out.write_arg(b"MAXITERATIONS");
+            out.write_arg_fmt(iterations);
+        }
+        if let Some(expansion) = self.expansion {
+            out.write_arg(b"EXPANSION");
+            out.write_arg_fmt(expansion);
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// Builder options for [`cf_insert`] and [`cf_insertnx`] commands.
+///
+/// [`cf_insert`]: ../trait.BloomCommands.html#method.cf_insert
+/// [`cf_insertnx`]: ../trait.BloomCommands.html#method.cf_insertnx
+#[derive(Default, Debug, Clone)]
+pub struct CfInsertOptions {
+    capacity: Option<u64>,
+    no_create: bool,
+}
+
+impl CfInsertOptions {
+    /// Set `CAPACITY <capacity>` cmd arg, used when the filter is created by the command.
+    pub fn capacity(mut self, capacity: u64) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Set `NOCREATE` cmd arg, failing if the filter doesn't exist.
+    pub fn no_create(mut self) -> Self {
+        self.no_create = true;
+        self
+    }
+}
+
+impl ToRedisArgs for CfInsertOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(capacity) = self.capacity {
+            out.write_arg(b"CAPACITY");
+            out.write_arg_fmt(capacity);
+        }
+        if self.no_create {
+            out.write_arg(b"NOCREATE");
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// Builder options for [`topk_reserve`] command.
+///
+/// [`topk_reserve`]: ../trait.BloomCommands.html#method.topk_reserve
+#[derive(Default, Debug, Clone)]
+pub struct TopKReserveOptions {
+    params: Option<(u64, u64, f64)>,
+}
+
+impl TopKReserveOptions {
+    /// Set the `<width> <depth> <decay>` cmd args, the size of the sketch and
+    /// the probability of decaying the counters. The server defaults to 8, 7 and 0.9.
+    pub fn params(mut self, width: u64, depth: u64, decay: f64) -> Self {
+        self.params = Some((width, depth, decay));
+        self
+    }
+}
+
+impl ToRedisArgs for TopKReserveOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some((width, depth, decay)) = self.params {
+            out.write_arg_fmt(width);
+            out.write_arg_fmt(depth);
+            out.write_arg_fmt(decay);
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// Builder options for [`tdigest_merge`] command.
+///
+/// [`tdigest_merge`]: ../trait.BloomCommands.html#method.tdigest_merge
+#[derive(Default, Debug, Clone)]
+pub struct TDigestMergeOptions {
+    compression: Option<u64>,
+    override_dest: bool,
+}
+
+impl TDigestMergeOptions {
+    /// Set `COMPRESSION <compression>` cmd arg of the destination sketch.
+    pub fn compression(mut self, compression: u64) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Set `OVERRIDE` cmd arg, replacing the destination sketch instead of
+    /// merging the sources into it.
+    pub fn override_dest(mut self) -> Self {
+        self.override_dest = true;
+        self
+    }
+}
+
+impl ToRedisArgs for TDigestMergeOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(compression) = self.compression {
+            out.write_arg(b"COMPRESSION");
+            out.write_arg_fmt(compression);
+        }
+        if self.override_dest {
+            out.write_arg(b"OVERRIDE");
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// Collects the `[name, value, ...]` arrays (or maps with RESP3) of the `INFO` replies.
+fn info_map(v: &Value, command: &str) -> RedisResult<HashMap<String, Value>> {
+    match v {
+        Value::Array(_) | Value::Map(_) => FromRedisValue::from_redis_value(v),
+        _ => invalid_type_error!(v, format!("Response type not {command} compatible")),
+    }
+}
+
+fn info_field<T: FromRedisValue + Default>(
+    map: &HashMap<String, Value>,
+    name: &str,
+) -> RedisResult<T> {
+    match map.get(name) {
+        Some(Value::Nil) | None => Ok(T::default()),
+        Some(value) => T::from_redis_value(value),
+    }
+}
+
+/// Reply type used with [`bf_info`] command.
+///
+/// [`bf_info`]: ../trait.BloomCommands.html#method.bf_info
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct BfInfo {
+    /// Number of items the filter can hold before scaling.
+    pub capacity: u64,
+    /// Memory used by the filter, in bytes.
+    pub size: u64,
+    /// Number of sub-filters.
+    pub filters: u64,
+    /// Number of items added to the filter.
+    pub items_inserted: u64,
+    /// Expansion rate of the filter, `None` for non-scaling filters.
+    pub expansion_rate: Option<u64>,
+}
+
+impl FromRedisValue for BfInfo {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let map = info_map(v, "BF.INFO")?;
+        Ok(BfInfo {
+            capacity: info_field(&map, "Capacity")?,
+            size: info_field(&map, "Size")?,
+            filters: info_field(&map, "Number of filters")?,
+            items_inserted: info_field(&map, "Number of items inserted")?,
+            expansion_rate: info_field(&map, "Expansion rate")?,
+        })
+    }
+}
+
+/// Reply type used with [`cf_info`] command.
+///
+/// [`cf_info`]: ../trait.BloomCommands.html#method.cf_info
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct CfInfo {
+    /// Memory used by the filter, in bytes.
+    pub size: u64,
+    /// Number of buckets.
+    pub buckets: u64,
+    /// Number of sub-filters.
+    pub filters: u64,
+    /// Number of items added to the filter.
+    pub items_inserted: u64,
+    /// Number of items deleted from the filter.
+    pub items_deleted: u64,
+    /// Number of items in each bucket.
+    pub bucket_size: u64,
+    /// Expansion rate of the filter.
+    pub expansion_rate: u64,
+    /// Number of swaps attempted before the filter is considered full.
+    pub max_iterations: u64,
+}
+
+impl FromRedisValue for CfInfo {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let map = info_map(v, "CF.INFO")?;
+        Ok(CfInfo {
+            size: info_field(&map, "Size")?,
+            buckets: info_field(&map, "Number of buckets")?,
+            filters: info_field(&map, "Number of filters")?,
+            items_inserted: info_field(&map, "Number of items inserted")?,
+            items_deleted: info_field(&map, "Number of items deleted")?,
+            bucket_size: info_field(&map, "Bucket size")?,
+            expansion_rate: info_field(&map, "Expansion rate")?,
+            max_iterations: info_field(&map, "Max iterations")?,
+        })
+    }
+}
+
+/// Reply type used with [`cms_info`] command.
+///
+/// [`cms_info`]: ../trait.BloomCommands.html#method.cms_info
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct CmsInfo {
+    /// Number of counters in each array.
+    pub width: u64,
+    /// Number of counter arrays.
+    pub depth: u64,
+    /// Total of the counted increments.
+    pub count: u64,
+}
+
+impl FromRedisValue for CmsInfo {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let map = info_map(v, "CMS.INFO")?;
+        Ok(CmsInfo {
+            width: info_field(&map, "width")?,
+            depth: info_field(&map, "depth")?,
+            count: info_field(&map, "count")?,
+        })
+    }
+}
+
+/// Reply type used with [`topk_info`] command.
+///
+/// [`topk_info`]: ../trait.BloomCommands.html#method.topk_info
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct TopKInfo {
+    /// Number of top items kept.
+    pub k: u64,
+    /// Number of counters in each array.
+    pub width: u64,
+    /// Number of counter arrays.
+    pub depth: u64,
+    /// Probability of decaying the counters.
+    pub decay: f64,
+}
+
+impl FromRedisValue for TopKInfo {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let map = info_map(v, "TOPK.INFO")?;
+        Ok(TopKInfo {
+            k: info_field(&map, "k")?,
+            width: info_field(&map, "width")?,
+            depth: info_field(&map, "depth")?,
+            decay: info_field(&map, "decay")?,
+        })
+    }
+}
+
+/// Reply type used with [`tdigest_info`] command.
+///
+/// [`tdigest_info`]: ../trait.BloomCommands.html#method.tdigest_info
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct TDigestInfo {
+    /// Compression of the sketch.
+    pub compression: u64,
+    /// Number of centroids the sketch can hold.
+    pub capacity: u64,
+    /// Number of merged centroids.
+    pub merged_nodes: u64,
+    /// Number of centroids waiting to be merged.
+    pub unmerged_nodes: u64,
+    /// Total weight of the merged centroids.
+    pub merged_weight: f64,
+    /// Total weight of the centroids waiting to be merged.
+    pub unmerged_weight: f64,
+    /// Number of observations added to the sketch.
+    pub observations: u64,
+    /// Number of times the centroids were compressed.
+    pub total_compressions: u64,
+    /// Memory used by the sketch, in bytes.
+    pub memory_usage: u64,
+}
+
+impl FromRedisValue for TDigestInfo {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let map = info_map(v, "TDIGEST.INFO")?;
+        Ok(TDigestInfo {
+            compression: info_field(&map, "Compression")?,
+            capacity: info_field(&map, "Capacity")?,
+            merged_nodes: info_field(&map, "Merged nodes")?,
+            unmerged_nodes: info_field(&map, "Unmerged nodes")?,
+            merged_weight: info_field(&map, "Merged weight")?,
+            unmerged_weight: info_field(&map, "Unmerged weight")?,
+            observations: info_field(&map, "Observations")?,
+            total_compressions: info_field(&map, "Total compressions")?,
+            memory_usage: info_field(&map, "Memory usage")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str;
+
+    macro_rules! assert_args {
+        ($value:expr, $($args:expr),+) => {
+            let args = $value.to_redis_args();
+            let strings: Vec<_> = args.iter()
+                                      .map(|a| str::from_utf8(a.as_ref()).unwrap())
+                                      .collect();
+            assert_eq!(strings, vec![$($args),+]);
+        }
+    }
+
+    fn bulk(s: &str) -> Value {
+        Value::BulkString(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_options_args() {
+        assert_eq!(BfReserveOptions::default().to_redis_args().len(), 0);
+        assert_args!(
+            BfReserveOptions::default().expansion(4).non_scaling(),
+            "EXPANSION",
+            "4",
+            "NONSCALING"
+        );
+        assert_args!(
+            BfInsertOptions::default()
+                .capacity(1000)
+                .error_rate(0.01)
+                .no_create(),
+            "CAPACITY",
+            "1000",
+            "ERROR",
+            "0.01",
+            "NOCREATE"
+        );
+        assert_args!(
+            CfReserveOptions::default()
+                .bucket_size(4)
+                .max_iterations(20),
+            "BUCKETSIZE",
+            "4",
+            "MAXITERATIONS",
+            "20"
+        );
+        assert_args!(
+            CfInsertOptions::default().capacity(10).no_create(),
+            "CAPACITY",
+            "10",
+            "NOCREATE"
+        );
+        assert_args!(
+            TopKReserveOptions::default().params(50, 4, 0.5),
+            "50",
+            "4",
+            "0.5"
+        );
+        assert_args!(
+            TDigestMergeOptions::default()
+                .compression(100)
+                .override_dest(),
+            "COMPRESSION",
+            "100",
+            "OVERRIDE"
+        );
+    }
+
+    #[test]
+    fn test_info_replies() {
+        let info: BfInfo = FromRedisValue::from_redis_value(&Value::Array(vec![
+            bulk("Capacity"),
+            Value::Int(100),
+            bulk("Size"),
+            Value::Int(240),
+            bulk("Number of filters"),
+            Value::Int(1),
+            bulk("Number of items inserted"),
+            Value::Int(2),
+            bulk("Expansion rate"),
+            Value::Nil,
+        ]))
+        .unwrap();
+        assert_eq!(
+            info,
+            BfInfo {
+                capacity: 100,
+                size: 240,
+                filters: 1,
+                items_inserted: 2,
+                expansion_rate: None,
+            }
+        );
+
+        let info: TopKInfo = FromRedisValue::from_redis_value(&Value::Map(vec![
+            (bulk("k"), Value::Int(3)),
+            (bulk("width"), Value::Int(8)),
+            (bulk("depth"), Value::Int(7)),
+            (bulk("decay"), Value::Double(0.9)),
+        ]))
+        .unwrap();
+        assert_eq!(info.k, 3);
+        assert_eq!(info.decay, 0.9);
+
+        let info: TopKInfo = FromRedisValue::from_redis_value(&Value::Array(vec![
+            bulk("k"),
+            Value::Int(3),
+            bulk("decay"),
+            bulk("0.9"),
+        ]))
+        .unwrap();
+        assert_eq!(info.decay, 0.9);
+
+        let result: RedisResult<CmsInfo> = FromRedisValue::from_redis_value(&Value::Int(1));
+        assert!(result.is_err());
+    }
+}
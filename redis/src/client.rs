@@ -2,6 +2,7 @@ use std::time::Duration;
 
 use crate::{
     connection::{connect, Connection, ConnectionInfo, ConnectionLike, IntoConnectionInfo},
+    instrumentation::{Instrumentation, Instrumenter},
     types::{RedisResult, Value},
 };
 #[cfg(feature = "aio")]
@@ -10,6 +11,7 @@ use std::net::IpAddr;
 use std::net::SocketAddr;
 #[cfg(feature = "aio")]
 use std::pin::Pin;
+use std::sync::Arc;
 
 #[cfg(feature = "tls-rustls")]
 use crate::tls::{inner_build_with_tls, TlsCertificates};
@@ -18,6 +20,7 @@ use crate::tls::{inner_build_with_tls, TlsCertificates};
 #[derive(Debug, Clone)]
 pub struct Client {
     pub(crate) connection_info: ConnectionInfo,
+    pub(crate) instrumenter: Option<Instrumenter>,
 }
 
 /// The client acts as connector to the redis server.  By itself it does not
@@ -43,16 +46,33 @@ impl Client {
     pub fn open<T: IntoConnectionInfo>(params: T) -> RedisResult<Client> {
         Ok(Client {
             connection_info: params.into_connection_info()?,
+            instrumenter: None,
         })
     }
 
+    /// Sets an instrumentation that is called after every request of the connections that are
+    /// created by the client. See the [`instrumentation`](crate::instrumentation) module.
+    pub fn with_instrumentation(mut self, instrumentation: Arc<dyn Instrumentation>) -> Client {
+        self.instrumenter = Some(Instrumenter::new(instrumentation));
+        self
+    }
+
+    /// Returns the instrumenter for the connections to the server, if the client is instrumented.
+    pub(crate) fn connection_instrumenter(&self) -> Option<Instrumenter> {
+        self.instrumenter
+            .as_ref()
+            .map(|instrumenter| instrumenter.for_node(&self.connection_info.addr))
+    }
+
     /// Instructs the client to actually connect to redis and returns a
     /// connection object.  The connection object can be used to send
     /// commands to the server.  This can fail with a variety of errors
     /// (like unreachable host) so it's important that you handle those
     /// errors.
     pub fn get_connection(&self) -> RedisResult<Connection> {
-        connect(&self.connection_info, None)
+        let mut con = connect(&self.connection_info, None)?;
+        con.set_instrumenter(self.connection_instrumenter());
+        Ok(con)
     }
 
     /// Instructs the client to actually connect to redis with specified
@@ -61,7 +81,9 @@ impl Client {
     /// a variety of errors (like unreachable host) so it's important
     /// that you handle those errors.
     pub fn get_connection_with_timeout(&self, timeout: Duration) -> RedisResult<Connection> {
-        connect(&self.connection_info, Some(timeout))
+        let mut con = connect(&self.connection_info, Some(timeout))?;
+        con.set_instrumenter(self.connection_instrumenter());
+        Ok(con)
     }
 
     /// Returns a reference of client connection info object.
@@ -413,6 +435,24 @@ impl Client {
         crate::aio::ConnectionManager::new(self.clone()).await
     }
 
+    /// Returns an async [`ConnectionPool`][connection-pool] from the client.
+    ///
+    /// Every connection that is checked out of the pool is used exclusively by its holder, which
+    /// allows sending blocking commands without delaying other requests.
+    ///
+    /// [connection-pool]: aio/struct.ConnectionPool.html
+    #[cfg(any(feature = "tokio-comp", feature = "async-std-comp"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "tokio-comp", feature = "async-std-comp")))
+    )]
+    pub async fn get_connection_pool(
+        &self,
+        config: crate::aio::ConnectionPoolConfig,
+    ) -> RedisResult<crate::aio::ConnectionPool> {
+        crate::aio::ConnectionPool::new(self.clone(), config).await
+    }
+
     /// Returns an async [`ConnectionManager`][connection-manager] from the client.
     ///
     /// The connection manager wraps a
@@ -617,7 +657,10 @@ impl Client {
             response_timeout,
         )
         .await
-        .map(|res| (res.0, res.1, ip))
+        .map(|(mut con, driver)| {
+            con.set_instrumenter(self.connection_instrumenter());
+            (con, driver, ip)
+        })
     }
 
     async fn get_simple_async_connection<T>(
@@ -39,7 +39,7 @@ use std::cell::RefCell;
 use std::collections::HashSet;
 use std::str::FromStr;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use rand::{seq::IteratorRandom, thread_rng, Rng};
 
@@ -47,23 +47,33 @@ use crate::cluster_pipeline::UNROUTABLE_ERROR;
 use crate::cluster_routing::{
     MultipleNodeRoutingInfo, ResponsePolicy, Routable, SingleNodeRoutingInfo, SlotAddr,
 };
+use crate::cluster_scan::ClusterScanner;
 use crate::cluster_slotmap::SlotMap;
-use crate::cluster_topology::{parse_and_count_slots, SLOT_SIZE};
-use crate::cmd::{cmd, Cmd};
+use crate::cluster_topology::{
+    parse_and_count_slots, should_fall_back_to_slots, ClusterTopology, SLOT_SIZE,
+};
+use crate::cmd::{cmd, cmd_len, Cmd, Iter};
 use crate::connection::{
     connect, Connection, ConnectionAddr, ConnectionInfo, ConnectionLike, RedisConnectionInfo,
 };
+use crate::instrumentation::{Instrumenter, RequestRecorder};
 use crate::parser::parse_redis_value;
-use crate::types::{ErrorKind, HashMap, RedisError, RedisResult, Value};
+use crate::pipeline::pipeline_len;
+use crate::types::{
+    from_owned_redis_value, ErrorKind, FromRedisValue, HashMap, InfoDict, RedisError, RedisResult,
+    Value,
+};
 pub use crate::TlsMode; // Pub for backwards compatibility
 use crate::{
     cluster_client::ClusterParams,
     cluster_routing::{Redirect, Route, RoutingInfo},
-    IntoConnectionInfo,
+    IntoConnectionInfo, ScanOptions,
 };
 
-pub use crate::cluster_client::{ClusterClient, ClusterClientBuilder};
+pub use crate::cluster_client::{ClusterClient, ClusterClientBuilder, NodeAddress};
 pub use crate::cluster_pipeline::{cluster_pipe, ClusterPipeline};
+pub use crate::cluster_scan::ClusterScanCursor;
+pub use crate::cluster_slotmap::ReadFromReplicaStrategy;
 
 #[cfg(feature = "tls-rustls")]
 use crate::tls::TlsConnParams;
@@ -103,6 +113,19 @@ impl<'a> Input<'a> {
                 .map(Output::Multi),
         }
     }
+
+    fn recorder(&self, instrumenter: &Instrumenter) -> RequestRecorder {
+        match self {
+            Input::Slice { cmd, routable: _ } => instrumenter.packed_command(cmd),
+            Input::Cmd(cmd) => instrumenter.command(cmd, cmd_len(cmd)),
+            Input::Commands {
+                cmd,
+                route: _,
+                offset,
+                count,
+            } => instrumenter.packed_pipeline(cmd, *offset, *count),
+        }
+    }
 }
 
 impl<'a> Routable for Input<'a> {
@@ -227,7 +250,10 @@ where
     ) -> RedisResult<Self> {
         let connection = Self {
             connections: RefCell::new(HashMap::new()),
-            slots: RefCell::new(SlotMap::new(vec![], cluster_params.read_from_replicas)),
+            slots: RefCell::new(SlotMap::new(
+                vec![],
+                cluster_params.read_from_replicas.clone(),
+            )),
             auto_reconnect: RefCell::new(true),
             cluster_params,
             read_timeout: RefCell::new(None),
@@ -292,14 +318,72 @@ where
         Ok(())
     }
 
+    /// Returns a snapshot of the cluster topology as currently known by the connection: the slot
+    /// ranges, the primaries and replicas that serve them, and whether the connection to each node
+    /// is open.
+    pub fn topology(&self) -> ClusterTopology {
+        let connections = self.connections.borrow();
+        ClusterTopology::from_slot_map(&self.slots.borrow(), |address| {
+            connections
+                .get(address)
+                .map_or(false, |connection| connection.is_open())
+        })
+    }
+
     /// Check that all connections it has are available (`PING` internally).
     #[doc(hidden)]
     pub fn check_connection(&mut self) -> bool {
         <Self as ConnectionLike>::check_connection(self)
     }
 
+    /// Runs a single step of a cluster-wide `SCAN`, starting from `cursor`. Returns the advanced
+    /// cursor along with the keys that were found, which might be empty even if the scan isn't
+    /// finished yet. The scan is done once [`ClusterScanCursor::is_finished`] returns `true`.
+    ///
+    /// Unlike `SCAN` on a single node, this covers the keys of all the primaries, and the cursor
+    /// stays valid if the topology changes or the connections are recreated in the middle of the
+    /// scan. If an error is returned, the scan can be resumed from the same cursor.
+    pub fn cluster_scan<T: FromRedisValue>(
+        &mut self,
+        cursor: &ClusterScanCursor,
+        options: &ScanOptions,
+    ) -> RedisResult<(ClusterScanCursor, Vec<T>)> {
+        let mut cursor = cursor.clone();
+        let keys = self.scan_step(&mut cursor, options)?;
+        Ok((cursor, from_owned_redis_value(Value::Array(keys))?))
+    }
+
+    /// Returns an iterator over the keys of all the primaries in the cluster, see
+    /// [`ClusterConnection::cluster_scan`].
+    /// If a `SCAN` fails, the iterator ends early, and the error is returned by `take_error`.
+    ///
+    /// ```rust,no_run
+    /// # let client = redis::cluster::ClusterClient::new(vec!["redis://127.0.0.1:6379/"]).unwrap();
+    /// # let mut con = client.get_connection().unwrap();
+    /// let options = redis::ScanOptions::default().pattern("user:*").count(100);
+    /// for key in con.cluster_scan_iter::<String>(options) {
+    ///     println!("{key}");
+    /// }
+    /// ```
+    pub fn cluster_scan_iter<T: FromRedisValue>(&mut self, options: ScanOptions) -> Iter<'_, T> {
+        Iter::cluster_scan(self, options)
+    }
+
     pub(crate) fn execute_pipeline(&mut self, pipe: &ClusterPipeline) -> RedisResult<Vec<Value>> {
-        self.send_recv_and_retry_cmds(pipe.commands())
+        let mut recorder = self
+            .cluster_params
+            .instrumenter
+            .as_ref()
+            .map(|instrumenter| {
+                let request_bytes = pipeline_len(pipe.commands(), false);
+                instrumenter.pipeline(false, pipe.commands().len(), request_bytes)
+            });
+        let result = self.send_recv_and_retry_cmds(pipe.commands(), recorder.as_mut());
+        if let Some(recorder) = recorder {
+            // Cluster connections only use database 0.
+            recorder.finish(0, &result);
+        }
+        result
     }
 
     /// Returns the connection status.
@@ -310,6 +394,15 @@ where
     /// sockets the connection is open until writing a command failed with a
     /// `BrokenPipe` error.
     fn create_initial_connections(&self) -> RedisResult<()> {
+        *self.connections.borrow_mut() = self.connect_to_initial_nodes()?;
+        self.refresh_slots()?;
+        Ok(())
+    }
+
+    // Connects to the first reachable initial node. The hostnames of the initial nodes are
+    // resolved again on every call, and all of their addresses are tried, so that a cluster
+    // replaced behind a stable DNS name can be found again.
+    fn connect_to_initial_nodes(&self) -> RedisResult<HashMap<String, C>> {
         let mut connections = HashMap::with_capacity(self.initial_nodes.len());
 
         for info in self.initial_nodes.iter() {
@@ -330,15 +423,29 @@ where
             )));
         }
 
-        *self.connections.borrow_mut() = connections;
-        self.refresh_slots()?;
-        Ok(())
+        Ok(connections)
     }
 
     // Query a node to discover slot-> master mappings.
     fn refresh_slots(&self) -> RedisResult<()> {
+        let result = self.create_new_slots(&mut self.connections.borrow_mut());
+        let new_slots = match result {
+            Ok(new_slots) => new_slots,
+            Err(err) => {
+                // None of the known nodes returned the topology, e.g. because the whole cluster
+                // was replaced. Look for it again through the initial nodes, and only keep their
+                // connections if they return it.
+                let mut initial_connections = match self.connect_to_initial_nodes() {
+                    Ok(initial_connections) => initial_connections,
+                    Err(_) => return Err(err),
+                };
+                let new_slots = self.create_new_slots(&mut initial_connections)?;
+                self.connections.borrow_mut().extend(initial_connections);
+                new_slots
+            }
+        };
         let mut slots = self.slots.borrow_mut();
-        *slots = self.create_new_slots()?;
+        *slots = new_slots;
 
         let mut nodes = slots.values().flatten().collect::<Vec<_>>();
         nodes.sort_unstable();
@@ -350,7 +457,15 @@ where
             .filter_map(|addr| {
                 if connections.contains_key(addr) {
                     let mut conn = connections.remove(addr).unwrap();
+                    let start = Instant::now();
                     if conn.check_connection() {
+                        // Measure the latency again, so that reads follow changes in the nodes'
+                        // latencies.
+                        if self.cluster_params.read_from_replicas.requires_latency() {
+                            self.cluster_params
+                                .nodes_metadata
+                                .set_latency(addr, start.elapsed());
+                        }
                         return Some((addr.to_string(), conn));
                     }
                 }
@@ -368,8 +483,7 @@ where
         Ok(())
     }
 
-    fn create_new_slots(&self) -> RedisResult<SlotMap> {
-        let mut connections = self.connections.borrow_mut();
+    fn create_new_slots(&self, connections: &mut HashMap<String, C>) -> RedisResult<SlotMap> {
         let mut rng = thread_rng();
         let len = connections.len();
         let samples = connections.iter_mut().choose_multiple(&mut rng, len);
@@ -379,13 +493,17 @@ where
             "didn't get any slots from server".to_string(),
         )));
         for (addr, conn) in samples {
-            let value = conn.req_command(&slot_cmd())?;
+            let value = match conn.req_command(&shards_cmd()) {
+                Err(err) if should_fall_back_to_slots(&err) => conn.req_command(&slot_cmd())?,
+                value => value?,
+            };
             let addr = addr.split(':').next().ok_or(RedisError::from((
                 ErrorKind::ClientError,
                 "can't parse node address",
             )))?;
             match parse_and_count_slots(&value, self.cluster_params.tls, addr).map(|slots_data| {
-                SlotMap::new(slots_data.1, self.cluster_params.read_from_replicas)
+                SlotMap::new(slots_data.1, self.cluster_params.read_from_replicas.clone())
+                    .with_nodes_metadata(self.cluster_params.nodes_metadata.clone())
             }) {
                 Ok(new_slots) => {
                     result = Ok(new_slots);
@@ -401,12 +519,24 @@ where
         let info = get_connection_info(node, self.cluster_params.clone())?;
 
         let mut conn = C::connect(info, Some(self.cluster_params.connection_timeout))?;
-        if self.cluster_params.read_from_replicas
-            != crate::cluster_slotmap::ReadFromReplicaStrategy::AlwaysFromPrimary
-        {
+        let read_from_replicas = &self.cluster_params.read_from_replicas;
+        if *read_from_replicas != ReadFromReplicaStrategy::AlwaysFromPrimary {
             // If READONLY is sent to primary nodes, it will have no effect
             cmd("READONLY").query(&mut conn)?;
         }
+        if read_from_replicas.requires_availability_zone() {
+            let info: InfoDict = cmd("INFO").arg("SERVER").query(&mut conn)?;
+            self.cluster_params
+                .nodes_metadata
+                .set_availability_zone(node, info.get("availability_zone"));
+        }
+        if read_from_replicas.requires_latency() {
+            let start = Instant::now();
+            cmd("PING").query::<()>(&mut conn)?;
+            self.cluster_params
+                .nodes_metadata
+                .set_latency(node, start.elapsed());
+        }
         conn.set_read_timeout(*self.read_timeout.borrow())?;
         conn.set_write_timeout(*self.write_timeout.borrow())?;
         Ok(conn)
@@ -661,8 +791,26 @@ where
         }
     }
 
-    #[allow(clippy::unnecessary_unwrap)]
     fn request(&self, input: Input) -> RedisResult<Output> {
+        let mut recorder = self
+            .cluster_params
+            .instrumenter
+            .as_ref()
+            .map(|instrumenter| input.recorder(instrumenter));
+        let result = self.request_with_recorder(input, recorder.as_mut());
+        if let Some(recorder) = recorder {
+            // Cluster connections only use database 0.
+            recorder.finish(0, &result);
+        }
+        result
+    }
+
+    #[allow(clippy::unnecessary_unwrap)]
+    fn request_with_recorder(
+        &self,
+        input: Input,
+        mut recorder: Option<&mut RequestRecorder>,
+    ) -> RedisResult<Output> {
         let route_option = match &input {
             Input::Slice { cmd: _, routable } => RoutingInfo::for_routable(routable),
             Input::Cmd(cmd) => RoutingInfo::for_routable(*cmd),
@@ -716,6 +864,9 @@ where
                         }
                     }
                 };
+                if let Some(recorder) = recorder.as_deref_mut() {
+                    recorder.set_node(Some(&addr));
+                }
                 (addr, input.send(conn))
             };
 
@@ -726,6 +877,12 @@ where
                         return Err(err);
                     }
                     retries += 1;
+                    if let Some(recorder) = recorder.as_deref_mut() {
+                        recorder.retries = retries;
+                        if err.redirect_node().is_some() {
+                            recorder.redirects += 1;
+                        }
+                    }
 
                     match err.retry_method() {
                         crate::types::RetryMethod::AskRedirect => {
@@ -752,11 +909,18 @@ where
                         }
                         crate::types::RetryMethod::Reconnect => {
                             if *self.auto_reconnect.borrow() {
+                                let mut reconnected = false;
                                 if let Ok(mut conn) = self.connect(&addr) {
                                     if conn.check_connection() {
                                         self.connections.borrow_mut().insert(addr, conn);
+                                        reconnected = true;
                                     }
                                 }
+                                if !reconnected {
+                                    // The node might have been replaced, so get a fresh view of
+                                    // the slots before retrying.
+                                    let _ = self.refresh_slots();
+                                }
                             }
                         }
                         crate::types::RetryMethod::NoRetry => {
@@ -769,7 +933,11 @@ where
         }
     }
 
-    fn send_recv_and_retry_cmds(&self, cmds: &[Cmd]) -> RedisResult<Vec<Value>> {
+    fn send_recv_and_retry_cmds(
+        &self,
+        cmds: &[Cmd],
+        mut recorder: Option<&mut RequestRecorder>,
+    ) -> RedisResult<Vec<Value>> {
         // Vector to hold the results, pre-populated with `Nil` values. This allows the original
         // cmd ordering to be re-established by inserting the response directly into the result
         // vector (e.g., results[10] = response).
@@ -791,7 +959,10 @@ where
         // retry logic that handles these cases.
         for retry_idx in to_retry {
             let cmd = &cmds[retry_idx];
-            results[retry_idx] = self.request(Input::Cmd(cmd))?.into();
+            if let Some(recorder) = recorder.as_deref_mut() {
+                recorder.retries += 1;
+            }
+            results[retry_idx] = self.request_with_recorder(Input::Cmd(cmd), None)?.into();
         }
         Ok(results)
     }
@@ -837,6 +1008,49 @@ where
     }
 }
 
+impl<C: Connect + ConnectionLike> ClusterScanner for ClusterConnection<C> {
+    fn scan_step(
+        &mut self,
+        cursor: &mut ClusterScanCursor,
+        options: &ScanOptions,
+    ) -> RedisResult<Vec<Value>> {
+        let mut retries = 0;
+        loop {
+            let next_command = cursor.next_command(&self.slots.borrow(), options)?;
+            let (addr, scan) = match next_command {
+                Some(next_command) => next_command,
+                None => return Ok(vec![]),
+            };
+            let rv = {
+                let mut connections = self.connections.borrow_mut();
+                self.get_connection_by_addr(&mut connections, &addr)
+                    .and_then(|conn| conn.req_command(&scan))
+            };
+            let err = match rv {
+                Ok(rv) => return cursor.handle_response(rv),
+                Err(err) => err,
+            };
+            if retries == self.cluster_params.retry_params.number_of_retries {
+                return Err(err);
+            }
+            retries += 1;
+            match err.retry_method() {
+                crate::types::RetryMethod::NoRetry => return Err(err),
+                crate::types::RetryMethod::Reconnect => {
+                    self.connections.borrow_mut().remove(&addr);
+                }
+                _ => thread::sleep(
+                    self.cluster_params
+                        .retry_params
+                        .wait_time_for_retry(retries),
+                ),
+            }
+            // The node might have been replaced, so get a fresh view of the slots before retrying.
+            self.refresh_slots()?;
+        }
+    }
+}
+
 const MULTI: &[u8] = "*1\r\n$5\r\nMULTI\r\n".as_bytes();
 impl<C: Connect + ConnectionLike> ConnectionLike for ClusterConnection<C> {
     fn supports_pipelining(&self) -> bool {
@@ -963,13 +1177,18 @@ pub(crate) fn get_connection_info(
         })
         .ok_or_else(invalid_error)?;
 
+    let address = cluster_params.map_node_address(host, port);
+    #[cfg(any(feature = "tls-native-tls", feature = "tls-rustls"))]
+    let tls_params = match address.tls_server_name {
+        Some(server_name) => Some(with_tls_server_name(cluster_params.tls_params, server_name)),
+        None => cluster_params.tls_params,
+    };
+    // Without TLS support, there is no server name to override.
+    #[cfg(not(any(feature = "tls-native-tls", feature = "tls-rustls")))]
+    let tls_params = cluster_params.tls_params;
+
     Ok(ConnectionInfo {
-        addr: get_connection_addr(
-            host.to_string(),
-            port,
-            cluster_params.tls,
-            cluster_params.tls_params,
-        ),
+        addr: get_connection_addr(address.host, address.port, cluster_params.tls, tls_params),
         redis: RedisConnectionInfo {
             password: cluster_params.password,
             username: cluster_params.username,
@@ -980,6 +1199,20 @@ pub(crate) fn get_connection_info(
     })
 }
 
+#[cfg(any(feature = "tls-native-tls", feature = "tls-rustls"))]
+fn with_tls_server_name(tls_params: Option<TlsConnParams>, server_name: String) -> TlsConnParams {
+    #[cfg(feature = "tls-rustls")]
+    let mut tls_params = tls_params.unwrap_or(TlsConnParams {
+        client_tls_params: None,
+        root_cert_store: None,
+        server_name: None,
+    });
+    #[cfg(all(feature = "tls-native-tls", not(feature = "tls-rustls")))]
+    let mut tls_params = tls_params.unwrap_or(TlsConnParams { server_name: None });
+    tls_params.server_name = Some(server_name);
+    tls_params
+}
+
 pub(crate) fn get_connection_addr(
     host: String,
     port: u16,
@@ -1009,6 +1242,12 @@ pub(crate) fn slot_cmd() -> Cmd {
     cmd
 }
 
+pub(crate) fn shards_cmd() -> Cmd {
+    let mut cmd = Cmd::new();
+    cmd.arg("CLUSTER").arg("SHARDS");
+    cmd
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1051,4 +1290,47 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn get_connection_info_applies_address_mapper() {
+        let params = ClusterParams {
+            tls: Some(TlsMode::Secure),
+            address_mapper: Some(std::sync::Arc::new(|host: &str, port| {
+                (host == "10.0.0.1")
+                    .then(|| NodeAddress::new("proxy", port + 1).with_tls_server_name("node1"))
+            })),
+            ..Default::default()
+        };
+
+        let info = get_connection_info("10.0.0.1:6379", params.clone()).unwrap();
+        match info.addr {
+            ConnectionAddr::TcpTls {
+                host,
+                port,
+                tls_params,
+                ..
+            } => {
+                assert_eq!((host.as_str(), port), ("proxy", 6380));
+                #[cfg(any(feature = "tls-native-tls", feature = "tls-rustls"))]
+                assert_eq!(tls_params.unwrap().server_name.as_deref(), Some("node1"));
+                #[cfg(not(any(feature = "tls-native-tls", feature = "tls-rustls")))]
+                assert!(tls_params.is_none());
+            }
+            addr => panic!("unexpected address {addr:?}"),
+        }
+
+        let info = get_connection_info("10.0.0.2:6379", params).unwrap();
+        match info.addr {
+            ConnectionAddr::TcpTls {
+                host,
+                port,
+                tls_params,
+                ..
+            } => {
+                assert_eq!((host.as_str(), port), ("10.0.0.2", 6379));
+                assert!(tls_params.is_none());
+            }
+            addr => panic!("unexpected address {addr:?}"),
+        }
+    }
 }
@@ -155,6 +155,11 @@ where
                 ReadFromReplicaStrategy::RoundRobin => {
                     self.round_robin_read_from_replica(slot_map_value)
                 }
+                _ => self
+                    .slot_map
+                    .read_candidates(slot_map_value, &self.read_from_replica_strategy)
+                    .into_iter()
+                    .find_map(|address| self.connection_for_address(address)),
             },
             SlotAddr::ReplicaRequired => self.round_robin_read_from_replica(slot_map_value),
         }
@@ -236,6 +241,10 @@ where
         self.connection_map.len()
     }
 
+    pub(crate) fn slot_map(&self) -> &SlotMap {
+        &self.slot_map
+    }
+
     pub(crate) fn get_current_topology_hash(&self) -> TopologyHash {
         self.topology_hash
     }
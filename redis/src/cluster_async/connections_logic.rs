@@ -1,11 +1,13 @@
 use std::net::{IpAddr, SocketAddr};
+use std::time::Instant;
 
 use super::{connections_container::ClusterNode, Connect};
 use crate::{
     aio::{get_socket_addrs, ConnectionLike, Runtime},
     cluster::get_connection_info,
     cluster_client::ClusterParams,
-    ErrorKind, RedisError, RedisResult,
+    cluster_slotmap::ReadFromReplicaStrategy,
+    ErrorKind, InfoDict, RedisError, RedisResult,
 };
 
 use futures::prelude::*;
@@ -40,12 +42,13 @@ where
 /// If no socket addresses are discovered for the node's host address, or if it's a non-DNS address, it returns false.
 /// In case the node's host address resolves to socket addresses and none of them match the current connection's IP,
 /// a DNS change is detected, so the current connection isn't valid anymore and a new connection should be made.
-async fn has_dns_changed(addr: &str, curr_ip: &IpAddr) -> bool {
+async fn has_dns_changed(addr: &str, curr_ip: &IpAddr, params: &ClusterParams) -> bool {
     let (host, port) = match get_host_and_port_from_addr(addr) {
         Some((host, port)) => (host, port),
         None => return false,
     };
-    let mut updated_addresses = match get_socket_addrs(host, port).await {
+    let address = params.map_node_address(host, port);
+    let mut updated_addresses = match get_socket_addrs(&address.host, address.port).await {
         Ok(socket_addrs) => socket_addrs,
         Err(_) => return false,
     };
@@ -139,7 +142,7 @@ where
             let (mut management_conn, management_ip): (C, Option<IpAddr>) = conn_2;
             if user_ip == management_ip {
                 // Set up both connections
-                if let Err(err) = setup_user_connection(&mut user_conn, params).await {
+                if let Err(err) = setup_user_connection(&mut user_conn, addr, params).await {
                     return err.into();
                 }
                 match setup_management_connection(&mut management_conn).await {
@@ -155,12 +158,12 @@ where
             } else {
                 // Use only the connection with the latest IP address
                 warn_mismatch_ip(addr, user_ip, management_ip);
-                if has_dns_changed(addr, &user_ip.unwrap()).await {
+                if has_dns_changed(addr, &user_ip.unwrap(), &params).await {
                     // The user_ip is incorrect. Use the created `management_conn` for the user connection
                     user_conn = management_conn;
                     user_ip = management_ip;
                 }
-                match setup_user_connection(&mut user_conn, params).await {
+                match setup_user_connection(&mut user_conn, addr, params).await {
                     Ok(_) => failed_management_connection(
                         addr,
                         to_future(user_conn),
@@ -174,7 +177,7 @@ where
         (Ok(conn), Err(err)) | (Err(err), Ok(conn)) => {
             // Only a single connection was successfully established. Use it for the user connection
             let (mut user_conn, user_ip): (C, Option<IpAddr>) = conn;
-            match setup_user_connection(&mut user_conn, params).await {
+            match setup_user_connection(&mut user_conn, addr, params).await {
                 Ok(_) => failed_management_connection(addr, to_future(user_conn), user_ip, err),
                 Err(err) => err.into(),
             }
@@ -219,7 +222,7 @@ where
         // An IP mismatch was detected. Attempt to establish a new connection to replace both the management and user connections.
         // Use the successfully established connection for the user, then proceed to create a new one for management.
         warn_mismatch_ip(addr, new_ip, prev_node.ip);
-        if let Err(err) = setup_user_connection(&mut new_conn, params.clone()).await {
+        if let Err(err) = setup_user_connection(&mut new_conn, addr, params.clone()).await {
             return ConnectAndCheckResult::Failed(err);
         }
         let user_connection = to_future(new_conn);
@@ -349,7 +352,7 @@ where
     }
 }
 
-async fn create_and_setup_user_connection<C>(
+pub(crate) async fn create_and_setup_user_connection<C>(
     node: &str,
     params: ClusterParams,
     socket_addr: Option<SocketAddr>,
@@ -359,7 +362,7 @@ where
 {
     let (mut conn, ip): (C, Option<IpAddr>) =
         create_connection(node, params.clone(), socket_addr).await?;
-    setup_user_connection(&mut conn, params).await?;
+    setup_user_connection(&mut conn, node, params).await?;
     Ok((conn, ip))
 }
 
@@ -377,18 +380,32 @@ where
     Ok((conn, ip))
 }
 
-async fn setup_user_connection<C>(conn: &mut C, params: ClusterParams) -> RedisResult<()>
+async fn setup_user_connection<C>(
+    conn: &mut C,
+    node: &str,
+    params: ClusterParams,
+) -> RedisResult<()>
 where
     C: ConnectionLike + Connect + Send + 'static,
 {
-    let read_from_replicas = params.read_from_replicas
-        != crate::cluster_slotmap::ReadFromReplicaStrategy::AlwaysFromPrimary;
+    let read_from_replicas = &params.read_from_replicas;
     let connection_timeout = params.connection_timeout;
+    conn.set_push_manager(params.push_manager.clone());
+    let start = Instant::now();
     check_connection(conn, connection_timeout).await?;
-    if read_from_replicas {
+    if read_from_replicas.requires_latency() {
+        params.nodes_metadata.set_latency(node, start.elapsed());
+    }
+    if *read_from_replicas != ReadFromReplicaStrategy::AlwaysFromPrimary {
         // If READONLY is sent to primary nodes, it will have no effect
         crate::cmd("READONLY").query_async(conn).await?;
     }
+    if read_from_replicas.requires_availability_zone() {
+        let info: InfoDict = crate::cmd("INFO").arg("SERVER").query_async(conn).await?;
+        params
+            .nodes_metadata
+            .set_availability_zone(node, info.get("availability_zone"));
+    }
     Ok(())
 }
 
@@ -468,7 +485,13 @@ where
                 return false;
             }
             let conn = node.user_connection.clone();
-            check(conn, timeout, "user").await
+            let start = Instant::now();
+            let failed = check(conn, timeout, "user").await;
+            // Measure the latency again, so that reads follow changes in the nodes' latencies.
+            if !failed && params.read_from_replicas.requires_latency() {
+                params.nodes_metadata.set_latency(address, start.elapsed());
+            }
+            failed
         },
     );
 
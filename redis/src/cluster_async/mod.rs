@@ -5,7 +5,7 @@
 //! the sync cluster module, certain commands do not route identically, due most notably to
 //! a current lack of support for routing commands to multiple nodes.
 //!
-//! Also note that pubsub functionality is not currently provided by this module.
+//! Also note that only sharded pubsub (see [`ClusterConnection::ssubscribe`]) is currently provided by this module.
 //!
 //! # Example
 //! ```rust,no_run
@@ -29,34 +29,41 @@ pub mod testing {
     pub use super::connections_logic::*;
 }
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt, io, mem,
     net::{IpAddr, SocketAddr},
     pin::Pin,
     sync::{
         atomic::{self, AtomicUsize, Ordering},
-        Arc, Mutex,
+        Arc, Mutex, Weak,
     },
     task::{self, Poll},
 };
 
 use crate::{
-    aio::{get_socket_addrs, ConnectionLike, MultiplexedConnection, Runtime},
-    cluster::slot_cmd,
+    aio::{boxed_sleep, get_socket_addrs, ConnectionLike, MultiplexedConnection, Runtime},
+    cluster::{shards_cmd, slot_cmd},
     cluster_async::connections_logic::{
-        get_host_and_port_from_addr, get_or_create_conn, ConnectionFuture, RefreshConnectionType,
+        check_node_connections, get_host_and_port_from_addr, get_or_create_conn, ConnectionFuture,
+        RefreshConnectionType,
     },
     cluster_client::{ClusterParams, RetryParams},
     cluster_routing::{
         self, MultipleNodeRoutingInfo, Redirect, ResponsePolicy, Route, SingleNodeRoutingInfo,
         SlotAddr,
     },
+    cluster_scan::{AsyncClusterScanner, ClusterScanCursor},
     cluster_topology::{
-        calculate_topology, DEFAULT_NUMBER_OF_REFRESH_SLOTS_RETRIES,
-        DEFAULT_REFRESH_SLOTS_RETRY_INITIAL_INTERVAL, DEFAULT_REFRESH_SLOTS_RETRY_TIMEOUT,
+        calculate_topology, get_slot, should_fall_back_to_slots, ClusterTopology, TopologyEvent,
+        DEFAULT_NUMBER_OF_REFRESH_SLOTS_RETRIES, DEFAULT_REFRESH_SLOTS_RETRY_INITIAL_INTERVAL,
+        DEFAULT_REFRESH_SLOTS_RETRY_TIMEOUT,
     },
-    Cmd, ConnectionInfo, ErrorKind, IntoConnectionInfo, RedisError, RedisFuture, RedisResult,
-    Value,
+    cmd::cmd_len,
+    from_owned_redis_value,
+    instrumentation::RequestRecorder,
+    types::{ProtocolVersion, RetryMethod},
+    AsyncIter, Cmd, ConnectionInfo, ErrorKind, FromRedisValue, IntoConnectionInfo, PushManager,
+    RedisError, RedisFuture, RedisResult, ScanOptions, ToRedisArgs, Value,
 };
 use std::time::Duration;
 
@@ -78,7 +85,7 @@ use futures::{future::BoxFuture, prelude::*, ready};
 use pin_project_lite::pin_project;
 use std::sync::atomic::AtomicBool;
 use tokio::sync::{
-    mpsc,
+    broadcast, mpsc,
     oneshot::{self, Receiver},
     RwLock,
 };
@@ -86,14 +93,95 @@ use tracing::{info, trace, warn};
 
 use self::{
     connections_container::{ConnectionAndAddress, ConnectionType, ConnectionsMap},
-    connections_logic::connect_and_check,
+    connections_logic::{connect_and_check, create_and_setup_user_connection},
 };
 
 /// This represents an async Redis Cluster connection. It stores the
 /// underlying connections maintained for each node in the cluster, as well
 /// as common parameters for connecting to nodes and executing commands.
 #[derive(Clone)]
-pub struct ClusterConnection<C = MultiplexedConnection>(mpsc::Sender<Message<C>>);
+pub struct ClusterConnection<C = MultiplexedConnection> {
+    sender: mpsc::Sender<Message<C>>,
+    // Weak, so that the pending requests of the core are dropped along with the connection task.
+    core: Weak<InnerCore<C>>,
+    push_manager: PushManager,
+    protocol: ProtocolVersion,
+    shard_channels: ShardChannels,
+    topology_events: SharedTopologyEvents,
+}
+
+/// The sharded pubsub channels that the connection is subscribed to, along with the node that each
+/// one was subscribed on, so that they can be resubscribed to when their slots move to other nodes.
+type ShardChannels = Arc<Mutex<HashMap<Vec<u8>, Option<ArcStr>>>>;
+
+/// The number of topology events that are kept for subscribers that didn't receive them yet.
+/// Subscribers that fall further behind miss the oldest events.
+const TOPOLOGY_EVENTS_CAPACITY: usize = 1024;
+
+/// The channel of the topology events of the connection, along with the last topology that was
+/// reported through it.
+struct TopologyEvents {
+    sender: broadcast::Sender<TopologyEvent>,
+    topology: ClusterTopology,
+}
+
+type SharedTopologyEvents = Arc<Mutex<TopologyEvents>>;
+
+impl Default for TopologyEvents {
+    fn default() -> Self {
+        TopologyEvents {
+            sender: broadcast::channel(TOPOLOGY_EVENTS_CAPACITY).0,
+            topology: ClusterTopology::default(),
+        }
+    }
+}
+
+impl TopologyEvents {
+    /// Replaces the known topology and reports the changes to the subscribers.
+    fn update(&mut self, topology: ClusterTopology) {
+        let events = self.topology.changes_to(&topology);
+        self.topology = topology;
+        self.send(events);
+    }
+
+    /// Reports a change in the health of the connection to `address`, unless the known topology
+    /// already has the same health for the node.
+    fn set_connected(&mut self, address: &str, connected: bool) {
+        if let Some(node) = self
+            .topology
+            .nodes
+            .iter_mut()
+            .find(|node| node.address == address)
+        {
+            if node.connected == connected {
+                return;
+            }
+            node.connected = connected;
+        }
+        let address = address.to_string();
+        let event = if connected {
+            TopologyEvent::ConnectionRestored { address }
+        } else {
+            TopologyEvent::ConnectionLost { address }
+        };
+        self.send(vec![event]);
+    }
+
+    fn send(&mut self, events: Vec<TopologyEvent>) {
+        for event in events {
+            // Fails if there are no subscribers.
+            let _ = self.sender.send(event);
+        }
+    }
+}
+
+fn topology_snapshot<C: Clone>(connections_container: &ConnectionsContainer<C>) -> ClusterTopology {
+    ClusterTopology::from_slot_map(connections_container.slot_map(), |address| {
+        connections_container
+            .connection_for_address(address)
+            .map_or(false, |(_, connection)| connection.peek().is_some())
+    })
+}
 
 impl<C> ClusterConnection<C>
 where
@@ -103,23 +191,46 @@ where
         initial_nodes: &[ConnectionInfo],
         cluster_params: ClusterParams,
     ) -> RedisResult<ClusterConnection<C>> {
-        ClusterConnInner::new(initial_nodes, cluster_params)
-            .await
-            .map(|inner| {
-                let (tx, mut rx) = mpsc::channel::<Message<_>>(100);
-                let stream = async move {
-                    let _ = stream::poll_fn(move |cx| rx.poll_recv(cx))
-                        .map(Ok)
-                        .forward(inner)
-                        .await;
-                };
-                #[cfg(feature = "tokio-comp")]
-                tokio::spawn(stream);
-                #[cfg(all(not(feature = "tokio-comp"), feature = "async-std-comp"))]
-                AsyncStd::spawn(stream);
-
-                ClusterConnection(tx)
-            })
+        // The parameters are shared by all the connections of a client, but each connection has
+        // its own push subscribers.
+        let push_manager = PushManager::default();
+        let cluster_params = ClusterParams {
+            push_manager: push_manager.clone(),
+            ..cluster_params
+        };
+        let protocol = cluster_params.protocol;
+        let shard_channels = ShardChannels::default();
+        let topology_events = SharedTopologyEvents::default();
+        ClusterConnInner::new(
+            initial_nodes,
+            cluster_params,
+            shard_channels.clone(),
+            topology_events.clone(),
+        )
+        .await
+        .map(|inner| {
+            let core = Arc::downgrade(&inner.inner);
+            let (tx, mut rx) = mpsc::channel::<Message<_>>(100);
+            let stream = async move {
+                let _ = stream::poll_fn(move |cx| rx.poll_recv(cx))
+                    .map(Ok)
+                    .forward(inner)
+                    .await;
+            };
+            #[cfg(feature = "tokio-comp")]
+            tokio::spawn(stream);
+            #[cfg(all(not(feature = "tokio-comp"), feature = "async-std-comp"))]
+            AsyncStd::spawn(stream);
+
+            ClusterConnection {
+                sender: tx,
+                core,
+                push_manager,
+                protocol,
+                shard_channels,
+                topology_events,
+            }
+        })
     }
 
     /// Send a command to the given `routing`. If `routing` is [None], it will be computed from `cmd`.
@@ -130,7 +241,7 @@ where
     ) -> RedisResult<Value> {
         trace!("route_command");
         let (sender, receiver) = oneshot::channel();
-        self.0
+        self.sender
             .send(Message {
                 cmd: CmdArg::Cmd {
                     cmd: Arc::new(cmd.clone()),
@@ -155,7 +266,7 @@ where
             })
             .map(|response| match response {
                 Response::Single(value) => value,
-                Response::Multiple(_) => unreachable!(),
+                Response::Multiple(_) | Response::Batch(_) => unreachable!(),
             })
     }
 
@@ -168,13 +279,14 @@ where
         route: SingleNodeRoutingInfo,
     ) -> RedisResult<Vec<Value>> {
         let (sender, receiver) = oneshot::channel();
-        self.0
+        self.sender
             .send(Message {
                 cmd: CmdArg::Pipeline {
                     pipeline: Arc::new(pipeline.clone()),
                     offset,
                     count,
                     route: route.into(),
+                    batch: false,
                 },
                 sender,
             })
@@ -186,9 +298,438 @@ where
             .unwrap_or_else(|_| Err(RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe))))
             .map(|response| match response {
                 Response::Multiple(values) => values,
-                Response::Single(_) => unreachable!(),
+                Response::Single(_) | Response::Batch(_) => unreachable!(),
             })
     }
+
+    /// Send the commands in `pipeline` to the nodes that own their slots. The pipeline is split into a
+    /// sub-pipeline per node, the sub-pipelines are sent concurrently, and the results are returned in
+    /// the original order of the commands. Commands without a specific route are sent along with the
+    /// first command that has one.
+    ///
+    /// Atomic pipelines can't be split, and will return a `CrossSlot` error.
+    pub async fn route_multi_slot_pipeline<'a>(
+        &'a mut self,
+        pipeline: &'a crate::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisResult<Vec<Value>> {
+        if pipeline.is_atomic() {
+            return Err((
+                ErrorKind::CrossSlot,
+                "Atomic pipelines can't be split between slots",
+            )
+                .into());
+        }
+        let (sender, receiver) = oneshot::channel();
+        self.sender
+            .send(Message {
+                cmd: CmdArg::MultiSlotPipeline {
+                    pipeline: Arc::new(pipeline.clone()),
+                    offset,
+                    count,
+                },
+                sender,
+            })
+            .await
+            .map_err(|_| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))?;
+
+        receiver
+            .await
+            .unwrap_or_else(|_| Err(RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe))))
+            .map(|response| match response {
+                Response::Multiple(values) => values,
+                Response::Single(_) | Response::Batch(_) => unreachable!(),
+            })
+    }
+
+    /// Runs a transaction on the primary that owns the slot of `keys`, in the manner of
+    /// [`crate::aio::transaction_async`]. All the keys must belong to the same slot.
+    ///
+    /// The transaction runs on a dedicated connection to the primary, so that the `WATCH` on the
+    /// keys isn't affected by other requests. The keys are watched, and `func` is called with the
+    /// connection and a fresh pipeline in atomic mode. `func` should query the pipeline with the
+    /// connection and return the result, which is `None` if `EXEC` was aborted because a watched
+    /// key was modified, in which case the keys are watched again and `func` is called again.
+    ///
+    /// Opening the dedicated connection takes a round trip for the connection itself and one for
+    /// its setup, so it's reused when the transaction is retried on the same node, and closed
+    /// once the transaction is done.
+    ///
+    /// If the slot moved to another node, the transaction is restarted on the new primary, and if
+    /// the slot is being migrated, an `ASK` redirect is followed by running the next attempt on
+    /// the importing node. Errors that guarantee that the transaction wasn't executed, such as
+    /// `MOVED`, `ASK` and `TRYAGAIN`, are retried up to the configured number of retries, and
+    /// other errors are returned as is.
+    ///
+    /// ```rust,no_run
+    /// use futures::FutureExt;
+    /// use redis::AsyncCommands;
+    /// # async fn increment() -> redis::RedisResult<()> {
+    /// # let client = redis::cluster::ClusterClient::new(vec!["redis://127.0.0.1:6379/"])?;
+    /// # let mut con = client.get_async_connection().await?;
+    /// let key = "the_key";
+    /// let (new_val,): (isize,) = con
+    ///     .transaction(&[key], |con, mut pipe| {
+    ///         async move {
+    ///             let old_val: isize = con.get(key).await?;
+    ///             pipe.set(key, old_val + 1).ignore().get(key).query_async(con).await
+    ///         }
+    ///         .boxed()
+    ///     })
+    ///     .await?;
+    /// println!("The incremented number is: {}", new_val);
+    /// # Ok(()) }
+    /// ```
+    pub async fn transaction<K, T, F>(&mut self, keys: &[K], mut func: F) -> RedisResult<T>
+    where
+        K: ToRedisArgs,
+        F: for<'a> FnMut(&'a mut C, crate::Pipeline) -> RedisFuture<'a, Option<T>>,
+    {
+        let route = Route::new(transaction_slot(keys)?, SlotAddr::Master);
+        let core = self
+            .core
+            .upgrade()
+            .ok_or_else(|| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))?;
+        let params = core.cluster_params.clone();
+        let mut address = Self::primary_address(&core, &route).await?;
+        drop(core);
+
+        // The connection of the previous attempt, along with its node and whether it's a
+        // connection to an importing node that sends `ASKING`.
+        let mut connection: Option<(String, bool, C)> = None;
+        // An `ASK` redirect only applies to the next attempt.
+        let mut ask_node = None;
+        let mut retries = 0;
+        loop {
+            let (node, asking) = match ask_node.take() {
+                Some(node) => (node, true),
+                None => (address.clone(), false),
+            };
+            let reusable = matches!(
+                &connection,
+                Some((conn_node, conn_asking, _)) if *conn_node == node && *conn_asking == asking
+            );
+            if !reusable {
+                connection = None;
+                match create_and_setup_user_connection::<C>(&node, params.clone(), None).await {
+                    Ok((mut conn, _)) => {
+                        conn.set_asking(asking);
+                        connection = Some((node, asking, conn));
+                    }
+                    Err(err) if matches!(err.retry_method(), RetryMethod::Reconnect) => {
+                        // The node might have failed over, so the slots are refreshed before
+                        // retrying.
+                        if retries >= params.retry_params.number_of_retries {
+                            return Err(err);
+                        }
+                        retries += 1;
+                        let core = self.core.upgrade().ok_or(err)?;
+                        ClusterConnInner::refresh_slots_with_retries(core.clone()).await?;
+                        address = Self::primary_address(&core, &route).await?;
+                        continue;
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            let conn = match connection.as_mut() {
+                Some((_, _, conn)) => conn,
+                None => unreachable!("the connection was just created"),
+            };
+
+            let err = match crate::aio::transaction_async(conn, keys, &mut func).await {
+                Ok(value) => return Ok(value),
+                Err(err) => err,
+            };
+            if retries >= params.retry_params.number_of_retries {
+                return Err(err);
+            }
+            match err.retry_method() {
+                RetryMethod::MovedRedirect => {
+                    let node = match err.redirect_node() {
+                        Some((node, _slot)) => node.to_string(),
+                        None => return Err(err),
+                    };
+                    if let Some(core) = self.core.upgrade() {
+                        ClusterConnInner::refresh_slots_with_retries(core).await?;
+                    }
+                    address = node;
+                }
+                RetryMethod::AskRedirect => match err.redirect_node() {
+                    Some((node, _slot)) => ask_node = Some(node.to_string()),
+                    None => return Err(err),
+                },
+                RetryMethod::WaitAndRetry => {
+                    boxed_sleep(params.retry_params.wait_time_for_retry(retries)).await;
+                }
+                _ => return Err(err),
+            }
+            retries += 1;
+        }
+    }
+
+    async fn primary_address(core: &InnerCore<C>, route: &Route) -> RedisResult<String> {
+        core.conn_lock
+            .read()
+            .await
+            .slot_map()
+            .slot_value_for_route(route)
+            .map(|slot_value| slot_value.addrs.primary.clone())
+            .ok_or_else(|| {
+                (
+                    ErrorKind::ClusterConnectionNotFound,
+                    "No primary found for the slot of the transaction",
+                )
+                    .into()
+            })
+    }
+
+    /// Runs a single step of a cluster-wide `SCAN`, starting from `cursor`. Returns the advanced
+    /// cursor along with the keys that were found, which might be empty even if the scan isn't
+    /// finished yet. The scan is done once [`ClusterScanCursor::is_finished`] returns `true`.
+    ///
+    /// Unlike `SCAN` on a single node, this covers the keys of all the primaries, and the cursor
+    /// stays valid if the topology changes or the connections are recreated in the middle of the
+    /// scan. If an error is returned, the scan can be resumed from the same cursor.
+    pub async fn cluster_scan<T: FromRedisValue>(
+        &mut self,
+        cursor: &ClusterScanCursor,
+        options: &ScanOptions,
+    ) -> RedisResult<(ClusterScanCursor, Vec<T>)> {
+        let mut cursor = cursor.clone();
+        let keys = self.scan_step(&mut cursor, options).await?;
+        Ok((cursor, from_owned_redis_value(Value::Array(keys))?))
+    }
+
+    /// Returns a stream over the keys of all the primaries in the cluster, see
+    /// [`ClusterConnection::cluster_scan`].
+    /// If a `SCAN` fails, the stream ends early, and the error is returned by `take_error`.
+    ///
+    /// ```rust,no_run
+    /// # async fn scan() -> redis::RedisResult<()> {
+    /// # let client = redis::cluster::ClusterClient::new(vec!["redis://127.0.0.1:6379/"])?;
+    /// # let mut con = client.get_async_connection().await?;
+    /// let options = redis::ScanOptions::default().pattern("user:*").count(100);
+    /// let mut iter = con.cluster_scan_iter::<String>(options);
+    /// while let Some(key) = iter.next_item().await {
+    ///     println!("{key}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cluster_scan_iter<'a, T: FromRedisValue + 'a>(
+        &'a mut self,
+        options: ScanOptions,
+    ) -> AsyncIter<'a, T> {
+        AsyncIter::cluster_scan(self, options)
+    }
+
+    /// Subscribes to sharded channels, each one on the primary that owns the channel's slot.
+    /// Messages are delivered through the connection's [`PushManager`], and the subscriptions are
+    /// renewed when the slots move to other nodes.
+    pub async fn ssubscribe(&mut self, channel_name: impl ToRedisArgs) -> RedisResult<()> {
+        self.check_resp3()?;
+        for channel in channel_name.to_redis_args() {
+            self.shard_channels
+                .lock()
+                .unwrap()
+                .insert(channel.clone(), None);
+            let mut cmd = crate::cmd("SSUBSCRIBE");
+            cmd.arg(&channel);
+            let route = shard_channel_route(&channel);
+            let result = self
+                .route_command(
+                    &cmd,
+                    cluster_routing::RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(
+                        route,
+                    )),
+                )
+                .await;
+            if let Err(err) = result {
+                self.shard_channels.lock().unwrap().remove(&channel);
+                return Err(err);
+            }
+            // The subscription is renewed if the channel's slot is later found on another node.
+            if let Some(core) = self.core.upgrade() {
+                let address = core
+                    .conn_lock
+                    .read()
+                    .await
+                    .connection_for_route(&route)
+                    .map(|(address, _)| address);
+                if let Some(subscribed_address) =
+                    self.shard_channels.lock().unwrap().get_mut(&channel)
+                {
+                    *subscribed_address = address;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Unsubscribes from sharded channels.
+    pub async fn sunsubscribe(&mut self, channel_name: impl ToRedisArgs) -> RedisResult<()> {
+        self.check_resp3()?;
+        for channel in channel_name.to_redis_args() {
+            self.shard_channels.lock().unwrap().remove(&channel);
+            let mut cmd = crate::cmd("SUNSUBSCRIBE");
+            cmd.arg(&channel);
+            self.route_command(
+                &cmd,
+                cluster_routing::RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(
+                    shard_channel_route(&channel),
+                )),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Returns a snapshot of the cluster topology as currently known by the connection: the slot
+    /// ranges, the primaries and replicas that serve them, and whether the connection to each node
+    /// is established.
+    pub async fn topology(&self) -> RedisResult<ClusterTopology> {
+        let core = self
+            .core
+            .upgrade()
+            .ok_or_else(|| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))?;
+        let topology = topology_snapshot(&*core.conn_lock.read().await);
+        Ok(topology)
+    }
+
+    /// Returns a stream of the changes in the cluster topology that the connection detects from
+    /// now on: nodes that were added or removed, primary failovers, slot ranges that moved, and
+    /// connections to nodes that were lost or restored.
+    ///
+    /// Topology changes are detected when the slots are refreshed, either after a `MOVED` error
+    /// or by the periodic topology checks, so the events might arrive some time after the change
+    /// happened in the cluster. Each call returns a new, independent stream. The events are
+    /// buffered for streams that aren't polled, up to a limit of 1024 events, after which the
+    /// oldest events are skipped.
+    ///
+    /// ```rust,no_run
+    /// # async fn watch() -> redis::RedisResult<()> {
+    /// use futures::StreamExt;
+    /// use redis::cluster_topology::TopologyEvent;
+    ///
+    /// # let client = redis::cluster::ClusterClient::new(vec!["redis://127.0.0.1:6379/"])?;
+    /// let con = client.get_async_connection().await?;
+    /// let mut events = con.topology_events();
+    /// while let Some(event) = events.next().await {
+    ///     if let TopologyEvent::SlotsMoved { start, end, .. } = event {
+    ///         println!("slots {start}-{end} moved");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn topology_events(&self) -> impl Stream<Item = TopologyEvent> + Send + 'static {
+        let receiver = self.topology_events.lock().unwrap().sender.subscribe();
+        stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Skipped {skipped} topology events of a lagging subscriber");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// Returns the `PushManager` that gathers the push messages of all the node connections,
+    /// including the sharded pub/sub messages of every shard. Each cluster connection has a
+    /// manager of its own, which is shared with its clones.
+    pub fn get_push_manager(&self) -> PushManager {
+        self.push_manager.clone()
+    }
+
+    fn check_resp3(&self) -> RedisResult<()> {
+        if self.protocol == ProtocolVersion::RESP2 {
+            return Err(RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "RESP3 is required for this command",
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl<C> AsyncClusterScanner for ClusterConnection<C>
+where
+    C: ConnectionLike + Connect + Clone + Send + Sync + Unpin + 'static,
+{
+    fn scan_step<'a>(
+        &'a mut self,
+        cursor: &'a mut ClusterScanCursor,
+        options: &'a ScanOptions,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        async move {
+            let core = self
+                .core
+                .upgrade()
+                .ok_or_else(|| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))?;
+            let next_command =
+                cursor.next_command(core.conn_lock.read().await.slot_map(), options)?;
+            drop(core);
+            let (address, scan) = match next_command {
+                Some(next_command) => next_command,
+                None => return Ok(vec![]),
+            };
+            let (sender, receiver) = oneshot::channel();
+            self.sender
+                .send(Message {
+                    cmd: CmdArg::Cmd {
+                        cmd: Arc::new(scan),
+                        routing: InternalRoutingInfo::SingleNode(
+                            InternalSingleNodeRouting::ByAddress(address),
+                        ),
+                    },
+                    sender,
+                })
+                .await
+                .map_err(|_| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))?;
+            let response = receiver
+                .await
+                .unwrap_or_else(|_| {
+                    Err(RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))
+                })
+                .map(|response| match response {
+                    Response::Single(value) => value,
+                    Response::Multiple(_) | Response::Batch(_) => unreachable!(),
+                })?;
+            cursor.handle_response(response)
+        }
+        .boxed()
+    }
+}
+
+/// Returns the slot of the keys of a transaction, which must all belong to the same slot.
+fn transaction_slot<K: ToRedisArgs>(keys: &[K]) -> RedisResult<u16> {
+    let mut slots = keys
+        .iter()
+        .flat_map(|key| key.to_redis_args())
+        .map(|key| get_slot(&key));
+    let slot = slots.next().ok_or_else(|| {
+        RedisError::from((
+            ErrorKind::ClientError,
+            "A transaction requires at least one key",
+        ))
+    })?;
+    if slots.any(|other| other != slot) {
+        return Err((
+            ErrorKind::CrossSlot,
+            "The keys of a transaction must belong to the same slot",
+        )
+            .into());
+    }
+    Ok(slot)
+}
+
+fn shard_channel_route(channel_name: &[u8]) -> Route {
+    Route::new(get_slot(channel_name), SlotAddr::Master)
 }
 
 type ConnectionMap<C> = connections_container::ConnectionsMap<ConnectionFuture<C>>;
@@ -201,6 +742,8 @@ struct InnerCore<C> {
     pending_requests: Mutex<Vec<PendingRequest<C>>>,
     slot_refresh_in_progress: AtomicBool,
     initial_nodes: Vec<ConnectionInfo>,
+    shard_channels: ShardChannels,
+    topology_events: SharedTopologyEvents,
 }
 
 type Core<C> = Arc<InnerCore<C>>;
@@ -292,24 +835,32 @@ enum CmdArg<C> {
         offset: usize,
         count: usize,
         route: InternalSingleNodeRouting<C>,
+        // Set for the batches of a multi-slot pipeline, whose replies are returned separately.
+        batch: bool,
+    },
+    // A non-atomic pipeline whose commands are split between the nodes that own their slots.
+    MultiSlotPipeline {
+        pipeline: Arc<crate::Pipeline>,
+        offset: usize,
+        count: usize,
     },
 }
 
-fn route_for_pipeline(pipeline: &crate::Pipeline) -> RedisResult<Option<Route>> {
-    fn route_for_command(cmd: &Cmd) -> Option<Route> {
-        match cluster_routing::RoutingInfo::for_routable(cmd) {
-            Some(cluster_routing::RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)) => None,
-            Some(cluster_routing::RoutingInfo::SingleNode(
-                SingleNodeRoutingInfo::SpecificNode(route),
-            )) => Some(route),
-            Some(cluster_routing::RoutingInfo::MultiNode(_)) => None,
-            Some(cluster_routing::RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
-                ..
-            })) => None,
-            None => None,
-        }
+fn route_for_command(cmd: &Cmd) -> Option<Route> {
+    match cluster_routing::RoutingInfo::for_routable(cmd) {
+        Some(cluster_routing::RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)) => None,
+        Some(cluster_routing::RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(
+            route,
+        ))) => Some(route),
+        Some(cluster_routing::RoutingInfo::MultiNode(_)) => None,
+        Some(cluster_routing::RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+            ..
+        })) => None,
+        None => None,
     }
+}
 
+fn route_for_pipeline(pipeline: &crate::Pipeline) -> RedisResult<Option<Route>> {
     // Find first specific slot and send to it. There's no need to check If later commands
     // should be routed to a different slot, since the server will return an error indicating this.
     pipeline.cmd_iter().map(route_for_command).try_fold(
@@ -330,17 +881,134 @@ fn route_for_pipeline(pipeline: &crate::Pipeline) -> RedisResult<Option<Route>>
     )
 }
 
-fn boxed_sleep(duration: Duration) -> BoxFuture<'static, ()> {
-    #[cfg(feature = "tokio-comp")]
-    return Box::pin(tokio::time::sleep(duration));
+/// Returns the route of every command in the pipeline. Commands that don't have a route of their own
+/// (e.g. unkeyed commands) are sent along with the first command that has a specific route.
+fn routes_for_pipeline_commands(pipeline: &crate::Pipeline) -> Option<Vec<Route>> {
+    let routes: Vec<_> = pipeline.cmd_iter().map(route_for_command).collect();
+    let default_route = *routes.iter().flatten().next()?;
+    Some(
+        routes
+            .into_iter()
+            .map(|route| route.unwrap_or(default_route))
+            .collect(),
+    )
+}
 
-    #[cfg(all(not(feature = "tokio-comp"), feature = "async-std-comp"))]
-    return Box::pin(async_std::task::sleep(duration));
+/// Groups the indices of the pipeline's commands by slot. A slot's route requires the primary if any
+/// of the commands routed to that slot requires the primary.
+fn group_indices_by_slot(routes: &[Route], indices: Vec<usize>) -> Vec<(Route, Vec<usize>)> {
+    let mut slot_batches: Vec<(Route, Vec<usize>)> = Vec::new();
+    for index in indices {
+        let route = &routes[index];
+        match slot_batches
+            .iter_mut()
+            .find(|(batch_route, _)| batch_route.slot() == route.slot())
+        {
+            Some((batch_route, batch_indices)) => {
+                if route.slot_addr() == SlotAddr::Master {
+                    *batch_route = *route;
+                }
+                batch_indices.push(index);
+            }
+            None => slot_batches.push((*route, vec![index])),
+        }
+    }
+    slot_batches
+}
+
+/// Creates a pipeline out of the commands in the given indices of the original pipeline.
+fn sub_pipeline(commands: &[&Cmd], indices: &[usize]) -> crate::Pipeline {
+    let mut pipeline = crate::Pipeline::with_capacity(indices.len());
+    for index in indices {
+        pipeline.add_command(commands[*index].clone());
+    }
+    pipeline
+}
+
+/// The results of a batch of a multi-slot pipeline that was sent to a single node.
+#[derive(Debug, Default)]
+struct BatchResults {
+    values: Vec<(usize, Value)>,
+    // Commands whose slot moved to another node, which weren't executed.
+    moved: Vec<usize>,
+    // Commands that should be sent to another node with `ASKING`, since their slot is migrating.
+    asked: Vec<(String, usize)>,
+}
+
+/// Sorts the replies to a batch of the commands in the given indices into values and commands that
+/// should be redirected. Other errors fail the pipeline, since redirecting or resending commands
+/// that might have run could run them twice.
+fn split_batch_results(
+    commands: &[&Cmd],
+    routes: &[Route],
+    indices: Vec<usize>,
+    result: RedisResult<Vec<RedisResult<Value>>>,
+) -> RedisResult<BatchResults> {
+    let mut batch_results = BatchResults::default();
+    let results = match result {
+        Ok(results) => results,
+        // The connection didn't return the reply of every command. A node replies with a
+        // redirect to every command of a slot it doesn't serve, so a batch of a single slot can
+        // be redirected as a whole.
+        Err(err)
+            if indices.iter().all(|index| {
+                route_for_command(commands[*index]).is_some()
+                    && routes[*index].slot() == routes[indices[0]].slot()
+            }) =>
+        {
+            match (err.kind(), err.redirect_node()) {
+                (ErrorKind::Moved, Some(_)) => batch_results.moved = indices,
+                (ErrorKind::Ask, Some((address, _slot))) => {
+                    let address = address.to_string();
+                    batch_results.asked = indices
+                        .into_iter()
+                        .map(|index| (address.clone(), index))
+                        .collect();
+                }
+                _ => return Err(err),
+            }
+            return Ok(batch_results);
+        }
+        Err(err) => return Err(err),
+    };
+
+    let mut first_err = None;
+    for (index, result) in indices.into_iter().zip(results) {
+        let err = match result {
+            Ok(value) => {
+                batch_results.values.push((index, value));
+                continue;
+            }
+            Err(err) => err,
+        };
+        // Unrouted commands are only sent along with routed commands, and aren't redirected.
+        if route_for_command(commands[index]).is_some() {
+            match (err.kind(), err.redirect_node()) {
+                (ErrorKind::Moved, Some(_)) => {
+                    batch_results.moved.push(index);
+                    continue;
+                }
+                (ErrorKind::Ask, Some((address, _slot))) => {
+                    batch_results.asked.push((address.to_string(), index));
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        first_err.get_or_insert(err);
+    }
+    match first_err {
+        Some(err) => Err(err),
+        None => Ok(batch_results),
+    }
 }
 
 enum Response {
     Single(Value),
     Multiple(Vec<Value>),
+    // The replies of a batch of a multi-slot pipeline, which are sorted by the caller. A redirect of
+    // the whole batch is returned as its error.
+    Batch(RedisResult<Vec<RedisResult<Value>>>),
 }
 
 enum OperationTarget {
@@ -348,7 +1016,7 @@ enum OperationTarget {
     FanOut,
     NotFound,
 }
-type OperationResult = Result<Response, (OperationTarget, RedisError)>;
+type OperationResult = Result<(OperationTarget, Response), (OperationTarget, RedisError)>;
 
 impl From<ArcStr> for OperationTarget {
     fn from(address: ArcStr) -> Self {
@@ -390,7 +1058,7 @@ struct RequestInfo<C> {
 }
 
 impl<C> RequestInfo<C> {
-    fn set_redirect(&mut self, redirect: Option<Redirect>) {
+    fn set_redirect(&mut self, redirect: Option<Redirect>) -> RedisResult<()> {
         if let Some(redirect) = redirect {
             match &mut self.cmd {
                 CmdArg::Cmd { routing, .. } => match routing {
@@ -413,8 +1081,18 @@ impl<C> RequestInfo<C> {
                     };
                     *route = redirect;
                 }
+                // The batches of a multi-slot pipeline are redirected separately, so a redirect
+                // of the pipeline as a whole can't be followed.
+                CmdArg::MultiSlotPipeline { .. } => {
+                    return Err((
+                        ErrorKind::ClientError,
+                        "Multi-slot pipelines can't be redirected",
+                    )
+                        .into());
+                }
             }
         }
+        Ok(())
     }
 
     fn reset_redirect(&mut self) {
@@ -438,6 +1116,7 @@ impl<C> RequestInfo<C> {
                     *route = previous_routing;
                 }
             }
+            CmdArg::MultiSlotPipeline { .. } => {}
         }
     }
 }
@@ -461,6 +1140,30 @@ struct PendingRequest<C> {
     retry: u32,
     sender: oneshot::Sender<RedisResult<Response>>,
     info: RequestInfo<C>,
+    // Only set for the requests of the user, and not for the requests that they are split into.
+    recorder: Option<RequestRecorder>,
+}
+
+impl<C> PendingRequest<C> {
+    fn record_target(&mut self, target: &OperationTarget) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            match target {
+                OperationTarget::Node { address } => recorder.set_node(Some(address)),
+                OperationTarget::FanOut => recorder.set_node(None),
+                OperationTarget::NotFound => {}
+            }
+        }
+    }
+
+    fn respond(self, result: RedisResult<Response>) {
+        if let Some(mut recorder) = self.recorder {
+            recorder.retries = self.retry;
+            // Cluster connections only use database 0.
+            recorder.finish(0, &result);
+        }
+        // If `send` errors the receiver has dropped and thus does not care about the message
+        let _ = self.sender.send(result);
+    }
 }
 
 pin_project! {
@@ -515,12 +1218,14 @@ impl<C> Future for Request<C> {
             _ => panic!("Request future must be Some"),
         };
         match ready!(future.poll(cx)) {
-            Ok(item) => {
+            Ok((target, item)) => {
+                this.request.as_mut().unwrap().record_target(&target);
                 self.respond(Ok(item));
                 Next::Done.into()
             }
             Err((target, err)) => {
                 let request = this.request.as_mut().unwrap();
+                request.record_target(&target);
 
                 if request.retry >= this.retry_params.number_of_retries {
                     self.respond(Err(err));
@@ -562,18 +1267,30 @@ impl<C> Future for Request<C> {
                 match err.retry_method() {
                     crate::types::RetryMethod::AskRedirect => {
                         let mut request = this.request.take().unwrap();
-                        request.info.set_redirect(
+                        if let Some(recorder) = request.recorder.as_mut() {
+                            recorder.redirects += 1;
+                        }
+                        if let Err(err) = request.info.set_redirect(
                             err.redirect_node()
                                 .map(|(node, _slot)| Redirect::Ask(node.to_string())),
-                        );
+                        ) {
+                            request.respond(Err(err));
+                            return Next::Done.into();
+                        }
                         Next::Retry { request }.into()
                     }
                     crate::types::RetryMethod::MovedRedirect => {
                         let mut request = this.request.take().unwrap();
-                        request.info.set_redirect(
+                        if let Some(recorder) = request.recorder.as_mut() {
+                            recorder.redirects += 1;
+                        }
+                        if let Err(err) = request.info.set_redirect(
                             err.redirect_node()
                                 .map(|(node, _slot)| Redirect::Moved(node.to_string())),
-                        );
+                        ) {
+                            request.respond(Err(err));
+                            return Next::Done.into();
+                        }
                         Next::RefreshSlots {
                             request,
                             sleep_duration: None,
@@ -622,14 +1339,11 @@ impl<C> Future for Request<C> {
 
 impl<C> Request<C> {
     fn respond(self: Pin<&mut Self>, msg: RedisResult<Response>) {
-        // If `send` errors the receiver has dropped and thus does not care about the message
-        let _ = self
-            .project()
+        self.project()
             .request
             .take()
             .expect("Result should only be sent once")
-            .sender
-            .send(msg);
+            .respond(msg);
     }
 }
 
@@ -646,20 +1360,25 @@ where
     async fn new(
         initial_nodes: &[ConnectionInfo],
         cluster_params: ClusterParams,
+        shard_channels: ShardChannels,
+        topology_events: SharedTopologyEvents,
     ) -> RedisResult<Disposable<Self>> {
         let connections = Self::create_initial_connections(initial_nodes, &cluster_params).await?;
         let topology_checks_interval = cluster_params.topology_checks_interval;
+        let seed_rediscovery_interval = cluster_params.seed_rediscovery_interval;
         let inner = Arc::new(InnerCore {
             conn_lock: RwLock::new(ConnectionsContainer::new(
                 Default::default(),
                 connections,
-                cluster_params.read_from_replicas,
+                cluster_params.read_from_replicas.clone(),
                 0,
             )),
             cluster_params,
             pending_requests: Mutex::new(Vec::new()),
             slot_refresh_in_progress: AtomicBool::new(false),
             initial_nodes: initial_nodes.to_vec(),
+            shard_channels,
+            topology_events,
         });
         let shutdown_flag = Arc::new(AtomicBool::new(false));
         let connection = ClusterConnInner {
@@ -681,6 +1400,17 @@ where
             #[cfg(all(not(feature = "tokio-comp"), feature = "async-std-comp"))]
             AsyncStd::spawn(periodic_task);
         }
+        if let Some(duration) = seed_rediscovery_interval {
+            let periodic_task = ClusterConnInner::periodic_seed_rediscovery(
+                connection.inner.clone(),
+                duration,
+                connection.shutdown_flag.clone(),
+            );
+            #[cfg(feature = "tokio-comp")]
+            tokio::spawn(periodic_task);
+            #[cfg(all(not(feature = "tokio-comp"), feature = "async-std-comp"))]
+            AsyncStd::spawn(periodic_task);
+        }
 
         Ok(Disposable::new(connection))
     }
@@ -690,6 +1420,7 @@ where
     /// Returns a vector of tuples, each containing a node's address (including the hostname) and its corresponding SocketAddr if retrieved.
     pub(crate) async fn try_to_expand_initial_nodes(
         initial_nodes: &[ConnectionInfo],
+        params: &ClusterParams,
     ) -> Vec<(String, Option<SocketAddr>)> {
         stream::iter(initial_nodes)
             .fold(
@@ -709,7 +1440,8 @@ where
                             return acc;
                         }
                     };
-                    match get_socket_addrs(host, *port).await {
+                    let address = params.map_node_address(host, *port);
+                    match get_socket_addrs(&address.host, address.port).await {
                         Ok(socket_addrs) => {
                             for addr in socket_addrs {
                                 acc.push((info.addr.to_string(), Some(addr)));
@@ -731,7 +1463,7 @@ where
         params: &ClusterParams,
     ) -> RedisResult<ConnectionMap<C>> {
         let initial_nodes: Vec<(String, Option<SocketAddr>)> =
-            Self::try_to_expand_initial_nodes(initial_nodes).await;
+            Self::try_to_expand_initial_nodes(initial_nodes, params).await;
         let connections = stream::iter(initial_nodes.iter().cloned())
             .map(|(node_addr, socket_addr)| {
                 let params: ClusterParams = params.clone();
@@ -784,27 +1516,50 @@ where
     fn reconnect_to_initial_nodes(&mut self) -> impl Future<Output = ()> {
         let inner = self.inner.clone();
         async move {
-            let connection_map =
-                match Self::create_initial_connections(&inner.initial_nodes, &inner.cluster_params)
-                    .await
-                {
-                    Ok(map) => map,
-                    Err(err) => {
-                        warn!("Can't reconnect to initial nodes: `{err}`");
-                        return;
-                    }
-                };
-            let mut write_lock = inner.conn_lock.write().await;
-            *write_lock = ConnectionsContainer::new(
-                Default::default(),
-                connection_map,
-                inner.cluster_params.read_from_replicas,
-                0,
-            );
-            drop(write_lock);
-            if let Err(err) = Self::refresh_slots_with_retries(inner.clone()).await {
-                warn!("Can't refresh slots with initial nodes: `{err}`");
+            let _ = Self::reconnect_from_initial_nodes(inner).await;
+        }
+    }
+
+    /// Replaces all connections with connections to the initial nodes and refreshes the slots
+    /// through them. The hostnames of the initial nodes are resolved again, so that a cluster
+    /// replaced behind a stable DNS name can be found.
+    async fn reconnect_from_initial_nodes(inner: Core<C>) -> RedisResult<()> {
+        let connection_map =
+            match Self::create_initial_connections(&inner.initial_nodes, &inner.cluster_params)
+                .await
+            {
+                Ok(map) => map,
+                Err(err) => {
+                    warn!("Can't reconnect to initial nodes: `{err}`");
+                    return Err(err);
+                }
             };
+        let mut write_lock = inner.conn_lock.write().await;
+        *write_lock = ConnectionsContainer::new(
+            Default::default(),
+            connection_map,
+            inner.cluster_params.read_from_replicas.clone(),
+            0,
+        );
+        drop(write_lock);
+        let res = Self::refresh_slots_with_retries(inner).await;
+        if let Err(err) = &res {
+            warn!("Can't refresh slots with initial nodes: `{err}`");
+        }
+        res
+    }
+
+    /// Refreshes the slots through the known nodes, and through the initial nodes if none of the
+    /// known nodes returns the topology, e.g. because the whole cluster was replaced.
+    async fn refresh_slots_or_reconnect(inner: Core<C>) -> RedisResult<()> {
+        match Self::refresh_slots_with_retries(inner.clone()).await {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                warn!("Can't refresh slots with the known nodes: `{err}`");
+                Self::reconnect_from_initial_nodes(inner)
+                    .await
+                    .map_err(|_| err)
+            }
         }
     }
 
@@ -814,15 +1569,54 @@ where
         conn_type: RefreshConnectionType,
     ) {
         info!("Started refreshing connections to {:?}", addresses);
+        let refreshed_addresses = addresses.clone();
+        // Only the user connections are reported, management connections aren't used for requests.
+        let report_health = conn_type != RefreshConnectionType::OnlyManagementConnection;
         let mut connections_container = inner.conn_lock.write().await;
         let cluster_params = &inner.cluster_params;
+        let topology_events = &inner.topology_events;
         stream::iter(addresses.into_iter())
             .fold(
                 &mut *connections_container,
                 |connections_container, address| async move {
                     let node_option = connections_container.remove_node(&address);
-                    let node =
-                        get_or_create_conn(&address, node_option, cluster_params, conn_type).await;
+                    // Existing connections are only replaced if they fail their health check.
+                    let failed = match &node_option {
+                        Some(node) => {
+                            check_node_connections(node, cluster_params, conn_type, &address).await
+                        }
+                        None => Some(conn_type),
+                    };
+                    let node = match (node_option, failed) {
+                        (Some(node), None) => Ok(node),
+                        (node_option, failed) => {
+                            let failed = failed.unwrap_or(conn_type);
+                            if report_health
+                                && node_option.is_some()
+                                && failed != RefreshConnectionType::OnlyManagementConnection
+                            {
+                                topology_events
+                                    .lock()
+                                    .unwrap()
+                                    .set_connected(&address, false);
+                            }
+                            connect_and_check(
+                                &address,
+                                cluster_params.clone(),
+                                None,
+                                failed,
+                                node_option,
+                            )
+                            .await
+                            .get_node()
+                        }
+                    };
+                    if report_health {
+                        topology_events
+                            .lock()
+                            .unwrap()
+                            .set_connected(&address, node.is_ok());
+                    }
                     if let Ok(node) = node {
                         connections_container.replace_or_add_connection_for_address(address, node);
                     }
@@ -830,7 +1624,66 @@ where
                 },
             )
             .await;
+        drop(connections_container);
         info!("refresh connections completed");
+        if conn_type != RefreshConnectionType::OnlyManagementConnection {
+            Self::resubscribe_shard_channels(&inner, Some(&refreshed_addresses)).await;
+        }
+    }
+
+    /// Sends SSUBSCRIBE for the tracked sharded channels whose slots are now owned by other nodes
+    /// than the ones they were subscribed on. If `reconnected` is given, the channels owned by
+    /// these addresses are resubscribed as well, since the subscriptions were lost along with the
+    /// old connections.
+    async fn resubscribe_shard_channels(inner: &Core<C>, reconnected: Option<&[ArcStr]>) {
+        let channels: Vec<(Vec<u8>, Option<ArcStr>)> = inner
+            .shard_channels
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(channel, address)| (channel.clone(), address.clone()))
+            .collect();
+        if channels.is_empty() {
+            return;
+        }
+        let targets: Vec<(Vec<u8>, ArcStr, ConnectionFuture<C>)> = {
+            let read_guard = inner.conn_lock.read().await;
+            channels
+                .into_iter()
+                .filter_map(|(channel, subscribed_address)| {
+                    let (address, conn) =
+                        read_guard.connection_for_route(&shard_channel_route(&channel))?;
+                    let moved = subscribed_address.as_ref() != Some(&address);
+                    let reconnected =
+                        reconnected.map_or(false, |reconnected| reconnected.contains(&address));
+                    if moved || reconnected {
+                        Some((channel, address, conn))
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        };
+        for (channel, address, conn) in targets {
+            let mut conn = conn.await;
+            let mut cmd = crate::cmd("SSUBSCRIBE");
+            cmd.arg(&channel);
+            match cmd.query_async::<_, ()>(&mut conn).await {
+                Ok(()) => {
+                    if let Some(subscribed_address) =
+                        inner.shard_channels.lock().unwrap().get_mut(&channel)
+                    {
+                        *subscribed_address = Some(address);
+                    }
+                }
+                Err(err) => {
+                    warn!(
+                        "Failed to resubscribe to sharded channel `{}` on {address}: {err}",
+                        String::from_utf8_lossy(&channel)
+                    );
+                }
+            }
+        }
     }
 
     async fn aggregate_results(
@@ -840,7 +1693,7 @@ where
     ) -> RedisResult<Value> {
         let extract_result = |response| match response {
             Response::Single(value) => value,
-            Response::Multiple(_) => unreachable!(),
+            Response::Multiple(_) | Response::Batch(_) => unreachable!(),
         };
 
         let convert_result = |res: Result<RedisResult<Response>, _>| {
@@ -960,6 +1813,45 @@ where
         }
     }
 
+    async fn periodic_seed_rediscovery(
+        inner: Arc<InnerCore<C>>,
+        interval_duration: Duration,
+        shutdown_flag: Arc<AtomicBool>,
+    ) {
+        let mut seed_addresses = Self::resolve_initial_nodes(&inner).await;
+        loop {
+            if shutdown_flag.load(Ordering::Relaxed) {
+                return;
+            }
+            let _ = boxed_sleep(interval_duration).await;
+
+            let resolved_addresses = Self::resolve_initial_nodes(&inner).await;
+            if resolved_addresses.is_empty() || resolved_addresses == seed_addresses {
+                continue;
+            }
+            info!(
+                "The addresses of the initial nodes changed to {:?}, reconnecting through them",
+                resolved_addresses
+            );
+            // On failure the previous addresses are kept, so that the next check tries again.
+            if Self::reconnect_from_initial_nodes(inner.clone())
+                .await
+                .is_ok()
+            {
+                seed_addresses = resolved_addresses;
+            }
+        }
+    }
+
+    /// Returns the socket addresses that the hostnames of the initial nodes currently resolve to.
+    async fn resolve_initial_nodes(inner: &InnerCore<C>) -> HashSet<SocketAddr> {
+        Self::try_to_expand_initial_nodes(&inner.initial_nodes, &inner.cluster_params)
+            .await
+            .into_iter()
+            .filter_map(|(_, socket_addr)| socket_addr)
+            .collect()
+    }
+
     /// Queries log2n nodes (where n represents the number of cluster nodes) to determine whether their
     /// topology view differs from the one currently stored in the connection manager.
     /// Returns true if change was detected, otherwise false.
@@ -1026,6 +1918,7 @@ where
         .0?;
         info!("Found slot map: {new_slots}");
         let connections = &*read_guard;
+        let cluster_params = &inner.cluster_params;
         // Create a new connection vector of the found nodes
         let mut nodes = new_slots.values().flatten().collect::<Vec<_>>();
         nodes.sort_unstable();
@@ -1048,7 +1941,8 @@ where
                             return addrs_and_conns;
                         }
                     };
-                    let conn = get_socket_addrs(host, port)
+                    let address = cluster_params.map_node_address(host, port);
+                    let conn = get_socket_addrs(&address.host, address.port)
                         .await
                         .ok()
                         .map(|mut socket_addresses| {
@@ -1085,11 +1979,15 @@ where
         // Replace the current slot map and connection vector with the new ones
         let mut write_guard = inner.conn_lock.write().await;
         *write_guard = ConnectionsContainer::new(
-            new_slots,
+            new_slots.with_nodes_metadata(inner.cluster_params.nodes_metadata.clone()),
             new_connections,
-            inner.cluster_params.read_from_replicas,
+            inner.cluster_params.read_from_replicas.clone(),
             topology_hash,
         );
+        let topology = topology_snapshot(&write_guard);
+        drop(write_guard);
+        inner.topology_events.lock().unwrap().update(topology);
+        Self::resubscribe_shard_channels(&inner, None).await;
         Ok(())
     }
 
@@ -1135,6 +2033,7 @@ where
                             Some(PendingRequest {
                                 retry: 0,
                                 sender,
+                                recorder: None,
                                 info: RequestInfo {
                                     cmd: CmdArg::Cmd {
                                         cmd,
@@ -1193,10 +2092,213 @@ where
 
         Self::aggregate_results(receivers, routing, response_policy)
             .await
-            .map(Response::Single)
+            .map(|value| (OperationTarget::FanOut, Response::Single(value)))
             .map_err(|err| (OperationTarget::FanOut, err))
     }
 
+    /// Splits the pipeline into a sub-pipeline per node, according to the slots of the commands, and
+    /// sends the sub-pipelines concurrently. The results are returned in the original command order.
+    async fn execute_pipeline_on_multiple_nodes(
+        pipeline: Arc<crate::Pipeline>,
+        offset: usize,
+        count: usize,
+        core: Core<C>,
+    ) -> OperationResult {
+        trace!("execute_pipeline_on_multiple_nodes");
+        let commands: Vec<&Cmd> = pipeline.cmd_iter().collect();
+        let routes = routes_for_pipeline_commands(&pipeline).ok_or_else(|| {
+            (
+                OperationTarget::FanOut,
+                RedisError::from((
+                    ErrorKind::ClientError,
+                    "Multi-slot pipeline doesn't contain any routable command",
+                )),
+            )
+        })?;
+
+        let connections_container = core.conn_lock.read().await;
+        if connections_container.is_empty() {
+            return OperationResult::Err((
+                OperationTarget::FanOut,
+                (
+                    ErrorKind::ClusterConnectionNotFound,
+                    "No connections found for multi-slot pipeline",
+                )
+                    .into(),
+            ));
+        }
+        let mut node_batches: Vec<(ArcStr, Route, Vec<usize>)> = Vec::new();
+        let mut to_retry = Vec::new();
+        // Grouping by slot sends all the commands of a slot to its primary if any of them requires
+        // the primary, so that reads from replicas don't miss the writes of the same pipeline. The
+        // slots are then merged into a single batch per node.
+        for (route, indices) in group_indices_by_slot(&routes, (0..commands.len()).collect()) {
+            match connections_container.connection_for_route(&route) {
+                Some((address, _conn)) => {
+                    match node_batches
+                        .iter_mut()
+                        .find(|(batch_address, _, _)| *batch_address == address)
+                    {
+                        Some((_, _, batch_indices)) => batch_indices.extend(indices),
+                        None => node_batches.push((address, route, indices)),
+                    }
+                }
+                // The commands weren't sent, so they can be retried.
+                None => to_retry.extend(indices),
+            }
+        }
+        drop(connections_container);
+
+        // The batches are sent through the regular request flow, so that a batch that fails due to
+        // a lost connection is retried after reconnecting. A batch is routed by the route of its
+        // first slot, and the commands of slots that moved meanwhile are redirected below.
+        let batch_receivers = Self::send_pipeline_requests(
+            &core,
+            node_batches
+                .into_iter()
+                .map(|(address, route, mut indices)| {
+                    indices.sort_unstable();
+                    trace!("Sending a pipeline batch to `{address}`");
+                    let request = CmdArg::Pipeline {
+                        pipeline: Arc::new(sub_pipeline(&commands, &indices)),
+                        offset: 0,
+                        count: indices.len(),
+                        route: InternalSingleNodeRouting::SpecificNode(route),
+                        batch: true,
+                    };
+                    (indices, false, request)
+                }),
+        );
+
+        let mut values = vec![Value::Nil; commands.len()];
+        let mut to_ask: Vec<(String, Vec<usize>)> = Vec::new();
+        for (indices, _asking, receiver) in batch_receivers {
+            let result = match Self::receive_pipeline_response(receiver).await? {
+                Response::Batch(result) => result,
+                Response::Single(_) | Response::Multiple(_) => unreachable!(),
+            };
+            let results = split_batch_results(&commands, &routes, indices, result)
+                .map_err(|err| (OperationTarget::FanOut, err))?;
+            if !results.moved.is_empty() || !results.asked.is_empty() {
+                trace!("Pipeline batch was redirected, retrying by slot");
+            }
+            for (index, value) in results.values {
+                values[index] = value;
+            }
+            to_retry.extend(results.moved);
+            for (ask_address, index) in results.asked {
+                match to_ask
+                    .iter_mut()
+                    .find(|(batch_address, _)| *batch_address == ask_address)
+                {
+                    Some((_, batch_indices)) => batch_indices.push(index),
+                    None => to_ask.push((ask_address, vec![index])),
+                }
+            }
+        }
+
+        if !to_retry.is_empty() || !to_ask.is_empty() {
+            // Batches that were moved, or that had no known node, are resent per slot, so that
+            // redirects and reconnects are handled separately for every slot. Commands that were
+            // asked to go to a migrating slot's new node are sent there, each one preceded by
+            // `ASKING`, whose replies are dropped.
+            to_retry.sort_unstable();
+            let moved_requests =
+                group_indices_by_slot(&routes, to_retry)
+                    .into_iter()
+                    .map(|(route, indices)| {
+                        let request = CmdArg::Pipeline {
+                            pipeline: Arc::new(sub_pipeline(&commands, &indices)),
+                            offset: 0,
+                            count: indices.len(),
+                            route: InternalSingleNodeRouting::SpecificNode(route),
+                            batch: false,
+                        };
+                        (indices, false, request)
+                    });
+            let asked_requests = to_ask.into_iter().map(|(address, indices)| {
+                let mut pipeline = crate::Pipeline::with_capacity(indices.len() * 2);
+                for index in &indices {
+                    pipeline.add_command(crate::cmd::cmd("ASKING"));
+                    pipeline.add_command(commands[*index].clone());
+                }
+                let request = CmdArg::Pipeline {
+                    pipeline: Arc::new(pipeline),
+                    offset: 0,
+                    count: indices.len() * 2,
+                    route: InternalSingleNodeRouting::Redirect {
+                        redirect: Redirect::Ask(address),
+                        previous_routing: Box::new(InternalSingleNodeRouting::SpecificNode(
+                            routes[indices[0]],
+                        )),
+                    },
+                    batch: false,
+                };
+                (indices, true, request)
+            });
+            let receivers =
+                Self::send_pipeline_requests(&core, moved_requests.chain(asked_requests));
+
+            for (indices, asking, receiver) in receivers {
+                let batch_values = match Self::receive_pipeline_response(receiver).await? {
+                    Response::Multiple(batch_values) => batch_values,
+                    Response::Single(_) | Response::Batch(_) => unreachable!(),
+                };
+                // Drop the replies of the `ASKING` commands.
+                let step = if asking { 2 } else { 1 };
+                let batch_values = batch_values.into_iter().skip(step - 1).step_by(step);
+                for (index, value) in indices.into_iter().zip(batch_values) {
+                    values[index] = value;
+                }
+            }
+        }
+
+        Ok((
+            OperationTarget::FanOut,
+            Response::Multiple(values.into_iter().skip(offset).take(count).collect()),
+        ))
+    }
+
+    /// Adds the requests of the batches of a multi-slot pipeline to the pending requests, and
+    /// returns the receivers of their responses along with the indices of their commands.
+    #[allow(clippy::type_complexity)]
+    fn send_pipeline_requests(
+        core: &Core<C>,
+        requests: impl Iterator<Item = (Vec<usize>, bool, CmdArg<C>)>,
+    ) -> Vec<(Vec<usize>, bool, Receiver<RedisResult<Response>>)> {
+        let (receivers, requests): (Vec<_>, Vec<_>) = requests
+            .map(|(indices, asking, cmd)| {
+                let (sender, receiver) = oneshot::channel();
+                let request = PendingRequest {
+                    retry: 0,
+                    sender,
+                    recorder: None,
+                    info: RequestInfo { cmd },
+                };
+                ((indices, asking, receiver), request)
+            })
+            .unzip();
+        core.pending_requests.lock().unwrap().extend(requests);
+        receivers
+    }
+
+    async fn receive_pipeline_response(
+        receiver: Receiver<RedisResult<Response>>,
+    ) -> Result<Response, (OperationTarget, RedisError)> {
+        match receiver.await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(err)) => Err((OperationTarget::FanOut, err)),
+            Err(_) => Err((
+                OperationTarget::FanOut,
+                (
+                    ErrorKind::ResponseError,
+                    "request wasn't handled due to internal failure",
+                )
+                    .into(),
+            )),
+        }
+    }
+
     async fn try_cmd_request(
         cmd: Arc<Cmd>,
         routing: InternalRoutingInfo<C>,
@@ -1223,24 +2325,39 @@ where
         let (address, mut conn) = Self::get_connection(routing, core)
             .await
             .map_err(|err| (OperationTarget::NotFound, err))?;
-        conn.req_packed_command(&cmd)
-            .await
-            .map(Response::Single)
-            .map_err(|err| (address.into(), err))
+        match conn.req_packed_command(&cmd).await {
+            Ok(value) => Ok((address.into(), Response::Single(value))),
+            Err(err) => Err((address.into(), err)),
+        }
     }
 
     async fn try_pipeline_request(
         pipeline: Arc<crate::Pipeline>,
         offset: usize,
         count: usize,
+        batch: bool,
         conn: impl Future<Output = RedisResult<(ArcStr, C)>>,
     ) -> OperationResult {
         trace!("try_pipeline_request");
         let (address, mut conn) = conn.await.map_err(|err| (OperationTarget::NotFound, err))?;
-        conn.req_packed_commands(&pipeline, offset, count)
-            .await
-            .map(Response::Multiple)
-            .map_err(|err| (OperationTarget::Node { address }, err))
+        if batch {
+            return match conn
+                .req_packed_commands_with_errors(&pipeline, offset, count)
+                .await
+            {
+                Ok(values) => Ok((address.into(), Response::Batch(Ok(values)))),
+                // The commands of a batch might belong to different slots, so the caller decides
+                // which of them follow the redirect.
+                Err(err) if err.redirect_node().is_some() => {
+                    Ok((address.into(), Response::Batch(Err(err))))
+                }
+                Err(err) => Err((address.into(), err)),
+            };
+        }
+        match conn.req_packed_commands(&pipeline, offset, count).await {
+            Ok(values) => Ok((address.into(), Response::Multiple(values))),
+            Err(err) => Err((address.into(), err)),
+        }
     }
 
     async fn try_request(info: RequestInfo<C>, core: Core<C>) -> OperationResult {
@@ -1251,15 +2368,22 @@ where
                 offset,
                 count,
                 route,
+                batch,
             } => {
                 Self::try_pipeline_request(
                     pipeline,
                     offset,
                     count,
+                    batch,
                     Self::get_connection(route, core),
                 )
                 .await
             }
+            CmdArg::MultiSlotPipeline {
+                pipeline,
+                offset,
+                count,
+            } => Self::execute_pipeline_on_multiple_nodes(pipeline, offset, count, core).await,
         }
     }
 
@@ -1530,7 +2654,7 @@ where
                     .as_mut()
                     .respond(Err(self.refresh_error.take().unwrap()));
             } else if let Some(request) = self.inner.pending_requests.lock().unwrap().pop() {
-                let _ = request.sender.send(Err(self.refresh_error.take().unwrap()));
+                request.respond(Err(self.refresh_error.take().unwrap()));
             }
         }
     }
@@ -1578,6 +2702,20 @@ where
     fn start_send(self: Pin<&mut Self>, msg: Message<C>) -> Result<(), Self::Error> {
         let Message { cmd, sender } = msg;
 
+        let recorder =
+            self.inner
+                .cluster_params
+                .instrumenter
+                .as_ref()
+                .map(|instrumenter| match &cmd {
+                    CmdArg::Cmd { cmd, .. } => instrumenter.command(cmd, cmd_len(cmd)),
+                    CmdArg::Pipeline { pipeline, .. }
+                    | CmdArg::MultiSlotPipeline { pipeline, .. } => instrumenter.pipeline(
+                        pipeline.is_atomic(),
+                        pipeline.cmd_iter().count(),
+                        pipeline.packed_len(),
+                    ),
+                });
         let info = RequestInfo { cmd };
 
         self.inner
@@ -1588,6 +2726,7 @@ where
                 retry: 0,
                 sender,
                 info,
+                recorder,
             });
         Ok(())
     }
@@ -1617,7 +2756,7 @@ where
                 PollFlushAction::None => return Poll::Ready(Ok(())),
                 PollFlushAction::RebuildSlots => {
                     self.state = ConnectionState::Recover(RecoverFuture::RecoverSlots(Box::pin(
-                        ClusterConnInner::refresh_slots_with_retries(self.inner.clone()),
+                        ClusterConnInner::refresh_slots_or_reconnect(self.inner.clone()),
                     )));
                 }
                 PollFlushAction::Reconnect(addresses) => {
@@ -1678,7 +2817,12 @@ where
     let topology_join_results =
         futures::future::join_all(requested_nodes.map(|(addr, conn)| async move {
             let mut conn: C = conn.await;
-            let res = conn.req_packed_command(&slot_cmd()).await;
+            let res = match conn.req_packed_command(&shards_cmd()).await {
+                Err(err) if should_fall_back_to_slots(&err) => {
+                    conn.req_packed_command(&slot_cmd()).await
+                }
+                res => res,
+            };
             (addr, res)
         }))
         .await;
@@ -1700,7 +2844,7 @@ where
             curr_retry,
             inner.cluster_params.tls,
             num_of_nodes_to_query,
-            inner.cluster_params.read_from_replicas,
+            inner.cluster_params.read_from_replicas.clone(),
         ),
         failed_addresses,
     )
@@ -1724,7 +2868,16 @@ where
         count: usize,
     ) -> RedisFuture<'a, Vec<Value>> {
         async move {
-            let route = route_for_pipeline(pipeline)?;
+            let route = match route_for_pipeline(pipeline) {
+                Ok(route) => route,
+                // Non-atomic pipelines can be split between the nodes that own the commands' slots.
+                Err(err) if err.kind() == ErrorKind::CrossSlot && !pipeline.is_atomic() => {
+                    return self
+                        .route_multi_slot_pipeline(pipeline, offset, count)
+                        .await;
+                }
+                Err(err) => return Err(err),
+            };
             self.route_pipeline(pipeline, offset, count, route.into())
                 .await
         }
@@ -1749,6 +2902,16 @@ pub trait Connect: Sized {
     ) -> RedisFuture<'a, (Self, Option<IpAddr>)>
     where
         T: IntoConnectionInfo + Send + 'a;
+
+    /// Sets the [`PushManager`] that receives the push messages of the connection.
+    /// Connections that don't support push messages can ignore this.
+    fn set_push_manager(&mut self, _push_manager: PushManager) {}
+
+    /// Makes the connection send `ASKING` before its commands, so that transactions can follow
+    /// `ASK` redirects to the node that imports their slot. Transactions on connections that
+    /// ignore this are retried until the migration of the slot completes or the retries run out.
+    #[doc(hidden)]
+    fn set_asking(&mut self, _asking: bool) {}
 }
 
 impl Connect for MultiplexedConnection {
@@ -1790,16 +2953,31 @@ impl Connect for MultiplexedConnection {
         }
         .boxed()
     }
+
+    fn set_push_manager(&mut self, push_manager: PushManager) {
+        self.replace_push_manager(push_manager);
+    }
+
+    fn set_asking(&mut self, asking: bool) {
+        MultiplexedConnection::set_asking(self, asking);
+    }
 }
 
 #[cfg(test)]
 mod pipeline_routing_tests {
-    use super::route_for_pipeline;
+    use super::{
+        group_indices_by_slot, route_for_pipeline, routes_for_pipeline_commands,
+        split_batch_results,
+    };
     use crate::{
         cluster_routing::{Route, SlotAddr},
-        cmd,
+        cmd, ErrorKind, RedisError, Value,
     };
 
+    fn redirect_error(kind: ErrorKind, code: &'static str, detail: &str) -> RedisError {
+        RedisError::from((kind, code, detail.to_string()))
+    }
+
     #[test]
     fn test_first_route_is_found() {
         let mut pipeline = crate::Pipeline::new();
@@ -1858,6 +3036,44 @@ mod pipeline_routing_tests {
         );
     }
 
+    #[test]
+    fn test_unrouted_commands_use_first_specific_route() {
+        let mut pipeline = crate::Pipeline::new();
+
+        pipeline
+            .add_command(cmd("FLUSHALL")) // route to all masters
+            .get("foo") // route to replica of slot 12182
+            .set("baz", "bar") // route to primary of slot 4813
+            .cmd("CONFIG").arg("GET").arg("timeout"); // unkeyed command
+
+        assert_eq!(
+            routes_for_pipeline_commands(&pipeline),
+            Some(vec![
+                Route::new(12182, SlotAddr::ReplicaOptional),
+                Route::new(12182, SlotAddr::ReplicaOptional),
+                Route::new(4813, SlotAddr::Master),
+                Route::new(12182, SlotAddr::ReplicaOptional),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_group_indices_by_slot_prefers_primary() {
+        let routes = vec![
+            Route::new(12182, SlotAddr::ReplicaOptional),
+            Route::new(4813, SlotAddr::ReplicaOptional),
+            Route::new(12182, SlotAddr::Master),
+        ];
+
+        assert_eq!(
+            group_indices_by_slot(&routes, vec![0, 1, 2]),
+            vec![
+                (Route::new(12182, SlotAddr::Master), vec![0, 2]),
+                (Route::new(4813, SlotAddr::ReplicaOptional), vec![1]),
+            ]
+        );
+    }
+
     #[test]
     fn unkeyed_commands_dont_affect_route() {
         let mut pipeline = crate::Pipeline::new();
@@ -1874,4 +3090,81 @@ mod pipeline_routing_tests {
             Ok(Some(Route::new(12182, SlotAddr::Master)))
         );
     }
+
+    #[test]
+    fn test_split_batch_results_redirects_only_the_redirected_commands() {
+        let mut pipeline = crate::Pipeline::new();
+        pipeline
+            .get("baz") // slot 4813
+            .get("bar") // slot 5061
+            .set("baz", "value"); // slot 4813
+        let commands: Vec<_> = pipeline.cmd_iter().collect();
+        let routes = routes_for_pipeline_commands(&pipeline).unwrap();
+
+        let results = split_batch_results(
+            &commands,
+            &routes,
+            vec![0, 1, 2],
+            Ok(vec![
+                Err(redirect_error(ErrorKind::Ask, "ASK", "4813 node:6380")),
+                Ok(Value::Nil),
+                Err(redirect_error(ErrorKind::Moved, "MOVED", "4813 node:6381")),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(results.values, vec![(1, Value::Nil)]);
+        assert_eq!(results.asked, vec![("node:6380".to_string(), 0)]);
+        assert_eq!(results.moved, vec![2]);
+    }
+
+    #[test]
+    fn test_split_batch_results_redirects_a_failed_batch_of_a_single_slot() {
+        let mut pipeline = crate::Pipeline::new();
+        pipeline.get("baz").get("bar").get("{baz}2");
+        let commands: Vec<_> = pipeline.cmd_iter().collect();
+        let routes = routes_for_pipeline_commands(&pipeline).unwrap();
+
+        let results = split_batch_results(
+            &commands,
+            &routes,
+            vec![0, 2],
+            Err(redirect_error(ErrorKind::Ask, "ASK", "4813 node:6380")),
+        )
+        .unwrap();
+        assert_eq!(
+            results.asked,
+            vec![("node:6380".to_string(), 0), ("node:6380".to_string(), 2)]
+        );
+
+        // The commands of the other slot might have run, so the batch can't be resent.
+        let err = split_batch_results(
+            &commands,
+            &routes,
+            vec![0, 1, 2],
+            Err(redirect_error(ErrorKind::Moved, "MOVED", "4813 node:6380")),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Moved);
+    }
+
+    #[test]
+    fn test_split_batch_results_fails_on_other_errors() {
+        let mut pipeline = crate::Pipeline::new();
+        pipeline.get("baz").incr("bar", 1);
+        let commands: Vec<_> = pipeline.cmd_iter().collect();
+        let routes = routes_for_pipeline_commands(&pipeline).unwrap();
+
+        let err = split_batch_results(
+            &commands,
+            &routes,
+            vec![0, 1],
+            Ok(vec![
+                Err(redirect_error(ErrorKind::Moved, "MOVED", "4813 node:6380")),
+                Err(RedisError::from((ErrorKind::ResponseError, "WRONGTYPE"))),
+            ]),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ResponseError);
+    }
 }
@@ -1,8 +1,10 @@
-use crate::cluster_slotmap::ReadFromReplicaStrategy;
+use crate::cluster_slotmap::{NodesMetadata, ReadFromReplicaStrategy};
 use crate::connection::{ConnectionAddr, ConnectionInfo, IntoConnectionInfo};
+use crate::instrumentation::{Instrumentation, Instrumenter};
 use crate::types::{ErrorKind, ProtocolVersion, RedisError, RedisResult};
 use crate::{cluster, cluster::TlsMode};
 use rand::Rng;
+use std::sync::Arc;
 use std::time::Duration;
 
 #[cfg(feature = "tls-rustls")]
@@ -31,11 +33,48 @@ struct BuilderParams {
     retries_configuration: RetryParams,
     connection_timeout: Option<Duration>,
     topology_checks_interval: Option<Duration>,
+    #[cfg(feature = "cluster-async")]
+    seed_rediscovery_interval: Option<Duration>,
     client_name: Option<String>,
     response_timeout: Option<Duration>,
     protocol: ProtocolVersion,
+    instrumentation: Option<Arc<dyn Instrumentation>>,
+    address_mapper: Option<NodeAddressMapper>,
+}
+
+/// The address the client connects to for a cluster node.
+///
+/// Returned by the mapper set with [`ClusterClientBuilder::node_address_mapper`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NodeAddress {
+    /// The host to connect to.
+    pub host: String,
+    /// The port to connect to.
+    pub port: u16,
+    /// The name used for SNI and certificate verification on TLS connections. When `None`, the
+    /// mapped host is used.
+    pub tls_server_name: Option<String>,
+}
+
+impl NodeAddress {
+    /// Creates an address with the given host and port, and no TLS server name override.
+    pub fn new(host: impl Into<String>, port: u16) -> Self {
+        Self {
+            host: host.into(),
+            port,
+            tls_server_name: None,
+        }
+    }
+
+    /// Sets the name used for SNI and certificate verification on TLS connections.
+    pub fn with_tls_server_name(mut self, tls_server_name: impl Into<String>) -> Self {
+        self.tls_server_name = Some(tls_server_name.into());
+        self
+    }
 }
 
+pub(crate) type NodeAddressMapper = Arc<dyn Fn(&str, u16) -> Option<NodeAddress> + Send + Sync>;
+
 #[derive(Clone)]
 pub(crate) struct RetryParams {
     pub(crate) number_of_retries: u32,
@@ -86,11 +125,25 @@ pub struct ClusterParams {
     pub(crate) tls: Option<TlsMode>,
     pub(crate) retry_params: RetryParams,
     pub(crate) topology_checks_interval: Option<Duration>,
+    #[cfg(feature = "cluster-async")]
+    pub(crate) seed_rediscovery_interval: Option<Duration>,
     pub(crate) tls_params: Option<TlsConnParams>,
     pub(crate) client_name: Option<String>,
     pub(crate) connection_timeout: Duration,
     pub(crate) response_timeout: Duration,
     pub(crate) protocol: ProtocolVersion,
+    /// The metadata of the nodes, which is collected when connecting to them. Shared between
+    /// clones of the parameters.
+    pub(crate) nodes_metadata: NodesMetadata,
+    /// Reports the requests of the cluster connections. The connections to the nodes aren't
+    /// instrumented separately.
+    pub(crate) instrumenter: Option<Instrumenter>,
+    /// Rewrites the node addresses before connecting to them.
+    pub(crate) address_mapper: Option<NodeAddressMapper>,
+    /// Receives the push messages of the user connections. Replaced by each async connection when
+    /// it's created, so that connections of the same client don't share their subscribers.
+    #[cfg(feature = "cluster-async")]
+    pub(crate) push_manager: crate::PushManager,
 }
 
 impl ClusterParams {
@@ -113,12 +166,28 @@ impl ClusterParams {
             retry_params: value.retries_configuration,
             connection_timeout: value.connection_timeout.unwrap_or(Duration::MAX),
             topology_checks_interval: value.topology_checks_interval,
+            #[cfg(feature = "cluster-async")]
+            seed_rediscovery_interval: value.seed_rediscovery_interval,
             tls_params,
             client_name: value.client_name,
             response_timeout: value.response_timeout.unwrap_or(Duration::MAX),
             protocol: value.protocol,
+            nodes_metadata: Default::default(),
+            instrumenter: value.instrumentation.map(Instrumenter::new),
+            address_mapper: value.address_mapper,
+            #[cfg(feature = "cluster-async")]
+            push_manager: Default::default(),
         })
     }
+
+    /// Returns the address to connect to for the node at `host:port`, after applying the address
+    /// mapper.
+    pub(crate) fn map_node_address(&self, host: &str, port: u16) -> NodeAddress {
+        self.address_mapper
+            .as_ref()
+            .and_then(|mapper| mapper(host, port))
+            .unwrap_or_else(|| NodeAddress::new(host, port))
+    }
 }
 
 /// Used to configure and build a [`ClusterClient`].
@@ -320,6 +389,61 @@ impl ClusterClientBuilder {
         self
     }
 
+    /// Sets the strategy that chooses the nodes that serve reads for all new connections (default
+    /// is [`ReadFromReplicaStrategy::AlwaysFromPrimary`]).
+    ///
+    /// Write queries always go to the primary nodes.
+    pub fn read_from_replica_strategy(
+        mut self,
+        strategy: ReadFromReplicaStrategy,
+    ) -> ClusterClientBuilder {
+        self.builder_params.read_from_replicas = strategy;
+        self
+    }
+
+    /// Sets an instrumentation that is called after every request of the cluster connections.
+    /// A single event is reported for every request, after all of its retries and redirects.
+    ///
+    /// See the [`instrumentation`](crate::instrumentation) module.
+    pub fn instrumentation(
+        mut self,
+        instrumentation: Arc<dyn Instrumentation>,
+    ) -> ClusterClientBuilder {
+        self.builder_params.instrumentation = Some(instrumentation);
+        self
+    }
+
+    /// Sets a mapper that rewrites node addresses before the client connects to them.
+    ///
+    /// Cluster nodes behind NAT, or running in containers, often announce addresses that the
+    /// client can't reach. The mapper is called with the host and port of every node the client
+    /// connects to, whether it comes from the initial nodes, the cluster topology or a `MOVED`/`ASK`
+    /// redirect, and returns the address to connect to instead, or `None` to keep the original one.
+    /// The returned address can also override the name used for SNI and certificate verification on
+    /// TLS connections.
+    ///
+    /// Nodes are still identified by the addresses the cluster announces, so routing and redirects
+    /// are unaffected by the mapping.
+    ///
+    /// ```rust,no_run
+    /// use redis::cluster::{ClusterClientBuilder, NodeAddress};
+    ///
+    /// let client = ClusterClientBuilder::new(vec!["redis://127.0.0.1:7000/"])
+    ///     .node_address_mapper(|host, port| {
+    ///         host.starts_with("10.0.")
+    ///             .then(|| NodeAddress::new("127.0.0.1", port))
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn node_address_mapper(
+        mut self,
+        mapper: impl Fn(&str, u16) -> Option<NodeAddress> + Send + Sync + 'static,
+    ) -> ClusterClientBuilder {
+        self.builder_params.address_mapper = Some(Arc::new(mapper));
+        self
+    }
+
     /// Enables periodic topology checks for this client.
     ///
     /// If enabled, periodic topology checks will be executed at the configured intervals to examine whether there
@@ -331,6 +455,22 @@ impl ClusterClientBuilder {
         self
     }
 
+    /// Enables periodic rediscovery of the cluster through the initial nodes.
+    ///
+    /// If enabled, the hostnames of the initial nodes are resolved again at the configured
+    /// intervals. When they resolve to a different set of addresses, e.g. because the whole cluster
+    /// was replaced behind a stable DNS name, the connections are recreated through the initial
+    /// nodes and the slots are refreshed from them.
+    ///
+    /// Regardless of this setting, the initial nodes are resolved again whenever none of the known
+    /// nodes can provide the cluster's topology. The periodic rediscovery only runs on async
+    /// cluster connections.
+    #[cfg(feature = "cluster-async")]
+    pub fn periodic_seed_rediscovery(mut self, interval: Duration) -> ClusterClientBuilder {
+        self.builder_params.seed_rediscovery_interval = Some(interval);
+        self
+    }
+
     /// Enables timing out on slow connection time.
     ///
     /// If enabled, the cluster will only wait the given time on each connection attempt to each node.
@@ -602,9 +602,21 @@ pub fn is_readonly(routable: &impl Routable) -> bool {
 pub fn is_readonly_cmd(cmd: &[u8]) -> bool {
     matches!(
         cmd,
-        b"BITCOUNT"
+        b"BF.CARD"
+            | b"BF.EXISTS"
+            | b"BF.INFO"
+            | b"BF.MEXISTS"
+            | b"BF.SCANDUMP"
+            | b"BITCOUNT"
             | b"BITFIELD_RO"
             | b"BITPOS"
+            | b"CF.COUNT"
+            | b"CF.EXISTS"
+            | b"CF.INFO"
+            | b"CF.MEXISTS"
+            | b"CF.SCANDUMP"
+            | b"CMS.INFO"
+            | b"CMS.QUERY"
             | b"DBSIZE"
             | b"DUMP"
             | b"EVALSHA_RO"
@@ -662,6 +674,20 @@ pub fn is_readonly_cmd(cmd: &[u8]) -> bool {
             | b"STRLEN"
             | b"SUBSTR"
             | b"SUNION"
+            | b"TDIGEST.BYRANK"
+            | b"TDIGEST.BYREVRANK"
+            | b"TDIGEST.CDF"
+            | b"TDIGEST.INFO"
+            | b"TDIGEST.MAX"
+            | b"TDIGEST.MIN"
+            | b"TDIGEST.QUANTILE"
+            | b"TDIGEST.RANK"
+            | b"TDIGEST.REVRANK"
+            | b"TDIGEST.TRIMMED_MEAN"
+            | b"TOPK.COUNT"
+            | b"TOPK.INFO"
+            | b"TOPK.LIST"
+            | b"TOPK.QUERY"
             | b"TOUCH"
             | b"TTL"
             | b"TYPE"
@@ -766,7 +792,7 @@ impl Routable for Value {
     }
 }
 
-#[derive(Debug, Hash)]
+#[derive(Debug, Hash, PartialEq, Eq)]
 pub(crate) struct Slot {
     pub(crate) start: u16,
     pub(crate) end: u16,
@@ -1081,6 +1107,46 @@ mod tests {
                     )),
                 )),
             ),
+            (
+                cmd("BF.ADD").arg("filter").arg("item"),
+                Some(RoutingInfo::SingleNode(
+                    SingleNodeRoutingInfo::SpecificNode(Route::new(
+                        slot(b"filter"),
+                        SlotAddr::Master,
+                    )),
+                )),
+            ),
+            (
+                cmd("BF.MEXISTS").arg("filter").arg("a").arg("b"),
+                Some(RoutingInfo::SingleNode(
+                    SingleNodeRoutingInfo::SpecificNode(Route::new(
+                        slot(b"filter"),
+                        SlotAddr::ReplicaOptional,
+                    )),
+                )),
+            ),
+            (
+                cmd("CMS.MERGE")
+                    .arg("{sketch}dest")
+                    .arg("2")
+                    .arg("{sketch}a")
+                    .arg("{sketch}b"),
+                Some(RoutingInfo::SingleNode(
+                    SingleNodeRoutingInfo::SpecificNode(Route::new(
+                        slot(b"sketch"),
+                        SlotAddr::Master,
+                    )),
+                )),
+            ),
+            (
+                cmd("TDIGEST.QUANTILE").arg("digest").arg("0.5"),
+                Some(RoutingInfo::SingleNode(
+                    SingleNodeRoutingInfo::SpecificNode(Route::new(
+                        slot(b"digest"),
+                        SlotAddr::ReplicaOptional,
+                    )),
+                )),
+            ),
         ] {
             assert_eq!(
                 RoutingInfo::for_routable(cmd),
@@ -0,0 +1,265 @@
+//! Cluster-wide `SCAN`.
+//!
+//! A cluster scan walks the primaries one after the other, and runs a regular `SCAN` on each of
+//! them. The progress is kept in a [`ClusterScanCursor`], which records the slots that were
+//! already covered instead of the addresses of the nodes that were scanned. This allows the scan
+//! to continue after the topology changed, or after the connections were recreated: whenever the
+//! primary that is currently being scanned no longer owns the same slots, its scan starts over,
+//! and slots are only marked as covered once a full `SCAN` of their owner was completed.
+//!
+//! Like `SCAN`, a cluster scan might return a key more than once.
+
+use crate::cluster_routing::{Route, SlotAddr};
+use crate::cluster_slotmap::SlotMap;
+use crate::cluster_topology::SLOT_SIZE;
+use crate::cmd::{cmd, Cmd};
+use crate::types::{ErrorKind, RedisResult, Value};
+use crate::ScanOptions;
+
+/// A cluster connection that can run the steps of a cluster scan.
+pub(crate) trait ClusterScanner {
+    /// Sends the next `SCAN` of `cursor` and advances it. Returns the keys that were found.
+    fn scan_step(
+        &mut self,
+        cursor: &mut ClusterScanCursor,
+        options: &ScanOptions,
+    ) -> RedisResult<Vec<Value>>;
+}
+
+/// An async cluster connection that can run the steps of a cluster scan.
+#[cfg(feature = "cluster-async")]
+pub(crate) trait AsyncClusterScanner: Send {
+    /// Sends the next `SCAN` of `cursor` and advances it. Returns the keys that were found.
+    fn scan_step<'a>(
+        &'a mut self,
+        cursor: &'a mut ClusterScanCursor,
+        options: &'a ScanOptions,
+    ) -> crate::RedisFuture<'a, Vec<Value>>;
+}
+
+const BITS_PER_WORD: usize = u64::BITS as usize;
+const NUM_OF_WORDS: usize = SLOT_SIZE as usize / BITS_PER_WORD;
+
+/// The cursor of a cluster-wide scan.
+///
+/// A new scan starts with [`ClusterScanCursor::default`], and is done once
+/// [`ClusterScanCursor::is_finished`] returns `true`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClusterScanCursor {
+    scanned_slots: Vec<u64>,
+    node: Option<NodeScanState>,
+    finished: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct NodeScanState {
+    address: String,
+    cursor: u64,
+    slot_ranges: Vec<(u16, u16)>,
+}
+
+impl Default for ClusterScanCursor {
+    fn default() -> Self {
+        Self {
+            scanned_slots: vec![0; NUM_OF_WORDS],
+            node: None,
+            finished: false,
+        }
+    }
+}
+
+impl ClusterScanCursor {
+    /// Returns `true` if all the slots of the cluster were scanned.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn mark_scanned(&mut self, slot_ranges: &[(u16, u16)]) {
+        for (start, end) in slot_ranges {
+            for slot in *start as usize..=*end as usize {
+                self.scanned_slots[slot / BITS_PER_WORD] |= 1 << (slot % BITS_PER_WORD);
+            }
+        }
+    }
+
+    fn first_unscanned_slot(&self) -> Option<u16> {
+        self.scanned_slots
+            .iter()
+            .enumerate()
+            .find(|(_, word)| **word != u64::MAX)
+            .map(|(index, word)| (index * BITS_PER_WORD) as u16 + word.trailing_ones() as u16)
+    }
+
+    /// Returns the address of the primary that should be scanned next, and the `SCAN` command
+    /// for it. Returns `None` if the scan is finished.
+    pub(crate) fn next_command(
+        &mut self,
+        slot_map: &SlotMap,
+        options: &ScanOptions,
+    ) -> RedisResult<Option<(String, Cmd)>> {
+        if self.finished {
+            return Ok(None);
+        }
+        if let Some(node) = &self.node {
+            if slot_map.slot_ranges_for_primary(&node.address) != node.slot_ranges {
+                // The slots of the node changed, so its cursor can't be trusted anymore.
+                self.node = None;
+            }
+        }
+        if self.node.is_none() {
+            let slot = match self.first_unscanned_slot() {
+                Some(slot) => slot,
+                None => {
+                    self.finished = true;
+                    return Ok(None);
+                }
+            };
+            let address = slot_map
+                .slot_addr_for_route(&Route::new(slot, SlotAddr::Master))
+                .ok_or((ErrorKind::ClusterDown, "Missing slot coverage"))?
+                .to_string();
+            let slot_ranges = slot_map.slot_ranges_for_primary(&address);
+            self.node = Some(NodeScanState {
+                address,
+                cursor: 0,
+                slot_ranges,
+            });
+        }
+        // The node was set above.
+        let node = self.node.as_ref().unwrap();
+        let mut scan = cmd("SCAN");
+        scan.arg(node.cursor).arg(options);
+        Ok(Some((node.address.clone(), scan)))
+    }
+
+    /// Advances the cursor with the reply of the command returned by `next_command`, and returns
+    /// the keys that were found.
+    pub(crate) fn handle_response(&mut self, response: Value) -> RedisResult<Vec<Value>> {
+        let (cursor, keys): (u64, Vec<Value>) = crate::from_owned_redis_value(response)?;
+        let mut node = match self.node.take() {
+            Some(node) => node,
+            None => return Ok(keys),
+        };
+        if cursor == 0 {
+            self.mark_scanned(&node.slot_ranges);
+            self.finished = self.first_unscanned_slot().is_none();
+        } else {
+            node.cursor = cursor;
+            self.node = Some(node);
+        }
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cluster_routing::Slot;
+    use crate::cluster_slotmap::ReadFromReplicaStrategy;
+
+    fn slot_map(slots: &[(u16, u16, &str)]) -> SlotMap {
+        SlotMap::new(
+            slots
+                .iter()
+                .map(|(start, end, addr)| Slot::new(*start, *end, addr.to_string(), vec![]))
+                .collect(),
+            ReadFromReplicaStrategy::AlwaysFromPrimary,
+        )
+    }
+
+    fn scan_reply(cursor: u64, keys: &[&str]) -> Value {
+        Value::Array(vec![
+            Value::BulkString(cursor.to_string().into_bytes()),
+            Value::Array(
+                keys.iter()
+                    .map(|key| Value::BulkString(key.as_bytes().to_vec()))
+                    .collect(),
+            ),
+        ])
+    }
+
+    fn next_address(cursor: &mut ClusterScanCursor, slot_map: &SlotMap) -> Option<String> {
+        cursor
+            .next_command(slot_map, &ScanOptions::default())
+            .unwrap()
+            .map(|(address, _)| address)
+    }
+
+    #[test]
+    fn test_cluster_scan_visits_every_primary_once() {
+        let slot_map = slot_map(&[
+            (0, 5000, "node1:6379"),
+            (5001, 10000, "node2:6379"),
+            (10001, 16383, "node1:6379"),
+        ]);
+        let mut cursor = ClusterScanCursor::default();
+
+        assert_eq!(
+            next_address(&mut cursor, &slot_map).as_deref(),
+            Some("node1:6379")
+        );
+        cursor.handle_response(scan_reply(7, &["a"])).unwrap();
+        let (_, scan) = cursor
+            .next_command(&slot_map, &ScanOptions::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            scan.get_packed_command(),
+            cmd("SCAN").arg(7).get_packed_command()
+        );
+        cursor.handle_response(scan_reply(0, &["b"])).unwrap();
+        assert!(!cursor.is_finished());
+
+        assert_eq!(
+            next_address(&mut cursor, &slot_map).as_deref(),
+            Some("node2:6379")
+        );
+        cursor.handle_response(scan_reply(0, &["c"])).unwrap();
+        assert!(cursor.is_finished());
+        assert_eq!(next_address(&mut cursor, &slot_map), None);
+    }
+
+    #[test]
+    fn test_cluster_scan_restarts_node_after_topology_change() {
+        let old_slot_map = slot_map(&[(0, 8191, "node1:6379"), (8192, 16383, "node2:6379")]);
+        let new_slot_map = slot_map(&[(0, 4095, "node1:6379"), (4096, 16383, "node2:6379")]);
+        let mut cursor = ClusterScanCursor::default();
+
+        assert_eq!(
+            next_address(&mut cursor, &old_slot_map).as_deref(),
+            Some("node1:6379")
+        );
+        cursor.handle_response(scan_reply(7, &[])).unwrap();
+
+        // node1 lost slots, so its scan starts over.
+        let (address, scan) = cursor
+            .next_command(&new_slot_map, &ScanOptions::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(address, "node1:6379");
+        assert_eq!(
+            scan.get_packed_command(),
+            cmd("SCAN").arg(0).get_packed_command()
+        );
+        cursor.handle_response(scan_reply(0, &[])).unwrap();
+
+        // the moved slots are covered by node2.
+        assert_eq!(
+            next_address(&mut cursor, &new_slot_map).as_deref(),
+            Some("node2:6379")
+        );
+        cursor.handle_response(scan_reply(0, &[])).unwrap();
+        assert!(cursor.is_finished());
+    }
+
+    #[test]
+    fn test_cluster_scan_fails_on_missing_slot_coverage() {
+        let slot_map = slot_map(&[(100, 16383, "node1:6379")]);
+        let mut cursor = ClusterScanCursor::default();
+
+        let err = cursor
+            .next_command(&slot_map, &ScanOptions::default())
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClusterDown);
+    }
+}
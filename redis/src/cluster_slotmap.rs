@@ -1,7 +1,8 @@
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Display,
-    sync::atomic::AtomicUsize,
+    sync::{atomic::AtomicUsize, Arc, RwLock},
+    time::Duration,
 };
 
 use crate::cluster_routing::{Route, Slot, SlotAddr, SlotAddrs};
@@ -23,24 +24,146 @@ impl SlotMapValue {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Copy)]
-pub(crate) enum ReadFromReplicaStrategy {
+/// Defines which node serves read-only commands that can be sent to replicas.
+///
+/// Commands that must be sent to a primary are always sent to the primary of their slot, and
+/// slots without replicas are always read from their primary.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReadFromReplicaStrategy {
+    /// Always read from the primary.
     #[default]
     AlwaysFromPrimary,
+    /// Read from the replicas of the slot in a round-robin manner.
     RoundRobin,
+    /// Read from the primary and the replicas of the slot in a round-robin manner.
+    PrimaryAndReplicas,
+    /// Read from the replicas that are in the given availability zone in a round-robin manner.
+    /// If no replica of the slot is in the availability zone, read from the other replicas in a
+    /// round-robin manner.
+    ///
+    /// The availability zone of a node is read from the `availability_zone` field of its
+    /// `INFO SERVER` reply.
+    AZAffinity(String),
+    /// Read from the replica with the lowest latency, which is measured when connecting to it and
+    /// again whenever the slots are refreshed.
+    LowestLatency,
+}
+
+impl ReadFromReplicaStrategy {
+    pub(crate) fn requires_availability_zone(&self) -> bool {
+        matches!(self, ReadFromReplicaStrategy::AZAffinity(_))
+    }
+
+    pub(crate) fn requires_latency(&self) -> bool {
+        matches!(self, ReadFromReplicaStrategy::LowestLatency)
+    }
+}
+
+/// Information about a node, which is used to choose the node that serves reads.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct NodeMetadata {
+    pub(crate) availability_zone: Option<String>,
+    pub(crate) latency: Option<Duration>,
+}
+
+/// The metadata of the nodes of a cluster, by address. It's shared between the connections of a
+/// client, and outlives topology changes.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct NodesMetadata(Arc<RwLock<HashMap<String, NodeMetadata>>>);
+
+impl NodesMetadata {
+    pub(crate) fn set_availability_zone(&self, address: &str, availability_zone: Option<String>) {
+        self.0
+            .write()
+            .unwrap()
+            .entry(address.to_string())
+            .or_default()
+            .availability_zone = availability_zone;
+    }
+
+    pub(crate) fn set_latency(&self, address: &str, latency: Duration) {
+        self.0
+            .write()
+            .unwrap()
+            .entry(address.to_string())
+            .or_default()
+            .latency = Some(latency);
+    }
 }
 
 #[derive(Debug, Default)]
 pub(crate) struct SlotMap {
     slots: BTreeMap<u16, SlotMapValue>,
     read_from_replica: ReadFromReplicaStrategy,
+    nodes_metadata: NodesMetadata,
 }
 
-fn get_address_from_slot(
-    slot: &SlotMapValue,
-    read_from_replica: ReadFromReplicaStrategy,
+fn rotate<T>(mut items: Vec<T>, slot: &SlotMapValue) -> Vec<T> {
+    if !items.is_empty() {
+        let index = slot
+            .latest_used_replica
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+            % items.len();
+        items.rotate_left(index);
+    }
+    items
+}
+
+/// Returns the addresses that may serve a read from `slot` according to `read_from_replica`,
+/// ordered by preference.
+fn read_candidates<'a>(
+    slot: &'a SlotMapValue,
+    read_from_replica: &ReadFromReplicaStrategy,
+    nodes_metadata: &NodesMetadata,
+) -> Vec<&'a str> {
+    let primary = slot.addrs.primary.as_str();
+    let replicas = slot.addrs.replicas.iter().map(|replica| replica.as_str());
+    let mut candidates = match read_from_replica {
+        ReadFromReplicaStrategy::AlwaysFromPrimary => return vec![primary],
+        ReadFromReplicaStrategy::RoundRobin => rotate(replicas.collect(), slot),
+        ReadFromReplicaStrategy::PrimaryAndReplicas => {
+            return rotate(std::iter::once(primary).chain(replicas).collect(), slot)
+        }
+        ReadFromReplicaStrategy::AZAffinity(availability_zone) => {
+            let metadata = nodes_metadata.0.read().unwrap();
+            let (mut local, remote): (Vec<_>, Vec<_>) = replicas.partition(|replica| {
+                metadata
+                    .get(*replica)
+                    .and_then(|node| node.availability_zone.as_ref())
+                    == Some(availability_zone)
+            });
+            if local.is_empty() {
+                rotate(remote, slot)
+            } else {
+                local = rotate(local, slot);
+                local.extend(remote);
+                local
+            }
+        }
+        ReadFromReplicaStrategy::LowestLatency => {
+            let metadata = nodes_metadata.0.read().unwrap();
+            let mut replicas: Vec<_> = replicas.collect();
+            // Replicas with an unknown latency are tried last.
+            replicas.sort_by_key(|replica| {
+                metadata
+                    .get(*replica)
+                    .and_then(|node| node.latency)
+                    .unwrap_or(Duration::MAX)
+            });
+            replicas
+        }
+    };
+    candidates.push(primary);
+    candidates
+}
+
+fn get_address_from_slot<'a>(
+    slot: &'a SlotMapValue,
+    read_from_replica: &ReadFromReplicaStrategy,
+    nodes_metadata: &NodesMetadata,
     slot_addr: SlotAddr,
-) -> &str {
+) -> &'a str {
     if slot_addr == SlotAddr::Master || slot.addrs.replicas.is_empty() {
         return slot.addrs.primary.as_str();
     }
@@ -53,6 +176,7 @@ fn get_address_from_slot(
                 % slot.addrs.replicas.len();
             slot.addrs.replicas[index].as_str()
         }
+        _ => read_candidates(slot, read_from_replica, nodes_metadata)[0],
     }
 }
 
@@ -61,6 +185,7 @@ impl SlotMap {
         let mut this = Self {
             slots: BTreeMap::new(),
             read_from_replica,
+            nodes_metadata: NodesMetadata::default(),
         };
         this.slots.extend(
             slots
@@ -70,6 +195,12 @@ impl SlotMap {
         this
     }
 
+    /// Sets the metadata that is used to choose the nodes that serve reads.
+    pub(crate) fn with_nodes_metadata(mut self, nodes_metadata: NodesMetadata) -> Self {
+        self.nodes_metadata = nodes_metadata;
+        self
+    }
+
     pub fn slot_value_for_route(&self, route: &Route) -> Option<&SlotMapValue> {
         let slot = route.slot();
         self.slots
@@ -86,14 +217,37 @@ impl SlotMap {
 
     pub fn slot_addr_for_route(&self, route: &Route) -> Option<&str> {
         self.slot_value_for_route(route).map(|slot_value| {
-            get_address_from_slot(slot_value, self.read_from_replica, route.slot_addr())
+            get_address_from_slot(
+                slot_value,
+                &self.read_from_replica,
+                &self.nodes_metadata,
+                route.slot_addr(),
+            )
         })
     }
 
+    /// Returns the addresses that may serve a read from `slot_value` according to
+    /// `read_from_replica`, ordered by preference. The primary is always one of the addresses.
+    #[cfg(any(test, feature = "cluster-async"))]
+    pub(crate) fn read_candidates<'a>(
+        &self,
+        slot_value: &'a SlotMapValue,
+        read_from_replica: &ReadFromReplicaStrategy,
+    ) -> Vec<&'a str> {
+        read_candidates(slot_value, read_from_replica, &self.nodes_metadata)
+    }
+
     pub fn values(&self) -> impl Iterator<Item = &SlotAddrs> {
         self.slots.values().map(|slot_value| &slot_value.addrs)
     }
 
+    /// Returns the `(start, end)` slot ranges with the nodes serving them, in ascending order.
+    pub(crate) fn slot_ranges(&self) -> impl Iterator<Item = (u16, u16, &SlotAddrs)> {
+        self.slots
+            .iter()
+            .map(|(end, slot_value)| (slot_value.start, *end, &slot_value.addrs))
+    }
+
     fn all_unique_addresses(&self, only_primaries: bool) -> HashSet<&str> {
         let mut addresses = HashSet::new();
         for slot in self.values() {
@@ -114,6 +268,15 @@ impl SlotMap {
         self.all_unique_addresses(false)
     }
 
+    /// Returns the slot ranges that are served by the primary at `address`, in ascending order.
+    pub fn slot_ranges_for_primary(&self, address: &str) -> Vec<(u16, u16)> {
+        self.slots
+            .iter()
+            .filter(|(_, slot_value)| slot_value.addrs.primary.as_str() == address)
+            .map(|(end, slot_value)| (slot_value.start, *end))
+            .collect()
+    }
+
     pub fn addresses_for_multi_slot<'a, 'b>(
         &'a self,
         routes: &'b [(Route, Vec<usize>)],
@@ -347,6 +510,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_slot_map_get_slot_ranges_for_primary() {
+        let slot_map = get_slot_map(ReadFromReplicaStrategy::AlwaysFromPrimary);
+        assert_eq!(
+            slot_map.slot_ranges_for_primary("node2:6379"),
+            vec![(1002, 2000), (3001, 4000)]
+        );
+        assert_eq!(slot_map.slot_ranges_for_primary("replica2:6379"), vec![]);
+    }
+
     #[test]
     fn test_slot_map_rotate_read_replicas() {
         let slot_map = get_slot_map(ReadFromReplicaStrategy::RoundRobin);
@@ -362,4 +535,68 @@ mod tests {
             vec!["replica4:6379", "replica5:6379", "replica6:6379"]
         );
     }
+
+    #[test]
+    fn test_slot_map_read_from_primary_and_replicas() {
+        let slot_map = get_slot_map(ReadFromReplicaStrategy::PrimaryAndReplicas);
+        let route = Route::new(1, SlotAddr::ReplicaOptional);
+        let mut addresses = vec![
+            slot_map.slot_addr_for_route(&route).unwrap(),
+            slot_map.slot_addr_for_route(&route).unwrap(),
+        ];
+        addresses.sort();
+        assert_eq!(addresses, vec!["node1:6379", "replica1:6379"]);
+    }
+
+    #[test]
+    fn test_slot_map_read_from_replicas_in_availability_zone() {
+        let nodes_metadata = NodesMetadata::default();
+        nodes_metadata.set_availability_zone("replica4:6379", Some("az-b".to_string()));
+        nodes_metadata.set_availability_zone("replica5:6379", Some("az-a".to_string()));
+        nodes_metadata.set_availability_zone("replica6:6379", Some("az-a".to_string()));
+        nodes_metadata.set_availability_zone("replica2:6379", Some("az-b".to_string()));
+        let slot_map = get_slot_map(ReadFromReplicaStrategy::AZAffinity("az-a".to_string()))
+            .with_nodes_metadata(nodes_metadata);
+
+        let route = Route::new(2001, SlotAddr::ReplicaOptional);
+        let mut addresses = vec![
+            slot_map.slot_addr_for_route(&route).unwrap(),
+            slot_map.slot_addr_for_route(&route).unwrap(),
+        ];
+        addresses.sort();
+        assert_eq!(addresses, vec!["replica5:6379", "replica6:6379"]);
+
+        // no replica of the slot is in the availability zone.
+        let route = Route::new(1002, SlotAddr::ReplicaOptional);
+        let mut addresses = vec![
+            slot_map.slot_addr_for_route(&route).unwrap(),
+            slot_map.slot_addr_for_route(&route).unwrap(),
+        ];
+        addresses.sort();
+        assert_eq!(addresses, vec!["replica2:6379", "replica3:6379"]);
+    }
+
+    #[test]
+    fn test_slot_map_read_from_replica_with_lowest_latency() {
+        let nodes_metadata = NodesMetadata::default();
+        nodes_metadata.set_latency("replica4:6379", Duration::from_millis(5));
+        nodes_metadata.set_latency("replica5:6379", Duration::from_millis(1));
+        let slot_map = get_slot_map(ReadFromReplicaStrategy::LowestLatency)
+            .with_nodes_metadata(nodes_metadata.clone());
+        let route = Route::new(2001, SlotAddr::ReplicaOptional);
+        let slot_value = slot_map.slot_value_for_route(&route).unwrap();
+
+        assert_eq!(
+            slot_map.read_candidates(slot_value, &ReadFromReplicaStrategy::LowestLatency),
+            vec![
+                "replica5:6379",
+                "replica4:6379",
+                "replica6:6379",
+                "node3:6379"
+            ]
+        );
+
+        nodes_metadata.set_latency("replica6:6379", Duration::from_micros(100));
+        assert_eq!(slot_map.slot_addr_for_route(&route), Some("replica6:6379"));
+    }
 }
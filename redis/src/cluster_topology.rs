@@ -3,10 +3,11 @@
 use crate::cluster::get_connection_addr;
 use crate::cluster_routing::Slot;
 use crate::cluster_slotmap::{ReadFromReplicaStrategy, SlotMap};
-use crate::{cluster::TlsMode, ErrorKind, RedisError, RedisResult, Value};
+use crate::{cluster::TlsMode, ErrorKind, FromRedisValue, RedisError, RedisResult, Value};
 use derivative::Derivative;
-use std::collections::{hash_map::DefaultHasher, HashMap};
+use std::collections::{hash_map::DefaultHasher, BTreeMap, HashMap};
 use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 use std::time::Duration;
 
 /// The default number of refersh topology retries
@@ -65,13 +66,102 @@ pub fn get_slot(key: &[u8]) -> u16 {
     slot(key)
 }
 
-// Parse slot data from raw redis value.
+/// Returns true if `err` was returned by a node that can't answer `CLUSTER SHARDS`, either because
+/// it's older than Redis 7 or because the command isn't allowed, so `CLUSTER SLOTS` should be used
+/// instead.
+pub(crate) fn should_fall_back_to_slots(err: &RedisError) -> bool {
+    matches!(
+        err.kind(),
+        ErrorKind::ResponseError | ErrorKind::ExtensionError
+    )
+}
+
+// A `CLUSTER SHARDS` reply is an array of shards, each one starting with the `slots` field, while
+// each entry of a `CLUSTER SLOTS` reply starts with the first slot of the range.
+fn is_shards_reply(raw_resp: &Value) -> bool {
+    let first_shard = match raw_resp {
+        Value::Array(shards) => shards.first(),
+        _ => None,
+    };
+    match first_shard {
+        Some(Value::Map(_)) => true,
+        Some(Value::Array(fields)) => !matches!(fields.first(), Some(Value::Int(_))),
+        _ => false,
+    }
+}
+
+fn shard_node_address(
+    node: &ClusterShardNode,
+    tls: Option<TlsMode>,
+    addr_of_answering_node: &str,
+) -> Option<String> {
+    // The endpoint follows the same rules as the hostname in CLUSTER SLOTS: an empty endpoint
+    // is the answering node, and "?" is an unknown endpoint.
+    let host = match node.endpoint.as_str() {
+        "" => addr_of_answering_node,
+        "?" => return None,
+        endpoint => endpoint,
+    };
+    let port = match tls {
+        Some(_) => node.tls_port.or(node.port)?,
+        None => node.port?,
+    };
+    Some(get_connection_addr(host.to_string(), port, tls, None).to_string())
+}
+
+// Parse slot data from a `CLUSTER SHARDS` reply. Nodes that aren't online are skipped, and shards
+// without an online primary leave their slots uncovered.
+fn parse_and_count_shards(
+    raw_shards_resp: &Value,
+    tls: Option<TlsMode>,
+    addr_of_answering_node: &str,
+) -> RedisResult<(u16, Vec<Slot>)> {
+    let shards: Vec<ClusterShard> = FromRedisValue::from_redis_value(raw_shards_resp)?;
+    let mut slots = Vec::with_capacity(shards.len());
+    let mut count = 0;
+    for shard in shards {
+        let online_address = |role| {
+            shard
+                .nodes
+                .iter()
+                .filter(move |node| node.role == role && node.health == ShardNodeHealth::Online)
+                .filter_map(|node| shard_node_address(node, tls, addr_of_answering_node))
+        };
+        let primary = match online_address(NodeRole::Primary).next() {
+            Some(primary) => primary,
+            None => continue,
+        };
+        let mut replicas: Vec<String> = online_address(NodeRole::Replica).collect();
+        replicas.sort_unstable();
+        for (start, end) in shard.slots.iter() {
+            count += end - start;
+            slots.push(Slot::new(*start, *end, primary.clone(), replicas.clone()));
+        }
+    }
+    // Sort the slots like CLUSTER SLOTS does, so that views of the same topology are equal.
+    slots.sort_unstable_by_key(|slot| slot.start());
+    if slots.is_empty() {
+        return Err(RedisError::from((
+            ErrorKind::ResponseError,
+            "Error parsing slots: No healthy node found",
+            format!("Raw shards response: {:?}", raw_shards_resp),
+        )));
+    }
+
+    Ok((count, slots))
+}
+
+// Parse slot data from raw redis value, which is either a `CLUSTER SHARDS` or a `CLUSTER SLOTS` reply.
 pub(crate) fn parse_and_count_slots(
     raw_slot_resp: &Value,
     tls: Option<TlsMode>,
     // The DNS address of the node from which `raw_slot_resp` was received.
     addr_of_answering_node: &str,
 ) -> RedisResult<(u16, Vec<Slot>)> {
+    if is_shards_reply(raw_slot_resp) {
+        return parse_and_count_shards(raw_slot_resp, tls, addr_of_answering_node);
+    }
+
     // Parse response.
     let mut slots = Vec::with_capacity(2);
     let mut count = 0;
@@ -255,6 +345,687 @@ pub(crate) fn calculate_topology<'a>(
     }
 }
 
+/// The role of a node in the cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeRole {
+    /// The node serves writes for its slots.
+    Primary,
+    /// The node replicates a primary.
+    Replica,
+}
+
+/// A range of slots, along with the nodes that serve it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotRange {
+    /// The first slot of the range.
+    pub start: u16,
+    /// The last slot of the range, inclusive.
+    pub end: u16,
+    /// The address of the primary that owns the range.
+    pub primary: String,
+    /// The addresses of the replicas of the range.
+    pub replicas: Vec<String>,
+}
+
+impl SlotRange {
+    /// Returns true if `slot` is in the range.
+    pub fn contains(&self, slot: u16) -> bool {
+        self.start <= slot && slot <= self.end
+    }
+}
+
+/// A node of a [`ClusterTopology`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopologyNode {
+    /// The address of the node, as used by the connection.
+    pub address: String,
+    /// The role of the node.
+    pub role: NodeRole,
+    /// The `(start, end)` slot ranges owned by the node, empty for replicas.
+    pub slot_ranges: Vec<(u16, u16)>,
+    /// The addresses of the replicas of the node, empty for replicas.
+    pub replicas: Vec<String>,
+    /// The address of the primary that the node replicates, `None` for primaries.
+    pub primary: Option<String>,
+    /// Whether the connection currently holds a usable connection to the node.
+    pub connected: bool,
+}
+
+/// A read-only snapshot of the cluster topology, as known by a cluster connection.
+///
+/// The snapshot isn't updated when the connection refreshes its slots, so a new one should be
+/// taken whenever an up to date view is needed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClusterTopology {
+    /// The slot ranges of the cluster, in ascending order.
+    pub slot_ranges: Vec<SlotRange>,
+    /// The nodes of the cluster, ordered by address.
+    pub nodes: Vec<TopologyNode>,
+}
+
+impl ClusterTopology {
+    pub(crate) fn from_slot_map(slot_map: &SlotMap, is_connected: impl Fn(&str) -> bool) -> Self {
+        let mut slot_ranges = Vec::new();
+        let mut nodes: BTreeMap<&str, TopologyNode> = BTreeMap::new();
+        let new_node = |address: &str, role| TopologyNode {
+            address: address.to_string(),
+            role,
+            slot_ranges: Vec::new(),
+            replicas: Vec::new(),
+            primary: None,
+            connected: is_connected(address),
+        };
+
+        for (start, end, addrs) in slot_map.slot_ranges() {
+            let primary = nodes
+                .entry(addrs.primary.as_str())
+                .or_insert_with(|| new_node(&addrs.primary, NodeRole::Primary));
+            // A node that is listed as both a primary and a replica is in the middle of a
+            // failover, so the primary role takes precedence.
+            primary.role = NodeRole::Primary;
+            primary.primary = None;
+            primary.slot_ranges.push((start, end));
+            for replica in addrs.replicas.iter() {
+                if !primary.replicas.contains(replica) {
+                    primary.replicas.push(replica.clone());
+                }
+            }
+            for replica in addrs.replicas.iter() {
+                let node = nodes
+                    .entry(replica.as_str())
+                    .or_insert_with(|| new_node(replica, NodeRole::Replica));
+                if node.role == NodeRole::Replica {
+                    node.primary = Some(addrs.primary.clone());
+                }
+            }
+            slot_ranges.push(SlotRange {
+                start,
+                end,
+                primary: addrs.primary.clone(),
+                replicas: addrs.replicas.clone(),
+            });
+        }
+
+        let nodes = nodes
+            .into_values()
+            .map(|mut node| {
+                if node.role == NodeRole::Primary {
+                    node.replicas.sort_unstable();
+                } else {
+                    node.replicas.clear();
+                }
+                node
+            })
+            .collect();
+        ClusterTopology { slot_ranges, nodes }
+    }
+
+    /// Returns the node at `address`, if it's part of the topology.
+    pub fn node(&self, address: &str) -> Option<&TopologyNode> {
+        self.nodes.iter().find(|node| node.address == address)
+    }
+
+    /// Returns the primaries of the cluster.
+    pub fn primaries(&self) -> impl Iterator<Item = &TopologyNode> {
+        self.nodes
+            .iter()
+            .filter(|node| node.role == NodeRole::Primary)
+    }
+
+    /// Returns the replicas of the cluster.
+    pub fn replicas(&self) -> impl Iterator<Item = &TopologyNode> {
+        self.nodes
+            .iter()
+            .filter(|node| node.role == NodeRole::Replica)
+    }
+
+    /// Returns the slot range that contains `slot`, if it's covered.
+    pub fn slot_range(&self, slot: u16) -> Option<&SlotRange> {
+        self.slot_ranges.iter().find(|range| range.contains(slot))
+    }
+
+    /// Returns the slot range that contains the slot of `key`, if it's covered.
+    pub fn slot_range_for_key(&self, key: &[u8]) -> Option<&SlotRange> {
+        self.slot_range(get_slot(key))
+    }
+
+    fn slot_owners(&self) -> Vec<Option<&str>> {
+        let mut owners = vec![None; SLOT_SIZE as usize];
+        for range in self.slot_ranges.iter() {
+            for owner in &mut owners[range.start as usize..=range.end as usize] {
+                *owner = Some(range.primary.as_str());
+            }
+        }
+        owners
+    }
+
+    /// Returns the events that lead from this topology to `new`: the added nodes, the
+    /// failovers, the moved slot ranges, the removed nodes and the changes in connection health,
+    /// in this order.
+    pub fn changes_to(&self, new: &ClusterTopology) -> Vec<TopologyEvent> {
+        let mut events = Vec::new();
+
+        for node in new.nodes.iter() {
+            if self.node(&node.address).is_none() {
+                events.push(TopologyEvent::NodeAdded {
+                    address: node.address.clone(),
+                    role: node.role,
+                });
+            }
+        }
+
+        for node in new.primaries() {
+            let old_primary = match self.node(&node.address) {
+                Some(TopologyNode {
+                    role: NodeRole::Replica,
+                    primary: Some(old_primary),
+                    ..
+                }) => old_primary,
+                _ => continue,
+            };
+            let old_primary_demoted = new
+                .node(old_primary)
+                .map_or(true, |old_primary| old_primary.role == NodeRole::Replica);
+            if old_primary_demoted {
+                events.push(TopologyEvent::PrimaryFailover {
+                    old_primary: old_primary.clone(),
+                    new_primary: node.address.clone(),
+                });
+            }
+        }
+
+        let old_owners = self.slot_owners();
+        let new_owners = new.slot_owners();
+        let mut slot = 0;
+        while slot < SLOT_SIZE as usize {
+            let (from, to) = (old_owners[slot], new_owners[slot]);
+            let start = slot;
+            while slot < SLOT_SIZE as usize && old_owners[slot] == from && new_owners[slot] == to {
+                slot += 1;
+            }
+            if from != to {
+                events.push(TopologyEvent::SlotsMoved {
+                    start: start as u16,
+                    end: (slot - 1) as u16,
+                    from: from.map(str::to_string),
+                    to: to.map(str::to_string),
+                });
+            }
+        }
+
+        for node in self.nodes.iter() {
+            if new.node(&node.address).is_none() {
+                events.push(TopologyEvent::NodeRemoved {
+                    address: node.address.clone(),
+                });
+            }
+        }
+
+        for node in new.nodes.iter() {
+            match self.node(&node.address) {
+                Some(old_node) if old_node.connected && !node.connected => {
+                    events.push(TopologyEvent::ConnectionLost {
+                        address: node.address.clone(),
+                    })
+                }
+                Some(old_node) if !old_node.connected && node.connected => {
+                    events.push(TopologyEvent::ConnectionRestored {
+                        address: node.address.clone(),
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        events
+    }
+}
+
+/// A change in the cluster topology, as reported by
+/// [`ClusterConnection::topology_events`](crate::cluster_async::ClusterConnection::topology_events)
+/// or computed by [`ClusterTopology::changes_to`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TopologyEvent {
+    /// A node joined the topology.
+    NodeAdded {
+        /// The address of the node.
+        address: String,
+        /// The role of the node.
+        role: NodeRole,
+    },
+    /// A node left the topology.
+    NodeRemoved {
+        /// The address of the node.
+        address: String,
+    },
+    /// A replica was promoted in place of its primary.
+    PrimaryFailover {
+        /// The address of the previous primary.
+        old_primary: String,
+        /// The address of the promoted replica.
+        new_primary: String,
+    },
+    /// A range of slots moved to another primary. This is also reported for the slots of a
+    /// failed over primary.
+    SlotsMoved {
+        /// The first slot of the range.
+        start: u16,
+        /// The last slot of the range, inclusive.
+        end: u16,
+        /// The address of the previous primary, `None` if the slots weren't covered.
+        from: Option<String>,
+        /// The address of the new primary, `None` if the slots aren't covered anymore.
+        to: Option<String>,
+    },
+    /// The connection to a node was lost.
+    ConnectionLost {
+        /// The address of the node.
+        address: String,
+    },
+    /// The connection to a node was restored.
+    ConnectionRestored {
+        /// The address of the node.
+        address: String,
+    },
+}
+
+macro_rules! invalid_type_error {
+    ($v:expr, $det:expr) => {{
+        fail!((
+            ErrorKind::TypeError,
+            "Response was of incompatible type",
+            format!("{:?} (response was {:?})", $det, $v)
+        ));
+    }};
+}
+
+fn parse_number<T: FromStr>(value: &str, line: &str) -> RedisResult<T> {
+    match value.parse() {
+        Ok(value) => Ok(value),
+        Err(_) => invalid_type_error!(
+            line,
+            format!("Invalid number {value} in CLUSTER NODES line")
+        ),
+    }
+}
+
+fn parse_slot_number(slot: &str, line: &str) -> RedisResult<u16> {
+    match slot.parse::<u16>() {
+        Ok(slot) if slot < SLOT_SIZE => Ok(slot),
+        _ => invalid_type_error!(line, format!("Invalid slot {slot} in CLUSTER NODES line")),
+    }
+}
+
+/// A node of a [`ClusterNodes`] reply, parsed from one line of `CLUSTER NODES`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClusterNodesEntry {
+    /// The node ID.
+    pub id: String,
+    /// The IP of the node, empty if the node's address is unknown.
+    pub ip: String,
+    /// The client port of the node.
+    pub port: u16,
+    /// The cluster bus port of the node, `None` for servers that don't report it.
+    pub cluster_bus_port: Option<u16>,
+    /// The announced hostname of the node, if any.
+    pub hostname: Option<String>,
+    /// The flags of the node, such as `myself`, `master`, `slave`, `fail?` or `fail`.
+    pub flags: Vec<String>,
+    /// The ID of the primary that the node replicates, `None` for primaries.
+    pub primary_id: Option<String>,
+    /// Unix time in milliseconds at which the pending ping was sent, 0 if there is none.
+    pub ping_sent: u64,
+    /// Unix time in milliseconds at which the last pong was received.
+    pub pong_received: u64,
+    /// The configuration epoch of the node.
+    pub config_epoch: u64,
+    /// Whether the cluster bus link to the node is connected.
+    pub link_connected: bool,
+    /// The `(start, end)` slot ranges served by the node.
+    pub slots: Vec<(u16, u16)>,
+    /// The slots that are being migrated from the node, with the ID of the destination node.
+    pub migrating: Vec<(u16, String)>,
+    /// The slots that are being imported to the node, with the ID of the source node.
+    pub importing: Vec<(u16, String)>,
+}
+
+impl ClusterNodesEntry {
+    /// Parses a single line of a `CLUSTER NODES` reply.
+    pub fn parse(line: &str) -> RedisResult<Self> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 8 {
+            invalid_type_error!(line, "Not enough fields in CLUSTER NODES line");
+        }
+
+        // The address has the form `ip:port@cport[,hostname[,aux=value]*]`.
+        let mut address_parts = fields[1].split(',');
+        let address = address_parts.next().unwrap_or_default();
+        let hostname = address_parts
+            .next()
+            .filter(|hostname| !hostname.is_empty() && !hostname.contains('='))
+            .map(str::to_string);
+        let (address, cluster_bus_port) = match address.split_once('@') {
+            Some((address, cluster_bus_port)) => (address, Some(cluster_bus_port)),
+            None => (address, None),
+        };
+        let (ip, port) = match address.rsplit_once(':') {
+            Some(parts) => parts,
+            None => invalid_type_error!(line, "Invalid address in CLUSTER NODES line"),
+        };
+        let port = parse_number(port, line)?;
+        let cluster_bus_port = match cluster_bus_port {
+            Some(cluster_bus_port) => Some(parse_number(cluster_bus_port, line)?),
+            None => None,
+        };
+
+        let mut entry = ClusterNodesEntry {
+            id: fields[0].to_string(),
+            ip: ip.to_string(),
+            port,
+            cluster_bus_port,
+            hostname,
+            flags: fields[2].split(',').map(str::to_string).collect(),
+            primary_id: Some(fields[3])
+                .filter(|primary_id| *primary_id != "-")
+                .map(str::to_string),
+            ping_sent: parse_number(fields[4], line)?,
+            pong_received: parse_number(fields[5], line)?,
+            config_epoch: parse_number(fields[6], line)?,
+            link_connected: fields[7] == "connected",
+            ..Default::default()
+        };
+
+        for slot in &fields[8..] {
+            if let Some(transfer) = slot.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                if let Some((slot, node_id)) = transfer.split_once("->-") {
+                    entry
+                        .migrating
+                        .push((parse_slot_number(slot, line)?, node_id.to_string()));
+                } else if let Some((slot, node_id)) = transfer.split_once("-<-") {
+                    entry
+                        .importing
+                        .push((parse_slot_number(slot, line)?, node_id.to_string()));
+                } else {
+                    invalid_type_error!(line, "Invalid slot transfer in CLUSTER NODES line");
+                }
+            } else if let Some((start, end)) = slot.split_once('-') {
+                entry.slots.push((
+                    parse_slot_number(start, line)?,
+                    parse_slot_number(end, line)?,
+                ));
+            } else {
+                let slot = parse_slot_number(slot, line)?;
+                entry.slots.push((slot, slot));
+            }
+        }
+
+        Ok(entry)
+    }
+
+    /// Returns the `ip:port` address of the node.
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.ip, self.port)
+    }
+
+    /// Returns true if the node has the given flag.
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    /// Returns true if this is the node that answered the command.
+    pub fn is_myself(&self) -> bool {
+        self.has_flag("myself")
+    }
+
+    /// Returns the role of the node, `None` if the node has neither the `master` nor the `slave`
+    /// flag, for example because its address isn't known yet.
+    pub fn role(&self) -> Option<NodeRole> {
+        if self.has_flag("master") {
+            Some(NodeRole::Primary)
+        } else if self.has_flag("slave") {
+            Some(NodeRole::Replica)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the node is flagged as failing, either by this node (`fail?`) or by the
+    /// majority of the primaries (`fail`).
+    pub fn is_failing(&self) -> bool {
+        self.has_flag("fail") || self.has_flag("fail?")
+    }
+}
+
+/// Reply type used with the `CLUSTER NODES` command.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClusterNodes {
+    /// The nodes known by the node that answered the command.
+    pub nodes: Vec<ClusterNodesEntry>,
+}
+
+impl ClusterNodes {
+    /// Returns the node that answered the command.
+    pub fn myself(&self) -> Option<&ClusterNodesEntry> {
+        self.nodes.iter().find(|node| node.is_myself())
+    }
+
+    /// Returns the node with the given ID.
+    pub fn node(&self, id: &str) -> Option<&ClusterNodesEntry> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Returns the replicas of the primary with the given ID.
+    pub fn replicas_of<'a>(
+        &'a self,
+        primary_id: &'a str,
+    ) -> impl Iterator<Item = &'a ClusterNodesEntry> + 'a {
+        self.nodes
+            .iter()
+            .filter(move |node| node.primary_id.as_deref() == Some(primary_id))
+    }
+}
+
+impl FromRedisValue for ClusterNodes {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let text = String::from_redis_value(v)?;
+        let nodes = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(ClusterNodesEntry::parse)
+            .collect::<RedisResult<_>>()?;
+        Ok(ClusterNodes { nodes })
+    }
+}
+
+/// The health of a node in a [`ClusterShard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShardNodeHealth {
+    /// The node is serving requests.
+    Online,
+    /// The node is flagged as failing.
+    Failed,
+    /// The node is loading its data and can't serve requests yet.
+    Loading,
+}
+
+/// A node of a [`ClusterShard`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClusterShardNode {
+    /// The node ID.
+    pub id: String,
+    /// The preferred endpoint to reach the node, as configured by `cluster-preferred-endpoint-type`.
+    pub endpoint: String,
+    /// The IP of the node.
+    pub ip: String,
+    /// The announced hostname of the node, if any.
+    pub hostname: Option<String>,
+    /// The plaintext port of the node, `None` if the node only accepts TLS connections.
+    pub port: Option<u16>,
+    /// The TLS port of the node, if TLS is enabled.
+    pub tls_port: Option<u16>,
+    /// The role of the node.
+    pub role: NodeRole,
+    /// The replication offset of the node.
+    pub replication_offset: u64,
+    /// The health of the node.
+    pub health: ShardNodeHealth,
+}
+
+fn field_map(v: &Value, reply: &str) -> RedisResult<HashMap<String, Value>> {
+    match v {
+        Value::Array(_) | Value::Map(_) => FromRedisValue::from_redis_value(v),
+        _ => invalid_type_error!(v, format!("Response type not {reply} compatible")),
+    }
+}
+
+fn required_field<T: FromRedisValue>(map: &HashMap<String, Value>, name: &str) -> RedisResult<T> {
+    match map.get(name) {
+        Some(value) => T::from_redis_value(value),
+        None => invalid_type_error!(map, format!("Missing field {name} in CLUSTER SHARDS reply")),
+    }
+}
+
+fn optional_field<T: FromRedisValue>(
+    map: &HashMap<String, Value>,
+    name: &str,
+) -> RedisResult<Option<T>> {
+    match map.get(name) {
+        Some(Value::Nil) | None => Ok(None),
+        Some(value) => T::from_redis_value(value).map(Some),
+    }
+}
+
+impl FromRedisValue for ClusterShardNode {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let map = field_map(v, "CLUSTER SHARDS node")?;
+        let role = match required_field::<String>(&map, "role")?.as_str() {
+            "master" | "primary" => NodeRole::Primary,
+            "replica" | "slave" => NodeRole::Replica,
+            role => invalid_type_error!(v, format!("Unknown node role {role}")),
+        };
+        let health = match required_field::<String>(&map, "health")?.as_str() {
+            "online" => ShardNodeHealth::Online,
+            "failed" => ShardNodeHealth::Failed,
+            "loading" => ShardNodeHealth::Loading,
+            health => invalid_type_error!(v, format!("Unknown node health {health}")),
+        };
+        Ok(ClusterShardNode {
+            id: required_field(&map, "id")?,
+            endpoint: required_field(&map, "endpoint")?,
+            ip: required_field(&map, "ip")?,
+            hostname: optional_field::<String>(&map, "hostname")?
+                .filter(|hostname| !hostname.is_empty()),
+            port: optional_field(&map, "port")?,
+            tls_port: optional_field(&map, "tls-port")?,
+            role,
+            replication_offset: optional_field(&map, "replication-offset")?.unwrap_or_default(),
+            health,
+        })
+    }
+}
+
+/// A shard of a `CLUSTER SHARDS` reply, which is parsed as a `Vec<ClusterShard>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClusterShard {
+    /// The `(start, end)` slot ranges served by the shard, empty for shards without slots.
+    pub slots: Vec<(u16, u16)>,
+    /// The nodes of the shard.
+    pub nodes: Vec<ClusterShardNode>,
+}
+
+impl ClusterShard {
+    /// Returns the primary of the shard, if it has one.
+    pub fn primary(&self) -> Option<&ClusterShardNode> {
+        self.nodes
+            .iter()
+            .find(|node| node.role == NodeRole::Primary)
+    }
+
+    /// Returns the replicas of the shard.
+    pub fn replicas(&self) -> impl Iterator<Item = &ClusterShardNode> {
+        self.nodes
+            .iter()
+            .filter(|node| node.role == NodeRole::Replica)
+    }
+}
+
+impl FromRedisValue for ClusterShard {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let map = field_map(v, "CLUSTER SHARDS")?;
+        let bounds: Vec<u16> = required_field(&map, "slots")?;
+        if bounds.len() % 2 != 0 {
+            invalid_type_error!(v, "Odd number of slot bounds in CLUSTER SHARDS reply");
+        }
+        Ok(ClusterShard {
+            slots: bounds
+                .chunks_exact(2)
+                .map(|bounds| (bounds[0], bounds[1]))
+                .collect(),
+            nodes: required_field(&map, "nodes")?,
+        })
+    }
+}
+
+/// Reply type used with the `CLUSTER INFO` command.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClusterInfo {
+    /// Whether `cluster_state` is `ok`, meaning that the node can serve queries.
+    pub state_ok: bool,
+    /// The number of slots that are assigned to a node.
+    pub slots_assigned: u64,
+    /// The number of slots whose node isn't flagged as failing.
+    pub slots_ok: u64,
+    /// The number of slots whose node is flagged as `fail?`.
+    pub slots_pfail: u64,
+    /// The number of slots whose node is flagged as `fail`.
+    pub slots_fail: u64,
+    /// The number of known nodes, including nodes in handshake state.
+    pub known_nodes: u64,
+    /// The number of primaries that serve at least one slot.
+    pub size: u64,
+    /// The current epoch of the cluster.
+    pub current_epoch: u64,
+    /// The configuration epoch of the node that answered the command.
+    pub my_epoch: u64,
+    /// All the fields of the reply, including the ones above.
+    pub fields: HashMap<String, String>,
+}
+
+impl FromRedisValue for ClusterInfo {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let text = String::from_redis_value(v)?;
+        let fields: HashMap<String, String> = text
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect();
+        let number = |name: &str| -> RedisResult<u64> {
+            match fields.get(name) {
+                None => Ok(0),
+                Some(value) => match value.parse() {
+                    Ok(value) => Ok(value),
+                    Err(_) => {
+                        invalid_type_error!(v, format!("Invalid {name} in CLUSTER INFO reply"))
+                    }
+                },
+            }
+        };
+        Ok(ClusterInfo {
+            state_ok: fields.get("cluster_state").map(String::as_str) == Some("ok"),
+            slots_assigned: number("cluster_slots_assigned")?,
+            slots_ok: number("cluster_slots_ok")?,
+            slots_pfail: number("cluster_slots_pfail")?,
+            slots_fail: number("cluster_slots_fail")?,
+            known_nodes: number("cluster_known_nodes")?,
+            size: number("cluster_size")?,
+            current_epoch: number("cluster_current_epoch")?,
+            my_epoch: number("cluster_my_epoch")?,
+            fields,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -600,4 +1371,486 @@ mod tests {
         let expected: Vec<&SlotAddrs> = vec![&node_1];
         assert_eq!(res, expected);
     }
+
+    #[test]
+    fn test_topology_snapshot_from_slot_map() {
+        let slot_map = SlotMap::new(
+            vec![
+                Slot::new(
+                    0,
+                    4000,
+                    "node1:6379".to_owned(),
+                    vec!["replica1:6379".to_owned()],
+                ),
+                Slot::new(4001, 8000, "node2:6379".to_owned(), vec![]),
+                Slot::new(
+                    8001,
+                    16383,
+                    "node1:6379".to_owned(),
+                    vec!["replica1:6379".to_owned()],
+                ),
+            ],
+            ReadFromReplicaStrategy::AlwaysFromPrimary,
+        );
+        let topology =
+            ClusterTopology::from_slot_map(&slot_map, |address| address != "replica1:6379");
+
+        assert_eq!(topology.slot_ranges.len(), 3);
+        assert_eq!(
+            topology.slot_range_for_key(b"foo"),
+            Some(&SlotRange {
+                start: 8001,
+                end: 16383,
+                primary: "node1:6379".to_owned(),
+                replicas: vec!["replica1:6379".to_owned()],
+            })
+        );
+        assert_eq!(
+            topology.nodes,
+            vec![
+                TopologyNode {
+                    address: "node1:6379".to_owned(),
+                    role: NodeRole::Primary,
+                    slot_ranges: vec![(0, 4000), (8001, 16383)],
+                    replicas: vec!["replica1:6379".to_owned()],
+                    primary: None,
+                    connected: true,
+                },
+                TopologyNode {
+                    address: "node2:6379".to_owned(),
+                    role: NodeRole::Primary,
+                    slot_ranges: vec![(4001, 8000)],
+                    replicas: vec![],
+                    primary: None,
+                    connected: true,
+                },
+                TopologyNode {
+                    address: "replica1:6379".to_owned(),
+                    role: NodeRole::Replica,
+                    slot_ranges: vec![],
+                    replicas: vec![],
+                    primary: Some("node1:6379".to_owned()),
+                    connected: false,
+                },
+            ]
+        );
+        assert_eq!(topology.primaries().count(), 2);
+        assert_eq!(topology.replicas().count(), 1);
+    }
+
+    #[test]
+    fn test_topology_changes() {
+        let old = ClusterTopology::from_slot_map(
+            &SlotMap::new(
+                vec![
+                    Slot::new(
+                        0,
+                        8000,
+                        "node1:6379".to_owned(),
+                        vec!["replica1:6379".to_owned()],
+                    ),
+                    Slot::new(8001, 16383, "node2:6379".to_owned(), vec![]),
+                ],
+                ReadFromReplicaStrategy::AlwaysFromPrimary,
+            ),
+            |_| true,
+        );
+        let new = ClusterTopology::from_slot_map(
+            &SlotMap::new(
+                vec![
+                    Slot::new(0, 8000, "replica1:6379".to_owned(), vec![]),
+                    Slot::new(8001, 9000, "node3:6379".to_owned(), vec![]),
+                    Slot::new(9001, 16383, "node2:6379".to_owned(), vec![]),
+                ],
+                ReadFromReplicaStrategy::AlwaysFromPrimary,
+            ),
+            |address| address != "node2:6379",
+        );
+
+        assert_eq!(
+            old.changes_to(&new),
+            vec![
+                TopologyEvent::NodeAdded {
+                    address: "node3:6379".to_owned(),
+                    role: NodeRole::Primary,
+                },
+                TopologyEvent::PrimaryFailover {
+                    old_primary: "node1:6379".to_owned(),
+                    new_primary: "replica1:6379".to_owned(),
+                },
+                TopologyEvent::SlotsMoved {
+                    start: 0,
+                    end: 8000,
+                    from: Some("node1:6379".to_owned()),
+                    to: Some("replica1:6379".to_owned()),
+                },
+                TopologyEvent::SlotsMoved {
+                    start: 8001,
+                    end: 9000,
+                    from: Some("node2:6379".to_owned()),
+                    to: Some("node3:6379".to_owned()),
+                },
+                TopologyEvent::NodeRemoved {
+                    address: "node1:6379".to_owned(),
+                },
+                TopologyEvent::ConnectionLost {
+                    address: "node2:6379".to_owned(),
+                },
+            ]
+        );
+        assert_eq!(new.changes_to(&new), vec![]);
+        assert_eq!(
+            ClusterTopology::default().changes_to(&ClusterTopology::default()),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_parse_cluster_nodes() {
+        let reply = Value::VerbatimString {
+            format: crate::VerbatimFormat::Text,
+            text: "\
+07c37dfeb235213a872192d90877d0cd55635b91 127.0.0.1:30004@31004,replica-4 slave e7d1eecce10fd6bb5eb35b9f99a514335d9ba9ca 0 1426238317239 4 connected
+e7d1eecce10fd6bb5eb35b9f99a514335d9ba9ca 127.0.0.1:30001@31001,,shard-id=abc myself,master - 0 0 1 connected 0-5460 [5461->-67ed2db8d677e59ec4a4cefb06858cf2a1a89fa1]
+67ed2db8d677e59ec4a4cefb06858cf2a1a89fa1 127.0.0.1:30002@31002 master,fail? - 1426238316232 1426238316232 2 disconnected 5461-10922 [5461-<-e7d1eecce10fd6bb5eb35b9f99a514335d9ba9ca]
+292f8b365bb7edb5e285caf0b7e6ddc7265d2f4f :0@0 noaddr - 0 0 0 connected 16383
+"
+            .to_owned(),
+        };
+        let nodes = ClusterNodes::from_redis_value(&reply).unwrap();
+        assert_eq!(nodes.nodes.len(), 4);
+
+        let replica = &nodes.nodes[0];
+        assert_eq!(replica.address(), "127.0.0.1:30004");
+        assert_eq!(replica.cluster_bus_port, Some(31004));
+        assert_eq!(replica.hostname.as_deref(), Some("replica-4"));
+        assert_eq!(replica.role(), Some(NodeRole::Replica));
+        assert_eq!(replica.pong_received, 1426238317239);
+        assert_eq!(replica.config_epoch, 4);
+
+        let myself = nodes.myself().unwrap();
+        assert_eq!(myself.id, "e7d1eecce10fd6bb5eb35b9f99a514335d9ba9ca");
+        assert_eq!(myself.hostname, None);
+        assert_eq!(myself.primary_id, None);
+        assert_eq!(myself.slots, vec![(0, 5460)]);
+        assert_eq!(
+            myself.migrating,
+            vec![(5461, "67ed2db8d677e59ec4a4cefb06858cf2a1a89fa1".to_owned())]
+        );
+        assert_eq!(
+            nodes.replicas_of(&myself.id).collect::<Vec<_>>(),
+            vec![replica]
+        );
+
+        let failing = nodes
+            .node("67ed2db8d677e59ec4a4cefb06858cf2a1a89fa1")
+            .unwrap();
+        assert!(failing.is_failing());
+        assert!(!failing.link_connected);
+        assert_eq!(failing.slots, vec![(5461, 10922)]);
+        assert_eq!(failing.importing.len(), 1);
+
+        let unknown = &nodes.nodes[3];
+        assert_eq!(unknown.ip, "");
+        assert_eq!(unknown.role(), None);
+        assert_eq!(unknown.slots, vec![(16383, 16383)]);
+
+        assert!(ClusterNodesEntry::parse("id 127.0.0.1:30001@31001 master").is_err());
+        assert!(
+            ClusterNodesEntry::parse("id 127.0.0.1:30001 master - 0 0 1 connected 16384").is_err()
+        );
+    }
+
+    fn shard_node_fields(role: &str, port: i64, health: &str) -> Vec<(Value, Value)> {
+        vec![
+            (
+                Value::BulkString(b"id".to_vec()),
+                Value::BulkString(format!("id-{port}").into_bytes()),
+            ),
+            (Value::BulkString(b"port".to_vec()), Value::Int(port)),
+            (
+                Value::BulkString(b"ip".to_vec()),
+                Value::BulkString(b"127.0.0.1".to_vec()),
+            ),
+            (
+                Value::BulkString(b"endpoint".to_vec()),
+                Value::BulkString(b"127.0.0.1".to_vec()),
+            ),
+            (
+                Value::BulkString(b"hostname".to_vec()),
+                Value::BulkString(vec![]),
+            ),
+            (
+                Value::BulkString(b"role".to_vec()),
+                Value::BulkString(role.as_bytes().to_vec()),
+            ),
+            (
+                Value::BulkString(b"replication-offset".to_vec()),
+                Value::Int(72156),
+            ),
+            (
+                Value::BulkString(b"health".to_vec()),
+                Value::BulkString(health.as_bytes().to_vec()),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_parse_cluster_shards() {
+        let flatten = |fields: Vec<(Value, Value)>| {
+            Value::Array(fields.into_iter().flat_map(|(k, v)| [k, v]).collect())
+        };
+        // RESP2 replies are flat arrays of field names and values.
+        let resp2 = Value::Array(vec![flatten(vec![
+            (
+                Value::BulkString(b"slots".to_vec()),
+                Value::Array(vec![
+                    Value::Int(0),
+                    Value::Int(5460),
+                    Value::Int(10923),
+                    Value::Int(10923),
+                ]),
+            ),
+            (
+                Value::BulkString(b"nodes".to_vec()),
+                Value::Array(vec![
+                    flatten(shard_node_fields("master", 30001, "online")),
+                    flatten(shard_node_fields("replica", 30004, "loading")),
+                ]),
+            ),
+        ])]);
+        // RESP3 replies are maps.
+        let resp3 = Value::Array(vec![Value::Map(vec![
+            (
+                Value::BulkString(b"slots".to_vec()),
+                Value::Array(vec![
+                    Value::Int(0),
+                    Value::Int(5460),
+                    Value::Int(10923),
+                    Value::Int(10923),
+                ]),
+            ),
+            (
+                Value::BulkString(b"nodes".to_vec()),
+                Value::Array(vec![
+                    Value::Map(shard_node_fields("master", 30001, "online")),
+                    Value::Map(shard_node_fields("replica", 30004, "loading")),
+                ]),
+            ),
+        ])]);
+
+        for reply in [resp2, resp3] {
+            let shards: Vec<ClusterShard> = FromRedisValue::from_redis_value(&reply).unwrap();
+            assert_eq!(shards.len(), 1);
+            let shard = &shards[0];
+            assert_eq!(shard.slots, vec![(0, 5460), (10923, 10923)]);
+            assert_eq!(
+                shard.primary(),
+                Some(&ClusterShardNode {
+                    id: "id-30001".to_owned(),
+                    endpoint: "127.0.0.1".to_owned(),
+                    ip: "127.0.0.1".to_owned(),
+                    hostname: None,
+                    port: Some(30001),
+                    tls_port: None,
+                    role: NodeRole::Primary,
+                    replication_offset: 72156,
+                    health: ShardNodeHealth::Online,
+                })
+            );
+            let replicas: Vec<_> = shard.replicas().collect();
+            assert_eq!(replicas.len(), 1);
+            assert_eq!(replicas[0].health, ShardNodeHealth::Loading);
+        }
+
+        let unknown_health = Value::Map(shard_node_fields("master", 30001, "sleeping"));
+        assert!(ClusterShardNode::from_redis_value(&unknown_health).is_err());
+    }
+
+    fn shard_value(slots: &[i64], nodes: Vec<Vec<(&str, Value)>>) -> Value {
+        let nodes = nodes
+            .into_iter()
+            .map(|fields| {
+                Value::Map(
+                    fields
+                        .into_iter()
+                        .map(|(name, value)| (Value::BulkString(name.as_bytes().to_vec()), value))
+                        .collect(),
+                )
+            })
+            .collect();
+        Value::Map(vec![
+            (
+                Value::BulkString(b"slots".to_vec()),
+                Value::Array(slots.iter().map(|slot| Value::Int(*slot)).collect()),
+            ),
+            (Value::BulkString(b"nodes".to_vec()), Value::Array(nodes)),
+        ])
+    }
+
+    fn shard_node(
+        endpoint: &str,
+        port: Option<i64>,
+        tls_port: Option<i64>,
+        role: &str,
+        health: &str,
+    ) -> Vec<(&'static str, Value)> {
+        let mut fields = vec![
+            (
+                "id",
+                Value::BulkString(format!("{endpoint}-{role}").into_bytes()),
+            ),
+            ("ip", Value::BulkString(b"10.0.0.1".to_vec())),
+            ("endpoint", Value::BulkString(endpoint.as_bytes().to_vec())),
+            ("role", Value::BulkString(role.as_bytes().to_vec())),
+            ("replication-offset", Value::Int(0)),
+            ("health", Value::BulkString(health.as_bytes().to_vec())),
+        ];
+        if let Some(port) = port {
+            fields.push(("port", Value::Int(port)));
+        }
+        if let Some(tls_port) = tls_port {
+            fields.push(("tls-port", Value::Int(tls_port)));
+        }
+        fields
+    }
+
+    #[test]
+    fn parse_shards_skips_unhealthy_nodes() {
+        let view = Value::Array(vec![
+            shard_value(
+                &[8001, 16383],
+                vec![
+                    shard_node("primary2", Some(6379), None, "master", "online"),
+                    shard_node("replica2", Some(6379), None, "replica", "online"),
+                ],
+            ),
+            shard_value(
+                &[0, 4000, 4001, 8000],
+                vec![
+                    shard_node("primary1", Some(6379), None, "master", "online"),
+                    shard_node("replica1_2", Some(6379), None, "replica", "loading"),
+                    shard_node("replica1_1", Some(6379), None, "replica", "online"),
+                    shard_node("replica1_3", Some(6379), None, "replica", "failed"),
+                ],
+            ),
+            // A shard whose primary failed doesn't cover its slots.
+            shard_value(
+                &[],
+                vec![shard_node("primary3", Some(6379), None, "master", "failed")],
+            ),
+        ]);
+        let (slot_count, slots) = parse_and_count_slots(&view, None, "node").unwrap();
+        assert_eq!(slot_count, 16381);
+        assert_eq!(
+            slots,
+            vec![
+                Slot::new(
+                    0,
+                    4000,
+                    "primary1:6379".to_owned(),
+                    vec!["replica1_1:6379".to_owned()]
+                ),
+                Slot::new(
+                    4001,
+                    8000,
+                    "primary1:6379".to_owned(),
+                    vec!["replica1_1:6379".to_owned()]
+                ),
+                Slot::new(
+                    8001,
+                    16383,
+                    "primary2:6379".to_owned(),
+                    vec!["replica2:6379".to_owned()]
+                ),
+            ]
+        );
+
+        let view = Value::Array(vec![shard_value(
+            &[0, 16383],
+            vec![shard_node("primary", Some(6379), None, "master", "failed")],
+        )]);
+        assert!(parse_and_count_slots(&view, None, "node").is_err());
+    }
+
+    #[test]
+    fn parse_shards_and_slots_return_the_same_view() {
+        let shards = Value::Array(vec![
+            shard_value(
+                &[4001, 16383],
+                vec![shard_node("primary2", Some(6380), None, "master", "online")],
+            ),
+            shard_value(
+                &[0, 4000],
+                vec![
+                    shard_node("primary1", Some(6379), None, "master", "online"),
+                    shard_node("replica1", Some(6381), None, "replica", "online"),
+                ],
+            ),
+        ]);
+        let slots = Value::Array(vec![
+            slot_value_with_replicas(0, 4000, vec![("primary1", 6379), ("replica1", 6381)]),
+            slot_value(4001, 16383, "primary2", 6380),
+        ]);
+        let res1 = parse_and_count_slots(&shards, None, "node").unwrap();
+        let res2 = parse_and_count_slots(&slots, None, "node").unwrap();
+        assert_eq!(calculate_hash(&res1), calculate_hash(&res2));
+    }
+
+    #[test]
+    fn parse_shards_uses_tls_port_and_answering_node() {
+        let view = Value::Array(vec![shard_value(
+            &[0, 16383],
+            vec![
+                shard_node("", Some(6379), Some(16379), "master", "online"),
+                shard_node("replica", None, Some(16380), "replica", "online"),
+                shard_node("?", Some(6381), None, "replica", "online"),
+            ],
+        )]);
+
+        let (_, slots) = parse_and_count_slots(&view, Some(TlsMode::Secure), "node").unwrap();
+        assert_eq!(
+            slots,
+            vec![Slot::new(
+                0,
+                16383,
+                "node:16379".to_owned(),
+                vec!["replica:16380".to_owned()]
+            )]
+        );
+
+        // Without TLS, the replica that only has a TLS port can't be used.
+        let (_, slots) = parse_and_count_slots(&view, None, "node").unwrap();
+        assert_eq!(
+            slots,
+            vec![Slot::new(0, 16383, "node:6379".to_owned(), vec![])]
+        );
+    }
+
+    #[test]
+    fn test_parse_cluster_info() {
+        let reply = Value::BulkString(
+            b"cluster_state:ok\r\ncluster_slots_assigned:16384\r\ncluster_slots_ok:16384\r\n\
+cluster_slots_pfail:0\r\ncluster_slots_fail:0\r\ncluster_known_nodes:6\r\ncluster_size:3\r\n\
+cluster_current_epoch:6\r\ncluster_my_epoch:2\r\ncluster_stats_messages_sent:1483972\r\n"
+                .to_vec(),
+        );
+        let info = ClusterInfo::from_redis_value(&reply).unwrap();
+        assert!(info.state_ok);
+        assert_eq!(info.slots_assigned, 16384);
+        assert_eq!(info.slots_ok, 16384);
+        assert_eq!(info.known_nodes, 6);
+        assert_eq!(info.size, 3);
+        assert_eq!(info.current_epoch, 6);
+        assert_eq!(info.my_epoch, 2);
+        assert_eq!(
+            info.fields
+                .get("cluster_stats_messages_sent")
+                .map(String::as_str),
+            Some("1483972")
+        );
+
+        let reply = Value::BulkString(b"cluster_state:fail\r\ncluster_size:x\r\n".to_vec());
+        assert!(ClusterInfo::from_redis_value(&reply).is_err());
+    }
 }
@@ -8,9 +8,21 @@ use futures_util::{
 use std::pin::Pin;
 use std::{fmt, io};
 
+#[cfg(feature = "cluster-async")]
+use crate::cluster_scan::AsyncClusterScanner;
+#[cfg(feature = "cluster")]
+use crate::cluster_scan::{ClusterScanCursor, ClusterScanner};
 use crate::connection::ConnectionLike;
 use crate::pipeline::Pipeline;
-use crate::types::{from_owned_redis_value, FromRedisValue, RedisResult, RedisWrite, ToRedisArgs};
+#[cfg(feature = "cluster")]
+use crate::types::Value;
+use crate::types::{
+    from_owned_redis_value, FromRedisValue, RedisError, RedisResult, RedisWrite, ToRedisArgs,
+};
+#[cfg(feature = "cluster")]
+use crate::ScanOptions;
+#[cfg(feature = "cluster")]
+use tracing::warn;
 
 /// An argument to a redis command
 #[derive(Clone)]
@@ -35,9 +47,56 @@ pub struct Cmd {
 /// Represents a redis iterator.
 pub struct Iter<'a, T: FromRedisValue> {
     batch: std::vec::IntoIter<T>,
-    cursor: u64,
-    con: &'a mut (dyn ConnectionLike + 'a),
-    cmd: Cmd,
+    source: IterSource<'a>,
+}
+
+/// Where the batches of an `Iter` come from.
+enum IterSource<'a> {
+    /// A command with a `cursor_arg`, sent to a single connection.
+    Cursor {
+        cursor: u64,
+        con: &'a mut (dyn ConnectionLike + 'a),
+        cmd: Cmd,
+    },
+    /// A `SCAN` over all the primaries of a cluster.
+    #[cfg(feature = "cluster")]
+    ClusterScan {
+        con: &'a mut (dyn ClusterScanner + 'a),
+        cursor: ClusterScanCursor,
+        options: ScanOptions,
+        // The error that ended the scan before all the primaries were scanned.
+        error: Option<RedisError>,
+    },
+}
+
+impl<'a, T: FromRedisValue> Iter<'a, T> {
+    #[cfg(feature = "cluster")]
+    pub(crate) fn cluster_scan(
+        con: &'a mut (dyn ClusterScanner + 'a),
+        options: ScanOptions,
+    ) -> Self {
+        Iter {
+            batch: Vec::new().into_iter(),
+            source: IterSource::ClusterScan {
+                con,
+                cursor: ClusterScanCursor::default(),
+                options,
+                error: None,
+            },
+        }
+    }
+
+    /// Returns the error that ended a cluster scan before all the primaries were scanned, if
+    /// there was one. The iterator ends once a `SCAN` fails or its keys can't be parsed, so
+    /// without checking this, a failed scan looks like a finished one. Other iterators always
+    /// return `None`.
+    pub fn take_error(&mut self) -> Option<RedisError> {
+        match &mut self.source {
+            IterSource::Cursor { .. } => None,
+            #[cfg(feature = "cluster")]
+            IterSource::ClusterScan { error, .. } => error.take(),
+        }
+    }
 }
 
 impl<'a, T: FromRedisValue> Iterator for Iter<'a, T> {
@@ -53,15 +112,44 @@ impl<'a, T: FromRedisValue> Iterator for Iter<'a, T> {
             if let Some(v) = self.batch.next() {
                 return Some(v);
             };
-            if self.cursor == 0 {
-                return None;
-            }
-
-            let pcmd = self.cmd.get_packed_command_with_cursor(self.cursor)?;
-            let rv = self.con.req_packed_command(&pcmd).ok()?;
-            let (cur, batch): (u64, Vec<T>) = from_owned_redis_value(rv).ok()?;
+            let batch: Vec<T> = match &mut self.source {
+                IterSource::Cursor { cursor, con, cmd } => {
+                    if *cursor == 0 {
+                        return None;
+                    }
+
+                    let pcmd = cmd.get_packed_command_with_cursor(*cursor)?;
+                    let rv = con.req_packed_command(&pcmd).ok()?;
+                    let (cur, batch): (u64, Vec<T>) = from_owned_redis_value(rv).ok()?;
+
+                    *cursor = cur;
+                    batch
+                }
+                #[cfg(feature = "cluster")]
+                IterSource::ClusterScan {
+                    con,
+                    cursor,
+                    options,
+                    error,
+                } => {
+                    if cursor.is_finished() || error.is_some() {
+                        return None;
+                    }
+
+                    let keys = con
+                        .scan_step(cursor, options)
+                        .and_then(|keys| from_owned_redis_value::<Vec<T>>(Value::Array(keys)));
+                    match keys {
+                        Ok(keys) => keys,
+                        Err(err) => {
+                            warn!("Cluster scan stopped before it was finished: {err}");
+                            *error = Some(err);
+                            return None;
+                        }
+                    }
+                }
+            };
 
-            self.cursor = cur;
             self.batch = batch.into_iter();
         }
     }
@@ -74,8 +162,27 @@ use crate::aio::ConnectionLike as AsyncConnection;
 #[cfg(feature = "aio")]
 struct AsyncIterInner<'a, T: FromRedisValue + 'a> {
     batch: std::vec::IntoIter<T>,
-    con: &'a mut (dyn AsyncConnection + Send + 'a),
-    cmd: Cmd,
+    source: AsyncIterSource<'a>,
+}
+
+/// Where the batches of an `AsyncIter` come from.
+#[cfg(feature = "aio")]
+enum AsyncIterSource<'a> {
+    /// A command with a `cursor_arg`, sent to a single connection.
+    Cursor {
+        con: &'a mut (dyn AsyncConnection + Send + 'a),
+        cmd: Cmd,
+    },
+    /// A `SCAN` over all the primaries of a cluster.
+    #[cfg(feature = "cluster-async")]
+    ClusterScan {
+        con: &'a mut (dyn AsyncClusterScanner + 'a),
+        cursor: ClusterScanCursor,
+        options: ScanOptions,
+        // The error that ended the scan before all the primaries were scanned, boxed to keep the
+        // pending state of `AsyncIter` small.
+        error: Option<Box<RedisError>>,
+    },
 }
 
 /// Represents the state of AsyncIter
@@ -104,23 +211,89 @@ impl<'a, T: FromRedisValue + 'a> AsyncIterInner<'a, T> {
             if let Some(v) = self.batch.next() {
                 return Some(v);
             };
-            if let Some(cursor) = self.cmd.cursor {
-                if cursor == 0 {
-                    return None;
+            let batch: Vec<T> = match &mut self.source {
+                AsyncIterSource::Cursor { con, cmd } => {
+                    if let Some(cursor) = cmd.cursor {
+                        if cursor == 0 {
+                            return None;
+                        }
+                    } else {
+                        return None;
+                    }
+
+                    let rv = con.req_packed_command(cmd).await.ok()?;
+                    let (cur, batch): (u64, Vec<T>) = from_owned_redis_value(rv).ok()?;
+
+                    cmd.cursor = Some(cur);
+                    batch
                 }
-            } else {
-                return None;
-            }
-
-            let rv = self.con.req_packed_command(&self.cmd).await.ok()?;
-            let (cur, batch): (u64, Vec<T>) = from_owned_redis_value(rv).ok()?;
+                #[cfg(feature = "cluster-async")]
+                AsyncIterSource::ClusterScan {
+                    con,
+                    cursor,
+                    options,
+                    error,
+                } => {
+                    if cursor.is_finished() || error.is_some() {
+                        return None;
+                    }
+
+                    let keys = con
+                        .scan_step(cursor, options)
+                        .await
+                        .and_then(|keys| from_owned_redis_value::<Vec<T>>(Value::Array(keys)));
+                    match keys {
+                        Ok(keys) => keys,
+                        Err(err) => {
+                            warn!("Cluster scan stopped before it was finished: {err}");
+                            *error = Some(Box::new(err));
+                            return None;
+                        }
+                    }
+                }
+            };
 
-            self.cmd.cursor = Some(cur);
             self.batch = batch.into_iter();
         }
     }
 }
 
+#[cfg(feature = "aio")]
+impl<'a, T: FromRedisValue + 'a> AsyncIter<'a, T> {
+    #[cfg(feature = "cluster-async")]
+    pub(crate) fn cluster_scan(
+        con: &'a mut (dyn AsyncClusterScanner + 'a),
+        options: ScanOptions,
+    ) -> Self {
+        AsyncIter {
+            inner: IterOrFuture::Iter(AsyncIterInner {
+                batch: Vec::new().into_iter(),
+                source: AsyncIterSource::ClusterScan {
+                    con,
+                    cursor: ClusterScanCursor::default(),
+                    options,
+                    error: None,
+                },
+            }),
+        }
+    }
+
+    /// Returns the error that ended a cluster scan before all the primaries were scanned, if
+    /// there was one. The stream ends once a `SCAN` fails or its keys can't be parsed, so
+    /// without checking this, a failed scan looks like a finished one. Other iterators, and
+    /// iterators that are in the middle of fetching keys, always return `None`.
+    pub fn take_error(&mut self) -> Option<RedisError> {
+        match &mut self.inner {
+            #[cfg(feature = "cluster-async")]
+            IterOrFuture::Iter(AsyncIterInner {
+                source: AsyncIterSource::ClusterScan { error, .. },
+                ..
+            }) => error.take().map(|err| *err),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(feature = "aio")]
 impl<'a, T: FromRedisValue + 'a + Unpin + Send> AsyncIter<'a, T> {
     /// ```rust,no_run
@@ -465,9 +638,11 @@ impl Cmd {
 
         Ok(Iter {
             batch: batch.into_iter(),
-            cursor,
-            con,
-            cmd: self,
+            source: IterSource::Cursor {
+                cursor,
+                con,
+                cmd: self,
+            },
         })
     }
 
@@ -508,8 +683,7 @@ impl Cmd {
         Ok(AsyncIter {
             inner: IterOrFuture::Iter(AsyncIterInner {
                 batch: batch.into_iter(),
-                con,
-                cmd: self,
+                source: AsyncIterSource::Cursor { con, cmd: self },
             }),
         })
     }
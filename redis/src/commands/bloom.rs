@@ -0,0 +1,673 @@
+// can't use rustfmt here because it screws up the file.
+#![cfg_attr(rustfmt, rustfmt_skip)]
+use crate::cmd::{cmd, Cmd};
+use crate::connection::ConnectionLike;
+use crate::pipeline::Pipeline;
+use crate::bloom::{BfInsertOptions, BfReserveOptions, CfInsertOptions, CfReserveOptions, TDigestMergeOptions, TopKReserveOptions};
+use crate::types::{FromRedisValue, RedisResult, ToRedisArgs};
+
+#[cfg(feature = "cluster")]
+use crate::commands::ClusterPipeline;
+
+macro_rules! implement_bloom_commands {
+    (
+        $lifetime: lifetime
+        $(
+            $(#[$attr:meta])+
+            fn $name:ident<$($tyargs:ident : $ty:ident),*>(
+                $($argname:ident: $argty:ty),*) $body:block
+        )*
+    ) => (
+
+        /// Implements RedisBloom commands for connection like objects.  This
+        /// allows you to send commands straight to a connection or client.  It
+        /// is also implemented for redis results of clients which makes for
+        /// very convenient access in some basic cases.
+        ///
+        /// This allows you to use nicer syntax for some common operations.
+        /// For instance this code:
+        ///
+        /// ```rust,no_run
+        /// # fn do_something() -> redis::RedisResult<()> {
+        /// let client = redis::Client::open("redis://127.0.0.1/")?;
+        /// let mut con = client.get_connection()?;
+        /// redis::cmd("BF.RESERVE").arg("visitors").arg(0.01).arg(1000).execute(&mut con);
+        /// let seen: Vec<bool> = redis::cmd("BF.MEXISTS").arg("visitors").arg("alice").arg("bob").query(&mut con)?;
+        /// # Ok(()) }
+        /// ```
+        ///
+        /// Will become this:
+        ///
+        /// ```rust,no_run
+        /// use redis::BloomCommands;
+        /// use redis::bloom::BfReserveOptions;
+        /// # fn do_something() -> redis::RedisResult<()> {
+        /// let client = redis::Client::open("redis://127.0.0.1/")?;
+        /// let mut con = client.get_connection()?;
+        /// let _: () = con.bf_reserve("visitors", 0.01, 1000, &BfReserveOptions::default())?;
+        /// let seen: Vec<bool> = con.bf_mexists("visitors", &["alice", "bob"])?;
+        /// # Ok(()) }
+        /// ```
+        pub trait BloomCommands : ConnectionLike + Sized {
+            $(
+                $(#[$attr])*
+                #[inline]
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                fn $name<$lifetime, $($tyargs: $ty, )* RV: FromRedisValue>(
+                    &mut self $(, $argname: $argty)*) -> RedisResult<RV>
+                    { Cmd::$name($($argname),*).query(self) }
+            )*
+        }
+
+        impl Cmd {
+            $(
+                $(#[$attr])*
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                pub fn $name<$lifetime, $($tyargs: $ty),*>($($argname: $argty),*) -> Self {
+                    $body
+                }
+            )*
+        }
+
+        /// Implements RedisBloom commands over asynchronous connections. This
+        /// allows you to send commands straight to a connection or client.
+        ///
+        /// This allows you to use nicer syntax for some common operations.
+        /// For instance this code:
+        ///
+        /// ```rust,no_run
+        /// # async fn do_something() -> redis::RedisResult<()> {
+        /// let client = redis::Client::open("redis://127.0.0.1/")?;
+        /// let mut con = client.get_async_connection().await?;
+        /// let added: bool = redis::cmd("BF.ADD").arg("visitors").arg("alice").query_async(&mut con).await?;
+        /// # Ok(()) }
+        /// ```
+        ///
+        /// Will become this:
+        ///
+        /// ```rust,no_run
+        /// use redis::BloomAsyncCommands;
+        /// # async fn do_something() -> redis::RedisResult<()> {
+        /// let client = redis::Client::open("redis://127.0.0.1/")?;
+        /// let mut con = client.get_async_connection().await?;
+        /// let added: bool = con.bf_add("visitors", "alice").await?;
+        /// # Ok(()) }
+        /// ```
+        #[cfg(feature = "aio")]
+        pub trait BloomAsyncCommands : crate::aio::ConnectionLike + Send + Sized {
+            $(
+                $(#[$attr])*
+                #[inline]
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                fn $name<$lifetime, $($tyargs: $ty + Send + Sync + $lifetime,)* RV>(
+                    & $lifetime mut self
+                    $(, $argname: $argty)*
+                ) -> $crate::types::RedisFuture<'a, RV>
+                where
+                    RV: FromRedisValue,
+                {
+                    Box::pin(async move {
+                        $body.query_async(self).await
+                    })
+                }
+            )*
+        }
+
+        /// Implements RedisBloom commands for pipelines.  Unlike the regular
+        /// commands trait, this returns the pipeline rather than a result
+        /// directly.  Other than that it works the same however.
+        impl Pipeline {
+            $(
+                $(#[$attr])*
+                #[inline]
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                pub fn $name<$lifetime, $($tyargs: $ty),*>(
+                    &mut self $(, $argname: $argty)*
+                ) -> &mut Self {
+                    self.add_command($body)
+                }
+            )*
+        }
+
+        /// Implements RedisBloom commands for cluster pipelines.  Unlike the regular
+        /// commands trait, this returns the cluster pipeline rather than a result
+        /// directly.  Other than that it works the same however.
+        #[cfg(feature = "cluster")]
+        impl ClusterPipeline {
+            $(
+                $(#[$attr])*
+                #[inline]
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                pub fn $name<$lifetime, $($tyargs: $ty),*>(
+                    &mut self $(, $argname: $argty)*
+                ) -> &mut Self {
+                    self.add_command($body)
+                }
+            )*
+        }
+
+    )
+}
+
+implement_bloom_commands! {
+    'a
+
+    /// Creates an empty Bloom filter with the given false positive rate and initial capacity.
+    ///
+    /// ```text
+    /// BF.RESERVE <key> <error_rate> <capacity> [EXPANSION expansion] [NONSCALING]
+    /// ```
+    fn bf_reserve<K: ToRedisArgs>(key: K, error_rate: f64, capacity: u64, options: &'a BfReserveOptions) {
+        let mut cmd = cmd("BF.RESERVE");
+
+        cmd.arg(key)
+           .arg(error_rate)
+           .arg(capacity)
+           .arg(options);
+
+        cmd
+    }
+
+    /// Adds an item to a Bloom filter, creating it if needed. Returns `false` if the item may already have been added.
+    fn bf_add<K: ToRedisArgs, I: ToRedisArgs>(key: K, item: I) {
+        let mut cmd = cmd("BF.ADD");
+
+        cmd.arg(key)
+           .arg(item);
+
+        cmd
+    }
+
+    /// Adds multiple items to a Bloom filter, creating it if needed.
+    fn bf_madd<K: ToRedisArgs, I: ToRedisArgs>(key: K, items: I) {
+        let mut cmd = cmd("BF.MADD");
+
+        cmd.arg(key)
+           .arg(items);
+
+        cmd
+    }
+
+    /// Adds multiple items to a Bloom filter with the given `options`, which also
+    /// configure the filter if it is created by the command.
+    ///
+    /// ```text
+    /// BF.INSERT <key> [CAPACITY capacity] [ERROR error] ... ITEMS <item> ...
+    /// ```
+    fn bf_insert<K: ToRedisArgs, I: ToRedisArgs>(key: K, items: I, options: &'a BfInsertOptions) {
+        let mut cmd = cmd("BF.INSERT");
+
+        cmd.arg(key)
+           .arg(options)
+           .arg("ITEMS")
+           .arg(items);
+
+        cmd
+    }
+
+    /// Checks whether an item may have been added to a Bloom filter.
+    fn bf_exists<K: ToRedisArgs, I: ToRedisArgs>(key: K, item: I) {
+        let mut cmd = cmd("BF.EXISTS");
+
+        cmd.arg(key)
+           .arg(item);
+
+        cmd
+    }
+
+    /// Checks whether each of the items may have been added to a Bloom filter.
+    fn bf_mexists<K: ToRedisArgs, I: ToRedisArgs>(key: K, items: I) {
+        let mut cmd = cmd("BF.MEXISTS");
+
+        cmd.arg(key)
+           .arg(items);
+
+        cmd
+    }
+
+    /// Returns the number of items added to a Bloom filter.
+    fn bf_card<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("BF.CARD");
+
+        cmd.arg(key);
+
+        cmd
+    }
+
+    /// Returns information about a Bloom filter, which can be converted to a
+    /// [`BfInfo`](crate::bloom::BfInfo).
+    fn bf_info<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("BF.INFO");
+
+        cmd.arg(key);
+
+        cmd
+    }
+
+    /// Creates an empty Cuckoo filter with the given initial capacity.
+    ///
+    /// ```text
+    /// CF.RESERVE <key> <capacity> [BUCKETSIZE size] [MAXITERATIONS iterations] [EXPANSION expansion]
+    /// ```
+    fn cf_reserve<K: ToRedisArgs>(key: K, capacity: u64, options: &'a CfReserveOptions) {
+        let mut cmd = cmd("CF.RESERVE");
+
+        cmd.arg(key)
+           .arg(capacity)
+           .arg(options);
+
+        cmd
+    }
+
+    /// Adds an item to a Cuckoo filter, creating it if needed.
+    fn cf_add<K: ToRedisArgs, I: ToRedisArgs>(key: K, item: I) {
+        let mut cmd = cmd("CF.ADD");
+
+        cmd.arg(key)
+           .arg(item);
+
+        cmd
+    }
+
+    /// Adds an item to a Cuckoo filter if it may not have been added yet.
+    /// Returns `false` if the item may already have been added.
+    fn cf_addnx<K: ToRedisArgs, I: ToRedisArgs>(key: K, item: I) {
+        let mut cmd = cmd("CF.ADDNX");
+
+        cmd.arg(key)
+           .arg(item);
+
+        cmd
+    }
+
+    /// Adds multiple items to a Cuckoo filter with the given `options`.
+    ///
+    /// ```text
+    /// CF.INSERT <key> [CAPACITY capacity] [NOCREATE] ITEMS <item> ...
+    /// ```
+    fn cf_insert<K: ToRedisArgs, I: ToRedisArgs>(key: K, items: I, options: &'a CfInsertOptions) {
+        let mut cmd = cmd("CF.INSERT");
+
+        cmd.arg(key)
+           .arg(options)
+           .arg("ITEMS")
+           .arg(items);
+
+        cmd
+    }
+
+    /// Adds the items that may not have been added yet to a Cuckoo filter with the given `options`.
+    fn cf_insertnx<K: ToRedisArgs, I: ToRedisArgs>(key: K, items: I, options: &'a CfInsertOptions) {
+        let mut cmd = cmd("CF.INSERTNX");
+
+        cmd.arg(key)
+           .arg(options)
+           .arg("ITEMS")
+           .arg(items);
+
+        cmd
+    }
+
+    /// Checks whether an item may have been added to a Cuckoo filter.
+    fn cf_exists<K: ToRedisArgs, I: ToRedisArgs>(key: K, item: I) {
+        let mut cmd = cmd("CF.EXISTS");
+
+        cmd.arg(key)
+           .arg(item);
+
+        cmd
+    }
+
+    /// Checks whether each of the items may have been added to a Cuckoo filter.
+    fn cf_mexists<K: ToRedisArgs, I: ToRedisArgs>(key: K, items: I) {
+        let mut cmd = cmd("CF.MEXISTS");
+
+        cmd.arg(key)
+           .arg(items);
+
+        cmd
+    }
+
+    /// Deletes one occurrence of an item from a Cuckoo filter.
+    fn cf_del<K: ToRedisArgs, I: ToRedisArgs>(key: K, item: I) {
+        let mut cmd = cmd("CF.DEL");
+
+        cmd.arg(key)
+           .arg(item);
+
+        cmd
+    }
+
+    /// Returns an estimate of the number of times an item was added to a Cuckoo filter.
+    fn cf_count<K: ToRedisArgs, I: ToRedisArgs>(key: K, item: I) {
+        let mut cmd = cmd("CF.COUNT");
+
+        cmd.arg(key)
+           .arg(item);
+
+        cmd
+    }
+
+    /// Returns information about a Cuckoo filter, which can be converted to a
+    /// [`CfInfo`](crate::bloom::CfInfo).
+    fn cf_info<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("CF.INFO");
+
+        cmd.arg(key);
+
+        cmd
+    }
+
+    /// Creates a Count-Min sketch with the given dimensions.
+    fn cms_initbydim<K: ToRedisArgs>(key: K, width: u64, depth: u64) {
+        let mut cmd = cmd("CMS.INITBYDIM");
+
+        cmd.arg(key)
+           .arg(width)
+           .arg(depth);
+
+        cmd
+    }
+
+    /// Creates a Count-Min sketch sized for the given error rate and probability of a bad estimate.
+    fn cms_initbyprob<K: ToRedisArgs>(key: K, error: f64, probability: f64) {
+        let mut cmd = cmd("CMS.INITBYPROB");
+
+        cmd.arg(key)
+           .arg(error)
+           .arg(probability);
+
+        cmd
+    }
+
+    /// Increments the counts of items, given as `(item, increment)` pairs, and returns their new counts.
+    fn cms_incrby<K: ToRedisArgs, I: ToRedisArgs>(key: K, items: &'a [(I, u64)]) {
+        let mut cmd = cmd("CMS.INCRBY");
+
+        cmd.arg(key)
+           .arg(items);
+
+        cmd
+    }
+
+    /// Returns the counts of the items in a Count-Min sketch.
+    fn cms_query<K: ToRedisArgs, I: ToRedisArgs>(key: K, items: I) {
+        let mut cmd = cmd("CMS.QUERY");
+
+        cmd.arg(key)
+           .arg(items);
+
+        cmd
+    }
+
+    /// Merges the Count-Min sketches `sources` into `dest`, which must have the same dimensions.
+    fn cms_merge<D: ToRedisArgs, S: ToRedisArgs>(dest: D, sources: &'a [S]) {
+        let mut cmd = cmd("CMS.MERGE");
+
+        cmd.arg(dest)
+           .arg(sources.len())
+           .arg(sources);
+
+        cmd
+    }
+
+    /// Merges the Count-Min sketches `sources` into `dest`, multiplying the counts
+    /// of each source by its weight.
+    fn cms_merge_weighted<D: ToRedisArgs, S: ToRedisArgs>(dest: D, sources: &'a [(S, u64)]) {
+        let mut cmd = cmd("CMS.MERGE");
+
+        cmd.arg(dest)
+           .arg(sources.len())
+           .arg(sources.iter().map(|(source, _)| source).collect::<Vec<_>>())
+           .arg("WEIGHTS")
+           .arg(sources.iter().map(|(_, weight)| weight).collect::<Vec<_>>());
+
+        cmd
+    }
+
+    /// Returns information about a Count-Min sketch, which can be converted to a
+    /// [`CmsInfo`](crate::bloom::CmsInfo).
+    fn cms_info<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("CMS.INFO");
+
+        cmd.arg(key);
+
+        cmd
+    }
+
+    /// Creates a Top-K sketch keeping the `k` most frequent items.
+    ///
+    /// ```text
+    /// TOPK.RESERVE <key> <k> [<width> <depth> <decay>]
+    /// ```
+    fn topk_reserve<K: ToRedisArgs>(key: K, k: u64, options: &'a TopKReserveOptions) {
+        let mut cmd = cmd("TOPK.RESERVE");
+
+        cmd.arg(key)
+           .arg(k)
+           .arg(options);
+
+        cmd
+    }
+
+    /// Adds items to a Top-K sketch. Returns, for each item, the item it expelled
+    /// from the top list if any.
+    fn topk_add<K: ToRedisArgs, I: ToRedisArgs>(key: K, items: I) {
+        let mut cmd = cmd("TOPK.ADD");
+
+        cmd.arg(key)
+           .arg(items);
+
+        cmd
+    }
+
+    /// Increments the counts of items, given as `(item, increment)` pairs. Returns,
+    /// for each item, the item it expelled from the top list if any.
+    fn topk_incrby<K: ToRedisArgs, I: ToRedisArgs>(key: K, items: &'a [(I, u64)]) {
+        let mut cmd = cmd("TOPK.INCRBY");
+
+        cmd.arg(key)
+           .arg(items);
+
+        cmd
+    }
+
+    /// Checks whether each of the items is in the top list.
+    fn topk_query<K: ToRedisArgs, I: ToRedisArgs>(key: K, items: I) {
+        let mut cmd = cmd("TOPK.QUERY");
+
+        cmd.arg(key)
+           .arg(items);
+
+        cmd
+    }
+
+    /// Returns the top list.
+    fn topk_list<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("TOPK.LIST");
+
+        cmd.arg(key);
+
+        cmd
+    }
+
+    /// Returns the top list along with the count of each item, which can be converted
+    /// to a `Vec<(String, u64)>`.
+    fn topk_list_with_count<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("TOPK.LIST");
+
+        cmd.arg(key)
+           .arg("WITHCOUNT");
+
+        cmd
+    }
+
+    /// Returns information about a Top-K sketch, which can be converted to a
+    /// [`TopKInfo`](crate::bloom::TopKInfo).
+    fn topk_info<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("TOPK.INFO");
+
+        cmd.arg(key);
+
+        cmd
+    }
+
+    /// Creates an empty t-digest sketch.
+    fn tdigest_create<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("TDIGEST.CREATE");
+
+        cmd.arg(key);
+
+        cmd
+    }
+
+    /// Creates an empty t-digest sketch with the given compression, trading accuracy for memory.
+    fn tdigest_create_compression<K: ToRedisArgs>(key: K, compression: u64) {
+        let mut cmd = cmd("TDIGEST.CREATE");
+
+        cmd.arg(key)
+           .arg("COMPRESSION")
+           .arg(compression);
+
+        cmd
+    }
+
+    /// Adds observations to a t-digest sketch.
+    fn tdigest_add<K: ToRedisArgs>(key: K, values: &'a [f64]) {
+        let mut cmd = cmd("TDIGEST.ADD");
+
+        cmd.arg(key)
+           .arg(values);
+
+        cmd
+    }
+
+    /// Empties a t-digest sketch.
+    fn tdigest_reset<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("TDIGEST.RESET");
+
+        cmd.arg(key);
+
+        cmd
+    }
+
+    /// Merges the t-digest sketches `sources` into `dest`, creating it if needed.
+    ///
+    /// ```text
+    /// TDIGEST.MERGE <dest> <numkeys> <source> ... [COMPRESSION compression] [OVERRIDE]
+    /// ```
+    fn tdigest_merge<D: ToRedisArgs, S: ToRedisArgs>(dest: D, sources: &'a [S], options: &'a TDigestMergeOptions) {
+        let mut cmd = cmd("TDIGEST.MERGE");
+
+        cmd.arg(dest)
+           .arg(sources.len())
+           .arg(sources)
+           .arg(options);
+
+        cmd
+    }
+
+    /// Returns the minimal observation of a t-digest sketch, `nan` if it is empty.
+    fn tdigest_min<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("TDIGEST.MIN");
+
+        cmd.arg(key);
+
+        cmd
+    }
+
+    /// Returns the maximal observation of a t-digest sketch, `nan` if it is empty.
+    fn tdigest_max<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("TDIGEST.MAX");
+
+        cmd.arg(key);
+
+        cmd
+    }
+
+    /// Returns an estimate of the value at each of the given quantiles.
+    fn tdigest_quantile<K: ToRedisArgs>(key: K, quantiles: &'a [f64]) {
+        let mut cmd = cmd("TDIGEST.QUANTILE");
+
+        cmd.arg(key)
+           .arg(quantiles);
+
+        cmd
+    }
+
+    /// Returns an estimate of the fraction of observations lower than or equal to each of the values.
+    fn tdigest_cdf<K: ToRedisArgs>(key: K, values: &'a [f64]) {
+        let mut cmd = cmd("TDIGEST.CDF");
+
+        cmd.arg(key)
+           .arg(values);
+
+        cmd
+    }
+
+    /// Returns an estimate of the rank of each of the values, from the lowest observation.
+    fn tdigest_rank<K: ToRedisArgs>(key: K, values: &'a [f64]) {
+        let mut cmd = cmd("TDIGEST.RANK");
+
+        cmd.arg(key)
+           .arg(values);
+
+        cmd
+    }
+
+    /// Returns an estimate of the rank of each of the values, from the highest observation.
+    fn tdigest_revrank<K: ToRedisArgs>(key: K, values: &'a [f64]) {
+        let mut cmd = cmd("TDIGEST.REVRANK");
+
+        cmd.arg(key)
+           .arg(values);
+
+        cmd
+    }
+
+    /// Returns an estimate of the value at each of the ranks, from the lowest observation.
+    fn tdigest_byrank<K: ToRedisArgs>(key: K, ranks: &'a [u64]) {
+        let mut cmd = cmd("TDIGEST.BYRANK");
+
+        cmd.arg(key)
+           .arg(ranks);
+
+        cmd
+    }
+
+    /// Returns an estimate of the value at each of the ranks, from the highest observation.
+    fn tdigest_byrevrank<K: ToRedisArgs>(key: K, ranks: &'a [u64]) {
+        let mut cmd = cmd("TDIGEST.BYREVRANK");
+
+        cmd.arg(key)
+           .arg(ranks);
+
+        cmd
+    }
+
+    /// Returns an estimate of the mean of the observations between the `low` and `high` quantiles.
+    fn tdigest_trimmed_mean<K: ToRedisArgs>(key: K, low: f64, high: f64) {
+        let mut cmd = cmd("TDIGEST.TRIMMED_MEAN");
+
+        cmd.arg(key)
+           .arg(low)
+           .arg(high);
+
+        cmd
+    }
+
+    /// Returns information about a t-digest sketch, which can be converted to a
+    /// [`TDigestInfo`](crate::bloom::TDigestInfo).
+    fn tdigest_info<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("TDIGEST.INFO");
+
+        cmd.arg(key);
+
+        cmd
+    }
+}
+
+impl<T> BloomCommands for T where T: ConnectionLike {}
+
+#[cfg(feature = "aio")]
+impl<T> BloomAsyncCommands for T where T: crate::aio::ConnectionLike + Send + Sized {}
@@ -8,6 +8,16 @@ use crate::types::{FromRedisValue, NumericBehavior, RedisResult, ToRedisArgs, Re
 #[macro_use]
 mod macros;
 
+#[cfg(feature = "bloom")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bloom")))]
+mod bloom;
+
+#[cfg(feature = "bloom")]
+pub use bloom::BloomCommands;
+
+#[cfg(all(feature = "bloom", feature = "aio"))]
+pub use bloom::BloomAsyncCommands;
+
 #[cfg(feature = "json")]
 #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
 mod json;
@@ -18,6 +28,26 @@ pub use json::JsonCommands;
 #[cfg(all(feature = "json", feature = "aio"))]
 pub use json::JsonAsyncCommands;
 
+#[cfg(feature = "search")]
+#[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+mod search;
+
+#[cfg(feature = "search")]
+pub use search::SearchCommands;
+
+#[cfg(all(feature = "search", feature = "aio"))]
+pub use search::SearchAsyncCommands;
+
+#[cfg(feature = "timeseries")]
+#[cfg_attr(docsrs, doc(cfg(feature = "timeseries")))]
+mod timeseries;
+
+#[cfg(feature = "timeseries")]
+pub use timeseries::TimeSeriesCommands;
+
+#[cfg(all(feature = "timeseries", feature = "aio"))]
+pub use timeseries::TimeSeriesAsyncCommands;
+
 #[cfg(feature = "cluster")]
 use crate::cluster_pipeline::ClusterPipeline;
 
@@ -865,6 +895,11 @@ implement_commands! {
         cmd("PUBLISH").arg(channel).arg(message)
     }
 
+    /// Posts a message to the given sharded channel.
+    fn spublish<K: ToRedisArgs, E: ToRedisArgs>(channel: K, message: E) {
+        cmd("SPUBLISH").arg(channel).arg(message)
+    }
+
     // Object commands
 
     /// Returns the encoding of a key.
@@ -1209,6 +1244,65 @@ implement_commands! {
             .arg(options)
     }
 
+    /// Return the members of a sorted set populated with geospatial information
+    /// using [`geo_add`](#method.geo_add), which are within the borders of the area
+    /// specified by a `shape` around an `origin`.
+    ///
+    /// Every item in the result can be read with [`redis::geo::GeoSearchResult`][1],
+    /// which support the multiple formats returned by `GEOSEARCH`.
+    ///
+    /// [1]: ./geo/struct.GeoSearchResult.html
+    ///
+    /// ```rust,no_run
+    /// use redis::{Commands, RedisResult};
+    /// use redis::geo::{GeoSearchOptions, GeoSearchOrigin, GeoSearchResult, GeoSearchShape, Unit};
+    ///
+    /// fn search(con: &mut redis::Connection) -> Vec<GeoSearchResult> {
+    ///     let opts = GeoSearchOptions::default().with_dist().with_coord();
+    ///     con.geo_search(
+    ///         "my_gis",
+    ///         GeoSearchOrigin::lon_lat(15.90, 37.21),
+    ///         GeoSearchShape::Radius(51.39, Unit::Kilometers),
+    ///         opts,
+    ///     )
+    ///     .unwrap()
+    /// }
+    /// ```
+    #[cfg(feature = "geospatial")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "geospatial")))]
+    fn geo_search<K: ToRedisArgs>(
+        key: K,
+        origin: geo::GeoSearchOrigin,
+        shape: geo::GeoSearchShape,
+        options: geo::GeoSearchOptions
+    ) {
+        cmd("GEOSEARCH")
+            .arg(key)
+            .arg(origin)
+            .arg(shape)
+            .arg(options)
+    }
+
+    /// Store the members of `source` within the area specified by a `shape`
+    /// around an `origin` in the sorted set `destination`, and return the
+    /// number of stored members.
+    #[cfg(feature = "geospatial")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "geospatial")))]
+    fn geo_search_store<D: ToRedisArgs, S: ToRedisArgs>(
+        destination: D,
+        source: S,
+        origin: geo::GeoSearchOrigin,
+        shape: geo::GeoSearchShape,
+        options: geo::GeoSearchOptions
+    ) {
+        cmd("GEOSEARCHSTORE")
+            .arg(destination)
+            .arg(source)
+            .arg(origin)
+            .arg(shape)
+            .arg(options)
+    }
+
     //
     // streams commands
     //
@@ -1311,6 +1405,118 @@ implement_commands! {
 
 
 
+    /// Add a stream message with optional arguments such as `NOMKSTREAM`,
+    /// and trimming the stream by `MAXLEN` or `MINID` with a `LIMIT`.
+    ///
+    /// ```no_run
+    /// use redis::{Commands, RedisResult};
+    /// use redis::streams::{StreamAddOptions, StreamTrimOptions, StreamTrimmingMode};
+    /// let client = redis::Client::open("redis://127.0.0.1/0").unwrap();
+    /// let mut con = client.get_connection().unwrap();
+    ///
+    /// let opts = StreamAddOptions::default()
+    ///     .nomkstream()
+    ///     .trim(StreamTrimOptions::minid(StreamTrimmingMode::Approx, "1000-0").limit(100));
+    /// let id: RedisResult<Option<String>> = con.xadd_options("k1", "*", &[("h", "w")], &opts);
+    /// ```
+    ///
+    /// ```text
+    /// XADD key [NOMKSTREAM] [<MAXLEN|MINID> [~|=] <threshold> [LIMIT <count>]]
+    ///     <ID or *> [field value] [field value] ...
+    /// ```
+    #[cfg(feature = "streams")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "streams")))]
+    fn xadd_options<K: ToRedisArgs, ID: ToRedisArgs, I: ToRedisArgs>(
+        key: K,
+        id: ID,
+        items: I,
+        options: &'a streams::StreamAddOptions
+    ) {
+        cmd("XADD")
+            .arg(key)
+            .arg(options)
+            .arg(id)
+            .arg(items)
+    }
+
+
+    /// Transfer ownership of pending messages that have been idle for more than
+    /// `min_idle_time` milliseconds to `consumer`, scanning the pending entries
+    /// list of `group` from `start`. Take note of the StreamAutoClaimReply return type.
+    ///
+    /// ```text
+    /// XAUTOCLAIM <key> <group> <consumer> <min-idle-time> <start>
+    /// ```
+    #[cfg(feature = "streams")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "streams")))]
+    fn xautoclaim<K: ToRedisArgs, G: ToRedisArgs, C: ToRedisArgs, MIT: ToRedisArgs, S: ToRedisArgs>(
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: MIT,
+        start: S
+    ) {
+        cmd("XAUTOCLAIM")
+            .arg(key)
+            .arg(group)
+            .arg(consumer)
+            .arg(min_idle_time)
+            .arg(start)
+    }
+
+    /// This is the optional arguments version of `xautoclaim`.
+    ///
+    /// ```no_run
+    /// use redis::{Commands, RedisResult};
+    /// use redis::streams::{StreamAutoClaimOptions, StreamAutoClaimReply};
+    /// let client = redis::Client::open("redis://127.0.0.1/0").unwrap();
+    /// let mut con = client.get_connection().unwrap();
+    ///
+    /// // Claim up to 10 messages of group "g1" idle for more than 60s,
+    /// // walking the pending entries list with the returned cursor.
+    /// let mut start = "0-0".to_string();
+    /// loop {
+    ///     let opts = StreamAutoClaimOptions::default().count(10);
+    ///     let reply: StreamAutoClaimReply = con
+    ///         .xautoclaim_options("k1", "g1", "c1", 60_000, &start, opts)
+    ///         .unwrap();
+    ///     // handle reply.claimed ...
+    ///     if reply.next_stream_id == "0-0" {
+    ///         break;
+    ///     }
+    ///     start = reply.next_stream_id;
+    /// }
+    /// ```
+    ///
+    /// ```text
+    /// XAUTOCLAIM <key> <group> <consumer> <min-idle-time> <start>
+    ///     [COUNT <count>] [JUSTID]
+    /// ```
+    #[cfg(feature = "streams")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "streams")))]
+    fn xautoclaim_options<
+        K: ToRedisArgs,
+        G: ToRedisArgs,
+        C: ToRedisArgs,
+        MIT: ToRedisArgs,
+        S: ToRedisArgs
+    >(
+        key: K,
+        group: G,
+        consumer: C,
+        min_idle_time: MIT,
+        start: S,
+        options: streams::StreamAutoClaimOptions
+    ) {
+        cmd("XAUTOCLAIM")
+            .arg(key)
+            .arg(group)
+            .arg(consumer)
+            .arg(min_idle_time)
+            .arg(start)
+            .arg(options)
+    }
+
     /// Claim pending, unacked messages, after some period of time,
     /// currently checked out by another consumer.
     ///
@@ -1460,6 +1666,27 @@ implement_commands! {
     }
 
 
+    /// Create a `consumer` in an existing consumer `group` of the stream `key`.
+    /// Returns 1 if the consumer was created and 0 if it already existed.
+    ///
+    /// ```text
+    /// XGROUP CREATECONSUMER <key> <groupname> <consumername>
+    /// ```
+    #[cfg(feature = "streams")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "streams")))]
+    fn xgroup_createconsumer<K: ToRedisArgs, G: ToRedisArgs, C: ToRedisArgs>(
+        key: K,
+        group: G,
+        consumer: C
+    ) {
+        cmd("XGROUP")
+            .arg("CREATECONSUMER")
+            .arg(key)
+            .arg(group)
+            .arg(consumer)
+    }
+
+
     /// Alter which `id` you want consumers to begin reading from an existing
     /// consumer `group`.
     ///
@@ -1571,6 +1798,37 @@ implement_commands! {
         cmd("XINFO").arg("STREAM").arg(key)
     }
 
+    /// Returns the full state of the stream, including its entries, consumer
+    /// groups, consumers and pending messages. The server limits the entries and
+    /// pending messages it returns to 10 each, see `xinfo_stream_full_count`.
+    /// Take note of the StreamInfoStreamFullReply return type.
+    ///
+    /// ```text
+    /// XINFO STREAM <key> FULL
+    /// ```
+    #[cfg(feature = "streams")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "streams")))]
+    fn xinfo_stream_full<K: ToRedisArgs>(key: K) {
+        cmd("XINFO").arg("STREAM").arg(key).arg("FULL")
+    }
+
+    /// Returns the full state of the stream, limiting the entries and pending
+    /// messages returned to `count`. A `count` of 0 returns all of them.
+    ///
+    /// ```text
+    /// XINFO STREAM <key> FULL [COUNT <count>]
+    /// ```
+    #[cfg(feature = "streams")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "streams")))]
+    fn xinfo_stream_full_count<K: ToRedisArgs>(key: K, count: usize) {
+        cmd("XINFO")
+            .arg("STREAM")
+            .arg(key)
+            .arg("FULL")
+            .arg("COUNT")
+            .arg(count)
+    }
+
     /// Returns the number of messages for a given stream `key`.
     ///
     /// ```text
@@ -1855,6 +2113,38 @@ implement_commands! {
     }
 
 
+    /// Set the last generated ID of the stream `key`.
+    ///
+    /// ```text
+    /// XSETID <key> <last-id>
+    /// ```
+    #[cfg(feature = "streams")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "streams")))]
+    fn xsetid<K: ToRedisArgs, ID: ToRedisArgs>(
+        key: K,
+        last_id: ID
+    ) {
+        cmd("XSETID").arg(key).arg(last_id)
+    }
+
+    /// This is the optional arguments version of `xsetid`, which also
+    /// sets the entries added and max deleted ID counters of the stream.
+    ///
+    /// ```text
+    /// XSETID <key> <last-id> [ENTRIESADDED <entries-added>]
+    ///     [MAXDELETEDID <max-deleted-id>]
+    /// ```
+    #[cfg(feature = "streams")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "streams")))]
+    fn xsetid_options<K: ToRedisArgs, ID: ToRedisArgs>(
+        key: K,
+        last_id: ID,
+        options: &'a streams::StreamSetIdOptions
+    ) {
+        cmd("XSETID").arg(key).arg(last_id).arg(options)
+    }
+
+
     /// Trim a stream `key` to a MAXLEN count.
     ///
     /// ```text
@@ -1868,6 +2158,21 @@ implement_commands! {
     ) {
         cmd("XTRIM").arg(key).arg(maxlen)
     }
+
+    /// Trim a stream `key` by `MAXLEN` or `MINID`, optionally
+    /// limiting the number of evicted entries.
+    ///
+    /// ```text
+    /// XTRIM <key> <MAXLEN|MINID> [~|=] <threshold> [LIMIT <count>]
+    /// ```
+    #[cfg(feature = "streams")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "streams")))]
+    fn xtrim_options<K: ToRedisArgs>(
+        key: K,
+        options: &'a streams::StreamTrimOptions
+    ) {
+        cmd("XTRIM").arg(key).arg(options)
+    }
 }
 
 /// Allows pubsub callbacks to stop receiving messages.
@@ -2053,6 +2358,70 @@ impl ToRedisArgs for LposOptions {
     }
 }
 
+/// Options for the [SCAN](https://redis.io/commands/scan) command
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use redis::ScanOptions;
+/// let opts = ScanOptions::default()
+///     .pattern("user:*")
+///     .count(100)
+///     .object_type("hash");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ScanOptions {
+    pattern: Option<Vec<u8>>,
+    count: Option<usize>,
+    object_type: Option<String>,
+}
+
+impl ScanOptions {
+    /// Only return keys that match the given glob-style pattern.
+    pub fn pattern<P: ToRedisArgs>(mut self, pattern: P) -> Self {
+        self.pattern = pattern.to_redis_args().into_iter().next();
+        self
+    }
+
+    /// Hint for the amount of work that should be done on every call.
+    pub fn count(mut self, n: usize) -> Self {
+        self.count = Some(n);
+        self
+    }
+
+    /// Only return keys of the given type, e.g. `string`, `hash` or `zset`.
+    pub fn object_type<T: Into<String>>(mut self, object_type: T) -> Self {
+        self.object_type = Some(object_type.into());
+        self
+    }
+}
+
+impl ToRedisArgs for ScanOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(pattern) = &self.pattern {
+            out.write_arg(b"MATCH");
+            out.write_arg(pattern);
+        }
+
+        if let Some(n) = self.count {
+            out.write_arg(b"COUNT");
+            out.write_arg_fmt(n);
+        }
+
+        if let Some(object_type) = &self.object_type {
+            out.write_arg(b"TYPE");
+            out.write_arg(object_type.as_bytes());
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
 /// Enum for the LEFT | RIGHT args used by some commands
 pub enum Direction {
     /// Targets the first element (head) of the list
@@ -0,0 +1,353 @@
+// can't use rustfmt here because it screws up the file.
+#![cfg_attr(rustfmt, rustfmt_skip)]
+use crate::cmd::{cmd, Cmd};
+use crate::connection::ConnectionLike;
+use crate::pipeline::Pipeline;
+use crate::search::{AggregateOptions, IndexOptions, Schema, SchemaField, SearchOptions};
+use crate::types::{FromRedisValue, RedisResult, ToRedisArgs};
+
+#[cfg(feature = "cluster")]
+use crate::commands::ClusterPipeline;
+
+macro_rules! implement_search_commands {
+    (
+        $lifetime: lifetime
+        $(
+            $(#[$attr:meta])+
+            fn $name:ident<$($tyargs:ident : $ty:ident),*>(
+                $($argname:ident: $argty:ty),*) $body:block
+        )*
+    ) => (
+
+        /// Implements RediSearch commands for connection like objects.  This
+        /// allows you to send commands straight to a connection or client.  It
+        /// is also implemented for redis results of clients which makes for
+        /// very convenient access in some basic cases.
+        ///
+        /// This allows you to use nicer syntax for some common operations.
+        /// For instance this code:
+        ///
+        /// ```rust,no_run
+        /// # fn do_something() -> redis::RedisResult<()> {
+        /// let client = redis::Client::open("redis://127.0.0.1/")?;
+        /// let mut con = client.get_connection()?;
+        /// redis::cmd("FT.CREATE").arg("idx").arg("ON").arg("HASH").arg("PREFIX").arg(1).arg("doc:")
+        ///     .arg("SCHEMA").arg("title").arg("TEXT").arg("SORTABLE").execute(&mut con);
+        /// let reply: redis::Value = redis::cmd("FT.SEARCH").arg("idx").arg("hello")
+        ///     .arg("LIMIT").arg(0).arg(5).query(&mut con)?;
+        /// # Ok(()) }
+        /// ```
+        ///
+        /// Will become this:
+        ///
+        /// ```rust,no_run
+        /// use redis::SearchCommands;
+        /// use redis::search::{IndexOptions, IndexType, Schema, SchemaField, SearchOptions, SearchReply};
+        /// # fn do_something() -> redis::RedisResult<()> {
+        /// let client = redis::Client::open("redis://127.0.0.1/")?;
+        /// let mut con = client.get_connection()?;
+        /// let options = IndexOptions::default().on(IndexType::Hash).prefix("doc:");
+        /// let schema = Schema::default().field(SchemaField::text("title").sortable());
+        /// let _: () = con.ft_create("idx", &options, &schema)?;
+        /// let reply: SearchReply = con.ft_search("idx", "hello", &SearchOptions::default().limit(0, 5))?;
+        /// # Ok(()) }
+        /// ```
+        pub trait SearchCommands : ConnectionLike + Sized {
+            $(
+                $(#[$attr])*
+                #[inline]
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                fn $name<$lifetime, $($tyargs: $ty, )* RV: FromRedisValue>(
+                    &mut self $(, $argname: $argty)*) -> RedisResult<RV>
+                    { Cmd::$name($($argname),*).query(self) }
+            )*
+        }
+
+        impl Cmd {
+            $(
+                $(#[$attr])*
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                pub fn $name<$lifetime, $($tyargs: $ty),*>($($argname: $argty),*) -> Self {
+                    $body
+                }
+            )*
+        }
+
+        /// Implements RediSearch commands over asynchronous connections. This
+        /// allows you to send commands straight to a connection or client.
+        ///
+        /// This allows you to use nicer syntax for some common operations.
+        /// For instance this code:
+        ///
+        /// ```rust,no_run
+        /// # async fn do_something() -> redis::RedisResult<()> {
+        /// let client = redis::Client::open("redis://127.0.0.1/")?;
+        /// let mut con = client.get_async_connection().await?;
+        /// let reply: redis::Value = redis::cmd("FT.SEARCH").arg("idx").arg("hello")
+        ///     .arg("LIMIT").arg(0).arg(5).query_async(&mut con).await?;
+        /// # Ok(()) }
+        /// ```
+        ///
+        /// Will become this:
+        ///
+        /// ```rust,no_run
+        /// use redis::SearchAsyncCommands;
+        /// use redis::search::{SearchOptions, SearchReply};
+        /// # async fn do_something() -> redis::RedisResult<()> {
+        /// let client = redis::Client::open("redis://127.0.0.1/")?;
+        /// let mut con = client.get_async_connection().await?;
+        /// let options = SearchOptions::default().limit(0, 5);
+        /// let reply: SearchReply = con.ft_search("idx", "hello", &options).await?;
+        /// # Ok(()) }
+        /// ```
+        #[cfg(feature = "aio")]
+        pub trait SearchAsyncCommands : crate::aio::ConnectionLike + Send + Sized {
+            $(
+                $(#[$attr])*
+                #[inline]
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                fn $name<$lifetime, $($tyargs: $ty + Send + Sync + $lifetime,)* RV>(
+                    & $lifetime mut self
+                    $(, $argname: $argty)*
+                ) -> $crate::types::RedisFuture<'a, RV>
+                where
+                    RV: FromRedisValue,
+                {
+                    Box::pin(async move {
+                        $body.query_async(self).await
+                    })
+                }
+            )*
+        }
+
+        /// Implements RediSearch commands for pipelines.  Unlike the regular
+        /// commands trait, this returns the pipeline rather than a result
+        /// directly.  Other than that it works the same however.
+        impl Pipeline {
+            $(
+                $(#[$attr])*
+                #[inline]
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                pub fn $name<$lifetime, $($tyargs: $ty),*>(
+                    &mut self $(, $argname: $argty)*
+                ) -> &mut Self {
+                    self.add_command($body)
+                }
+            )*
+        }
+
+        /// Implements RediSearch commands for cluster pipelines.  Unlike the regular
+        /// commands trait, this returns the cluster pipeline rather than a result
+        /// directly.  Other than that it works the same however.
+        #[cfg(feature = "cluster")]
+        impl ClusterPipeline {
+            $(
+                $(#[$attr])*
+                #[inline]
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                pub fn $name<$lifetime, $($tyargs: $ty),*>(
+                    &mut self $(, $argname: $argty)*
+                ) -> &mut Self {
+                    self.add_command($body)
+                }
+            )*
+        }
+
+    )
+}
+
+implement_search_commands! {
+    'a
+
+    /// Creates the index `index` with the given `options` and `schema`.
+    ///
+    /// ```text
+    /// FT.CREATE <index> [ON HASH|JSON] [PREFIX count prefix ...] ... SCHEMA <field> ...
+    /// ```
+    fn ft_create<I: ToRedisArgs>(index: I, options: &'a IndexOptions, schema: &'a Schema) {
+        let mut cmd = cmd("FT.CREATE");
+
+        cmd.arg(index)
+           .arg(options)
+           .arg(schema);
+
+        cmd
+    }
+
+    /// Adds a field to the schema of `index`.
+    ///
+    /// ```text
+    /// FT.ALTER <index> SCHEMA ADD <field> ...
+    /// ```
+    fn ft_alter<I: ToRedisArgs>(index: I, field: &'a SchemaField) {
+        let mut cmd = cmd("FT.ALTER");
+
+        cmd.arg(index)
+           .arg("SCHEMA")
+           .arg("ADD")
+           .arg(field);
+
+        cmd
+    }
+
+    /// Deletes the index `index`, keeping the indexed documents.
+    fn ft_dropindex<I: ToRedisArgs>(index: I) {
+        let mut cmd = cmd("FT.DROPINDEX");
+
+        cmd.arg(index);
+
+        cmd
+    }
+
+    /// Deletes the index `index` along with the indexed documents.
+    fn ft_dropindex_dd<I: ToRedisArgs>(index: I) {
+        let mut cmd = cmd("FT.DROPINDEX");
+
+        cmd.arg(index)
+           .arg("DD");
+
+        cmd
+    }
+
+    /// Returns information and statistics about `index`.
+    fn ft_info<I: ToRedisArgs>(index: I) {
+        let mut cmd = cmd("FT.INFO");
+
+        cmd.arg(index);
+
+        cmd
+    }
+
+    /// Returns the names of all the existing indexes.
+    fn ft_list<>() {
+        cmd("FT._LIST")
+    }
+
+    /// Searches `index` with the given `query`, returning the matching documents.
+    ///
+    /// The reply can be converted to a [`SearchReply`](crate::search::SearchReply),
+    /// including replies to searches with `NOCONTENT`. With RESP2 and `WITHSCORES`,
+    /// `WITHPAYLOADS` or `WITHSORTKEYS`, convert it with
+    /// [`SearchReply::parse`](crate::search::SearchReply::parse) instead.
+    ///
+    /// ```text
+    /// FT.SEARCH <index> <query> [NOCONTENT] [WITHSCORES] ... [LIMIT offset num]
+    /// ```
+    fn ft_search<I: ToRedisArgs, Q: ToRedisArgs>(index: I, query: Q, options: &'a SearchOptions) {
+        let mut cmd = cmd("FT.SEARCH");
+
+        cmd.arg(index)
+           .arg(query)
+           .arg(options);
+
+        cmd
+    }
+
+    /// Runs an aggregation over the documents of `index` matching `query`.
+    ///
+    /// The reply can be converted to an [`AggregateReply`](crate::search::AggregateReply).
+    ///
+    /// ```text
+    /// FT.AGGREGATE <index> <query> [LOAD ...] [GROUPBY ...] ... [WITHCURSOR]
+    /// ```
+    fn ft_aggregate<I: ToRedisArgs, Q: ToRedisArgs>(index: I, query: Q, options: &'a AggregateOptions) {
+        let mut cmd = cmd("FT.AGGREGATE");
+
+        cmd.arg(index)
+           .arg(query)
+           .arg(options);
+
+        cmd
+    }
+
+    /// Reads the next batch of results of an aggregation made with a cursor.
+    ///
+    /// The reply can be converted to an [`AggregateReply`](crate::search::AggregateReply).
+    fn ft_cursor_read<I: ToRedisArgs>(index: I, cursor: u64) {
+        let mut cmd = cmd("FT.CURSOR");
+
+        cmd.arg("READ")
+           .arg(index)
+           .arg(cursor);
+
+        cmd
+    }
+
+    /// Reads the next batch of up to `count` results of an aggregation made with a cursor.
+    fn ft_cursor_read_count<I: ToRedisArgs>(index: I, cursor: u64, count: usize) {
+        let mut cmd = cmd("FT.CURSOR");
+
+        cmd.arg("READ")
+           .arg(index)
+           .arg(cursor)
+           .arg("COUNT")
+           .arg(count);
+
+        cmd
+    }
+
+    /// Deletes a cursor before it was exhausted.
+    fn ft_cursor_del<I: ToRedisArgs>(index: I, cursor: u64) {
+        let mut cmd = cmd("FT.CURSOR");
+
+        cmd.arg("DEL")
+           .arg(index)
+           .arg(cursor);
+
+        cmd
+    }
+
+    /// Adds the alias `alias` to `index`.
+    fn ft_alias_add<A: ToRedisArgs, I: ToRedisArgs>(alias: A, index: I) {
+        let mut cmd = cmd("FT.ALIASADD");
+
+        cmd.arg(alias)
+           .arg(index);
+
+        cmd
+    }
+
+    /// Points the alias `alias` to `index`, removing it from any other index.
+    fn ft_alias_update<A: ToRedisArgs, I: ToRedisArgs>(alias: A, index: I) {
+        let mut cmd = cmd("FT.ALIASUPDATE");
+
+        cmd.arg(alias)
+           .arg(index);
+
+        cmd
+    }
+
+    /// Removes the alias `alias`.
+    fn ft_alias_del<A: ToRedisArgs>(alias: A) {
+        let mut cmd = cmd("FT.ALIASDEL");
+
+        cmd.arg(alias);
+
+        cmd
+    }
+
+    /// Returns the execution plan of `query` on `index`.
+    fn ft_explain<I: ToRedisArgs, Q: ToRedisArgs>(index: I, query: Q) {
+        let mut cmd = cmd("FT.EXPLAIN");
+
+        cmd.arg(index)
+           .arg(query);
+
+        cmd
+    }
+
+    /// Returns the distinct values indexed in the tag field `field` of `index`.
+    fn ft_tagvals<I: ToRedisArgs, F: ToRedisArgs>(index: I, field: F) {
+        let mut cmd = cmd("FT.TAGVALS");
+
+        cmd.arg(index)
+           .arg(field);
+
+        cmd
+    }
+}
+
+impl<T> SearchCommands for T where T: ConnectionLike {}
+
+#[cfg(feature = "aio")]
+impl<T> SearchAsyncCommands for T where T: crate::aio::ConnectionLike + Send + Sized {}
@@ -0,0 +1,333 @@
+// can't use rustfmt here because it screws up the file.
+#![cfg_attr(rustfmt, rustfmt_skip)]
+use crate::cmd::{cmd, Cmd};
+use crate::connection::ConnectionLike;
+use crate::pipeline::Pipeline;
+use crate::timeseries::{TsAddOptions, TsAggregationType, TsCreateOptions, TsMRangeOptions, TsRangeOptions};
+use crate::types::{FromRedisValue, RedisResult, ToRedisArgs};
+
+#[cfg(feature = "cluster")]
+use crate::commands::ClusterPipeline;
+
+macro_rules! implement_timeseries_commands {
+    (
+        $lifetime: lifetime
+        $(
+            $(#[$attr:meta])+
+            fn $name:ident<$($tyargs:ident : $ty:ident),*>(
+                $($argname:ident: $argty:ty),*) $body:block
+        )*
+    ) => (
+
+        /// Implements RedisTimeSeries commands for connection like objects.  This
+        /// allows you to send commands straight to a connection or client.  It
+        /// is also implemented for redis results of clients which makes for
+        /// very convenient access in some basic cases.
+        ///
+        /// This allows you to use nicer syntax for some common operations.
+        /// For instance this code:
+        ///
+        /// ```rust,no_run
+        /// # fn do_something() -> redis::RedisResult<()> {
+        /// let client = redis::Client::open("redis://127.0.0.1/")?;
+        /// let mut con = client.get_connection()?;
+        /// redis::cmd("TS.CREATE").arg("temp:1").arg("RETENTION").arg(60000).execute(&mut con);
+        /// let samples: Vec<(i64, f64)> = redis::cmd("TS.RANGE").arg("temp:1").arg("-").arg("+")
+        ///     .arg("AGGREGATION").arg("AVG").arg(1000).query(&mut con)?;
+        /// # Ok(()) }
+        /// ```
+        ///
+        /// Will become this:
+        ///
+        /// ```rust,no_run
+        /// use redis::TimeSeriesCommands;
+        /// use redis::timeseries::{TsAggregationType, TsCreateOptions, TsRangeOptions, TsSample};
+        /// # fn do_something() -> redis::RedisResult<()> {
+        /// let client = redis::Client::open("redis://127.0.0.1/")?;
+        /// let mut con = client.get_connection()?;
+        /// let _: () = con.ts_create("temp:1", &TsCreateOptions::default().retention(60000))?;
+        /// let options = TsRangeOptions::default().aggregation(TsAggregationType::Avg, 1000);
+        /// let samples: Vec<TsSample> = con.ts_range("temp:1", "-", "+", &options)?;
+        /// # Ok(()) }
+        /// ```
+        pub trait TimeSeriesCommands : ConnectionLike + Sized {
+            $(
+                $(#[$attr])*
+                #[inline]
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                fn $name<$lifetime, $($tyargs: $ty, )* RV: FromRedisValue>(
+                    &mut self $(, $argname: $argty)*) -> RedisResult<RV>
+                    { Cmd::$name($($argname),*).query(self) }
+            )*
+        }
+
+        impl Cmd {
+            $(
+                $(#[$attr])*
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                pub fn $name<$lifetime, $($tyargs: $ty),*>($($argname: $argty),*) -> Self {
+                    $body
+                }
+            )*
+        }
+
+        /// Implements RedisTimeSeries commands over asynchronous connections. This
+        /// allows you to send commands straight to a connection or client.
+        ///
+        /// This allows you to use nicer syntax for some common operations.
+        /// For instance this code:
+        ///
+        /// ```rust,no_run
+        /// # async fn do_something() -> redis::RedisResult<()> {
+        /// let client = redis::Client::open("redis://127.0.0.1/")?;
+        /// let mut con = client.get_async_connection().await?;
+        /// let samples: Vec<(i64, f64)> = redis::cmd("TS.RANGE").arg("temp:1").arg("-").arg("+")
+        ///     .arg("COUNT").arg(10).query_async(&mut con).await?;
+        /// # Ok(()) }
+        /// ```
+        ///
+        /// Will become this:
+        ///
+        /// ```rust,no_run
+        /// use redis::TimeSeriesAsyncCommands;
+        /// use redis::timeseries::{TsRangeOptions, TsSample};
+        /// # async fn do_something() -> redis::RedisResult<()> {
+        /// let client = redis::Client::open("redis://127.0.0.1/")?;
+        /// let mut con = client.get_async_connection().await?;
+        /// let options = TsRangeOptions::default().count(10);
+        /// let samples: Vec<TsSample> = con.ts_range("temp:1", "-", "+", &options).await?;
+        /// # Ok(()) }
+        /// ```
+        #[cfg(feature = "aio")]
+        pub trait TimeSeriesAsyncCommands : crate::aio::ConnectionLike + Send + Sized {
+            $(
+                $(#[$attr])*
+                #[inline]
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                fn $name<$lifetime, $($tyargs: $ty + Send + Sync + $lifetime,)* RV>(
+                    & $lifetime mut self
+                    $(, $argname: $argty)*
+                ) -> $crate::types::RedisFuture<'a, RV>
+                where
+                    RV: FromRedisValue,
+                {
+                    Box::pin(async move {
+                        $body.query_async(self).await
+                    })
+                }
+            )*
+        }
+
+        /// Implements RedisTimeSeries commands for pipelines.  Unlike the regular
+        /// commands trait, this returns the pipeline rather than a result
+        /// directly.  Other than that it works the same however.
+        impl Pipeline {
+            $(
+                $(#[$attr])*
+                #[inline]
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                pub fn $name<$lifetime, $($tyargs: $ty),*>(
+                    &mut self $(, $argname: $argty)*
+                ) -> &mut Self {
+                    self.add_command($body)
+                }
+            )*
+        }
+
+        /// Implements RedisTimeSeries commands for cluster pipelines.  Unlike the regular
+        /// commands trait, this returns the cluster pipeline rather than a result
+        /// directly.  Other than that it works the same however.
+        #[cfg(feature = "cluster")]
+        impl ClusterPipeline {
+            $(
+                $(#[$attr])*
+                #[inline]
+                #[allow(clippy::extra_unused_lifetimes, clippy::needless_lifetimes)]
+                pub fn $name<$lifetime, $($tyargs: $ty),*>(
+                    &mut self $(, $argname: $argty)*
+                ) -> &mut Self {
+                    self.add_command($body)
+                }
+            )*
+        }
+
+    )
+}
+
+implement_timeseries_commands! {
+    'a
+
+    /// Creates the time series `key`.
+    ///
+    /// ```text
+    /// TS.CREATE <key> [RETENTION ms] [ENCODING enc] [CHUNK_SIZE size] [DUPLICATE_POLICY policy] [LABELS label value ...]
+    /// ```
+    fn ts_create<K: ToRedisArgs>(key: K, options: &'a TsCreateOptions) {
+        let mut cmd = cmd("TS.CREATE");
+
+        cmd.arg(key)
+           .arg(options);
+
+        cmd
+    }
+
+    /// Appends a sample to the series `key`, creating it if needed, and returns
+    /// its timestamp. Use `"*"` as `timestamp` for the current server time.
+    fn ts_add<K: ToRedisArgs, T: ToRedisArgs>(key: K, timestamp: T, value: f64) {
+        let mut cmd = cmd("TS.ADD");
+
+        cmd.arg(key)
+           .arg(timestamp)
+           .arg(value);
+
+        cmd
+    }
+
+    /// Appends a sample to the series `key` with the given `options`, which
+    /// also configure the series if it is created by the command.
+    ///
+    /// ```text
+    /// TS.ADD <key> <timestamp> <value> [RETENTION ms] ... [ON_DUPLICATE policy] [LABELS label value ...]
+    /// ```
+    fn ts_add_options<K: ToRedisArgs, T: ToRedisArgs>(key: K, timestamp: T, value: f64, options: &'a TsAddOptions) {
+        let mut cmd = cmd("TS.ADD");
+
+        cmd.arg(key)
+           .arg(timestamp)
+           .arg(value)
+           .arg(options);
+
+        cmd
+    }
+
+    /// Appends samples to existing series, given as `(key, timestamp, value)`
+    /// tuples. Returns the timestamp of each sample, or an error for the
+    /// rejected ones.
+    fn ts_madd<K: ToRedisArgs, T: ToRedisArgs>(samples: &'a [(K, T, f64)]) {
+        let mut cmd = cmd("TS.MADD");
+
+        cmd.arg(samples);
+
+        cmd
+    }
+
+    /// Returns the last sample of the series `key`, which can be converted to a
+    /// [`TsSample`](crate::timeseries::TsSample). An empty series replies with an
+    /// empty array instead.
+    fn ts_get<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("TS.GET");
+
+        cmd.arg(key);
+
+        cmd
+    }
+
+    /// Deletes the samples of the series `key` between `from` and `to`
+    /// (inclusive) and returns their number.
+    fn ts_del<K: ToRedisArgs>(key: K, from: i64, to: i64) {
+        let mut cmd = cmd("TS.DEL");
+
+        cmd.arg(key)
+           .arg(from)
+           .arg(to);
+
+        cmd
+    }
+
+    /// Returns the samples of the series `key` between `from` and `to`, which
+    /// can be converted to a `Vec<TsSample>`. Use `"-"` and `"+"` for the first
+    /// and last samples of the series.
+    ///
+    /// ```text
+    /// TS.RANGE <key> <from> <to> [LATEST] [FILTER_BY_TS ts ...] ... [AGGREGATION aggregator bucket]
+    /// ```
+    fn ts_range<K: ToRedisArgs, F: ToRedisArgs, T: ToRedisArgs>(key: K, from: F, to: T, options: &'a TsRangeOptions) {
+        let mut cmd = cmd("TS.RANGE");
+
+        cmd.arg(key)
+           .arg(from)
+           .arg(to)
+           .arg(options);
+
+        cmd
+    }
+
+    /// Same as [`ts_range`](Self::ts_range), in reverse order.
+    fn ts_revrange<K: ToRedisArgs, F: ToRedisArgs, T: ToRedisArgs>(key: K, from: F, to: T, options: &'a TsRangeOptions) {
+        let mut cmd = cmd("TS.REVRANGE");
+
+        cmd.arg(key)
+           .arg(from)
+           .arg(to)
+           .arg(options);
+
+        cmd
+    }
+
+    /// Returns the samples between `from` and `to` of the series matching the
+    /// filters of `options`. The reply can be converted to a
+    /// [`TsMRangeReply`](crate::timeseries::TsMRangeReply).
+    ///
+    /// ```text
+    /// TS.MRANGE <from> <to> ... [WITHLABELS] ... FILTER <filter> ... [GROUPBY label REDUCE reducer]
+    /// ```
+    fn ts_mrange<F: ToRedisArgs, T: ToRedisArgs>(from: F, to: T, options: &'a TsMRangeOptions) {
+        let mut cmd = cmd("TS.MRANGE");
+
+        cmd.arg(from)
+           .arg(to)
+           .arg(options);
+
+        cmd
+    }
+
+    /// Same as [`ts_mrange`](Self::ts_mrange), in reverse order.
+    fn ts_mrevrange<F: ToRedisArgs, T: ToRedisArgs>(from: F, to: T, options: &'a TsMRangeOptions) {
+        let mut cmd = cmd("TS.MREVRANGE");
+
+        cmd.arg(from)
+           .arg(to)
+           .arg(options);
+
+        cmd
+    }
+
+    /// Creates a compaction rule, aggregating the samples of `source` into
+    /// `dest` by buckets of `bucket_duration` milliseconds.
+    fn ts_createrule<S: ToRedisArgs, D: ToRedisArgs>(source: S, dest: D, aggregation: TsAggregationType, bucket_duration: u64) {
+        let mut cmd = cmd("TS.CREATERULE");
+
+        cmd.arg(source)
+           .arg(dest)
+           .arg("AGGREGATION")
+           .arg(aggregation)
+           .arg(bucket_duration);
+
+        cmd
+    }
+
+    /// Deletes the compaction rule from `source` to `dest`.
+    fn ts_deleterule<S: ToRedisArgs, D: ToRedisArgs>(source: S, dest: D) {
+        let mut cmd = cmd("TS.DELETERULE");
+
+        cmd.arg(source)
+           .arg(dest);
+
+        cmd
+    }
+
+    /// Returns information and statistics about the series `key`, which can be
+    /// converted to a [`TsInfo`](crate::timeseries::TsInfo).
+    fn ts_info<K: ToRedisArgs>(key: K) {
+        let mut cmd = cmd("TS.INFO");
+
+        cmd.arg(key);
+
+        cmd
+    }
+}
+
+impl<T> TimeSeriesCommands for T where T: ConnectionLike {}
+
+#[cfg(feature = "aio")]
+impl<T> TimeSeriesAsyncCommands for T where T: crate::aio::ConnectionLike + Send + Sized {}
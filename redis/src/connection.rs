@@ -8,6 +8,7 @@ use std::str::{from_utf8, FromStr};
 use std::time::Duration;
 
 use crate::cmd::{cmd, pipe, Cmd};
+use crate::instrumentation::Instrumenter;
 use crate::parser::Parser;
 use crate::pipeline::Pipeline;
 use crate::types::{
@@ -43,11 +44,28 @@ use rustls_native_certs::load_native_certs;
 use crate::tls::TlsConnParams;
 
 // Non-exhaustive to prevent construction outside this crate
-#[cfg(not(feature = "tls-rustls"))]
+#[cfg(all(feature = "tls-native-tls", not(feature = "tls-rustls")))]
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct TlsConnParams {
+    pub(crate) server_name: Option<String>,
+}
+
+// Non-exhaustive to prevent construction outside this crate
+#[cfg(not(any(feature = "tls-native-tls", feature = "tls-rustls")))]
 #[derive(Clone, Debug)]
 #[non_exhaustive]
 pub struct TlsConnParams;
 
+/// Returns the name used for SNI and certificate verification when connecting to `host`.
+#[cfg(any(feature = "tls-native-tls", feature = "tls-rustls"))]
+pub(crate) fn tls_server_name<'a>(host: &'a str, tls_params: &'a Option<TlsConnParams>) -> &'a str {
+    tls_params
+        .as_ref()
+        .and_then(|params| params.server_name.as_deref())
+        .unwrap_or(host)
+}
+
 static DEFAULT_PORT: u16 = 6379;
 
 #[inline(always)]
@@ -548,6 +566,9 @@ pub struct Connection {
     /// `PushManager` instance for the connection.
     /// This is used to manage Push messages in RESP3 mode.
     push_manager: PushManager,
+
+    /// Reports the requests of the connection, if the client is instrumented.
+    instrumenter: Option<Instrumenter>,
 }
 
 /// Represents a pubsub connection.
@@ -609,8 +630,9 @@ impl ActualConnection {
                 ref host,
                 port,
                 insecure,
-                ..
+                ref tls_params,
             } => {
+                let server_name = tls_server_name(host, tls_params);
                 let tls_connector = if insecure {
                     TlsConnector::builder()
                         .danger_accept_invalid_certs(true)
@@ -624,7 +646,7 @@ impl ActualConnection {
                 let tls = match timeout {
                     None => {
                         let tcp = connect_tcp(addr)?;
-                        match tls_connector.connect(host, tcp) {
+                        match tls_connector.connect(server_name, tcp) {
                             Ok(res) => res,
                             Err(e) => {
                                 fail!((ErrorKind::IoError, "SSL Handshake error", e.to_string()));
@@ -646,7 +668,7 @@ impl ActualConnection {
                             };
                         }
                         match (tcp, last_error) {
-                            (Some(tcp), _) => tls_connector.connect(host, tcp).unwrap(),
+                            (Some(tcp), _) => tls_connector.connect(server_name, tcp).unwrap(),
                             (None, Some(e)) => {
                                 fail!(e);
                             }
@@ -675,7 +697,8 @@ impl ActualConnection {
                 let config = create_rustls_config(insecure, tls_params.clone())?;
                 let conn = rustls::ClientConnection::new(
                     Arc::new(config),
-                    rustls_pki_types::ServerName::try_from(host)?.to_owned(),
+                    rustls_pki_types::ServerName::try_from(tls_server_name(host, tls_params))?
+                        .to_owned(),
                 )?;
                 let reader = match timeout {
                     None => {
@@ -998,6 +1021,7 @@ fn setup_connection(
         pubsub: false,
         protocol: connection_info.protocol,
         push_manager: PushManager::new(),
+        instrumenter: None,
     };
 
     if connection_info.protocol != ProtocolVersion::RESP2 {
@@ -1314,36 +1338,21 @@ impl Connection {
         }
         result
     }
-}
-
-impl ConnectionLike for Connection {
-    /// Sends a [Cmd] into the TCP socket and reads a single response from it.
-    fn req_command(&mut self, cmd: &Cmd) -> RedisResult<Value> {
-        let pcmd = cmd.get_packed_command();
-        if self.pubsub {
-            self.exit_pubsub()?;
-        }
 
-        self.send_bytes(&pcmd)?;
-        if cmd.is_no_response() {
-            return Ok(Value::Nil);
-        }
-        loop {
-            match self.read_response()? {
-                Value::Push {
-                    kind: _kind,
-                    data: _data,
-                } => continue,
-                val => return Ok(val),
-            }
-        }
+    pub(crate) fn set_instrumenter(&mut self, instrumenter: Option<Instrumenter>) {
+        self.instrumenter = instrumenter;
     }
-    fn req_packed_command(&mut self, cmd: &[u8]) -> RedisResult<Value> {
+
+    /// Sends a packed command and reads its response, skipping push messages.
+    fn send_command(&mut self, cmd: &[u8], no_response: bool) -> RedisResult<Value> {
         if self.pubsub {
             self.exit_pubsub()?;
         }
 
         self.send_bytes(cmd)?;
+        if no_response {
+            return Ok(Value::Nil);
+        }
         loop {
             match self.read_response()? {
                 Value::Push {
@@ -1355,7 +1364,7 @@ impl ConnectionLike for Connection {
         }
     }
 
-    fn req_packed_commands(
+    fn send_pipeline(
         &mut self,
         cmd: &[u8],
         offset: usize,
@@ -1400,6 +1409,51 @@ impl ConnectionLike for Connection {
 
         first_err.map_or(Ok(rv), Err)
     }
+}
+
+impl ConnectionLike for Connection {
+    /// Sends a [Cmd] into the TCP socket and reads a single response from it.
+    fn req_command(&mut self, cmd: &Cmd) -> RedisResult<Value> {
+        let pcmd = cmd.get_packed_command();
+        let recorder = self
+            .instrumenter
+            .as_ref()
+            .map(|instrumenter| instrumenter.command(cmd, pcmd.len()));
+        let result = self.send_command(&pcmd, cmd.is_no_response());
+        if let Some(recorder) = recorder {
+            recorder.finish(self.db, &result);
+        }
+        result
+    }
+
+    fn req_packed_command(&mut self, cmd: &[u8]) -> RedisResult<Value> {
+        let recorder = self
+            .instrumenter
+            .as_ref()
+            .map(|instrumenter| instrumenter.packed_command(cmd));
+        let result = self.send_command(cmd, false);
+        if let Some(recorder) = recorder {
+            recorder.finish(self.db, &result);
+        }
+        result
+    }
+
+    fn req_packed_commands(
+        &mut self,
+        cmd: &[u8],
+        offset: usize,
+        count: usize,
+    ) -> RedisResult<Vec<Value>> {
+        let recorder = self
+            .instrumenter
+            .as_ref()
+            .map(|instrumenter| instrumenter.packed_pipeline(cmd, offset, count));
+        let result = self.send_pipeline(cmd, offset, count);
+        if let Some(recorder) = recorder {
+            recorder.finish(self.db, &result);
+        }
+        result
+    }
 
     fn get_db(&self) -> i64 {
         self.db
@@ -0,0 +1,290 @@
+#![cfg(feature = "script")]
+use crate::cmd::cmd;
+use crate::connection::ConnectionLike;
+use crate::types::{ErrorKind, FromRedisValue, RedisError, RedisResult, ToRedisArgs};
+use crate::Cmd;
+
+/// Represents a library of Redis functions.
+#[derive(Debug, Clone)]
+pub struct Library {
+    code: String,
+}
+
+/// The library object represents a Lua library of functions that can be
+/// loaded on the redis server (requires Redis 7).  The functions of the
+/// library are called through `Function` objects, which take care of loading
+/// the library if the server doesn't know the function.
+///
+/// In cluster mode, the library is loaded to all the primaries, and the
+/// functions are invoked on the primary that owns their keys.
+///
+/// Example:
+///
+/// ```rust,no_run
+/// # let client = redis::Client::open("redis://127.0.0.1/").unwrap();
+/// # let mut con = client.get_connection().unwrap();
+/// let library = redis::Library::new(r"#!lua name=mylib
+///     redis.register_function('add', function(keys, args)
+///         return tonumber(args[1]) + tonumber(args[2])
+///     end)
+/// ");
+/// let result = library.function("add").arg(1).arg(2).invoke(&mut con);
+/// assert_eq!(result, Ok(3));
+/// ```
+impl Library {
+    /// Creates a new library object. The code must start with a `#!lua name=<library name>`
+    /// shebang.
+    pub fn new(code: &str) -> Library {
+        Library {
+            code: code.to_string(),
+        }
+    }
+
+    /// Returns the name of the library, as declared in its shebang.
+    pub fn name(&self) -> Option<&str> {
+        let shebang = self.code.trim_start().lines().next()?.strip_prefix("#!")?;
+        shebang
+            .split_whitespace()
+            .find_map(|part| part.strip_prefix("name="))
+    }
+
+    /// Returns a function of the library, which is invoked with `FCALL`.
+    #[inline]
+    pub fn function(&self, name: &str) -> Function<'_> {
+        Function {
+            library: self,
+            name: name.to_string(),
+            read_only: false,
+        }
+    }
+
+    /// Loads the library and returns its name. If `replace` is true, an existing library
+    /// with the same name is replaced.
+    #[inline]
+    pub fn load(&self, con: &mut dyn ConnectionLike, replace: bool) -> RedisResult<String> {
+        self.load_cmd(replace).query(con)
+    }
+
+    /// Asynchronously loads the library and returns its name. If `replace` is true, an
+    /// existing library with the same name is replaced.
+    #[inline]
+    #[cfg(feature = "aio")]
+    pub async fn load_async<C>(&self, con: &mut C, replace: bool) -> RedisResult<String>
+    where
+        C: crate::aio::ConnectionLike,
+    {
+        self.load_cmd(replace).query_async(con).await
+    }
+
+    fn load_cmd(&self, replace: bool) -> Cmd {
+        let mut cmd = cmd("FUNCTION");
+        cmd.arg("LOAD");
+        if replace {
+            cmd.arg("REPLACE");
+        }
+        cmd.arg(self.code.as_bytes());
+        cmd
+    }
+}
+
+/// Represents a function of a `Library`.
+#[derive(Debug, Clone)]
+pub struct Function<'a> {
+    library: &'a Library,
+    name: String,
+    read_only: bool,
+}
+
+impl<'a> Function<'a> {
+    /// Invokes the function with `FCALL_RO`, which allows running it on replicas.  The
+    /// function must be registered with the `no-writes` flag.
+    #[inline]
+    pub fn read_only(mut self) -> Function<'a> {
+        self.read_only = true;
+        self
+    }
+
+    /// Creates a function invocation object with a key filled in.
+    #[inline]
+    pub fn key<T: ToRedisArgs>(&self, key: T) -> FunctionInvocation<'_> {
+        FunctionInvocation {
+            function: self,
+            args: vec![],
+            keys: key.to_redis_args(),
+        }
+    }
+
+    /// Creates a function invocation object with an argument filled in.
+    #[inline]
+    pub fn arg<T: ToRedisArgs>(&self, arg: T) -> FunctionInvocation<'_> {
+        FunctionInvocation {
+            function: self,
+            args: arg.to_redis_args(),
+            keys: vec![],
+        }
+    }
+
+    /// Returns an empty function invocation object.  This is primarily useful
+    /// for programmatically adding arguments and keys because the type will
+    /// not change.  Normally you can use `arg` and `key` directly.
+    #[inline]
+    pub fn prepare_invoke(&self) -> FunctionInvocation<'_> {
+        FunctionInvocation {
+            function: self,
+            args: vec![],
+            keys: vec![],
+        }
+    }
+
+    /// Invokes the function directly without arguments.
+    #[inline]
+    pub fn invoke<T: FromRedisValue>(&self, con: &mut dyn ConnectionLike) -> RedisResult<T> {
+        self.prepare_invoke().invoke(con)
+    }
+
+    /// Asynchronously invokes the function without arguments.
+    #[inline]
+    #[cfg(feature = "aio")]
+    pub async fn invoke_async<C, T>(&self, con: &mut C) -> RedisResult<T>
+    where
+        C: crate::aio::ConnectionLike,
+        T: FromRedisValue,
+    {
+        self.prepare_invoke().invoke_async(con).await
+    }
+}
+
+/// Represents a prepared function call.
+pub struct FunctionInvocation<'a> {
+    function: &'a Function<'a>,
+    args: Vec<Vec<u8>>,
+    keys: Vec<Vec<u8>>,
+}
+
+/// This type collects keys and other arguments for the function so that it
+/// can be then invoked.  While the `Function` type itself names the function,
+/// the `FunctionInvocation` holds the arguments that should be invoked until
+/// it's sent to the server.
+impl<'a> FunctionInvocation<'a> {
+    /// Adds a regular argument to the invocation.  This ends up in the `args`
+    /// table of the function.
+    #[inline]
+    pub fn arg<'b, T: ToRedisArgs>(&'b mut self, arg: T) -> &'b mut FunctionInvocation<'a>
+    where
+        'a: 'b,
+    {
+        arg.write_redis_args(&mut self.args);
+        self
+    }
+
+    /// Adds a key argument to the invocation.  This ends up in the `keys`
+    /// table of the function.
+    #[inline]
+    pub fn key<'b, T: ToRedisArgs>(&'b mut self, key: T) -> &'b mut FunctionInvocation<'a>
+    where
+        'a: 'b,
+    {
+        key.write_redis_args(&mut self.keys);
+        self
+    }
+
+    /// Invokes the function and returns the result.  If the server doesn't
+    /// know the function, the library is loaded and the function is invoked
+    /// again.
+    #[inline]
+    pub fn invoke<T: FromRedisValue>(&self, con: &mut dyn ConnectionLike) -> RedisResult<T> {
+        let fcall_cmd = self.fcall_cmd();
+        match fcall_cmd.query(con) {
+            Ok(val) => Ok(val),
+            Err(err) => {
+                if is_function_not_found(&err) {
+                    self.function.library.load(con, true)?;
+                    fcall_cmd.query(con)
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    /// Asynchronously invokes the function and returns the result.  If the
+    /// server doesn't know the function, the library is loaded and the
+    /// function is invoked again.
+    #[inline]
+    #[cfg(feature = "aio")]
+    pub async fn invoke_async<C, T>(&self, con: &mut C) -> RedisResult<T>
+    where
+        C: crate::aio::ConnectionLike,
+        T: FromRedisValue,
+    {
+        let fcall_cmd = self.fcall_cmd();
+        match fcall_cmd.query_async(con).await {
+            Ok(val) => Ok(val),
+            Err(err) => {
+                if is_function_not_found(&err) {
+                    self.function.library.load_async(con, true).await?;
+                    fcall_cmd.query_async(con).await
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    fn fcall_cmd(&self) -> Cmd {
+        let mut cmd = cmd(if self.function.read_only {
+            "FCALL_RO"
+        } else {
+            "FCALL"
+        });
+        cmd.arg(&self.function.name)
+            .arg(self.keys.len())
+            .arg(&*self.keys)
+            .arg(&*self.args);
+        cmd
+    }
+}
+
+fn is_function_not_found(err: &RedisError) -> bool {
+    err.kind() == ErrorKind::ResponseError && err.detail() == Some("Function not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Library;
+    use crate::cmd::cmd;
+
+    #[test]
+    fn function_call_should_work() {
+        let library = Library::new("#!lua name=mylib\nredis.register_function('f', f)");
+        assert_eq!(library.name(), Some("mylib"));
+
+        let function = library.function("f");
+        assert_eq!(
+            function
+                .key("dummy")
+                .arg(1)
+                .fcall_cmd()
+                .get_packed_command(),
+            cmd("FCALL")
+                .arg("f")
+                .arg(1)
+                .arg("dummy")
+                .arg(1)
+                .get_packed_command()
+        );
+        let function = function.read_only();
+        assert_eq!(
+            function.prepare_invoke().fcall_cmd().get_packed_command(),
+            cmd("FCALL_RO").arg("f").arg(0).get_packed_command()
+        );
+        assert_eq!(
+            library.load_cmd(true).get_packed_command(),
+            cmd("FUNCTION")
+                .arg("LOAD")
+                .arg("REPLACE")
+                .arg("#!lua name=mylib\nredis.register_function('f', f)")
+                .get_packed_command()
+        );
+    }
+}
@@ -305,10 +305,300 @@ impl RadiusSearchResult {
     }
 }
 
+/// The center of the area searched by [GEOSEARCH][1] and [GEOSEARCHSTORE][2].
+///
+/// [1]: https://redis.io/commands/geosearch
+/// [2]: https://redis.io/commands/geosearchstore
+pub enum GeoSearchOrigin {
+    /// Use the position of an existing member of the sorted set (`FROMMEMBER`).
+    Member(String),
+    /// Use the given longitude and latitude (`FROMLONLAT`).
+    LonLat(Coord<f64>),
+}
+
+impl GeoSearchOrigin {
+    /// Search around the position of `member`.
+    pub fn member<M: Into<String>>(member: M) -> Self {
+        GeoSearchOrigin::Member(member.into())
+    }
+
+    /// Search around the (longitude, latitude) position.
+    pub fn lon_lat(longitude: f64, latitude: f64) -> Self {
+        GeoSearchOrigin::LonLat(Coord::lon_lat(longitude, latitude))
+    }
+}
+
+impl ToRedisArgs for GeoSearchOrigin {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        match self {
+            GeoSearchOrigin::Member(member) => {
+                out.write_arg(b"FROMMEMBER");
+                out.write_arg(member.as_bytes());
+            }
+            GeoSearchOrigin::LonLat(coord) => {
+                out.write_arg(b"FROMLONLAT");
+                coord.write_redis_args(out);
+            }
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// The shape of the area searched by [GEOSEARCH][1] and [GEOSEARCHSTORE][2].
+///
+/// [1]: https://redis.io/commands/geosearch
+/// [2]: https://redis.io/commands/geosearchstore
+pub enum GeoSearchShape {
+    /// A circle with the given radius (`BYRADIUS`).
+    Radius(f64, Unit),
+    /// An axis-aligned rectangle with the given width and height (`BYBOX`).
+    Box(f64, f64, Unit),
+}
+
+impl ToRedisArgs for GeoSearchShape {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        match self {
+            GeoSearchShape::Radius(radius, unit) => {
+                out.write_arg(b"BYRADIUS");
+                radius.write_redis_args(out);
+                unit.write_redis_args(out);
+            }
+            GeoSearchShape::Box(width, height, unit) => {
+                out.write_arg(b"BYBOX");
+                width.write_redis_args(out);
+                height.write_redis_args(out);
+                unit.write_redis_args(out);
+            }
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// Options for the [GEOSEARCH][1] and [GEOSEARCHSTORE][2] commands
+///
+/// [1]: https://redis.io/commands/geosearch
+/// [2]: https://redis.io/commands/geosearchstore
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use redis::{Commands, RedisResult};
+/// use redis::geo::{GeoSearchOptions, GeoSearchOrigin, GeoSearchResult, GeoSearchShape, RadiusOrder, Unit};
+/// fn nearest_in_box(
+///     con: &mut redis::Connection,
+///     key: &str,
+///     member: &str,
+///     km: f64,
+///     limit: usize,
+/// ) -> RedisResult<Vec<GeoSearchResult>> {
+///     let opts = GeoSearchOptions::default()
+///         .order(RadiusOrder::Asc)
+///         .limit(limit)
+///         .with_dist();
+///     con.geo_search(
+///         key,
+///         GeoSearchOrigin::member(member),
+///         GeoSearchShape::Box(km, km, Unit::Kilometers),
+///         opts,
+///     )
+/// }
+/// ```
+#[derive(Default)]
+pub struct GeoSearchOptions {
+    with_coord: bool,
+    with_dist: bool,
+    with_hash: bool,
+    count: Option<usize>,
+    any: bool,
+    order: RadiusOrder,
+    store_dist: bool,
+}
+
+impl GeoSearchOptions {
+    /// Limit the results to the first N matching items.
+    pub fn limit(mut self, n: usize) -> Self {
+        self.count = Some(n);
+        self
+    }
+
+    /// Return as soon as `limit` matching items are found, instead of the
+    /// nearest ones. Only has an effect along with [`limit`](Self::limit).
+    pub fn any(mut self) -> Self {
+        self.any = true;
+        self
+    }
+
+    /// Sort the returned items
+    pub fn order(mut self, o: RadiusOrder) -> Self {
+        self.order = o;
+        self
+    }
+
+    /// Return the distance of the returned items from the specified center.
+    /// The distance is returned in the same unit as the unit of the shape.
+    ///
+    /// This feature can't be used with `geo_search_store`.
+    pub fn with_dist(mut self) -> Self {
+        self.with_dist = true;
+        self
+    }
+
+    /// Return the `longitude, latitude` coordinates of the matching items.
+    ///
+    /// This feature can't be used with `geo_search_store`.
+    pub fn with_coord(mut self) -> Self {
+        self.with_coord = true;
+        self
+    }
+
+    /// Return the raw geohash-encoded sorted set score of the matching items.
+    ///
+    /// This feature can't be used with `geo_search_store`.
+    pub fn with_hash(mut self) -> Self {
+        self.with_hash = true;
+        self
+    }
+
+    /// Store the distance from the center as the score of the stored items,
+    /// instead of their position.
+    ///
+    /// This feature can only be used with `geo_search_store`.
+    pub fn store_dist(mut self) -> Self {
+        self.store_dist = true;
+        self
+    }
+}
+
+impl ToRedisArgs for GeoSearchOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        match self.order {
+            RadiusOrder::Asc => out.write_arg(b"ASC"),
+            RadiusOrder::Desc => out.write_arg(b"DESC"),
+            _ => (),
+        };
+
+        if let Some(n) = self.count {
+            out.write_arg(b"COUNT");
+            out.write_arg_fmt(n);
+            if self.any {
+                out.write_arg(b"ANY");
+            }
+        }
+
+        if self.with_coord {
+            out.write_arg(b"WITHCOORD");
+        }
+
+        if self.with_dist {
+            out.write_arg(b"WITHDIST");
+        }
+
+        if self.with_hash {
+            out.write_arg(b"WITHHASH");
+        }
+
+        if self.store_dist {
+            out.write_arg(b"STOREDIST");
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// Contain an item returned by [`geo_search`][1].
+///
+/// [1]: ../trait.Commands.html#method.geo_search
+pub struct GeoSearchResult {
+    /// The name that was found.
+    pub name: String,
+    /// The coordinate if available.
+    pub coord: Option<Coord<f64>>,
+    /// The distance if available.
+    pub dist: Option<f64>,
+    /// The geohash-encoded score if available.
+    pub hash: Option<u64>,
+}
+
+impl FromRedisValue for GeoSearchResult {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        match *v {
+            Value::Array(ref items) => {
+                if let Some(result) = GeoSearchResult::parse_multi_values(items) {
+                    return Ok(result);
+                }
+            }
+            // If we receive only the member name, it will be a plain string
+            _ => {
+                if let Ok(name) = FromRedisValue::from_redis_value(v) {
+                    return Ok(GeoSearchResult {
+                        name,
+                        coord: None,
+                        dist: None,
+                        hash: None,
+                    });
+                }
+            }
+        }
+
+        invalid_type_error!(v, "Response type not GeoSearchResult compatible.");
+    }
+}
+
+impl GeoSearchResult {
+    fn parse_multi_values(items: &[Value]) -> Option<Self> {
+        let mut iter = items.iter();
+
+        // First item is always the member name
+        let name: String = match iter.next().map(FromRedisValue::from_redis_value) {
+            Some(Ok(n)) => n,
+            _ => return None,
+        };
+
+        let mut result = GeoSearchResult {
+            name,
+            coord: None,
+            dist: None,
+            hash: None,
+        };
+
+        // The distance, hash and coordinates follow in this order when requested,
+        // and are told apart by their type.
+        for item in iter {
+            match *item {
+                Value::Int(_) => result.hash = FromRedisValue::from_redis_value(item).ok(),
+                Value::Array(_) => result.coord = FromRedisValue::from_redis_value(item).ok(),
+                _ => result.dist = FromRedisValue::from_redis_value(item).ok(),
+            }
+        }
+
+        Some(result)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Coord, RadiusOptions, RadiusOrder};
-    use crate::types::ToRedisArgs;
+    use super::{
+        Coord, GeoSearchOptions, GeoSearchOrigin, GeoSearchResult, GeoSearchShape, RadiusOptions,
+        RadiusOrder, Unit,
+    };
+    use crate::types::{FromRedisValue, ToRedisArgs, Value};
     use std::str;
 
     macro_rules! assert_args {
@@ -358,4 +648,84 @@ mod tests {
             "ASC"
         );
     }
+    #[test]
+    fn test_geo_search_args() {
+        assert_args!(GeoSearchOrigin::member("Palermo"), "FROMMEMBER", "Palermo");
+        assert_args!(
+            GeoSearchOrigin::lon_lat(15.0, 37.0),
+            "FROMLONLAT",
+            "15.0",
+            "37.0"
+        );
+        assert_args!(
+            GeoSearchShape::Radius(200.0, Unit::Kilometers),
+            "BYRADIUS",
+            "200.0",
+            "km"
+        );
+        assert_args!(
+            GeoSearchShape::Box(400.0, 100.5, Unit::Meters),
+            "BYBOX",
+            "400.0",
+            "100.5",
+            "m"
+        );
+
+        let empty = GeoSearchOptions::default();
+        assert_eq!(ToRedisArgs::to_redis_args(&empty).len(), 0);
+
+        let opts = GeoSearchOptions::default;
+        assert_args!(
+            opts()
+                .with_hash()
+                .with_dist()
+                .with_coord()
+                .any()
+                .limit(5)
+                .order(RadiusOrder::Desc),
+            "DESC",
+            "COUNT",
+            "5",
+            "ANY",
+            "WITHCOORD",
+            "WITHDIST",
+            "WITHHASH"
+        );
+        // ANY is only valid along with COUNT
+        assert_args!(opts().any().store_dist(), "STOREDIST");
+    }
+
+    #[test]
+    fn test_geo_search_result() {
+        let result =
+            GeoSearchResult::from_redis_value(&Value::BulkString(b"Palermo".to_vec())).unwrap();
+        assert_eq!(result.name, "Palermo");
+        assert_eq!(result.dist, None);
+        assert_eq!(result.hash, None);
+        assert_eq!(result.coord, None);
+
+        let result = GeoSearchResult::from_redis_value(&Value::Array(vec![
+            Value::BulkString(b"Palermo".to_vec()),
+            Value::BulkString(b"190.4424".to_vec()),
+            Value::Int(3479099956230698),
+            Value::Array(vec![
+                Value::BulkString(b"13.361389".to_vec()),
+                Value::BulkString(b"38.115556".to_vec()),
+            ]),
+        ]))
+        .unwrap();
+        assert_eq!(result.name, "Palermo");
+        assert_eq!(result.dist, Some(190.4424));
+        assert_eq!(result.hash, Some(3479099956230698));
+        assert_eq!(result.coord, Some(Coord::lon_lat(13.361389, 38.115556)));
+
+        // Only WITHHASH
+        let result = GeoSearchResult::from_redis_value(&Value::Array(vec![
+            Value::BulkString(b"Palermo".to_vec()),
+            Value::Int(3479099956230698),
+        ]))
+        .unwrap();
+        assert_eq!(result.dist, None);
+        assert_eq!(result.hash, Some(3479099956230698));
+    }
 }
@@ -0,0 +1,413 @@
+//! Hooks for observing the requests that are sent to the server.
+//!
+//! An [`Instrumentation`] is called before and after every command and pipeline that is sent by
+//! a connection. When the request completes it receives a [`RequestEvent`] that describes the
+//! request: the command name, the node it was sent to, its latency, the number of bytes that were
+//! written, the number of retries and redirects, and the error if the request failed. The size of
+//! the replies isn't reported, since they are parsed while they are read.
+//!
+//! Instrumentation is set on a [`Client`](crate::Client) with
+//! [`Client::with_instrumentation`](crate::Client::with_instrumentation), and applies to the
+//! `Connection`, `MultiplexedConnection` and `ConnectionManager` objects that are created by the
+//! client. Cluster clients are instrumented with `ClusterClientBuilder::instrumentation`, in
+//! which case a single event is reported for every request, including its retries and
+//! redirects.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use redis::instrumentation::{Instrumentation, RequestEvent};
+//! use std::sync::Arc;
+//!
+//! struct LogSlowCommands;
+//!
+//! impl Instrumentation for LogSlowCommands {
+//!     fn on_request(&self, event: &RequestEvent<'_>) {
+//!         if event.duration.as_millis() > 100 {
+//!             println!("{} took {:?}", event.command, event.duration);
+//!         }
+//!     }
+//! }
+//!
+//! let client = redis::Client::open("redis://127.0.0.1/")
+//!     .unwrap()
+//!     .with_instrumentation(Arc::new(LogSlowCommands));
+//! ```
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::cmd::Cmd;
+use crate::types::{RedisError, RedisResult};
+
+/// Receives the requests that were sent to the server.
+///
+/// The methods are called synchronously on the task that sent the request, so they should not
+/// block.
+pub trait Instrumentation: Send + Sync {
+    /// Called before a command or a pipeline is sent. Does nothing by default.
+    fn on_request_start(&self, _event: &RequestStartEvent<'_>) {}
+
+    /// Called after a command or a pipeline was completed, either successfully or with an error.
+    fn on_request(&self, event: &RequestEvent<'_>);
+}
+
+/// A request that is about to be sent.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct RequestStartEvent<'a> {
+    /// The name of the command in upper case, `PIPELINE` for pipelines, or `MULTI` for
+    /// transactions.
+    pub command: &'a str,
+    /// The number of commands that are sent, which is 1 for single commands.
+    pub commands: usize,
+    /// The address of the node of the connection. Requests of cluster connections are started
+    /// before they are routed, so this is always `None` for them.
+    pub node: Option<&'a str>,
+    /// The time when the request was started.
+    pub start_time: SystemTime,
+}
+
+/// A completed request.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct RequestEvent<'a> {
+    /// The name of the command in upper case, `PIPELINE` for pipelines, or `MULTI` for
+    /// transactions.
+    pub command: &'a str,
+    /// The number of commands that were sent, which is 1 for single commands.
+    pub commands: usize,
+    /// The address of the node that the request was last sent to, if it was sent to a single
+    /// node.
+    pub node: Option<&'a str>,
+    /// The database of the connection.
+    pub db: i64,
+    /// The time when the request was started.
+    pub start_time: SystemTime,
+    /// The duration of the request, including its retries.
+    pub duration: Duration,
+    /// The number of bytes that were written for the request, not including retries.
+    pub request_bytes: usize,
+    /// The number of times that the request was sent again, including redirects.
+    pub retries: u32,
+    /// The number of `MOVED` and `ASK` redirects that the request followed.
+    pub redirects: u32,
+    /// The error of the request, if it failed.
+    pub error: Option<&'a RedisError>,
+}
+
+/// The instrumentation of a client, along with the node of the connection.
+#[derive(Clone)]
+pub(crate) struct Instrumenter {
+    instrumentation: Arc<dyn Instrumentation>,
+    node: Option<Arc<str>>,
+}
+
+impl fmt::Debug for Instrumenter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Instrumenter")
+            .field("node", &self.node)
+            .finish()
+    }
+}
+
+impl Instrumenter {
+    pub(crate) fn new(instrumentation: Arc<dyn Instrumentation>) -> Self {
+        Self {
+            instrumentation,
+            node: None,
+        }
+    }
+
+    /// Returns an instrumenter that reports the requests as sent to `node`.
+    pub(crate) fn for_node(&self, node: impl fmt::Display) -> Self {
+        Self {
+            instrumentation: self.instrumentation.clone(),
+            node: Some(node.to_string().into()),
+        }
+    }
+
+    /// Starts recording a single command.
+    pub(crate) fn command(&self, cmd: &Cmd, request_bytes: usize) -> RequestRecorder {
+        let command = cmd
+            .args_iter()
+            .next()
+            .and_then(|arg| match arg {
+                crate::cmd::Arg::Simple(name) => Some(command_name(name)),
+                crate::cmd::Arg::Cursor => None,
+            })
+            .unwrap_or_default();
+        self.start(command, 1, request_bytes)
+    }
+
+    /// Starts recording a single packed command.
+    pub(crate) fn packed_command(&self, packed: &[u8]) -> RequestRecorder {
+        let command = packed_command_name(packed).unwrap_or_default();
+        self.start(command, 1, packed.len())
+    }
+
+    /// Starts recording a pipeline of `commands` commands.
+    pub(crate) fn pipeline(
+        &self,
+        atomic: bool,
+        commands: usize,
+        request_bytes: usize,
+    ) -> RequestRecorder {
+        let command = if atomic { "MULTI" } else { "PIPELINE" };
+        self.start(command.to_string(), commands, request_bytes)
+    }
+
+    /// Starts recording a packed pipeline, which is read with the given offset and count.
+    pub(crate) fn packed_pipeline(
+        &self,
+        packed: &[u8],
+        offset: usize,
+        count: usize,
+    ) -> RequestRecorder {
+        // The responses of transactions are read after the `MULTI` and `QUEUED` responses.
+        let atomic = packed_command_name(packed).as_deref() == Some("MULTI");
+        let commands = if atomic {
+            offset.saturating_sub(1)
+        } else {
+            count
+        };
+        self.pipeline(atomic, commands, packed.len())
+    }
+
+    fn start(&self, command: String, commands: usize, request_bytes: usize) -> RequestRecorder {
+        let start_time = SystemTime::now();
+        self.instrumentation.on_request_start(&RequestStartEvent {
+            command: &command,
+            commands,
+            node: self.node.as_deref(),
+            start_time,
+        });
+        RequestRecorder {
+            instrumentation: self.instrumentation.clone(),
+            command,
+            commands,
+            node: self.node.clone(),
+            start: Instant::now(),
+            start_time,
+            request_bytes,
+            retries: 0,
+            redirects: 0,
+        }
+    }
+}
+
+/// Records a request until it's completed.
+pub(crate) struct RequestRecorder {
+    instrumentation: Arc<dyn Instrumentation>,
+    command: String,
+    commands: usize,
+    node: Option<Arc<str>>,
+    start: Instant,
+    start_time: SystemTime,
+    request_bytes: usize,
+    pub(crate) retries: u32,
+    pub(crate) redirects: u32,
+}
+
+impl RequestRecorder {
+    /// Sets the node that the request was sent to, or `None` if it was sent to multiple nodes.
+    #[cfg(feature = "cluster")]
+    pub(crate) fn set_node(&mut self, node: Option<&str>) {
+        if self.node.as_deref() != node {
+            self.node = node.map(Arc::from);
+        }
+    }
+
+    /// Reports the completed request.
+    pub(crate) fn finish<T>(self, db: i64, result: &RedisResult<T>) {
+        self.instrumentation.on_request(&RequestEvent {
+            command: &self.command,
+            commands: self.commands,
+            node: self.node.as_deref(),
+            db,
+            start_time: self.start_time,
+            duration: self.start.elapsed(),
+            request_bytes: self.request_bytes,
+            retries: self.retries,
+            redirects: self.redirects,
+            error: result.as_ref().err(),
+        });
+    }
+}
+
+fn command_name(name: &[u8]) -> String {
+    String::from_utf8_lossy(name).to_ascii_uppercase()
+}
+
+/// Reads the name of the first command in a packed request, such as `*2\r\n$3\r\nGET\r\n...`.
+fn packed_command_name(packed: &[u8]) -> Option<String> {
+    fn line(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+        let end = bytes.windows(2).position(|window| window == b"\r\n")?;
+        Some((&bytes[..end], &bytes[end + 2..]))
+    }
+
+    let (_, rest) = line(packed.strip_prefix(b"*")?)?;
+    let (len, rest) = line(rest.strip_prefix(b"$")?)?;
+    let len: usize = std::str::from_utf8(len).ok()?.parse().ok()?;
+    rest.get(..len).map(command_name)
+}
+
+/// An [`Instrumentation`] that records every request as an OpenTelemetry span, following the
+/// semantic conventions for database client spans.
+///
+/// The spans are named after the command, and are started with the current context as their
+/// parent when the request completes.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use redis::instrumentation::OpenTelemetryInstrumentation;
+/// use std::sync::Arc;
+///
+/// let client = redis::Client::open("redis://127.0.0.1/")
+///     .unwrap()
+///     .with_instrumentation(Arc::new(OpenTelemetryInstrumentation::default()));
+/// ```
+#[cfg(feature = "opentelemetry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
+pub struct OpenTelemetryInstrumentation<T = opentelemetry::global::BoxedTracer> {
+    tracer: T,
+}
+
+#[cfg(feature = "opentelemetry")]
+impl<T> OpenTelemetryInstrumentation<T>
+where
+    T: opentelemetry::trace::Tracer + Send + Sync,
+{
+    /// Creates an instrumentation that records the spans with `tracer`.
+    pub fn new(tracer: T) -> Self {
+        Self { tracer }
+    }
+}
+
+#[cfg(feature = "opentelemetry")]
+impl Default for OpenTelemetryInstrumentation {
+    /// Creates an instrumentation that records the spans with a tracer of the global tracer
+    /// provider.
+    fn default() -> Self {
+        Self::new(opentelemetry::global::tracer("redis"))
+    }
+}
+
+#[cfg(feature = "opentelemetry")]
+impl<T> Instrumentation for OpenTelemetryInstrumentation<T>
+where
+    T: opentelemetry::trace::Tracer + Send + Sync,
+{
+    fn on_request(&self, event: &RequestEvent<'_>) {
+        use opentelemetry::trace::{Span, SpanBuilder, SpanKind, Status};
+        use opentelemetry::KeyValue;
+
+        let mut attributes = vec![
+            KeyValue::new("db.system", "redis"),
+            KeyValue::new("db.operation", event.command.to_string()),
+            KeyValue::new("db.redis.database_index", event.db),
+        ];
+        if event.commands > 1 {
+            attributes.push(KeyValue::new(
+                "db.operation.batch.size",
+                event.commands as i64,
+            ));
+        }
+        if let Some(node) = event.node {
+            match node.rsplit_once(':') {
+                Some((host, port)) if port.parse::<i64>().is_ok() => {
+                    attributes.push(KeyValue::new("server.address", host.to_string()));
+                    attributes.push(KeyValue::new("server.port", port.parse::<i64>().unwrap()));
+                }
+                _ => attributes.push(KeyValue::new("server.address", node.to_string())),
+            }
+        }
+        if let Some(err) = event.error {
+            let error_type = err.code().unwrap_or_else(|| err.category());
+            attributes.push(KeyValue::new("error.type", error_type.to_string()));
+        }
+
+        let mut span = self.tracer.build(
+            SpanBuilder::from_name(event.command.to_string())
+                .with_kind(SpanKind::Client)
+                .with_start_time(event.start_time)
+                .with_attributes(attributes),
+        );
+        if let Some(err) = event.error {
+            span.set_status(Status::error(err.to_string()));
+        }
+        span.end_with_timestamp(event.start_time + event.duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // The command name, number of commands, node and request size of each request.
+    type Request = (String, usize, Option<String>, usize);
+
+    #[derive(Default)]
+    struct Recorded(Mutex<Vec<Request>>, Mutex<Vec<String>>);
+
+    impl Instrumentation for Recorded {
+        fn on_request_start(&self, event: &RequestStartEvent<'_>) {
+            self.1.lock().unwrap().push(event.command.to_string());
+        }
+
+        fn on_request(&self, event: &RequestEvent<'_>) {
+            self.0.lock().unwrap().push((
+                event.command.to_string(),
+                event.commands,
+                event.node.map(str::to_string),
+                event.request_bytes,
+            ));
+        }
+    }
+
+    #[test]
+    fn records_command_names_and_sizes() {
+        let recorded = Arc::new(Recorded::default());
+        let instrumenter = Instrumenter::new(recorded.clone()).for_node("127.0.0.1:6379");
+
+        let mut cmd = crate::cmd("get");
+        cmd.arg("foo");
+        let packed = cmd.get_packed_command();
+        instrumenter
+            .command(&cmd, packed.len())
+            .finish(0, &Ok::<_, RedisError>(()));
+        instrumenter
+            .packed_command(&packed)
+            .finish(0, &Ok::<_, RedisError>(()));
+
+        let mut pipeline = crate::pipe();
+        pipeline.atomic().set("foo", 1).get("foo");
+        let packed = pipeline.get_packed_pipeline();
+        instrumenter
+            .packed_pipeline(&packed, 3, 1)
+            .finish(0, &Ok::<_, RedisError>(()));
+
+        let node = Some("127.0.0.1:6379".to_string());
+        assert_eq!(
+            *recorded.0.lock().unwrap(),
+            vec![
+                ("GET".to_string(), 1, node.clone(), 22),
+                ("GET".to_string(), 1, node.clone(), 22),
+                ("MULTI".to_string(), 2, node, packed.len()),
+            ]
+        );
+        assert_eq!(*recorded.1.lock().unwrap(), vec!["GET", "GET", "MULTI"]);
+    }
+
+    #[test]
+    fn parses_packed_command_names() {
+        assert_eq!(
+            packed_command_name(b"*2\r\n$6\r\nclient\r\n$4\r\nlist\r\n"),
+            Some("CLIENT".to_string())
+        );
+        assert_eq!(packed_command_name(b"*1\r\n$6\r\nPI"), None);
+        assert_eq!(packed_command_name(b""), None);
+    }
+}
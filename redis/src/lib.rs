@@ -63,6 +63,8 @@
 //! * `tokio-comp`: enables support for tokio (optional)
 //! * `connection-manager`: enables support for automatic reconnection (optional)
 //! * `keep-alive`: enables keep-alive option on socket by means of `socket2` crate (optional)
+//! * `serde`: enables reading and writing values with serde, see [`serde`](crate::serde) (optional)
+//! * `opentelemetry`: enables recording requests as OpenTelemetry spans, see [`instrumentation`] (optional)
 //!
 //! ## Connection Parameters
 //!
@@ -266,7 +268,8 @@
 //! # Ok(()) }
 //! ```
 //!
-//! For more information see the `transaction` function.
+//! For more information see the `transaction` function. Async connections can use the
+//! `aio::transaction_async` function instead.
 //!
 //! # PubSub
 //!
@@ -315,6 +318,24 @@ let result : isize = script.arg(1).arg(2).invoke(&mut con)?;
 assert_eq!(result, 3);
 # Ok(()) }
 ```
+
+Redis 7 functions are supported in a similar way through the `Library` type,
+which loads the library with `FUNCTION LOAD` if the server doesn't know the
+called function.
+
+```rust,no_run
+# fn do_something() -> redis::RedisResult<()> {
+# let client = redis::Client::open("redis://127.0.0.1/").unwrap();
+# let mut con = client.get_connection().unwrap();
+let library = redis::Library::new(r"#!lua name=mylib
+    redis.register_function('add', function(keys, args)
+        return tonumber(args[1]) + tonumber(args[2])
+    end)
+");
+let result : isize = library.function("add").arg(1).arg(2).invoke(&mut con)?;
+assert_eq!(result, 3);
+# Ok(()) }
+```
 "##
 )]
 //!
@@ -365,7 +386,7 @@ assert_eq!(result, Ok(("foo".to_string(), b"bar".to_vec())));
 pub use crate::client::Client;
 pub use crate::cmd::{cmd, pack_command, pipe, Arg, Cmd, Iter};
 pub use crate::commands::{
-    Commands, ControlFlow, Direction, LposOptions, PubSubCommands, SetOptions,
+    Commands, ControlFlow, Direction, LposOptions, PubSubCommands, ScanOptions, SetOptions,
 };
 pub use crate::connection::{
     parse_redis_url, transaction, Connection, ConnectionAddr, ConnectionInfo, ConnectionLike,
@@ -379,6 +400,10 @@ pub use push_manager::{PushInfo, PushManager};
 #[cfg_attr(docsrs, doc(cfg(feature = "script")))]
 pub use crate::script::{Script, ScriptInvocation};
 
+#[cfg(feature = "script")]
+#[cfg_attr(docsrs, doc(cfg(feature = "script")))]
+pub use crate::function::{Function, FunctionInvocation, Library};
+
 // preserve grouping and order
 #[rustfmt::skip]
 pub use crate::types::{
@@ -412,10 +437,20 @@ pub use crate::types::{
     ProtocolVersion
 };
 
+#[cfg(feature = "bytes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+pub use crate::{
+    parser::parse_redis_bytes_value,
+    types::{from_bytes_value, BytesValue},
+};
+
 #[cfg(feature = "aio")]
 #[cfg_attr(docsrs, doc(cfg(feature = "aio")))]
 pub use crate::{
-    cmd::AsyncIter, commands::AsyncCommands, parser::parse_redis_value_async, types::RedisFuture,
+    cmd::AsyncIter,
+    commands::AsyncCommands,
+    parser::{parse_redis_value_async, BytesValueCodec},
+    types::RedisFuture,
 };
 
 mod macros;
@@ -429,16 +464,48 @@ pub mod acl;
 #[cfg_attr(docsrs, doc(cfg(feature = "aio")))]
 pub mod aio;
 
+#[cfg(feature = "bloom")]
+pub use crate::commands::BloomCommands;
+
+#[cfg(all(feature = "bloom", feature = "aio"))]
+pub use crate::commands::BloomAsyncCommands;
+
 #[cfg(feature = "json")]
 pub use crate::commands::JsonCommands;
 
 #[cfg(all(feature = "json", feature = "aio"))]
 pub use crate::commands::JsonAsyncCommands;
 
+#[cfg(feature = "search")]
+pub use crate::commands::SearchCommands;
+
+#[cfg(all(feature = "search", feature = "aio"))]
+pub use crate::commands::SearchAsyncCommands;
+
+#[cfg(feature = "timeseries")]
+pub use crate::commands::TimeSeriesCommands;
+
+#[cfg(all(feature = "timeseries", feature = "aio"))]
+pub use crate::commands::TimeSeriesAsyncCommands;
+
+#[cfg(feature = "bloom")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bloom")))]
+pub mod bloom;
+
 #[cfg(feature = "geospatial")]
 #[cfg_attr(docsrs, doc(cfg(feature = "geospatial")))]
 pub mod geo;
 
+#[cfg(feature = "search")]
+#[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+pub mod search;
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde;
+
+pub mod instrumentation;
+
 #[cfg(feature = "cluster")]
 #[cfg_attr(docsrs, doc(cfg(feature = "cluster")))]
 pub mod cluster;
@@ -459,6 +526,9 @@ pub mod testing {
 #[cfg(feature = "cluster")]
 mod cluster_pipeline;
 
+#[cfg(feature = "cluster")]
+mod cluster_scan;
+
 /// Routing information for cluster commands.
 #[cfg(feature = "cluster")]
 pub mod cluster_routing;
@@ -475,6 +545,10 @@ mod r2d2;
 #[cfg_attr(docsrs, doc(cfg(feature = "streams")))]
 pub mod streams;
 
+#[cfg(feature = "timeseries")]
+#[cfg_attr(docsrs, doc(cfg(feature = "timeseries")))]
+pub mod timeseries;
+
 #[cfg(feature = "cluster-async")]
 pub mod cluster_async;
 
@@ -491,6 +565,7 @@ mod client;
 mod cmd;
 mod commands;
 mod connection;
+mod function;
 mod parser;
 mod push_manager;
 mod script;
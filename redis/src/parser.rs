@@ -72,6 +72,20 @@ fn value<'a, I>(
 where
     I: RangeStream<Token = u8, Range = &'a [u8]>,
     I::Error: combine::ParseError<u8, &'a [u8], I::Position>,
+{
+    value_with_bulk_string(count, <[u8]>::to_vec)
+}
+
+/// Like `value`, but lets the caller decide how the payload of bulk strings is stored.
+fn value_with_bulk_string<'a, I, B, F>(
+    count: Option<usize>,
+    make_bulk_string: F,
+) -> impl combine::Parser<I, Output = InternalValue<B>, PartialState = AnySendSyncPartialState>
+where
+    I: RangeStream<Token = u8, Range = &'a [u8]>,
+    I::Error: combine::ParseError<u8, &'a [u8], I::Position>,
+    B: AsRef<[u8]> + Send + Sync + 'static,
+    F: Fn(&[u8]) -> B + Clone + Send + Sync + 'static,
 {
     let count = count.unwrap_or(1);
 
@@ -84,7 +98,7 @@ where
                     combine::value(b).right()
                 }
             })
-            .then_partial(move |&mut b| {
+            .then_partial(|&mut b| {
                 let line = || {
                     recognize(take_until_bytes(&b"\r\n"[..]).with(take(2).map(|_| ()))).and_then(
                         |line: &[u8]| {
@@ -115,12 +129,16 @@ where
                 };
 
                 let bulk_string = || {
+                    let make_bulk_string = make_bulk_string.clone();
                     int().then_partial(move |size| {
                         if *size < 0 {
                             combine::produce(|| InternalValue::Nil).left()
                         } else {
+                            let make_bulk_string = make_bulk_string.clone();
                             take(*size as usize)
-                                .map(|bs: &[u8]| InternalValue::BulkString(bs.to_vec()))
+                                .map(move |bs: &[u8]| {
+                                    InternalValue::BulkString(make_bulk_string(bs))
+                                })
                                 .skip(crlf())
                                 .right()
                         }
@@ -135,70 +153,89 @@ where
                 };
 
                 let array = || {
+                    let make_bulk_string = make_bulk_string.clone();
                     int().then_partial(move |&mut length| {
                         if length < 0 {
                             combine::produce(|| InternalValue::Nil).left()
                         } else {
                             let length = length as usize;
-                            combine::count_min_max(length, length, value(Some(count + 1)))
-                                .map(InternalValue::Array)
-                                .right()
+                            combine::count_min_max(
+                                length,
+                                length,
+                                value_with_bulk_string(Some(count + 1), make_bulk_string.clone()),
+                            )
+                            .map(InternalValue::Array)
+                            .right()
                         }
                     })
                 };
 
                 let error = || line().map(err_parser);
                 let map = || {
+                    let make_bulk_string = make_bulk_string.clone();
                     int().then_partial(move |&mut kv_length| {
                         let length = kv_length as usize * 2;
-                        combine::count_min_max(length, length, value(Some(count + 1))).map(
-                            move |result: Vec<InternalValue>| {
-                                let mut it = result.into_iter();
-                                let mut x = vec![];
-                                for _ in 0..kv_length {
-                                    if let (Some(k), Some(v)) = (it.next(), it.next()) {
-                                        x.push((k, v))
-                                    }
-                                }
-                                InternalValue::Map(x)
-                            },
+                        combine::count_min_max(
+                            length,
+                            length,
+                            value_with_bulk_string(Some(count + 1), make_bulk_string.clone()),
                         )
+                        .map(move |result: Vec<InternalValue<B>>| {
+                            let mut it = result.into_iter();
+                            let mut x = vec![];
+                            for _ in 0..kv_length {
+                                if let (Some(k), Some(v)) = (it.next(), it.next()) {
+                                    x.push((k, v))
+                                }
+                            }
+                            InternalValue::Map(x)
+                        })
                     })
                 };
                 let attribute = || {
+                    let make_bulk_string = make_bulk_string.clone();
                     int().then_partial(move |&mut kv_length| {
                         // + 1 is for data!
                         let length = kv_length as usize * 2 + 1;
-                        combine::count_min_max(length, length, value(Some(count + 1))).map(
-                            move |result: Vec<InternalValue>| {
-                                let mut it = result.into_iter();
-                                let mut attributes = vec![];
-                                for _ in 0..kv_length {
-                                    if let (Some(k), Some(v)) = (it.next(), it.next()) {
-                                        attributes.push((k, v))
-                                    }
-                                }
-                                InternalValue::Attribute {
-                                    data: Box::new(it.next().unwrap()),
-                                    attributes,
-                                }
-                            },
+                        combine::count_min_max(
+                            length,
+                            length,
+                            value_with_bulk_string(Some(count + 1), make_bulk_string.clone()),
                         )
+                        .map(move |result: Vec<InternalValue<B>>| {
+                            let mut it = result.into_iter();
+                            let mut attributes = vec![];
+                            for _ in 0..kv_length {
+                                if let (Some(k), Some(v)) = (it.next(), it.next()) {
+                                    attributes.push((k, v))
+                                }
+                            }
+                            InternalValue::Attribute {
+                                data: Box::new(it.next().unwrap()),
+                                attributes,
+                            }
+                        })
                     })
                 };
                 let set = || {
+                    let make_bulk_string = make_bulk_string.clone();
                     int().then_partial(move |&mut length| {
                         if length < 0 {
                             combine::produce(|| InternalValue::Nil).left()
                         } else {
                             let length = length as usize;
-                            combine::count_min_max(length, length, value(Some(count + 1)))
-                                .map(InternalValue::Set)
-                                .right()
+                            combine::count_min_max(
+                                length,
+                                length,
+                                value_with_bulk_string(Some(count + 1), make_bulk_string.clone()),
+                            )
+                            .map(InternalValue::Set)
+                            .right()
                         }
                     })
                 };
                 let push = || {
+                    let make_bulk_string = make_bulk_string.clone();
                     int().then_partial(move |&mut length| {
                         if length <= 0 {
                             combine::produce(|| InternalValue::Push {
@@ -208,29 +245,34 @@ where
                             .left()
                         } else {
                             let length = length as usize;
-                            combine::count_min_max(length, length, value(Some(count + 1)))
-                                .and_then(|result: Vec<InternalValue>| {
-                                    let mut it = result.into_iter();
-                                    let first = it.next().unwrap_or(InternalValue::Nil);
-                                    if let InternalValue::BulkString(kind) = first {
-                                        let push_kind = String::from_utf8(kind)
-                                            .map_err(StreamErrorFor::<I>::other)?;
-                                        Ok(InternalValue::Push {
-                                            kind: get_push_kind(push_kind),
-                                            data: it.collect(),
-                                        })
-                                    } else if let InternalValue::SimpleString(kind) = first {
-                                        Ok(InternalValue::Push {
-                                            kind: get_push_kind(kind),
-                                            data: it.collect(),
-                                        })
-                                    } else {
-                                        Err(StreamErrorFor::<I>::message_static_message(
-                                            "parse error when decoding push",
-                                        ))
-                                    }
-                                })
-                                .right()
+                            combine::count_min_max(
+                                length,
+                                length,
+                                value_with_bulk_string(Some(count + 1), make_bulk_string.clone()),
+                            )
+                            .and_then(|result: Vec<InternalValue<B>>| {
+                                let mut it = result.into_iter();
+                                let first = it.next().unwrap_or(InternalValue::Nil);
+                                if let InternalValue::BulkString(kind) = first {
+                                    let push_kind = str::from_utf8(kind.as_ref())
+                                        .map_err(StreamErrorFor::<I>::other)?
+                                        .to_string();
+                                    Ok(InternalValue::Push {
+                                        kind: get_push_kind(push_kind),
+                                        data: it.collect(),
+                                    })
+                                } else if let InternalValue::SimpleString(kind) = first {
+                                    Ok(InternalValue::Push {
+                                        kind: get_push_kind(kind),
+                                        data: it.collect(),
+                                    })
+                                } else {
+                                    Err(StreamErrorFor::<I>::message_static_message(
+                                        "parse error when decoding push",
+                                    ))
+                                }
+                            })
+                            .right()
                         }
                     })
                 };
@@ -302,11 +344,161 @@ where
     ))
 }
 
+#[cfg(feature = "bytes")]
+mod bytes_support {
+    use super::*;
+
+    use crate::types::BytesValue;
+    use bytes::Bytes;
+
+    fn parse_error(detail: impl Into<String>) -> RedisError {
+        RedisError::from((ErrorKind::ParseError, "parse error", detail.into()))
+    }
+
+    fn parse_length(line: &[u8]) -> RedisResult<i64> {
+        str::from_utf8(line)
+            .ok()
+            .and_then(|line| line.trim().parse::<i64>().ok())
+            .ok_or_else(|| parse_error("Expected integer, got garbage"))
+    }
+
+    /// Finds the end of the first complete value in a buffer, remembering how
+    /// far it got between calls so that data which was already scanned isn't
+    /// scanned again when more arrives.  Only the headers are inspected, so
+    /// the payload of bulk strings is skipped rather than scanned.
+    pub(crate) struct ValueScanner {
+        // The start of the next header.  Past the end of the buffer while the
+        // payload of a bulk string is still missing.
+        pos: usize,
+        // The number of values, including nested ones, that are yet to start.
+        pending: usize,
+    }
+
+    impl Default for ValueScanner {
+        fn default() -> Self {
+            ValueScanner { pos: 0, pending: 1 }
+        }
+    }
+
+    impl ValueScanner {
+        /// Returns the length of the first complete value in `buffer`, or
+        /// `None` if more data is needed.  The buffer must start with the same
+        /// bytes as in the previous call; after a length was returned, the
+        /// scanner starts over with the next value.
+        pub(crate) fn scan(&mut self, buffer: &[u8]) -> RedisResult<Option<usize>> {
+            let result = self.scan_headers(buffer);
+            if !matches!(result, Ok(None)) {
+                *self = ValueScanner::default();
+            }
+            result
+        }
+
+        fn scan_headers(&mut self, buffer: &[u8]) -> RedisResult<Option<usize>> {
+            while self.pending > 0 {
+                let kind = match buffer.get(self.pos) {
+                    Some(&kind) => kind,
+                    None => return Ok(None),
+                };
+                let line_start = self.pos + 1;
+                let line_len = match buffer[line_start..]
+                    .windows(2)
+                    .position(|window| window == b"\r\n")
+                {
+                    Some(line_len) => line_len,
+                    None => return Ok(None),
+                };
+                let line = &buffer[line_start..line_start + line_len];
+                let mut pos = line_start + line_len + 2;
+                let mut pending = self.pending - 1;
+
+                match kind {
+                    b'+' | b'-' | b':' | b'_' | b',' | b'#' | b'(' => {}
+                    b'$' | b'!' | b'=' => {
+                        let length = parse_length(line)?;
+                        if length >= 0 {
+                            pos = pos
+                                .checked_add(length as usize)
+                                .and_then(|end| end.checked_add(2))
+                                .ok_or_else(|| parse_error("Bulk string length is too large"))?;
+                        } else if kind != b'$' {
+                            return Err(parse_error("Expected length, got a negative number"));
+                        }
+                    }
+                    b'*' | b'~' | b'>' | b'%' | b'|' => {
+                        let length = parse_length(line)?;
+                        let elements = match kind {
+                            _ if length < 0 && matches!(kind, b'%' | b'|') => {
+                                return Err(parse_error("Expected length, got a negative number"));
+                            }
+                            _ if length <= 0 => Some(0),
+                            b'%' => (length as usize).checked_mul(2),
+                            b'|' => (length as usize)
+                                .checked_mul(2)
+                                .and_then(|elements| elements.checked_add(1)),
+                            _ => Some(length as usize),
+                        };
+                        pending = elements
+                            .and_then(|elements| pending.checked_add(elements))
+                            .ok_or_else(|| parse_error("Aggregate length is too large"))?;
+                    }
+                    kind => return Err(parse_error(format!("Unexpected type byte {kind:?}"))),
+                }
+                self.pos = pos;
+                self.pending = pending;
+            }
+            if self.pos > buffer.len() {
+                return Ok(None);
+            }
+            Ok(Some(self.pos))
+        }
+    }
+
+    /// Parses a buffer that holds exactly one complete value, slicing bulk
+    /// strings out of it.  The outer error signals a malformed response, the
+    /// inner one an error sent by the server.
+    pub(crate) fn parse_complete_value(frame: Bytes) -> RedisResult<RedisResult<BytesValue>> {
+        let buffer = frame.clone();
+        let make_bulk_string = move |bs: &[u8]| buffer.slice_ref(bs);
+        match value_with_bulk_string(None, make_bulk_string)
+            .parse(combine::easy::Stream(&frame[..]))
+        {
+            Ok((value, _)) => Ok(value.try_into_bytes_value()),
+            Err(err) => {
+                let err = err
+                    .map_position(|pos| pos.translate_position(&frame[..]))
+                    .map_range(|range| format!("{range:?}"))
+                    .to_string();
+                Err(parse_error(err))
+            }
+        }
+    }
+
+    /// Parses bytes into a [`BytesValue`].
+    ///
+    /// Unlike [`parse_redis_value`], the bulk strings of the returned value are
+    /// slices of `bytes` instead of copies, so reading large blobs out of a
+    /// buffer does not allocate for their payload.
+    pub fn parse_redis_bytes_value(bytes: &Bytes) -> RedisResult<BytesValue> {
+        match ValueScanner::default().scan(bytes)? {
+            Some(len) => parse_complete_value(bytes.slice(..len))?,
+            None => Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+        }
+    }
+}
+
+#[cfg(feature = "bytes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+pub use self::bytes_support::parse_redis_bytes_value;
+
 #[cfg(feature = "aio")]
 mod aio_support {
     use super::*;
 
-    use bytes::{Buf, BytesMut};
+    use bytes::{Buf, Bytes, BytesMut};
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
     use tokio::io::AsyncRead;
     use tokio_util::codec::{Decoder, Encoder};
 
@@ -370,6 +562,219 @@ mod aio_support {
         }
     }
 
+    /// A part of a response, as decoded by [`StreamingValueCodec`].
+    #[derive(Debug, PartialEq)]
+    pub(crate) enum ResponseFrame {
+        /// A complete value.
+        Value(RedisResult<Value>),
+        /// The header of a streamed bulk string, holding its total length.
+        BulkStart(usize),
+        /// The next chunk of a streamed bulk string.
+        BulkChunk(Bytes),
+        /// The streamed bulk string was read completely.
+        BulkEnd,
+        /// A complete value, decoded into a [`BytesValue`](crate::BytesValue).
+        BytesValue(RedisResult<crate::types::BytesValue>),
+    }
+
+    /// A [`ValueCodec`] that can hand out a bulk string reply in chunks instead of
+    /// buffering it completely.
+    ///
+    /// Whether the next reply gets streamed is decided by the shared `stream_next`
+    /// flag when the reply starts; everything else, including push messages that
+    /// arrive in between, is decoded as usual.  Likewise, the shared `bytes_next`
+    /// flag decodes the next reply into a `BytesValue` with a [`BytesValueCodec`].
+    pub(crate) struct StreamingValueCodec {
+        values: ValueCodec,
+        bytes_values: BytesValueCodec,
+        stream_next: Arc<AtomicBool>,
+        bytes_next: Arc<AtomicBool>,
+        // Set while a value is partially decoded by `values`.
+        value_in_progress: bool,
+        // Set while a value is partially decoded by `bytes_values`.
+        bytes_value_in_progress: bool,
+        // The number of payload bytes of the streamed bulk string that are yet to be read.
+        streaming: Option<usize>,
+    }
+
+    impl StreamingValueCodec {
+        pub(crate) fn new(stream_next: Arc<AtomicBool>, bytes_next: Arc<AtomicBool>) -> Self {
+            StreamingValueCodec {
+                values: ValueCodec::default(),
+                bytes_values: BytesValueCodec::default(),
+                stream_next,
+                bytes_next,
+                value_in_progress: false,
+                bytes_value_in_progress: false,
+                streaming: None,
+            }
+        }
+
+        fn decode_bulk_start(
+            &mut self,
+            bytes: &mut BytesMut,
+        ) -> RedisResult<Option<ResponseFrame>> {
+            let header_len = match bytes.windows(2).position(|window| window == b"\r\n") {
+                Some(header_len) => header_len,
+                None => return Ok(None),
+            };
+            let length = str::from_utf8(&bytes[1..header_len])
+                .ok()
+                .and_then(|length| length.trim().parse::<i64>().ok())
+                .ok_or_else(|| {
+                    RedisError::from((
+                        ErrorKind::ParseError,
+                        "parse error",
+                        "Expected integer, got garbage".to_string(),
+                    ))
+                })?;
+            if length < 0 {
+                // A nil reply is not streamed.
+                return self.decode_value(bytes);
+            }
+            bytes.advance(header_len + 2);
+            self.streaming = Some(length as usize);
+            Ok(Some(ResponseFrame::BulkStart(length as usize)))
+        }
+
+        fn decode_bulk_chunk(
+            &mut self,
+            bytes: &mut BytesMut,
+            remaining: usize,
+        ) -> RedisResult<Option<ResponseFrame>> {
+            if remaining > 0 {
+                if bytes.is_empty() {
+                    return Ok(None);
+                }
+                let chunk = bytes.split_to(remaining.min(bytes.len())).freeze();
+                self.streaming = Some(remaining - chunk.len());
+                return Ok(Some(ResponseFrame::BulkChunk(chunk)));
+            }
+            if bytes.len() < 2 {
+                return Ok(None);
+            }
+            if &bytes[..2] != b"\r\n" {
+                return Err(RedisError::from((
+                    ErrorKind::ParseError,
+                    "parse error",
+                    "Expected CRLF after bulk string".to_string(),
+                )));
+            }
+            bytes.advance(2);
+            self.streaming = None;
+            Ok(Some(ResponseFrame::BulkEnd))
+        }
+
+        fn decode_value(&mut self, bytes: &mut BytesMut) -> RedisResult<Option<ResponseFrame>> {
+            if bytes.is_empty() && !self.value_in_progress {
+                return Ok(None);
+            }
+            let value = self.values.decode(bytes)?;
+            self.value_in_progress = value.is_none();
+            Ok(value.map(ResponseFrame::Value))
+        }
+
+        fn decode_bytes_value(
+            &mut self,
+            bytes: &mut BytesMut,
+        ) -> RedisResult<Option<ResponseFrame>> {
+            if bytes.is_empty() && !self.bytes_value_in_progress {
+                return Ok(None);
+            }
+            let value = self.bytes_values.decode(bytes)?;
+            self.bytes_value_in_progress = value.is_none();
+            Ok(value.map(ResponseFrame::BytesValue))
+        }
+    }
+
+    impl Encoder<Vec<u8>> for StreamingValueCodec {
+        type Error = RedisError;
+        fn encode(&mut self, item: Vec<u8>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+            self.values.encode(item, dst)
+        }
+    }
+
+    impl Decoder for StreamingValueCodec {
+        type Item = ResponseFrame;
+        type Error = RedisError;
+
+        fn decode(&mut self, bytes: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+            if let Some(remaining) = self.streaming {
+                return self.decode_bulk_chunk(bytes, remaining);
+            }
+            if self.bytes_value_in_progress
+                || (!self.value_in_progress && self.bytes_next.load(Ordering::Acquire))
+            {
+                return self.decode_bytes_value(bytes);
+            }
+            if !self.value_in_progress && self.stream_next.load(Ordering::Acquire) {
+                match bytes.first() {
+                    None => return Ok(None),
+                    Some(b'$') => return self.decode_bulk_start(bytes),
+                    Some(_) => {}
+                }
+            }
+            self.decode_value(bytes)
+        }
+
+        fn decode_eof(&mut self, bytes: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+            if self.streaming.is_none()
+                && !self.bytes_value_in_progress
+                && !self.stream_next.load(Ordering::Acquire)
+                && !self.bytes_next.load(Ordering::Acquire)
+            {
+                let value = self.values.decode_eof(bytes)?;
+                self.value_in_progress = false;
+                return Ok(value.map(ResponseFrame::Value));
+            }
+            match self.decode(bytes)? {
+                Some(frame) => Ok(Some(frame)),
+                None if bytes.is_empty()
+                    && self.streaming.is_none()
+                    && !self.bytes_value_in_progress =>
+                {
+                    Ok(None)
+                }
+                None => Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+            }
+        }
+    }
+
+    /// A codec that decodes responses into [`BytesValue`]s.
+    ///
+    /// A value is only decoded once it has been received completely, at which
+    /// point it is split off the read buffer and its bulk strings are handed
+    /// out as slices of it instead of being copied.
+    ///
+    /// [`BytesValue`]: crate::BytesValue
+    #[derive(Default)]
+    pub struct BytesValueCodec {
+        scanner: super::bytes_support::ValueScanner,
+    }
+
+    impl Encoder<Vec<u8>> for BytesValueCodec {
+        type Error = RedisError;
+        fn encode(&mut self, item: Vec<u8>, dst: &mut BytesMut) -> Result<(), Self::Error> {
+            dst.extend_from_slice(item.as_ref());
+            Ok(())
+        }
+    }
+
+    impl Decoder for BytesValueCodec {
+        type Item = RedisResult<crate::types::BytesValue>;
+        type Error = RedisError;
+
+        fn decode(&mut self, bytes: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+            match self.scanner.scan(bytes)? {
+                Some(len) => {
+                    super::bytes_support::parse_complete_value(bytes.split_to(len).freeze())
+                        .map(Some)
+                }
+                None => Ok(None),
+            }
+        }
+    }
+
     /// Parses a redis value asynchronously.
     pub async fn parse_redis_value_async<R>(
         decoder: &mut combine::stream::Decoder<AnySendSyncPartialState, PointerOffset<[u8]>>,
@@ -655,4 +1060,199 @@ mod tests {
             Err(e) => assert!(matches!(e.kind(), ErrorKind::ParseError)),
         }
     }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn parse_bytes_value_slices_bulk_strings_out_of_the_buffer() {
+        use crate::types::BytesValue;
+
+        let bytes = bytes::Bytes::from_static(
+            b"*3\r\n$5\r\nhello\r\n%1\r\n+key\r\n$5\r\nworld\r\n$-1\r\n+trailing\r\n",
+        );
+        let value = parse_redis_bytes_value(&bytes).unwrap();
+
+        let buffer = bytes.as_ptr_range();
+        match &value {
+            BytesValue::Array(items) => match &items[0] {
+                BytesValue::BulkString(hello) => {
+                    assert_eq!(hello, "hello");
+                    assert!(buffer.contains(&hello.as_ptr()));
+                }
+                item => panic!("Expected BytesValue::BulkString, got {item:?}"),
+            },
+            value => panic!("Expected BytesValue::Array, got {value:?}"),
+        }
+
+        assert_eq!(
+            Value::from(value),
+            parse_redis_value(&bytes).unwrap(),
+            "both parsing modes must agree"
+        );
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn parse_bytes_value_reports_errors() {
+        let incomplete = bytes::Bytes::from_static(b"*2\r\n$5\r\nhello\r\n$5\r\nwor");
+        let err = parse_redis_bytes_value(&incomplete).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::IoError);
+
+        let nested_error =
+            bytes::Bytes::from_static(b"*3\r\n+OK\r\n-LOADING server is loading\r\n+OK\r\n");
+        let err = parse_redis_bytes_value(&nested_error).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BusyLoadingError);
+
+        let garbage = bytes::Bytes::from_static(b"$five\r\nhello\r\n");
+        let err = parse_redis_bytes_value(&garbage).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ParseError);
+    }
+
+    #[cfg(feature = "aio")]
+    #[test]
+    fn bytes_value_codec_decodes_values_split_across_reads() {
+        use crate::types::BytesValue;
+        use tokio_util::codec::Decoder;
+        let mut codec = BytesValueCodec::default();
+
+        let mut bytes = bytes::BytesMut::from(&b"*2\r\n$5\r\nhel"[..]);
+        assert_eq!(codec.decode(&mut bytes), Ok(None));
+        assert_eq!(bytes.len(), 11, "incomplete values must stay buffered");
+
+        bytes.extend_from_slice(b"lo\r\n:42\r\n+OK\r\n");
+        assert_eq!(
+            codec.decode(&mut bytes),
+            Ok(Some(Ok(BytesValue::Array(vec![
+                BytesValue::BulkString(bytes::Bytes::from_static(b"hello")),
+                BytesValue::Int(42),
+            ]))))
+        );
+        assert_eq!(codec.decode(&mut bytes), Ok(Some(Ok(BytesValue::Okay))));
+        assert_eq!(codec.decode_eof(&mut bytes), Ok(None));
+    }
+
+    #[cfg(feature = "aio")]
+    #[test]
+    fn streaming_codec_splits_bulk_strings_into_chunks() {
+        use std::sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        };
+        use tokio_util::codec::Decoder;
+
+        let stream_next = Arc::new(AtomicBool::new(false));
+        let mut codec =
+            StreamingValueCodec::new(stream_next.clone(), Arc::new(AtomicBool::new(false)));
+
+        let mut bytes = bytes::BytesMut::from(&b"$3\r\nfoo\r\n"[..]);
+        assert_eq!(
+            codec.decode(&mut bytes),
+            Ok(Some(ResponseFrame::Value(Ok(Value::BulkString(
+                b"foo".to_vec()
+            )))))
+        );
+
+        stream_next.store(true, Ordering::SeqCst);
+        let mut bytes = bytes::BytesMut::from(&b">2\r\n+message\r\n+hi\r\n$6\r\nfoo"[..]);
+        assert!(matches!(
+            codec.decode(&mut bytes),
+            Ok(Some(ResponseFrame::Value(Ok(Value::Push { .. }))))
+        ));
+        assert_eq!(
+            codec.decode(&mut bytes),
+            Ok(Some(ResponseFrame::BulkStart(6)))
+        );
+        assert_eq!(
+            codec.decode(&mut bytes),
+            Ok(Some(ResponseFrame::BulkChunk(bytes::Bytes::from_static(
+                b"foo"
+            ))))
+        );
+        assert_eq!(codec.decode(&mut bytes), Ok(None));
+        assert!(codec.decode_eof(&mut bytes).is_err());
+
+        bytes.extend_from_slice(b"bar\r");
+        assert_eq!(
+            codec.decode(&mut bytes),
+            Ok(Some(ResponseFrame::BulkChunk(bytes::Bytes::from_static(
+                b"bar"
+            ))))
+        );
+        assert_eq!(codec.decode(&mut bytes), Ok(None));
+        bytes.extend_from_slice(b"\n$-1\r\n");
+        assert_eq!(codec.decode(&mut bytes), Ok(Some(ResponseFrame::BulkEnd)));
+        assert_eq!(
+            codec.decode(&mut bytes),
+            Ok(Some(ResponseFrame::Value(Ok(Value::Nil))))
+        );
+
+        let mut bytes = bytes::BytesMut::from(&b"$1\r\nab\r\n"[..]);
+        assert_eq!(
+            codec.decode(&mut bytes),
+            Ok(Some(ResponseFrame::BulkStart(1)))
+        );
+        assert!(codec.decode(&mut bytes).is_ok());
+        assert_eq!(
+            codec.decode(&mut bytes).unwrap_err().kind(),
+            ErrorKind::ParseError
+        );
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn value_scanner_resumes_across_reads() {
+        let value = b"*3\r\n$5\r\nhello\r\n%1\r\n+key\r\n:1\r\n$-1\r\n:7\r\n";
+        let len = value.len() - 4;
+        let mut scanner = bytes_support::ValueScanner::default();
+        for end in 0..len {
+            assert_eq!(scanner.scan(&value[..end]), Ok(None), "{end}");
+        }
+        assert_eq!(scanner.scan(&value[..len]), Ok(Some(len)));
+        assert_eq!(scanner.scan(&value[len..]), Ok(Some(4)));
+
+        let mut scanner = bytes_support::ValueScanner::default();
+        assert_eq!(scanner.scan(b"*2\r\n$1000\r\n"), Ok(None));
+        assert_eq!(scanner.scan(b"*2\r\n$1000\r\nabc"), Ok(None));
+        let mut value = b"*2\r\n$1000\r\n".to_vec();
+        value.extend_from_slice(&[b'a'; 1000]);
+        value.extend_from_slice(b"\r\n:1\r\n");
+        assert_eq!(scanner.scan(&value), Ok(Some(value.len())));
+    }
+
+    #[cfg(feature = "aio")]
+    #[test]
+    fn streaming_codec_decodes_bytes_values() {
+        use crate::types::BytesValue;
+        use std::sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        };
+        use tokio_util::codec::Decoder;
+
+        let bytes_next = Arc::new(AtomicBool::new(true));
+        let mut codec =
+            StreamingValueCodec::new(Arc::new(AtomicBool::new(false)), bytes_next.clone());
+
+        let mut bytes = bytes::BytesMut::from(&b">2\r\n+message\r\n+hi\r\n$6\r\nfoo"[..]);
+        assert!(matches!(
+            codec.decode(&mut bytes),
+            Ok(Some(ResponseFrame::BytesValue(Ok(BytesValue::Push { .. }))))
+        ));
+        assert_eq!(codec.decode(&mut bytes), Ok(None));
+        assert!(codec.decode_eof(&mut bytes).is_err());
+
+        // The value that was started is finished even if the flag changes.
+        bytes_next.store(false, Ordering::SeqCst);
+        bytes.extend_from_slice(b"bar\r\n:1\r\n");
+        assert_eq!(
+            codec.decode(&mut bytes),
+            Ok(Some(ResponseFrame::BytesValue(Ok(BytesValue::BulkString(
+                bytes::Bytes::from_static(b"foobar")
+            )))))
+        );
+        assert_eq!(
+            codec.decode(&mut bytes),
+            Ok(Some(ResponseFrame::Value(Ok(Value::Int(1)))))
+        );
+        assert_eq!(codec.decode_eof(&mut bytes), Ok(None));
+    }
 }
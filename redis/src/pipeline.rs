@@ -70,11 +70,23 @@ impl Pipeline {
         self
     }
 
+    /// Returns `true` if the pipeline is in transaction mode (aka atomic mode).
+    #[cfg(any(feature = "aio", feature = "cluster-async"))]
+    pub(crate) fn is_atomic(&self) -> bool {
+        self.transaction_mode
+    }
+
     /// Returns the encoded pipeline commands.
     pub fn get_packed_pipeline(&self) -> Vec<u8> {
         encode_pipeline(&self.commands, self.transaction_mode)
     }
 
+    /// Returns the length of the packed pipeline, without packing it.
+    #[cfg(feature = "cluster-async")]
+    pub(crate) fn packed_len(&self) -> usize {
+        pipeline_len(&self.commands, self.transaction_mode)
+    }
+
     #[cfg(feature = "aio")]
     pub(crate) fn write_packed_pipeline(&self, out: &mut Vec<u8>) {
         write_pipeline(out, &self.commands, self.transaction_mode)
@@ -211,22 +223,26 @@ fn encode_pipeline(cmds: &[Cmd], atomic: bool) -> Vec<u8> {
     rv
 }
 
+/// Returns the length of the packed pipeline, without packing it.
+pub(crate) fn pipeline_len(cmds: &[Cmd], atomic: bool) -> usize {
+    let cmds_len = cmds.iter().map(cmd_len).sum::<usize>();
+    if atomic {
+        cmd_len(&cmd("MULTI")) + cmd_len(&cmd("EXEC")) + cmds_len
+    } else {
+        cmds_len
+    }
+}
+
 fn write_pipeline(rv: &mut Vec<u8>, cmds: &[Cmd], atomic: bool) {
-    let cmds_len = cmds.iter().map(cmd_len).sum();
+    rv.reserve(pipeline_len(cmds, atomic));
 
     if atomic {
-        let multi = cmd("MULTI");
-        let exec = cmd("EXEC");
-        rv.reserve(cmd_len(&multi) + cmd_len(&exec) + cmds_len);
-
-        multi.write_packed_command_preallocated(rv);
+        cmd("MULTI").write_packed_command_preallocated(rv);
         for cmd in cmds {
             cmd.write_packed_command_preallocated(rv);
         }
-        exec.write_packed_command_preallocated(rv);
+        cmd("EXEC").write_packed_command_preallocated(rv);
     } else {
-        rv.reserve(cmds_len);
-
         for cmd in cmds {
             cmd.write_packed_command_preallocated(rv);
         }
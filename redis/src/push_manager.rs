@@ -12,10 +12,19 @@ pub struct PushInfo {
     pub data: Vec<Value>,
 }
 
+/// A callback that is invoked synchronously for every push message, before it's sent to the channel.
+#[cfg(feature = "aio")]
+pub(crate) type PushListener = Arc<dyn Fn(&PushKind, &[Value]) + Send + Sync>;
+
+#[cfg(feature = "aio")]
+type WeakPushListener = std::sync::Weak<dyn Fn(&PushKind, &[Value]) + Send + Sync>;
+
 /// Manages Push messages for single tokio channel
 #[derive(Clone, Default)]
 pub struct PushManager {
     sender: Arc<ArcSwap<Option<mpsc::UnboundedSender<PushInfo>>>>,
+    #[cfg(feature = "aio")]
+    listeners: Arc<ArcSwap<Vec<WeakPushListener>>>,
 }
 impl PushManager {
     /// It checks if value's type is Push
@@ -30,6 +39,20 @@ impl PushManager {
     /// then creates PushInfo and invokes `send` method of sender
     pub(crate) fn try_send_raw(&self, value: &Value) {
         if let Value::Push { kind, data } = value {
+            #[cfg(feature = "aio")]
+            {
+                let mut has_dropped_listeners = false;
+                for listener in self.listeners.load().iter() {
+                    match listener.upgrade() {
+                        Some(listener) => listener(kind, data),
+                        None => has_dropped_listeners = true,
+                    }
+                }
+                if has_dropped_listeners {
+                    self.listeners
+                        .rcu(|listeners| live_listeners(listeners).collect::<Vec<_>>());
+                }
+            }
             let guard = self.sender.load();
             if let Some(sender) = guard.as_ref() {
                 let push_info = PushInfo {
@@ -47,14 +70,37 @@ impl PushManager {
         self.sender.store(Arc::new(Some(sender)));
     }
 
+    /// Adds a listener that is invoked for every push message, in addition to the channel and the
+    /// other listeners. The manager only keeps a weak reference to the listener, which is removed
+    /// once the listener is dropped.
+    #[cfg(feature = "aio")]
+    pub(crate) fn add_listener(&self, listener: &PushListener) {
+        let listener = Arc::downgrade(listener);
+        self.listeners.rcu(|listeners| {
+            live_listeners(listeners)
+                .chain(std::iter::once(listener.clone()))
+                .collect::<Vec<_>>()
+        });
+    }
+
     /// Creates new `PushManager`
     pub fn new() -> Self {
         PushManager {
             sender: Arc::from(ArcSwap::from(Arc::new(None))),
+            #[cfg(feature = "aio")]
+            listeners: Default::default(),
         }
     }
 }
 
+#[cfg(feature = "aio")]
+fn live_listeners(listeners: &[WeakPushListener]) -> impl Iterator<Item = WeakPushListener> + '_ {
+    listeners
+        .iter()
+        .filter(|listener| listener.strong_count() > 0)
+        .cloned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,6 +163,45 @@ mod tests {
             vec![Value::BulkString("hello2".to_string().into_bytes())]
         );
     }
+    #[cfg(feature = "aio")]
+    #[test]
+    fn test_push_manager_listener_receives_every_push() {
+        let push_manager = PushManager::new();
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        let listener: PushListener = Arc::new(move |kind, data| {
+            received_clone
+                .lock()
+                .unwrap()
+                .push((kind.clone(), data.to_vec()));
+        });
+        push_manager.add_listener(&listener);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        push_manager.clone().replace_sender(tx);
+
+        push_manager.try_send(&Ok(Value::Push {
+            kind: PushKind::Invalidate,
+            data: vec![Value::Array(vec![Value::BulkString(b"key".to_vec())])],
+        }));
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![(
+                PushKind::Invalidate,
+                vec![Value::Array(vec![Value::BulkString(b"key".to_vec())])]
+            )]
+        );
+        assert_eq!(rx.try_recv().unwrap().kind, PushKind::Invalidate);
+
+        drop(listener);
+        push_manager.try_send(&Ok(Value::Push {
+            kind: PushKind::Invalidate,
+            data: vec![],
+        }));
+        assert_eq!(received.lock().unwrap().len(), 1);
+        assert!(push_manager.listeners.load().is_empty());
+    }
+
     #[test]
     fn test_push_manager_multiple_channels_and_messages() {
         let push_manager = PushManager::new();
@@ -0,0 +1,1602 @@
+//! Defines types to use with the RediSearch (`FT.*`) commands of [`SearchCommands`].
+//!
+//! [`SearchCommands`]: ../trait.SearchCommands.html
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use redis::SearchCommands;
+//! use redis::search::{
+//!     IndexOptions, IndexType, Schema, SchemaField, SearchOptions, SearchReply, SortOrder,
+//! };
+//! # fn do_something() -> redis::RedisResult<()> {
+//! let client = redis::Client::open("redis://127.0.0.1/")?;
+//! let mut con = client.get_connection()?;
+//!
+//! let schema = Schema::default()
+//!     .field(SchemaField::text("title").weight(5.0))
+//!     .field(SchemaField::numeric("price").sortable())
+//!     .field(SchemaField::tag("brand"));
+//! let options = IndexOptions::default().on(IndexType::Hash).prefix("product:");
+//! let _: () = con.ft_create("products", &options, &schema)?;
+//!
+//! let options = SearchOptions::default()
+//!     .sort_by("price", SortOrder::Asc)
+//!     .limit(0, 10);
+//! let reply: SearchReply = con.ft_search("products", "@brand:{acme}", &options)?;
+//! for doc in reply.documents {
+//!     println!("{}: {:?}", doc.id, doc.fields);
+//! }
+//! # Ok(()) }
+//! ```
+
+use crate::types::{
+    ErrorKind, FromRedisValue, HashMap, RedisResult, RedisWrite, ToRedisArgs, Value,
+};
+
+macro_rules! invalid_type_error {
+    ($v:expr, $det:expr) => {{
+        fail!((
+            ErrorKind::TypeError,
+            "Response was of incompatible type",
+            format!("{:?} (response was {:?})", $det, $v)
+        ));
+    }};
+}
+
+/// Sort direction used by [`SearchOptions::sort_by`] and [`AggregateOptions::sort_by`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SortOrder {
+    /// Ascending order
+    Asc,
+    /// Descending order
+    Desc,
+}
+
+impl ToRedisArgs for SortOrder {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        match self {
+            SortOrder::Asc => out.write_arg(b"ASC"),
+            SortOrder::Desc => out.write_arg(b"DESC"),
+        }
+    }
+}
+
+/// The type of the keys indexed by an index.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum IndexType {
+    /// Index hashes (`ON HASH`), the server default.
+    Hash,
+    /// Index RedisJSON documents (`ON JSON`).
+    Json,
+}
+
+/// Builder options for the index definition of [`ft_create`].
+///
+/// [`ft_create`]: ../trait.SearchCommands.html#method.ft_create
+#[derive(Default, Debug, Clone)]
+pub struct IndexOptions {
+    on: Option<IndexType>,
+    prefixes: Vec<String>,
+    filter: Option<String>,
+    language: Option<String>,
+    score: Option<f64>,
+    max_text_fields: bool,
+    temporary: Option<u64>,
+    no_offsets: bool,
+    no_highlight: bool,
+    no_fields: bool,
+    no_freqs: bool,
+    stopwords: Option<Vec<String>>,
+    skip_initial_scan: bool,
+}
+
+impl IndexOptions {
+    /// Set `ON <HASH|JSON>` cmd arg.
+    pub fn on(mut self, index_type: IndexType) -> Self {
+        self.on = Some(index_type);
+        self
+    }
+
+    /// Add a key prefix to the `PREFIX` cmd arg. Only keys starting with one
+    /// of the prefixes are indexed.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefixes.push(prefix.into());
+        self
+    }
+
+    /// Set `FILTER <expression>` cmd arg, indexing only the keys matching it.
+    pub fn filter(mut self, expression: impl Into<String>) -> Self {
+        self.filter = Some(expression.into());
+        self
+    }
+
+    /// Set `LANGUAGE <language>` cmd arg, the default language of the documents.
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Set `SCORE <score>` cmd arg, the default score of the documents.
+    pub fn score(mut self, score: f64) -> Self {
+        self.score = Some(score);
+        self
+    }
+
+    /// Set `MAXTEXTFIELDS` cmd arg.
+    pub fn max_text_fields(mut self) -> Self {
+        self.max_text_fields = true;
+        self
+    }
+
+    /// Set `TEMPORARY <seconds>` cmd arg, expiring the index after it
+    /// stayed inactive for the given time.
+    pub fn temporary(mut self, seconds: u64) -> Self {
+        self.temporary = Some(seconds);
+        self
+    }
+
+    /// Set `NOOFFSETS` cmd arg.
+    pub fn no_offsets(mut self) -> Self {
+        self.no_offsets = true;
+        self
+    }
+
+    /// Set `NOHL` cmd arg.
+    pub fn no_highlight(mut self) -> Self {
+        self.no_highlight = true;
+        self
+    }
+
+    /// Set `NOFIELDS` cmd arg.
+    pub fn no_fields(mut self) -> Self {
+        self.no_fields = true;
+        self
+    }
+
+    /// Set `NOFREQS` cmd arg.
+    pub fn no_freqs(mut self) -> Self {
+        self.no_freqs = true;
+        self
+    }
+
+    /// Set `STOPWORDS <count> [<word> ...]` cmd arg. An empty list disables stopwords.
+    pub fn stopwords<S: Into<String>>(mut self, words: impl IntoIterator<Item = S>) -> Self {
+        self.stopwords = Some(words.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set `SKIPINITIALSCAN` cmd arg, so that existing keys aren't indexed.
+    pub fn skip_initial_scan(mut self) -> Self {
+        self.skip_initial_scan = true;
+        self
+    }
+}
+
+impl ToRedisArgs for IndexOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        match self.on {
+            Some(IndexType::Hash) => {
+                out.write_arg(b"ON");
+                out.write_arg(b"HASH");
+            }
+            Some(IndexType::Json) => {
+                out.write_arg(b"ON");
+                out.write_arg(b"JSON");
+            }
+            None => {}
+        }
+        if !self.prefixes.is_empty() {
+            out.write_arg(b"PREFIX");
+            out.write_arg_fmt(self.prefixes.len());
+            for prefix in &self.prefixes {
+                out.write_arg(prefix.as_bytes());
+            }
+        }
+        if let Some(ref filter) = self.filter {
+            out.write_arg(b"FILTER");
+            out.write_arg(filter.as_bytes());
+        }
+        if let Some(ref language) = self.language {
+            out.write_arg(b"LANGUAGE");
+            out.write_arg(language.as_bytes());
+        }
+        if let Some(score) = self.score {
+            out.write_arg(b"SCORE");
+            out.write_arg_fmt(score);
+        }
+        if self.max_text_fields {
+            out.write_arg(b"MAXTEXTFIELDS");
+        }
+        if let Some(seconds) = self.temporary {
+            out.write_arg(b"TEMPORARY");
+            out.write_arg_fmt(seconds);
+        }
+        if self.no_offsets {
+            out.write_arg(b"NOOFFSETS");
+        }
+        if self.no_highlight {
+            out.write_arg(b"NOHL");
+        }
+        if self.no_fields {
+            out.write_arg(b"NOFIELDS");
+        }
+        if self.no_freqs {
+            out.write_arg(b"NOFREQS");
+        }
+        if let Some(ref words) = self.stopwords {
+            out.write_arg(b"STOPWORDS");
+            out.write_arg_fmt(words.len());
+            for word in words {
+                out.write_arg(word.as_bytes());
+            }
+        }
+        if self.skip_initial_scan {
+            out.write_arg(b"SKIPINITIALSCAN");
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// The algorithm used to index a vector field.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum VectorAlgorithm {
+    /// Brute-force search (`FLAT`).
+    Flat,
+    /// Hierarchical Navigable Small World graphs (`HNSW`).
+    Hnsw,
+}
+
+#[derive(Debug, Clone)]
+enum FieldType {
+    Text,
+    Tag,
+    Numeric,
+    Geo,
+    GeoShape,
+    Vector(VectorAlgorithm, Vec<(String, String)>),
+}
+
+/// A field of a [`Schema`].
+///
+/// Options that don't apply to the type of the field are rejected by the server.
+#[derive(Debug, Clone)]
+pub struct SchemaField {
+    name: String,
+    alias: Option<String>,
+    field_type: FieldType,
+    sortable: bool,
+    unf: bool,
+    no_index: bool,
+    no_stem: bool,
+    weight: Option<f64>,
+    phonetic: Option<String>,
+    separator: Option<char>,
+    case_sensitive: bool,
+    with_suffix_trie: bool,
+}
+
+impl SchemaField {
+    fn new(name: impl Into<String>, field_type: FieldType) -> Self {
+        SchemaField {
+            name: name.into(),
+            alias: None,
+            field_type,
+            sortable: false,
+            unf: false,
+            no_index: false,
+            no_stem: false,
+            weight: None,
+            phonetic: None,
+            separator: None,
+            case_sensitive: false,
+            with_suffix_trie: false,
+        }
+    }
+
+    /// A full-text field (`TEXT`).
+    pub fn text(name: impl Into<String>) -> Self {
+        Self::new(name, FieldType::Text)
+    }
+
+    /// A tag field (`TAG`), matched exactly.
+    pub fn tag(name: impl Into<String>) -> Self {
+        Self::new(name, FieldType::Tag)
+    }
+
+    /// A numeric field (`NUMERIC`), allowing range queries.
+    pub fn numeric(name: impl Into<String>) -> Self {
+        Self::new(name, FieldType::Numeric)
+    }
+
+    /// A geographic field (`GEO`) holding `longitude,latitude` pairs.
+    pub fn geo(name: impl Into<String>) -> Self {
+        Self::new(name, FieldType::Geo)
+    }
+
+    /// A geometric field (`GEOSHAPE`) holding WKT shapes.
+    pub fn geo_shape(name: impl Into<String>) -> Self {
+        Self::new(name, FieldType::GeoShape)
+    }
+
+    /// A vector field (`VECTOR`). The attributes of the algorithm, such as
+    /// `TYPE`, `DIM` and `DISTANCE_METRIC`, are set with [`attribute`](Self::attribute).
+    pub fn vector(name: impl Into<String>, algorithm: VectorAlgorithm) -> Self {
+        Self::new(name, FieldType::Vector(algorithm, Vec::new()))
+    }
+
+    /// Adds an attribute of the algorithm of a vector field.
+    pub fn attribute(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        if let FieldType::Vector(_, ref mut attributes) = self.field_type {
+            attributes.push((name.into(), value.to_string()));
+        }
+        self
+    }
+
+    /// Set `AS <attribute>` cmd arg, the name the field is queried with.
+    /// This is required for JSON path fields.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Set `SORTABLE` cmd arg.
+    pub fn sortable(mut self) -> Self {
+        self.sortable = true;
+        self
+    }
+
+    /// Set `SORTABLE UNF` cmd args, sorting by the unnormalized value.
+    pub fn sortable_unf(mut self) -> Self {
+        self.sortable = true;
+        self.unf = true;
+        self
+    }
+
+    /// Set `NOINDEX` cmd arg, so that the field is only used for sorting.
+    pub fn no_index(mut self) -> Self {
+        self.no_index = true;
+        self
+    }
+
+    /// Set `NOSTEM` cmd arg of a text field.
+    pub fn no_stem(mut self) -> Self {
+        self.no_stem = true;
+        self
+    }
+
+    /// Set `WEIGHT <weight>` cmd arg of a text field.
+    pub fn weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Set `PHONETIC <matcher>` cmd arg of a text field, e.g. `dm:en`.
+    pub fn phonetic(mut self, matcher: impl Into<String>) -> Self {
+        self.phonetic = Some(matcher.into());
+        self
+    }
+
+    /// Set `SEPARATOR <separator>` cmd arg of a tag field.
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+
+    /// Set `CASESENSITIVE` cmd arg of a tag field.
+    pub fn case_sensitive(mut self) -> Self {
+        self.case_sensitive = true;
+        self
+    }
+
+    /// Set `WITHSUFFIXTRIE` cmd arg of a text or tag field.
+    pub fn with_suffix_trie(mut self) -> Self {
+        self.with_suffix_trie = true;
+        self
+    }
+}
+
+impl ToRedisArgs for SchemaField {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        out.write_arg(self.name.as_bytes());
+        if let Some(ref alias) = self.alias {
+            out.write_arg(b"AS");
+            out.write_arg(alias.as_bytes());
+        }
+        match self.field_type {
+            FieldType::Text => out.write_arg(b"TEXT"),
+            FieldType::Tag => out.write_arg(b"TAG"),
+            FieldType::Numeric => out.write_arg(b"NUMERIC"),
+            FieldType::Geo => out.write_arg(b"GEO"),
+            FieldType::GeoShape => out.write_arg(b"GEOSHAPE"),
+            FieldType::Vector(algorithm, ref attributes) => {
+                out.write_arg(b"VECTOR");
+                match algorithm {
+                    VectorAlgorithm::Flat => out.write_arg(b"FLAT"),
+                    VectorAlgorithm::Hnsw => out.write_arg(b"HNSW"),
+                }
+                out.write_arg_fmt(attributes.len() * 2);
+                for (name, value) in attributes {
+                    out.write_arg(name.as_bytes());
+                    out.write_arg(value.as_bytes());
+                }
+            }
+        }
+        if self.no_stem {
+            out.write_arg(b"NOSTEM");
+        }
+        if let Some(weight) = self.weight {
+            out.write_arg(b"WEIGHT");
+            out.write_arg_fmt(weight);
+        }
+        if let Some(ref matcher) = self.phonetic {
+            out.write_arg(b"PHONETIC");
+            out.write_arg(matcher.as_bytes());
+        }
+        if let Some(separator) = self.separator {
+            out.write_arg(b"SEPARATOR");
+            out.write_arg_fmt(separator);
+        }
+        if self.case_sensitive {
+            out.write_arg(b"CASESENSITIVE");
+        }
+        if self.with_suffix_trie {
+            out.write_arg(b"WITHSUFFIXTRIE");
+        }
+        if self.sortable {
+            out.write_arg(b"SORTABLE");
+            if self.unf {
+                out.write_arg(b"UNF");
+            }
+        }
+        if self.no_index {
+            out.write_arg(b"NOINDEX");
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// The fields of an index, used with [`ft_create`] and [`ft_alter`].
+///
+/// [`ft_create`]: ../trait.SearchCommands.html#method.ft_create
+/// [`ft_alter`]: ../trait.SearchCommands.html#method.ft_alter
+#[derive(Default, Debug, Clone)]
+pub struct Schema {
+    fields: Vec<SchemaField>,
+}
+
+impl Schema {
+    /// Adds a field to the schema.
+    pub fn field(mut self, field: SchemaField) -> Self {
+        self.fields.push(field);
+        self
+    }
+}
+
+impl ToRedisArgs for Schema {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        out.write_arg(b"SCHEMA");
+        for field in &self.fields {
+            field.write_redis_args(out);
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// Builder options for [`ft_search`] command.
+///
+/// [`ft_search`]: ../trait.SearchCommands.html#method.ft_search
+#[derive(Default, Debug, Clone)]
+pub struct SearchOptions {
+    no_content: bool,
+    verbatim: bool,
+    no_stopwords: bool,
+    with_scores: bool,
+    with_payloads: bool,
+    with_sort_keys: bool,
+    filters: Vec<(String, String, String)>,
+    in_keys: Vec<String>,
+    in_fields: Vec<String>,
+    return_fields: Option<Vec<(String, Option<String>)>>,
+    slop: Option<usize>,
+    timeout: Option<u64>,
+    in_order: bool,
+    language: Option<String>,
+    scorer: Option<String>,
+    sort_by: Option<(String, SortOrder)>,
+    limit: Option<(usize, usize)>,
+    params: Vec<(String, Vec<u8>)>,
+    dialect: Option<u32>,
+}
+
+impl SearchOptions {
+    /// Set `NOCONTENT` cmd arg, returning only the ids of the documents.
+    pub fn no_content(mut self) -> Self {
+        self.no_content = true;
+        self
+    }
+
+    /// Set `VERBATIM` cmd arg, disabling stemming of the query terms.
+    pub fn verbatim(mut self) -> Self {
+        self.verbatim = true;
+        self
+    }
+
+    /// Set `NOSTOPWORDS` cmd arg.
+    pub fn no_stopwords(mut self) -> Self {
+        self.no_stopwords = true;
+        self
+    }
+
+    /// Set `WITHSCORES` cmd arg, returning the relative score of each document.
+    pub fn with_scores(mut self) -> Self {
+        self.with_scores = true;
+        self
+    }
+
+    /// Set `WITHPAYLOADS` cmd arg, returning the payload of each document.
+    pub fn with_payloads(mut self) -> Self {
+        self.with_payloads = true;
+        self
+    }
+
+    /// Set `WITHSORTKEYS` cmd arg, returning the value each document was sorted by.
+    pub fn with_sort_keys(mut self) -> Self {
+        self.with_sort_keys = true;
+        self
+    }
+
+    /// Add a `FILTER <field> <min> <max>` cmd arg on a numeric field. Use
+    /// `-inf`/`+inf` for unbounded ranges, and a `(` prefix for exclusive bounds.
+    pub fn filter(
+        mut self,
+        field: impl Into<String>,
+        min: impl ToString,
+        max: impl ToString,
+    ) -> Self {
+        self.filters
+            .push((field.into(), min.to_string(), max.to_string()));
+        self
+    }
+
+    /// Add a key to the `INKEYS` cmd arg, limiting the search to the given documents.
+    pub fn in_key(mut self, key: impl Into<String>) -> Self {
+        self.in_keys.push(key.into());
+        self
+    }
+
+    /// Add a field to the `INFIELDS` cmd arg, limiting the search to the given fields.
+    pub fn in_field(mut self, field: impl Into<String>) -> Self {
+        self.in_fields.push(field.into());
+        self
+    }
+
+    /// Add a field to the `RETURN` cmd arg, limiting the fields returned for each document.
+    pub fn return_field(mut self, field: impl Into<String>) -> Self {
+        self.return_fields
+            .get_or_insert_with(Vec::new)
+            .push((field.into(), None));
+        self
+    }
+
+    /// Add a field to the `RETURN` cmd arg, returned under the name `alias`.
+    pub fn return_field_as(mut self, field: impl Into<String>, alias: impl Into<String>) -> Self {
+        self.return_fields
+            .get_or_insert_with(Vec::new)
+            .push((field.into(), Some(alias.into())));
+        self
+    }
+
+    /// Set `SLOP <slop>` cmd arg, the number of unmatched terms allowed between phrase terms.
+    pub fn slop(mut self, slop: usize) -> Self {
+        self.slop = Some(slop);
+        self
+    }
+
+    /// Set `TIMEOUT <milliseconds>` cmd arg.
+    pub fn timeout(mut self, ms: u64) -> Self {
+        self.timeout = Some(ms);
+        self
+    }
+
+    /// Set `INORDER` cmd arg, requiring phrase terms to appear in the query order.
+    pub fn in_order(mut self) -> Self {
+        self.in_order = true;
+        self
+    }
+
+    /// Set `LANGUAGE <language>` cmd arg, used to stem the query terms.
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Set `SCORER <scorer>` cmd arg, e.g. `BM25`.
+    pub fn scorer(mut self, scorer: impl Into<String>) -> Self {
+        self.scorer = Some(scorer.into());
+        self
+    }
+
+    /// Set `SORTBY <field> <ASC|DESC>` cmd arg.
+    pub fn sort_by(mut self, field: impl Into<String>, order: SortOrder) -> Self {
+        self.sort_by = Some((field.into(), order));
+        self
+    }
+
+    /// Set `LIMIT <offset> <num>` cmd arg. The server returns 10 documents by default.
+    pub fn limit(mut self, offset: usize, num: usize) -> Self {
+        self.limit = Some((offset, num));
+        self
+    }
+
+    /// Add a parameter to the `PARAMS` cmd arg, referenced as `$name` in the query.
+    /// Parameters require dialect 2 or greater.
+    pub fn param<V: ToRedisArgs>(mut self, name: impl Into<String>, value: V) -> Self {
+        let mut value = value.to_redis_args();
+        self.params
+            .push((name.into(), value.pop().unwrap_or_default()));
+        self
+    }
+
+    /// Set `DIALECT <dialect>` cmd arg.
+    pub fn dialect(mut self, dialect: u32) -> Self {
+        self.dialect = Some(dialect);
+        self
+    }
+}
+
+impl ToRedisArgs for SearchOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if self.no_content {
+            out.write_arg(b"NOCONTENT");
+        }
+        if self.verbatim {
+            out.write_arg(b"VERBATIM");
+        }
+        if self.no_stopwords {
+            out.write_arg(b"NOSTOPWORDS");
+        }
+        if self.with_scores {
+            out.write_arg(b"WITHSCORES");
+        }
+        if self.with_payloads {
+            out.write_arg(b"WITHPAYLOADS");
+        }
+        if self.with_sort_keys {
+            out.write_arg(b"WITHSORTKEYS");
+        }
+        for (field, min, max) in &self.filters {
+            out.write_arg(b"FILTER");
+            out.write_arg(field.as_bytes());
+            out.write_arg(min.as_bytes());
+            out.write_arg(max.as_bytes());
+        }
+        if !self.in_keys.is_empty() {
+            out.write_arg(b"INKEYS");
+            out.write_arg_fmt(self.in_keys.len());
+            for key in &self.in_keys {
+                out.write_arg(key.as_bytes());
+            }
+        }
+        if !self.in_fields.is_empty() {
+            out.write_arg(b"INFIELDS");
+            out.write_arg_fmt(self.in_fields.len());
+            for field in &self.in_fields {
+                out.write_arg(field.as_bytes());
+            }
+        }
+        if let Some(ref fields) = self.return_fields {
+            let count: usize = fields
+                .iter()
+                .map(|(_, alias)| if alias.is_some() { 3 } else { 1 })
+                .sum();
+            out.write_arg(b"RETURN");
+            out.write_arg_fmt(count);
+            for (field, alias) in fields {
+                out.write_arg(field.as_bytes());
+                if let Some(alias) = alias {
+                    out.write_arg(b"AS");
+                    out.write_arg(alias.as_bytes());
+                }
+            }
+        }
+        if let Some(slop) = self.slop {
+            out.write_arg(b"SLOP");
+            out.write_arg_fmt(slop);
+        }
+        if let Some(ms) = self.timeout {
+            out.write_arg(b"TIMEOUT");
+            out.write_arg_fmt(ms);
+        }
+        if self.in_order {
+            out.write_arg(b"INORDER");
+        }
+        if let Some(ref language) = self.language {
+            out.write_arg(b"LANGUAGE");
+            out.write_arg(language.as_bytes());
+        }
+        if let Some(ref scorer) = self.scorer {
+            out.write_arg(b"SCORER");
+            out.write_arg(scorer.as_bytes());
+        }
+        if let Some((ref field, order)) = self.sort_by {
+            out.write_arg(b"SORTBY");
+            out.write_arg(field.as_bytes());
+            order.write_redis_args(out);
+        }
+        if let Some((offset, num)) = self.limit {
+            out.write_arg(b"LIMIT");
+            out.write_arg_fmt(offset);
+            out.write_arg_fmt(num);
+        }
+        write_params(&self.params, out);
+        if let Some(dialect) = self.dialect {
+            out.write_arg(b"DIALECT");
+            out.write_arg_fmt(dialect);
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+fn write_params<W>(params: &[(String, Vec<u8>)], out: &mut W)
+where
+    W: ?Sized + RedisWrite,
+{
+    if !params.is_empty() {
+        out.write_arg(b"PARAMS");
+        out.write_arg_fmt(params.len() * 2);
+        for (name, value) in params {
+            out.write_arg(name.as_bytes());
+            out.write_arg(value);
+        }
+    }
+}
+
+/// A reducer of an [`AggregateOptions::group_by`] step, such as `COUNT` or `SUM`.
+#[derive(Debug, Clone)]
+pub struct Reducer {
+    function: String,
+    args: Vec<String>,
+    alias: Option<String>,
+}
+
+impl Reducer {
+    /// A reducer calling `function` with `args`.
+    pub fn new<S: Into<String>>(
+        function: impl Into<String>,
+        args: impl IntoIterator<Item = S>,
+    ) -> Self {
+        Reducer {
+            function: function.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            alias: None,
+        }
+    }
+
+    /// Counts the records of each group (`COUNT`).
+    pub fn count() -> Self {
+        Self::new("COUNT", Vec::<String>::new())
+    }
+
+    /// Counts the distinct values of `property` (`COUNT_DISTINCT`).
+    pub fn count_distinct(property: impl Into<String>) -> Self {
+        Self::new("COUNT_DISTINCT", [property])
+    }
+
+    /// Sums the values of `property` (`SUM`).
+    pub fn sum(property: impl Into<String>) -> Self {
+        Self::new("SUM", [property])
+    }
+
+    /// The minimal value of `property` (`MIN`).
+    pub fn min(property: impl Into<String>) -> Self {
+        Self::new("MIN", [property])
+    }
+
+    /// The maximal value of `property` (`MAX`).
+    pub fn max(property: impl Into<String>) -> Self {
+        Self::new("MAX", [property])
+    }
+
+    /// The average value of `property` (`AVG`).
+    pub fn avg(property: impl Into<String>) -> Self {
+        Self::new("AVG", [property])
+    }
+
+    /// The distinct values of `property` (`TOLIST`).
+    pub fn to_list(property: impl Into<String>) -> Self {
+        Self::new("TOLIST", [property])
+    }
+
+    /// Set `AS <name>` cmd arg, the property the result is stored in.
+    pub fn alias(mut self, name: impl Into<String>) -> Self {
+        self.alias = Some(name.into());
+        self
+    }
+}
+
+impl ToRedisArgs for Reducer {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        out.write_arg(b"REDUCE");
+        out.write_arg(self.function.as_bytes());
+        out.write_arg_fmt(self.args.len());
+        for arg in &self.args {
+            out.write_arg(arg.as_bytes());
+        }
+        if let Some(ref alias) = self.alias {
+            out.write_arg(b"AS");
+            out.write_arg(alias.as_bytes());
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Clone)]
+enum AggregateStep {
+    GroupBy(Vec<String>, Vec<Reducer>),
+    SortBy(Vec<(String, SortOrder)>, Option<usize>),
+    Apply(String, String),
+    Limit(usize, usize),
+    Filter(String),
+}
+
+/// Builder options for [`ft_aggregate`] command.
+///
+/// The pipeline steps (`GROUPBY`, `SORTBY`, `APPLY`, `LIMIT` and `FILTER`)
+/// are sent in the order they were added.
+///
+/// [`ft_aggregate`]: ../trait.SearchCommands.html#method.ft_aggregate
+#[derive(Default, Debug, Clone)]
+pub struct AggregateOptions {
+    verbatim: bool,
+    load: Option<Vec<String>>,
+    timeout: Option<u64>,
+    steps: Vec<AggregateStep>,
+    cursor: Option<(Option<usize>, Option<u64>)>,
+    params: Vec<(String, Vec<u8>)>,
+    dialect: Option<u32>,
+}
+
+impl AggregateOptions {
+    /// Set `VERBATIM` cmd arg, disabling stemming of the query terms.
+    pub fn verbatim(mut self) -> Self {
+        self.verbatim = true;
+        self
+    }
+
+    /// Add a field to the `LOAD` cmd arg, loading it from the document.
+    pub fn load(mut self, field: impl Into<String>) -> Self {
+        self.load.get_or_insert_with(Vec::new).push(field.into());
+        self
+    }
+
+    /// Set `LOAD *` cmd arg, loading every field of the documents.
+    pub fn load_all(mut self) -> Self {
+        self.load = Some(vec!["*".to_string()]);
+        self
+    }
+
+    /// Set `TIMEOUT <milliseconds>` cmd arg.
+    pub fn timeout(mut self, ms: u64) -> Self {
+        self.timeout = Some(ms);
+        self
+    }
+
+    /// Add a `GROUPBY` step grouping the records by `properties`, and reducing
+    /// each group with `reducers`.
+    pub fn group_by<S: Into<String>>(
+        mut self,
+        properties: impl IntoIterator<Item = S>,
+        reducers: impl IntoIterator<Item = Reducer>,
+    ) -> Self {
+        self.steps.push(AggregateStep::GroupBy(
+            properties.into_iter().map(Into::into).collect(),
+            reducers.into_iter().collect(),
+        ));
+        self
+    }
+
+    /// Add a `SORTBY` step sorting the records by `property`.
+    /// Consecutive calls are merged into a single step sorting by each property in turn.
+    pub fn sort_by(mut self, property: impl Into<String>, order: SortOrder) -> Self {
+        match self.steps.last_mut() {
+            Some(AggregateStep::SortBy(properties, None)) => {
+                properties.push((property.into(), order));
+            }
+            _ => self
+                .steps
+                .push(AggregateStep::SortBy(vec![(property.into(), order)], None)),
+        }
+        self
+    }
+
+    /// Set the `MAX <num>` cmd arg of the last `SORTBY` step, only keeping the first records.
+    pub fn sort_max(mut self, num: usize) -> Self {
+        if let Some(AggregateStep::SortBy(_, max)) = self.steps.last_mut() {
+            *max = Some(num);
+        }
+        self
+    }
+
+    /// Add an `APPLY <expression> AS <name>` step.
+    pub fn apply(mut self, expression: impl Into<String>, name: impl Into<String>) -> Self {
+        self.steps
+            .push(AggregateStep::Apply(expression.into(), name.into()));
+        self
+    }
+
+    /// Add a `LIMIT <offset> <num>` step.
+    pub fn limit(mut self, offset: usize, num: usize) -> Self {
+        self.steps.push(AggregateStep::Limit(offset, num));
+        self
+    }
+
+    /// Add a `FILTER <expression>` step.
+    pub fn filter(mut self, expression: impl Into<String>) -> Self {
+        self.steps.push(AggregateStep::Filter(expression.into()));
+        self
+    }
+
+    /// Set `WITHCURSOR` cmd arg, so that the results are read in batches with
+    /// [`ft_cursor_read`].
+    ///
+    /// [`ft_cursor_read`]: ../trait.SearchCommands.html#method.ft_cursor_read
+    pub fn with_cursor(mut self) -> Self {
+        self.cursor.get_or_insert((None, None));
+        self
+    }
+
+    /// Set `WITHCURSOR COUNT <count>` cmd args, the size of each batch.
+    pub fn cursor_count(mut self, count: usize) -> Self {
+        self.cursor.get_or_insert((None, None)).0 = Some(count);
+        self
+    }
+
+    /// Set `WITHCURSOR MAXIDLE <milliseconds>` cmd args, after which an unread cursor is deleted.
+    pub fn cursor_max_idle(mut self, ms: u64) -> Self {
+        self.cursor.get_or_insert((None, None)).1 = Some(ms);
+        self
+    }
+
+    /// Add a parameter to the `PARAMS` cmd arg, referenced as `$name` in the query.
+    /// Parameters require dialect 2 or greater.
+    pub fn param<V: ToRedisArgs>(mut self, name: impl Into<String>, value: V) -> Self {
+        let mut value = value.to_redis_args();
+        self.params
+            .push((name.into(), value.pop().unwrap_or_default()));
+        self
+    }
+
+    /// Set `DIALECT <dialect>` cmd arg.
+    pub fn dialect(mut self, dialect: u32) -> Self {
+        self.dialect = Some(dialect);
+        self
+    }
+}
+
+impl ToRedisArgs for AggregateOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if self.verbatim {
+            out.write_arg(b"VERBATIM");
+        }
+        if let Some(ref fields) = self.load {
+            out.write_arg(b"LOAD");
+            if fields.len() == 1 && fields[0] == "*" {
+                out.write_arg(b"*");
+            } else {
+                out.write_arg_fmt(fields.len());
+                for field in fields {
+                    out.write_arg(field.as_bytes());
+                }
+            }
+        }
+        if let Some(ms) = self.timeout {
+            out.write_arg(b"TIMEOUT");
+            out.write_arg_fmt(ms);
+        }
+        for step in &self.steps {
+            match step {
+                AggregateStep::GroupBy(properties, reducers) => {
+                    out.write_arg(b"GROUPBY");
+                    out.write_arg_fmt(properties.len());
+                    for property in properties {
+                        out.write_arg(property.as_bytes());
+                    }
+                    for reducer in reducers {
+                        reducer.write_redis_args(out);
+                    }
+                }
+                AggregateStep::SortBy(properties, max) => {
+                    out.write_arg(b"SORTBY");
+                    out.write_arg_fmt(properties.len() * 2);
+                    for (property, order) in properties {
+                        out.write_arg(property.as_bytes());
+                        order.write_redis_args(out);
+                    }
+                    if let Some(max) = max {
+                        out.write_arg(b"MAX");
+                        out.write_arg_fmt(max);
+                    }
+                }
+                AggregateStep::Apply(expression, name) => {
+                    out.write_arg(b"APPLY");
+                    out.write_arg(expression.as_bytes());
+                    out.write_arg(b"AS");
+                    out.write_arg(name.as_bytes());
+                }
+                AggregateStep::Limit(offset, num) => {
+                    out.write_arg(b"LIMIT");
+                    out.write_arg_fmt(offset);
+                    out.write_arg_fmt(num);
+                }
+                AggregateStep::Filter(expression) => {
+                    out.write_arg(b"FILTER");
+                    out.write_arg(expression.as_bytes());
+                }
+            }
+        }
+        if let Some((count, max_idle)) = self.cursor {
+            out.write_arg(b"WITHCURSOR");
+            if let Some(count) = count {
+                out.write_arg(b"COUNT");
+                out.write_arg_fmt(count);
+            }
+            if let Some(ms) = max_idle {
+                out.write_arg(b"MAXIDLE");
+                out.write_arg_fmt(ms);
+            }
+        }
+        write_params(&self.params, out);
+        if let Some(dialect) = self.dialect {
+            out.write_arg(b"DIALECT");
+            out.write_arg_fmt(dialect);
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// A document returned by [`ft_search`].
+///
+/// [`ft_search`]: ../trait.SearchCommands.html#method.ft_search
+#[derive(Default, Debug, Clone)]
+pub struct SearchDocument {
+    /// The key of the document.
+    pub id: String,
+    /// The relative score of the document, with [`SearchOptions::with_scores`].
+    pub score: Option<f64>,
+    /// The payload of the document, with [`SearchOptions::with_payloads`].
+    pub payload: Option<Vec<u8>>,
+    /// The value the document was sorted by, with [`SearchOptions::with_sort_keys`].
+    pub sort_key: Option<String>,
+    /// The returned fields of the document, empty with [`SearchOptions::no_content`].
+    pub fields: HashMap<String, Value>,
+}
+
+impl SearchDocument {
+    /// Fetches value of a given field and converts it to the specified type.
+    pub fn get<T: FromRedisValue>(&self, field: &str) -> Option<T> {
+        match self.fields.get(field) {
+            Some(v) => T::from_redis_value(v).ok(),
+            None => None,
+        }
+    }
+}
+
+/// Reply type used with [`ft_search`] command.
+///
+/// With RESP2, the layout of the reply depends on the `WITHSCORES`, `WITHPAYLOADS`
+/// and `WITHSORTKEYS` options, which `FromRedisValue` can't know about. Replies to
+/// searches using any of them must be parsed with [`SearchReply::parse`] instead.
+/// `FromRedisValue` recognizes `NOCONTENT` replies, which only list the ids of the
+/// documents. RESP3 replies are self-describing and can always be converted directly.
+///
+/// [`ft_search`]: ../trait.SearchCommands.html#method.ft_search
+#[derive(Default, Debug, Clone)]
+pub struct SearchReply {
+    /// The total number of matching documents, which may be more than the
+    /// documents returned due to `LIMIT`.
+    pub total: usize,
+    /// The returned documents.
+    pub documents: Vec<SearchDocument>,
+}
+
+impl SearchReply {
+    /// Parses the reply of a search that was made with `options`.
+    pub fn parse(v: &Value, options: &SearchOptions) -> RedisResult<Self> {
+        match v {
+            Value::Array(values) => Self::parse_resp2(v, values, options),
+            _ => Self::parse_resp3(v),
+        }
+    }
+
+    fn parse_resp2(v: &Value, values: &[Value], options: &SearchOptions) -> RedisResult<Self> {
+        let mut values = values.iter();
+        let total = match values.next() {
+            Some(total) => usize::from_redis_value(total)?,
+            None => invalid_type_error!(v, "Response type not FT.SEARCH compatible"),
+        };
+        let mut documents = Vec::new();
+        while let Some(id) = values.next() {
+            let mut doc = SearchDocument {
+                id: String::from_redis_value(id)?,
+                ..Default::default()
+            };
+            let mut next = || match values.next() {
+                Some(value) => Ok(value),
+                None => Err(crate::RedisError::from((
+                    ErrorKind::TypeError,
+                    "Response type not FT.SEARCH compatible",
+                    "missing document data".to_string(),
+                ))),
+            };
+            if options.with_scores {
+                doc.score = Some(f64::from_redis_value(next()?)?);
+            }
+            if options.with_payloads {
+                doc.payload = Option::from_redis_value(next()?)?;
+            }
+            if options.with_sort_keys {
+                doc.sort_key = Option::from_redis_value(next()?)?;
+            }
+            if !options.no_content {
+                doc.fields = parse_fields(next()?)?;
+            }
+            documents.push(doc);
+        }
+        Ok(SearchReply { total, documents })
+    }
+
+    fn parse_resp3(v: &Value) -> RedisResult<Self> {
+        let mut map: HashMap<String, Value> = FromRedisValue::from_redis_value(v)?;
+        let total = match map.get("total_results") {
+            Some(total) => usize::from_redis_value(total)?,
+            None => invalid_type_error!(v, "Response type not FT.SEARCH compatible"),
+        };
+        let results: Vec<HashMap<String, Value>> = match map.remove("results") {
+            Some(results) => FromRedisValue::from_owned_redis_value(results)?,
+            None => Vec::new(),
+        };
+        let mut documents = Vec::with_capacity(results.len());
+        for result in results {
+            let mut doc = SearchDocument::default();
+            for (key, value) in result {
+                match key.as_str() {
+                    "id" => doc.id = String::from_redis_value(&value)?,
+                    "score" => doc.score = Option::from_redis_value(&value)?,
+                    "payload" => doc.payload = Option::from_redis_value(&value)?,
+                    "sortkey" => doc.sort_key = Option::from_redis_value(&value)?,
+                    "extra_attributes" => doc.fields = parse_fields(&value)?,
+                    _ => {}
+                }
+            }
+            documents.push(doc);
+        }
+        Ok(SearchReply { total, documents })
+    }
+}
+
+impl FromRedisValue for SearchReply {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let options = match v {
+            // Without `NOCONTENT`, the id of every document is followed by an array of its fields.
+            Value::Array(values)
+                if values.len() > 1
+                    && values[1..]
+                        .iter()
+                        .all(|value| !matches!(value, Value::Array(_))) =>
+            {
+                SearchOptions::default().no_content()
+            }
+            _ => SearchOptions::default(),
+        };
+        SearchReply::parse(v, &options)
+    }
+}
+
+/// Reply type used with [`ft_aggregate`] and [`ft_cursor_read`] commands.
+///
+/// [`ft_aggregate`]: ../trait.SearchCommands.html#method.ft_aggregate
+/// [`ft_cursor_read`]: ../trait.SearchCommands.html#method.ft_cursor_read
+#[derive(Default, Debug, Clone)]
+pub struct AggregateReply {
+    /// The number of records reported by the server. With a cursor, this
+    /// isn't the total number of rows.
+    pub total: usize,
+    /// The properties of each returned record.
+    pub rows: Vec<HashMap<String, Value>>,
+    /// The id of the cursor to pass to [`ft_cursor_read`], if the aggregation
+    /// was made with a cursor and has more results. The cursor is exhausted once
+    /// the server returns 0, which is reported as `None`.
+    ///
+    /// [`ft_cursor_read`]: ../trait.SearchCommands.html#method.ft_cursor_read
+    pub cursor: Option<u64>,
+}
+
+impl FromRedisValue for AggregateReply {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        match v {
+            // WITHCURSOR replies with the results followed by the cursor id.
+            Value::Array(values)
+                if values.len() == 2
+                    && matches!(values[0], Value::Array(_) | Value::Map(_))
+                    && matches!(values[1], Value::Int(_)) =>
+            {
+                let mut reply = AggregateReply::from_redis_value(&values[0])?;
+                let cursor = u64::from_redis_value(&values[1])?;
+                reply.cursor = if cursor == 0 { None } else { Some(cursor) };
+                Ok(reply)
+            }
+            Value::Array(values) => {
+                let mut values = values.iter();
+                let total = match values.next() {
+                    Some(total) => usize::from_redis_value(total)?,
+                    None => invalid_type_error!(v, "Response type not FT.AGGREGATE compatible"),
+                };
+                let rows = values.map(parse_fields).collect::<RedisResult<_>>()?;
+                Ok(AggregateReply {
+                    total,
+                    rows,
+                    cursor: None,
+                })
+            }
+            _ => {
+                let mut map: HashMap<String, Value> = FromRedisValue::from_redis_value(v)?;
+                let total = match map.get("total_results") {
+                    Some(total) => usize::from_redis_value(total)?,
+                    None => invalid_type_error!(v, "Response type not FT.AGGREGATE compatible"),
+                };
+                let results: Vec<HashMap<String, Value>> = match map.remove("results") {
+                    Some(results) => FromRedisValue::from_owned_redis_value(results)?,
+                    None => Vec::new(),
+                };
+                let rows = results
+                    .iter()
+                    .map(|result| match result.get("extra_attributes") {
+                        Some(fields) => parse_fields(fields),
+                        None => Ok(HashMap::default()),
+                    })
+                    .collect::<RedisResult<_>>()?;
+                Ok(AggregateReply {
+                    total,
+                    rows,
+                    cursor: None,
+                })
+            }
+        }
+    }
+}
+
+/// Parses the flat `[field, value, ...]` arrays (or maps with RESP3) of documents and rows.
+fn parse_fields(v: &Value) -> RedisResult<HashMap<String, Value>> {
+    match v {
+        Value::Nil => Ok(HashMap::default()),
+        _ => FromRedisValue::from_redis_value(v),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str;
+
+    macro_rules! assert_args {
+        ($value:expr, $($args:expr),+) => {
+            let args = $value.to_redis_args();
+            let strings: Vec<_> = args.iter()
+                                      .map(|a| str::from_utf8(a.as_ref()).unwrap())
+                                      .collect();
+            assert_eq!(strings, vec![$($args),+]);
+        }
+    }
+
+    fn bulk(s: &str) -> Value {
+        Value::BulkString(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_schema_args() {
+        let schema = Schema::default()
+            .field(SchemaField::text("title").weight(5.0).sortable())
+            .field(SchemaField::tag("$.tags[*]").alias("tags").separator(';'))
+            .field(SchemaField::numeric("price").sortable_unf().no_index())
+            .field(
+                SchemaField::vector("embedding", VectorAlgorithm::Hnsw)
+                    .attribute("TYPE", "FLOAT32")
+                    .attribute("DIM", 4),
+            );
+        assert_args!(
+            &schema,
+            "SCHEMA",
+            "title",
+            "TEXT",
+            "WEIGHT",
+            "5",
+            "SORTABLE",
+            "$.tags[*]",
+            "AS",
+            "tags",
+            "TAG",
+            "SEPARATOR",
+            ";",
+            "price",
+            "NUMERIC",
+            "SORTABLE",
+            "UNF",
+            "NOINDEX",
+            "embedding",
+            "VECTOR",
+            "HNSW",
+            "4",
+            "TYPE",
+            "FLOAT32",
+            "DIM",
+            "4"
+        );
+
+        let empty = IndexOptions::default();
+        assert_eq!(empty.to_redis_args().len(), 0);
+
+        let options = IndexOptions::default()
+            .on(IndexType::Json)
+            .prefix("a:")
+            .prefix("b:")
+            .stopwords(Vec::<String>::new());
+        assert_args!(
+            &options,
+            "ON",
+            "JSON",
+            "PREFIX",
+            "2",
+            "a:",
+            "b:",
+            "STOPWORDS",
+            "0"
+        );
+    }
+
+    #[test]
+    fn test_search_options_args() {
+        let empty = SearchOptions::default();
+        assert_eq!(empty.to_redis_args().len(), 0);
+
+        let options = SearchOptions::default()
+            .with_scores()
+            .filter("price", 10, "+inf")
+            .return_field("title")
+            .return_field_as("$.price", "price")
+            .sort_by("price", SortOrder::Desc)
+            .limit(0, 5)
+            .param("vec", &b"\x00\x01"[..])
+            .dialect(2);
+        assert_args!(
+            &options,
+            "WITHSCORES",
+            "FILTER",
+            "price",
+            "10",
+            "+inf",
+            "RETURN",
+            "4",
+            "title",
+            "$.price",
+            "AS",
+            "price",
+            "SORTBY",
+            "price",
+            "DESC",
+            "LIMIT",
+            "0",
+            "5",
+            "PARAMS",
+            "2",
+            "vec",
+            "\x00\x01",
+            "DIALECT",
+            "2"
+        );
+    }
+
+    #[test]
+    fn test_aggregate_options_args() {
+        let options = AggregateOptions::default()
+            .load("@title")
+            .group_by(
+                ["@brand"],
+                [Reducer::count().alias("count"), Reducer::avg("@price")],
+            )
+            .sort_by("@count", SortOrder::Desc)
+            .sort_by("@brand", SortOrder::Asc)
+            .sort_max(10)
+            .apply("@count * 2", "double")
+            .filter("@count > 1")
+            .limit(0, 5)
+            .cursor_count(100);
+        assert_args!(
+            &options,
+            "LOAD",
+            "1",
+            "@title",
+            "GROUPBY",
+            "1",
+            "@brand",
+            "REDUCE",
+            "COUNT",
+            "0",
+            "AS",
+            "count",
+            "REDUCE",
+            "AVG",
+            "1",
+            "@price",
+            "SORTBY",
+            "4",
+            "@count",
+            "DESC",
+            "@brand",
+            "ASC",
+            "MAX",
+            "10",
+            "APPLY",
+            "@count * 2",
+            "AS",
+            "double",
+            "FILTER",
+            "@count > 1",
+            "LIMIT",
+            "0",
+            "5",
+            "WITHCURSOR",
+            "COUNT",
+            "100"
+        );
+
+        assert_args!(AggregateOptions::default().load_all(), "LOAD", "*");
+    }
+
+    #[test]
+    fn test_search_reply() {
+        let reply: SearchReply = FromRedisValue::from_redis_value(&Value::Array(vec![
+            Value::Int(2),
+            bulk("doc:1"),
+            Value::Array(vec![bulk("title"), bulk("hello")]),
+            bulk("doc:2"),
+            Value::Array(vec![bulk("title"), bulk("world")]),
+        ]))
+        .unwrap();
+        assert_eq!(reply.total, 2);
+        assert_eq!(reply.documents.len(), 2);
+        assert_eq!(reply.documents[1].id, "doc:2");
+        assert_eq!(
+            reply.documents[1].get::<String>("title"),
+            Some("world".to_string())
+        );
+
+        // a RESP2 reply to a search with `NOCONTENT`
+        let reply: SearchReply = FromRedisValue::from_redis_value(&Value::Array(vec![
+            Value::Int(3),
+            bulk("doc:1"),
+            bulk("doc:2"),
+        ]))
+        .unwrap();
+        assert_eq!(reply.total, 3);
+        assert_eq!(reply.documents.len(), 2);
+        assert_eq!(reply.documents[1].id, "doc:2");
+        assert!(reply.documents[1].fields.is_empty());
+
+        let options = SearchOptions::default()
+            .no_content()
+            .with_scores()
+            .with_payloads();
+        let reply = SearchReply::parse(
+            &Value::Array(vec![
+                Value::Int(10),
+                bulk("doc:1"),
+                bulk("1.5"),
+                Value::Nil,
+                bulk("doc:2"),
+                bulk("0.5"),
+                bulk("payload"),
+            ]),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(reply.total, 10);
+        assert_eq!(reply.documents[0].score, Some(1.5));
+        assert_eq!(reply.documents[0].payload, None);
+        assert!(reply.documents[0].fields.is_empty());
+        assert_eq!(reply.documents[1].payload, Some(b"payload".to_vec()));
+
+        // a RESP2 reply cut short
+        let result = SearchReply::parse(
+            &Value::Array(vec![Value::Int(1), bulk("doc:1")]),
+            &SearchOptions::default().with_scores(),
+        );
+        assert!(result.is_err());
+
+        let reply: SearchReply = FromRedisValue::from_redis_value(&Value::Map(vec![
+            (bulk("total_results"), Value::Int(1)),
+            (
+                bulk("results"),
+                Value::Array(vec![Value::Map(vec![
+                    (bulk("id"), bulk("doc:1")),
+                    (bulk("score"), Value::Double(2.0)),
+                    (
+                        bulk("extra_attributes"),
+                        Value::Map(vec![(bulk("title"), bulk("hello"))]),
+                    ),
+                ])]),
+            ),
+        ]))
+        .unwrap();
+        assert_eq!(reply.total, 1);
+        assert_eq!(reply.documents[0].id, "doc:1");
+        assert_eq!(reply.documents[0].score, Some(2.0));
+        assert_eq!(
+            reply.documents[0].get::<String>("title"),
+            Some("hello".to_string())
+        );
+    }
+
+    #[test]
+    fn test_aggregate_reply() {
+        let rows = Value::Array(vec![
+            Value::Int(2),
+            Value::Array(vec![bulk("brand"), bulk("acme"), bulk("count"), bulk("3")]),
+            Value::Array(vec![bulk("brand"), bulk("other"), bulk("count"), bulk("1")]),
+        ]);
+        let reply: AggregateReply = FromRedisValue::from_redis_value(&rows).unwrap();
+        assert_eq!(reply.total, 2);
+        assert_eq!(reply.rows.len(), 2);
+        assert_eq!(reply.rows[0].get("brand"), Some(&bulk("acme")));
+        assert_eq!(reply.cursor, None);
+
+        let reply: AggregateReply =
+            FromRedisValue::from_redis_value(&Value::Array(vec![rows.clone(), Value::Int(42)]))
+                .unwrap();
+        assert_eq!(reply.rows.len(), 2);
+        assert_eq!(reply.cursor, Some(42));
+
+        let reply: AggregateReply =
+            FromRedisValue::from_redis_value(&Value::Array(vec![rows, Value::Int(0)])).unwrap();
+        assert_eq!(reply.cursor, None);
+    }
+}
@@ -38,6 +38,9 @@
 //! assert_eq!(rv, "test_data");
 //! ```
 //!
+//! With the `connection-manager` feature, a SentinelClient can also be wrapped in a
+//! `SentinelConnectionManager`, which reconnects to the new master after a failover.
+//!
 //! If the sentinel's nodes are using TLS or require authentication, a full
 //! SentinelNodeConnectionInfo struct may be used instead of just the master's name:
 //!
@@ -110,6 +113,25 @@ use rand::Rng;
 
 #[cfg(feature = "aio")]
 use crate::aio::MultiplexedConnection as AsyncConnection;
+#[cfg(feature = "connection-manager")]
+use crate::aio::{boxed_sleep, Runtime, SharedRedisFuture};
+#[cfg(feature = "connection-manager")]
+use arc_swap::ArcSwap;
+#[cfg(feature = "connection-manager")]
+use futures::{
+    channel::oneshot,
+    future::{self, FutureExt, TryFutureExt},
+};
+#[cfg(feature = "connection-manager")]
+use std::{
+    sync::{Arc, Weak},
+    time::Duration,
+};
+#[cfg(feature = "connection-manager")]
+use tokio_retry::{
+    strategy::{jitter, ExponentialBackoff},
+    Retry,
+};
 
 use crate::{
     connection::ConnectionInfo, types::RedisResult, Client, Cmd, Connection, ErrorKind,
@@ -769,3 +791,353 @@ impl SentinelClient {
         client.get_multiplexed_async_connection().await
     }
 }
+
+/// A sentinel-aware version of the [`ConnectionManager`](crate::aio::ConnectionManager), which
+/// wraps a multiplexed connection to the node chosen by a [`SentinelClient`], and follows
+/// failovers of the master.
+///
+/// Like the `ConnectionManager`, this manager can be cloned, allowing requests to be sent
+/// concurrently on the same underlying connection.
+///
+/// ## Behavior
+///
+/// - Connections are created by resolving the node through the sentinels (with
+///   [`Sentinel::async_master_for`] for masters), and the role of the node is verified with the
+///   `ROLE` command after connecting. If the node does not have the expected role, for example
+///   because a failover is in progress, the connection attempt is retried with an exponential
+///   backoff.
+/// - The manager subscribes to the `+switch-master` channel of the first sentinel that it can
+///   reach. When a new master is promoted for the service, the manager reconnects in the
+///   background, and the requests that are issued afterwards are sent to the new node. After
+///   the subscription is restored, the master is queried again so that failovers that happened
+///   in the meantime are not missed.
+/// - When a request fails with an error that represents a "connection dropped" condition, or a
+///   connection to a master fails with a `READONLY` error because the master was demoted, the
+///   error is passed on to the user and a reconnection is triggered in the background. A
+///   failed reconnection is retried by the next request.
+/// - The subscription to the sentinels is closed once all the clones of the manager are
+///   dropped.
+///
+/// # Example
+/// ```rust,no_run
+/// use redis::AsyncCommands;
+/// use redis::sentinel::{SentinelClient, SentinelConnectionManager, SentinelServerType};
+///
+/// # async fn run() -> redis::RedisResult<()> {
+/// let nodes = vec!["redis://127.0.0.1:26379/", "redis://127.0.0.1:26380/"];
+/// let client = SentinelClient::build(nodes, String::from("master_name"), None, SentinelServerType::Master)?;
+/// let mut con = SentinelConnectionManager::new(client).await?;
+///
+/// let _: () = con.set("test", "test_data").await?;
+/// # Ok(()) }
+/// ```
+#[cfg(feature = "connection-manager")]
+#[cfg_attr(docsrs, doc(cfg(feature = "connection-manager")))]
+#[derive(Clone)]
+pub struct SentinelConnectionManager(Arc<SentinelConnectionManagerInner>);
+
+#[cfg(feature = "connection-manager")]
+struct SentinelConnectionManagerInner {
+    client: Arc<futures::lock::Mutex<SentinelClient>>,
+    server_type: SentinelServerType,
+    db: i64,
+    connection: ArcSwap<SharedRedisFuture<AsyncConnection>>,
+    runtime: Runtime,
+    retry_strategy: ExponentialBackoff,
+    number_of_retries: usize,
+    // Dropping the sender stops the subscription to the sentinels.
+    _subscription: oneshot::Sender<()>,
+}
+
+#[cfg(feature = "connection-manager")]
+impl SentinelConnectionManager {
+    const DEFAULT_CONNECTION_RETRY_EXPONENT_BASE: u64 = 2;
+    const DEFAULT_CONNECTION_RETRY_FACTOR: u64 = 100;
+    const DEFAULT_NUMBER_OF_CONNECTION_RETRIES: usize = 6;
+    const SUBSCRIPTION_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+    /// Connects to the node of the client, and subscribes to the failovers of its master.
+    pub async fn new(client: SentinelClient) -> RedisResult<Self> {
+        Self::new_with_backoff(
+            client,
+            Self::DEFAULT_CONNECTION_RETRY_EXPONENT_BASE,
+            Self::DEFAULT_CONNECTION_RETRY_FACTOR,
+            Self::DEFAULT_NUMBER_OF_CONNECTION_RETRIES,
+        )
+        .await
+    }
+
+    /// Connects to the node of the client, and subscribes to the failovers of its master.
+    ///
+    /// In case of connection issues, the manager will retry connecting number_of_retries
+    /// times, with an exponentially increasing delay, calculated as
+    /// rand(0 .. factor * (exponent_base ^ current-try)).
+    pub async fn new_with_backoff(
+        client: SentinelClient,
+        exponent_base: u64,
+        factor: u64,
+        number_of_retries: usize,
+    ) -> RedisResult<Self> {
+        let runtime = Runtime::locate();
+        let retry_strategy = ExponentialBackoff::from_millis(exponent_base).factor(factor);
+        let sentinels = client.sentinel.sentinels_connection_info.clone();
+        let service_name = client.service_name.clone();
+        let server_type = client.server_type.clone();
+        let db = client
+            .node_connection_info
+            .redis_connection_info
+            .as_ref()
+            .map_or(0, |info| info.db);
+
+        let client = Arc::new(futures::lock::Mutex::new(client));
+        let connection =
+            Self::new_connection(client.clone(), retry_strategy.clone(), number_of_retries).await?;
+
+        let (subscription, stopped) = oneshot::channel();
+        let inner = Arc::new(SentinelConnectionManagerInner {
+            client,
+            server_type,
+            db,
+            connection: ArcSwap::from_pointee(future::ok(connection).boxed().shared()),
+            runtime: runtime.clone(),
+            retry_strategy,
+            number_of_retries,
+            _subscription: subscription,
+        });
+
+        let follow_failovers =
+            Self::follow_failovers(Arc::downgrade(&inner), sentinels, service_name).boxed();
+        runtime.spawn(future::select(follow_failovers, stopped).map(|_| ()));
+
+        Ok(Self(inner))
+    }
+
+    async fn new_connection(
+        client: Arc<futures::lock::Mutex<SentinelClient>>,
+        exponential_backoff: ExponentialBackoff,
+        number_of_retries: usize,
+    ) -> RedisResult<AsyncConnection> {
+        let retry_strategy = exponential_backoff.map(jitter).take(number_of_retries);
+        Retry::spawn(retry_strategy, || async {
+            let mut client = client.lock().await;
+            let mut connection = client
+                .async_get_client()
+                .await?
+                .get_multiplexed_async_connection()
+                .await?;
+
+            let (role, kind) = match client.server_type {
+                SentinelServerType::Master => ("master", ErrorKind::MasterNameNotFoundBySentinel),
+                SentinelServerType::Replica => ("slave", ErrorKind::NoValidReplicasFoundBySentinel),
+            };
+            let result = crate::cmd("ROLE").query_async(&mut connection).await;
+            if !check_role_result(&result, role) {
+                fail!((
+                    kind,
+                    "Node returned by the sentinels has an unexpected role"
+                ));
+            }
+            Ok(connection)
+        })
+        .await
+    }
+
+    /// Listens to the `+switch-master` messages of the sentinels, and reconnects whenever the
+    /// master of the service changes. Only one sentinel is listened to at a time, and the
+    /// next one is used when its connection is lost. Since messages that are sent while no
+    /// sentinel is listened to are lost, the address of the master is queried after every
+    /// subscription, and the manager reconnects if it changed in the meantime.
+    async fn follow_failovers(
+        inner: Weak<SentinelConnectionManagerInner>,
+        sentinels: Vec<ConnectionInfo>,
+        service_name: String,
+    ) {
+        // The host and port of the master, as last reported by the sentinels.
+        let mut master_address = None;
+        loop {
+            for connection_info in &sentinels {
+                let client = match Client::open(connection_info.clone()) {
+                    Ok(client) => client,
+                    Err(_) => continue,
+                };
+                let mut pubsub = match client.get_async_pubsub().await {
+                    Ok(pubsub) => pubsub,
+                    Err(_) => continue,
+                };
+                if pubsub.subscribe("+switch-master").await.is_err() {
+                    continue;
+                }
+
+                if let Ok(address) = Self::query_master_address(&client, &service_name).await {
+                    let missed_failover = master_address
+                        .as_ref()
+                        .map_or(false, |previous| *previous != address);
+                    master_address = Some(address);
+                    if missed_failover {
+                        match inner.upgrade() {
+                            Some(inner) => inner.reconnect(&inner.connection.load()),
+                            None => return,
+                        }
+                    }
+                }
+
+                let mut messages = pubsub.into_on_message();
+                while let Some(message) = messages.next().await {
+                    // The payload is `<master name> <old ip> <old port> <new ip> <new port>`.
+                    let payload: String = match message.get_payload() {
+                        Ok(payload) => payload,
+                        Err(_) => continue,
+                    };
+                    let parts: Vec<&str> = payload.split(' ').collect();
+                    if parts.first() != Some(&service_name.as_str()) {
+                        continue;
+                    }
+                    if let [_, _, _, host, port] = parts[..] {
+                        master_address = Some((host.to_string(), port.to_string()));
+                    }
+                    match inner.upgrade() {
+                        Some(inner) => inner.reconnect(&inner.connection.load()),
+                        None => return,
+                    }
+                }
+            }
+
+            if inner.strong_count() == 0 {
+                return;
+            }
+            boxed_sleep(Self::SUBSCRIPTION_RETRY_DELAY).await;
+        }
+    }
+
+    /// Returns the host and port of the master of the service, as reported by a sentinel.
+    async fn query_master_address(
+        sentinel: &Client,
+        service_name: &str,
+    ) -> RedisResult<(String, String)> {
+        let mut connection = sentinel.get_multiplexed_async_connection().await?;
+        crate::cmd("SENTINEL")
+            .arg("GET-MASTER-ADDR-BY-NAME")
+            .arg(service_name)
+            .query_async(&mut connection)
+            .await
+    }
+
+    /// Sends an already encoded (packed) command into the TCP socket and
+    /// reads the single response from it.
+    pub async fn send_packed_command(&mut self, cmd: &Cmd) -> RedisResult<Value> {
+        let guard = self.0.connection.load();
+        let result = self
+            .0
+            .current_connection(&guard)
+            .await?
+            .send_packed_command(cmd)
+            .await;
+        self.0.reconnect_after_error(&result, &guard);
+        result
+    }
+
+    /// Sends multiple already encoded (packed) command into the TCP socket
+    /// and reads `count` responses from it.  This is used to implement
+    /// pipelining.
+    pub async fn send_packed_commands(
+        &mut self,
+        cmd: &crate::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisResult<Vec<Value>> {
+        let guard = self.0.connection.load();
+        let result = self
+            .0
+            .current_connection(&guard)
+            .await?
+            .send_packed_commands(cmd, offset, count)
+            .await;
+        self.0.reconnect_after_error(&result, &guard);
+        result
+    }
+}
+
+#[cfg(feature = "connection-manager")]
+impl SentinelConnectionManagerInner {
+    /// Awaits the connection that the guard points to, and reconnects if it could not be
+    /// created, unless the error is caused by the configuration of the client. Otherwise a
+    /// failed attempt, for example while the sentinels did not agree on a master yet, would
+    /// fail every following request.
+    async fn current_connection(
+        &self,
+        current: &arc_swap::Guard<Arc<SharedRedisFuture<AsyncConnection>>>,
+    ) -> RedisResult<AsyncConnection> {
+        let result = (***current)
+            .clone()
+            .await
+            .map_err(|e| e.clone_mostly("Reconnecting failed"));
+        if let Err(ref err) = result {
+            if !matches!(
+                err.kind(),
+                ErrorKind::AuthenticationFailed | ErrorKind::InvalidClientConfig
+            ) {
+                self.reconnect(current);
+            }
+        }
+        result
+    }
+
+    fn reconnect_after_error<T>(
+        &self,
+        result: &RedisResult<T>,
+        current: &arc_swap::Guard<Arc<SharedRedisFuture<AsyncConnection>>>,
+    ) {
+        if let Err(err) = result {
+            let demoted = matches!(self.server_type, SentinelServerType::Master)
+                && err.kind() == ErrorKind::ReadOnly;
+            if demoted || err.is_unrecoverable_error() {
+                self.reconnect(current);
+            }
+        }
+    }
+
+    /// Reconnects and overwrites the old connection, unless it was already replaced.
+    ///
+    /// The `current` guard points to the shared future that was active
+    /// when the connection loss or the failover was detected.
+    fn reconnect(&self, current: &arc_swap::Guard<Arc<SharedRedisFuture<AsyncConnection>>>) {
+        let new_connection: SharedRedisFuture<AsyncConnection> =
+            SentinelConnectionManager::new_connection(
+                self.client.clone(),
+                self.retry_strategy.clone(),
+                self.number_of_retries,
+            )
+            .map_err(Arc::new)
+            .boxed()
+            .shared();
+
+        let prev = self
+            .connection
+            .compare_and_swap(current, Arc::new(new_connection.clone()));
+
+        // If the swap happened, start the connection attempt immediately but do not wait on it.
+        if Arc::ptr_eq(&prev, current) {
+            self.runtime.spawn(new_connection.map(|_| ()));
+        }
+    }
+}
+
+#[cfg(feature = "connection-manager")]
+impl crate::aio::ConnectionLike for SentinelConnectionManager {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> crate::RedisFuture<'a, Value> {
+        (async move { self.send_packed_command(cmd).await }).boxed()
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a crate::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> crate::RedisFuture<'a, Vec<Value>> {
+        (async move { self.send_packed_commands(cmd, offset, count).await }).boxed()
+    }
+
+    fn get_db(&self) -> i64 {
+        self.0.db
+    }
+}
@@ -0,0 +1,737 @@
+//! Integration with [serde](https://serde.rs).
+//!
+//! This module lets any type that implements `serde::Deserialize` be read from a redis
+//! response with [`Serde`], and any type that implements `serde::Serialize` be written as command
+//! arguments with [`SerdeArgs`].
+//!
+//! Structs and maps are read from RESP3 maps and from the flat field/value arrays that
+//! `HGETALL` returns on RESP2, and are written as field/value pairs, which is the format that
+//! `HSET` expects. Since hash values are strings, numbers and booleans are parsed from strings
+//! when reading, and fields whose value is `None` are skipped when writing.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use redis::serde::{Serde, SerdeArgs};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct User {
+//!     name: String,
+//!     age: u32,
+//!     email: Option<String>,
+//! }
+//!
+//! # fn do_something() -> redis::RedisResult<()> {
+//! # let client = redis::Client::open("redis://127.0.0.1/")?;
+//! # let mut con = client.get_connection()?;
+//! let user = User { name: "Alice".to_string(), age: 42, email: None };
+//! let _: () = redis::cmd("HSET").arg("user:1").arg(SerdeArgs::new(&user)?).query(&mut con)?;
+//!
+//! let Serde(user): Serde<User> = redis::cmd("HGETALL").arg("user:1").query(&mut con)?;
+//! assert_eq!(user.age, 42);
+//! # Ok(()) }
+//! ```
+//!
+//! Nested structs, sequences inside of structs, and enum variants that carry data can't be
+//! represented as flat arguments, so [`SerdeArgs::new`] returns an error for them.
+
+use std::fmt;
+use std::str;
+
+use ::serde::de::value::{MapDeserializer, SeqDeserializer};
+use ::serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use ::serde::ser::{self, Impossible};
+use ::serde::{forward_to_deserialize_any, Deserialize, Serialize};
+
+use crate::types::{
+    ErrorKind, FromRedisValue, RedisError, RedisResult, RedisWrite, ToRedisArgs, Value,
+};
+
+impl de::Error for RedisError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        RedisError::from((
+            ErrorKind::TypeError,
+            "Response was of incompatible type",
+            msg.to_string(),
+        ))
+    }
+}
+
+impl ser::Error for RedisError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        RedisError::from((ErrorKind::Serialize, "Serialization Error", msg.to_string()))
+    }
+}
+
+/// A wrapper that reads its content with serde.
+///
+/// `Serde<T>` implements `FromRedisValue` if `T` implements `DeserializeOwned`, so it can be used
+/// wherever the crate expects this trait. See the [module documentation](self) for an example.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Serde<T>(pub T);
+
+impl<T: DeserializeOwned> FromRedisValue for Serde<T> {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        from_redis_value(v).map(Serde)
+    }
+}
+
+/// The arguments of a value that was serialized with serde.
+///
+/// The value is serialized when it's created, so that writing it as arguments can't fail. See
+/// the [module documentation](self) for an example.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SerdeArgs(Vec<Vec<u8>>);
+
+impl SerdeArgs {
+    /// Serializes the value into arguments. See [`to_redis_args`] for their format.
+    ///
+    /// Returns an error if the value can't be written as arguments, for example if it contains a
+    /// nested struct.
+    pub fn new<T: ?Sized + Serialize>(value: &T) -> RedisResult<Self> {
+        let mut args = Vec::new();
+        to_redis_args(value, &mut args)?;
+        Ok(SerdeArgs(args))
+    }
+}
+
+impl ToRedisArgs for SerdeArgs {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        for arg in &self.0 {
+            out.write_arg(arg);
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        self.0.len() == 1
+    }
+}
+
+/// Deserializes a value of type `T` from a redis value.
+pub fn from_redis_value<'de, T: Deserialize<'de>>(value: &'de Value) -> RedisResult<T> {
+    T::deserialize(Deserializer::new(value))
+}
+
+/// Serializes a value into redis arguments.
+///
+/// Structs and maps are written as field/value pairs, sequences and tuples as one argument per
+/// element, and other values as a single argument. `None` and unit values write nothing.
+pub fn to_redis_args<T, W>(value: &T, out: &mut W) -> RedisResult<()>
+where
+    T: ?Sized + Serialize,
+    W: ?Sized + RedisWrite,
+{
+    value.serialize(Serializer::new(out))
+}
+
+/// A serde `Deserializer` that reads from a borrowed redis [`Value`].
+pub struct Deserializer<'de> {
+    value: &'de Value,
+}
+
+impl<'de> Deserializer<'de> {
+    /// Creates a deserializer for the given value.
+    pub fn new(value: &'de Value) -> Self {
+        let value = match value {
+            Value::Attribute { data, .. } => data.as_ref(),
+            value => value,
+        };
+        Deserializer { value }
+    }
+
+    fn invalid_type(&self, exp: &dyn de::Expected) -> RedisError {
+        de::Error::custom(format_args!(
+            "invalid type: {:?}, expected {}",
+            self.value, exp
+        ))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, RedisError> for &'de Value {
+    type Deserializer = Deserializer<'de>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        Deserializer::new(self)
+    }
+}
+
+macro_rules! deserialize_number {
+    ($($method:ident => $visit:ident: $ty:ty,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> RedisResult<V::Value> {
+                visitor.$visit(<$ty>::from_redis_value(self.value)?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = RedisError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> RedisResult<V::Value> {
+        match self.value {
+            Value::Nil => visitor.visit_unit(),
+            Value::Int(val) => visitor.visit_i64(*val),
+            Value::BulkString(bytes) => match str::from_utf8(bytes) {
+                Ok(s) => visitor.visit_borrowed_str(s),
+                Err(_) => visitor.visit_borrowed_bytes(bytes),
+            },
+            Value::SimpleString(s) => visitor.visit_borrowed_str(s),
+            Value::VerbatimString { text, .. } => visitor.visit_borrowed_str(text),
+            Value::Okay => visitor.visit_borrowed_str("OK"),
+            Value::Double(val) => visitor.visit_f64(*val),
+            Value::Boolean(val) => visitor.visit_bool(*val),
+            Value::BigNumber(val) => visitor.visit_string(val.to_string()),
+            Value::Array(items) | Value::Set(items) | Value::Push { data: items, .. } => {
+                let mut seq = SeqDeserializer::new(items.iter());
+                let result = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(result)
+            }
+            Value::Map(pairs) => {
+                let mut map = MapDeserializer::new(pairs.iter().map(|(k, v)| (k, v)));
+                let result = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(result)
+            }
+            Value::Attribute { data, .. } => Deserializer::new(data).deserialize_any(visitor),
+        }
+    }
+
+    deserialize_number! {
+        deserialize_bool => visit_bool: bool,
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> RedisResult<V::Value> {
+        match self.value {
+            Value::Int(val) => visitor.visit_string(val.to_string()),
+            Value::Double(val) => visitor.visit_string(val.to_string()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> RedisResult<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> RedisResult<V::Value> {
+        match self.value {
+            Value::BulkString(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Value::SimpleString(s) => visitor.visit_borrowed_bytes(s.as_bytes()),
+            _ => self.deserialize_str(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> RedisResult<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> RedisResult<V::Value> {
+        match self.value {
+            Value::Nil => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> RedisResult<V::Value> {
+        match self.value {
+            Value::Nil | Value::Okay => visitor.visit_unit(),
+            _ => Err(self.invalid_type(&visitor)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> RedisResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> RedisResult<V::Value> {
+        match self.value {
+            // RESP2 returns maps as flat arrays of alternating keys and values.
+            Value::Array(items) if items.len() % 2 == 0 => {
+                let mut map =
+                    MapDeserializer::new(items.chunks_exact(2).map(|pair| (&pair[0], &pair[1])));
+                let result = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(result)
+            }
+            Value::Map(_) => self.deserialize_any(visitor),
+            _ => Err(self.invalid_type(&visitor)),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> RedisResult<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> RedisResult<V::Value> {
+        // Only unit variants are supported, and they are represented by their name.
+        let variant = match self.value {
+            Value::BulkString(bytes) => str::from_utf8(bytes).ok(),
+            Value::SimpleString(s) | Value::VerbatimString { text: s, .. } => Some(s.as_str()),
+            _ => None,
+        };
+        match variant {
+            Some(variant) => visitor.visit_enum(variant.into_deserializer()),
+            None => Err(self.invalid_type(&visitor)),
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> RedisResult<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> RedisResult<V::Value> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        char unit_struct seq tuple tuple_struct
+    }
+}
+
+/// A serde `Serializer` that writes a value as redis arguments into a [`RedisWrite`].
+///
+/// See [`to_redis_args`] for the format of the arguments.
+pub struct Serializer<'a, W: ?Sized> {
+    out: &'a mut W,
+}
+
+impl<'a, W: ?Sized + RedisWrite> Serializer<'a, W> {
+    /// Creates a serializer that writes into `out`.
+    pub fn new(out: &'a mut W) -> Self {
+        Serializer { out }
+    }
+
+    fn write(self, arg: Option<Vec<u8>>) -> RedisResult<()> {
+        if let Some(arg) = arg {
+            self.out.write_arg(&arg);
+        }
+        Ok(())
+    }
+}
+
+fn unsupported(what: &str) -> RedisError {
+    ser::Error::custom(format_args!("{what} can't be written as a single argument"))
+}
+
+macro_rules! serialize_scalars {
+    ($($method:ident: $ty:ty,)*) => {
+        $(
+            fn $method(self, v: $ty) -> RedisResult<Self::Ok> {
+                let arg = ArgSerializer.$method(v)?;
+                self.write(arg)
+            }
+        )*
+    };
+}
+
+impl<'a, W: ?Sized + RedisWrite> ser::Serializer for Serializer<'a, W> {
+    type Ok = ();
+    type Error = RedisError;
+    type SerializeSeq = Compound<'a, W>;
+    type SerializeTuple = Compound<'a, W>;
+    type SerializeTupleStruct = Compound<'a, W>;
+    type SerializeTupleVariant = Impossible<(), RedisError>;
+    type SerializeMap = Compound<'a, W>;
+    type SerializeStruct = Compound<'a, W>;
+    type SerializeStructVariant = Impossible<(), RedisError>;
+
+    serialize_scalars! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str,
+        serialize_bytes: &[u8],
+    }
+
+    fn serialize_none(self) -> RedisResult<()> {
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> RedisResult<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> RedisResult<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> RedisResult<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> RedisResult<()> {
+        let arg = ArgSerializer.serialize_unit_variant(name, variant_index, variant)?;
+        self.write(arg)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> RedisResult<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> RedisResult<()> {
+        Err(unsupported("An enum variant with data"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> RedisResult<Self::SerializeSeq> {
+        Ok(Compound::new(self.out))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> RedisResult<Self::SerializeTuple> {
+        Ok(Compound::new(self.out))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> RedisResult<Self::SerializeTupleStruct> {
+        Ok(Compound::new(self.out))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> RedisResult<Self::SerializeTupleVariant> {
+        Err(unsupported("An enum variant with data"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> RedisResult<Self::SerializeMap> {
+        Ok(Compound::new(self.out))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> RedisResult<Self::SerializeStruct> {
+        Ok(Compound::new(self.out))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> RedisResult<Self::SerializeStructVariant> {
+        Err(unsupported("An enum variant with data"))
+    }
+}
+
+/// Writes the elements of a sequence, or the field/value pairs of a struct or a map.
+#[doc(hidden)]
+pub struct Compound<'a, W: ?Sized> {
+    out: &'a mut W,
+    // The key of a map entry whose value wasn't serialized yet.
+    key: Option<Vec<u8>>,
+}
+
+impl<'a, W: ?Sized + RedisWrite> Compound<'a, W> {
+    fn new(out: &'a mut W) -> Self {
+        Compound { out, key: None }
+    }
+
+    fn write_element<T: ?Sized + Serialize>(&mut self, value: &T) -> RedisResult<()> {
+        match value.serialize(ArgSerializer)? {
+            Some(arg) => {
+                self.out.write_arg(&arg);
+                Ok(())
+            }
+            None => Err(ser::Error::custom(
+                "A nil element of a sequence can't be written as an argument",
+            )),
+        }
+    }
+
+    fn write_pair<T: ?Sized + Serialize>(&mut self, key: &[u8], value: &T) -> RedisResult<()> {
+        // Pairs without a value are skipped, so that optional fields can be left out.
+        if let Some(value) = value.serialize(ArgSerializer)? {
+            self.out.write_arg(key);
+            self.out.write_arg(&value);
+        }
+        Ok(())
+    }
+}
+
+impl<'a, W: ?Sized + RedisWrite> ser::SerializeSeq for Compound<'a, W> {
+    type Ok = ();
+    type Error = RedisError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> RedisResult<()> {
+        self.write_element(value)
+    }
+
+    fn end(self) -> RedisResult<()> {
+        Ok(())
+    }
+}
+
+impl<'a, W: ?Sized + RedisWrite> ser::SerializeTuple for Compound<'a, W> {
+    type Ok = ();
+    type Error = RedisError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> RedisResult<()> {
+        self.write_element(value)
+    }
+
+    fn end(self) -> RedisResult<()> {
+        Ok(())
+    }
+}
+
+impl<'a, W: ?Sized + RedisWrite> ser::SerializeTupleStruct for Compound<'a, W> {
+    type Ok = ();
+    type Error = RedisError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> RedisResult<()> {
+        self.write_element(value)
+    }
+
+    fn end(self) -> RedisResult<()> {
+        Ok(())
+    }
+}
+
+impl<'a, W: ?Sized + RedisWrite> ser::SerializeMap for Compound<'a, W> {
+    type Ok = ();
+    type Error = RedisError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> RedisResult<()> {
+        match key.serialize(ArgSerializer)? {
+            Some(key) => {
+                self.key = Some(key);
+                Ok(())
+            }
+            None => Err(ser::Error::custom(
+                "A nil map key can't be written as an argument",
+            )),
+        }
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> RedisResult<()> {
+        let key = self.key.take().ok_or_else(|| {
+            <RedisError as ser::Error>::custom("A map value was serialized before its key")
+        })?;
+        self.write_pair(&key, value)
+    }
+
+    fn end(self) -> RedisResult<()> {
+        Ok(())
+    }
+}
+
+impl<'a, W: ?Sized + RedisWrite> ser::SerializeStruct for Compound<'a, W> {
+    type Ok = ();
+    type Error = RedisError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> RedisResult<()> {
+        self.write_pair(key.as_bytes(), value)
+    }
+
+    fn end(self) -> RedisResult<()> {
+        Ok(())
+    }
+}
+
+/// Serializes a value that is written as a single argument, or as no argument if it's `None`.
+struct ArgSerializer;
+
+fn single_arg<T: ToRedisArgs>(value: T) -> RedisResult<Option<Vec<u8>>> {
+    Ok(value.to_redis_args().pop())
+}
+
+macro_rules! serialize_args {
+    ($($method:ident: $ty:ty,)*) => {
+        $(
+            fn $method(self, v: $ty) -> RedisResult<Self::Ok> {
+                single_arg(v)
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for ArgSerializer {
+    type Ok = Option<Vec<u8>>;
+    type Error = RedisError;
+    type SerializeSeq = Impossible<Self::Ok, RedisError>;
+    type SerializeTuple = Impossible<Self::Ok, RedisError>;
+    type SerializeTupleStruct = Impossible<Self::Ok, RedisError>;
+    type SerializeTupleVariant = Impossible<Self::Ok, RedisError>;
+    type SerializeMap = Impossible<Self::Ok, RedisError>;
+    type SerializeStruct = Impossible<Self::Ok, RedisError>;
+    type SerializeStructVariant = Impossible<Self::Ok, RedisError>;
+
+    serialize_args! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_str: &str,
+    }
+
+    fn serialize_char(self, v: char) -> RedisResult<Self::Ok> {
+        single_arg(v.encode_utf8(&mut [0; 4]) as &str)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> RedisResult<Self::Ok> {
+        Ok(Some(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> RedisResult<Self::Ok> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> RedisResult<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> RedisResult<Self::Ok> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> RedisResult<Self::Ok> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> RedisResult<Self::Ok> {
+        single_arg(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> RedisResult<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> RedisResult<Self::Ok> {
+        Err(unsupported("An enum variant with data"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> RedisResult<Self::SerializeSeq> {
+        Err(unsupported("A nested sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> RedisResult<Self::SerializeTuple> {
+        Err(unsupported("A nested tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> RedisResult<Self::SerializeTupleStruct> {
+        Err(unsupported("A nested tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> RedisResult<Self::SerializeTupleVariant> {
+        Err(unsupported("An enum variant with data"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> RedisResult<Self::SerializeMap> {
+        Err(unsupported("A nested map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> RedisResult<Self::SerializeStruct> {
+        Err(unsupported("A nested struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> RedisResult<Self::SerializeStructVariant> {
+        Err(unsupported("An enum variant with data"))
+    }
+}
@@ -5,6 +5,19 @@ use crate::{
 };
 
 use std::io::{Error, ErrorKind};
+#[cfg(feature = "aio")]
+use std::{
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "aio")]
+use crate::AsyncCommands;
+#[cfg(feature = "aio")]
+use futures_util::{
+    future::{self, Either},
+    Future, FutureExt,
+};
 
 // Stream Maxlen Enum
 
@@ -34,6 +47,151 @@ impl ToRedisArgs for StreamMaxlen {
     }
 }
 
+/// Utility enum for passing the `=` or `~` modifier of the `MAXLEN`
+/// and `MINID` trimming arguments into [`StreamTrimOptions`].
+#[derive(PartialEq, Eq, Clone, Debug, Copy)]
+pub enum StreamTrimmingMode {
+    /// Trim exactly
+    Exact,
+    /// Trim approximately, which is more efficient
+    Approx,
+}
+
+impl ToRedisArgs for StreamTrimmingMode {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        match self {
+            StreamTrimmingMode::Exact => out.write_arg(b"="),
+            StreamTrimmingMode::Approx => out.write_arg(b"~"),
+        }
+    }
+}
+
+/// Utility enum for the strategy used to trim a stream.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum StreamTrimStrategy {
+    /// Set `MAXLEN [= or ~] <count>` cmd arg, evicting entries
+    /// while the stream is longer than the count.
+    MaxLen(StreamTrimmingMode, usize),
+    /// Set `MINID [= or ~] <id>` cmd arg, evicting entries
+    /// with IDs lower than the given id.
+    MinId(StreamTrimmingMode, String),
+}
+
+impl ToRedisArgs for StreamTrimStrategy {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        match self {
+            StreamTrimStrategy::MaxLen(mode, count) => {
+                out.write_arg(b"MAXLEN");
+                mode.write_redis_args(out);
+                count.write_redis_args(out);
+            }
+            StreamTrimStrategy::MinId(mode, id) => {
+                out.write_arg(b"MINID");
+                mode.write_redis_args(out);
+                id.write_redis_args(out);
+            }
+        }
+    }
+}
+
+/// Builder options for [`xtrim_options`] command and the trimming
+/// arguments of [`xadd_options`].
+///
+/// [`xtrim_options`]: ../trait.Commands.html#method.xtrim_options
+/// [`xadd_options`]: ../trait.Commands.html#method.xadd_options
+///
+#[derive(Debug, Clone)]
+pub struct StreamTrimOptions {
+    /// Set `MAXLEN` or `MINID` cmd arg.
+    strategy: StreamTrimStrategy,
+    /// Set `LIMIT <count>` cmd arg.
+    limit: Option<usize>,
+}
+
+impl StreamTrimOptions {
+    /// Set `MAXLEN [= or ~] <count>` cmd arg.
+    pub fn maxlen(mode: StreamTrimmingMode, max: usize) -> Self {
+        Self {
+            strategy: StreamTrimStrategy::MaxLen(mode, max),
+            limit: None,
+        }
+    }
+
+    /// Set `MINID [= or ~] <id>` cmd arg.
+    pub fn minid(mode: StreamTrimmingMode, id: impl Into<String>) -> Self {
+        Self {
+            strategy: StreamTrimStrategy::MinId(mode, id.into()),
+            limit: None,
+        }
+    }
+
+    /// Set `LIMIT <count>` cmd arg, the maximum number of evicted entries.
+    /// The server only accepts it with [`StreamTrimmingMode::Approx`].
+    pub fn limit(mut self, count: usize) -> Self {
+        self.limit = Some(count);
+        self
+    }
+}
+
+impl ToRedisArgs for StreamTrimOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        self.strategy.write_redis_args(out);
+        if let Some(ref count) = self.limit {
+            out.write_arg(b"LIMIT");
+            out.write_arg(format!("{count}").as_bytes());
+        }
+    }
+}
+
+/// Builder options for [`xadd_options`] command.
+///
+/// [`xadd_options`]: ../trait.Commands.html#method.xadd_options
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamAddOptions {
+    /// Set `NOMKSTREAM` cmd arg.
+    nomkstream: bool,
+    /// Set the trimming cmd args.
+    trim: Option<StreamTrimOptions>,
+}
+
+impl StreamAddOptions {
+    /// Set `NOMKSTREAM` cmd arg, so that a missing stream isn't created.
+    pub fn nomkstream(mut self) -> Self {
+        self.nomkstream = true;
+        self
+    }
+
+    /// Trims the stream while adding the entry.
+    pub fn trim(mut self, trim: StreamTrimOptions) -> Self {
+        self.trim = Some(trim);
+        self
+    }
+}
+
+impl ToRedisArgs for StreamAddOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if self.nomkstream {
+            out.write_arg(b"NOMKSTREAM");
+        }
+        if let Some(ref trim) = self.trim {
+            trim.write_redis_args(out);
+        }
+    }
+}
+
 /// Builder options for [`xclaim_options`] command.
 ///
 /// [`xclaim_options`]: ../trait.Commands.html#method.xclaim_options
@@ -112,6 +270,90 @@ impl ToRedisArgs for StreamClaimOptions {
     }
 }
 
+/// Builder options for [`xautoclaim_options`] command.
+///
+/// [`xautoclaim_options`]: ../trait.Commands.html#method.xautoclaim_options
+///
+#[derive(Default, Debug)]
+pub struct StreamAutoClaimOptions {
+    /// Set `COUNT <count>` cmd arg.
+    count: Option<usize>,
+    /// Set `JUSTID` cmd arg.
+    justid: bool,
+}
+
+impl StreamAutoClaimOptions {
+    /// Set `COUNT <count>` cmd arg.
+    pub fn count(mut self, n: usize) -> Self {
+        self.count = Some(n);
+        self
+    }
+
+    /// Set `JUSTID` cmd arg to true. The claimed entries of the
+    /// [`StreamAutoClaimReply`] are then returned without their fields.
+    pub fn with_justid(mut self) -> Self {
+        self.justid = true;
+        self
+    }
+}
+
+impl ToRedisArgs for StreamAutoClaimOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(ref n) = self.count {
+            out.write_arg(b"COUNT");
+            out.write_arg(format!("{n}").as_bytes());
+        }
+        if self.justid {
+            out.write_arg(b"JUSTID");
+        }
+    }
+}
+
+/// Builder options for [`xsetid_options`] command.
+///
+/// [`xsetid_options`]: ../trait.Commands.html#method.xsetid_options
+///
+#[derive(Default, Debug)]
+pub struct StreamSetIdOptions {
+    /// Set `ENTRIESADDED <entries-added>` cmd arg.
+    entries_added: Option<usize>,
+    /// Set `MAXDELETEDID <max-deleted-id>` cmd arg.
+    max_deleted_id: Option<String>,
+}
+
+impl StreamSetIdOptions {
+    /// Set `ENTRIESADDED <entries-added>` cmd arg.
+    pub fn entries_added(mut self, n: usize) -> Self {
+        self.entries_added = Some(n);
+        self
+    }
+
+    /// Set `MAXDELETEDID <max-deleted-id>` cmd arg.
+    pub fn max_deleted_id(mut self, id: impl Into<String>) -> Self {
+        self.max_deleted_id = Some(id.into());
+        self
+    }
+}
+
+impl ToRedisArgs for StreamSetIdOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(ref n) = self.entries_added {
+            out.write_arg(b"ENTRIESADDED");
+            out.write_arg(format!("{n}").as_bytes());
+        }
+        if let Some(ref id) = self.max_deleted_id {
+            out.write_arg(b"MAXDELETEDID");
+            out.write_arg(id.as_bytes());
+        }
+    }
+}
+
 /// Argument to `StreamReadOptions`
 /// Represents the Redis `GROUP <groupname> <consumername>` cmd arg.
 /// This option will toggle the cmd from `XREAD` to `XREADGROUP`
@@ -248,6 +490,27 @@ pub struct StreamClaimReply {
     pub ids: Vec<StreamId>,
 }
 
+/// Reply type used with [`xautoclaim`] and [`xautoclaim_options`] commands.
+///
+/// Represents the pending messages whose ownership was transferred, along with
+/// the cursor to pass as `start` to the next call.
+///
+/// [`xautoclaim`]: ../trait.Commands.html#method.xautoclaim
+/// [`xautoclaim_options`]: ../trait.Commands.html#method.xautoclaim_options
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamAutoClaimReply {
+    /// The ID to use as `start` of the next call, or `0-0` once the
+    /// whole pending entries list was scanned.
+    pub next_stream_id: String,
+    /// The claimed messages. Their field/value pairs are empty when
+    /// `JUSTID` was requested.
+    pub claimed: Vec<StreamId>,
+    /// IDs of pending messages that no longer exist in the stream and
+    /// were removed from the pending entries list (Redis 7.0 and later).
+    pub deleted_ids: Vec<String>,
+}
+
 /// Reply type used with [`xpending`] command.
 ///
 /// Data returned here were fetched from the stream without
@@ -334,6 +597,95 @@ pub struct StreamInfoStreamReply {
     pub last_entry: StreamId,
 }
 
+/// Reply type used with [`xinfo_stream_full`] and [`xinfo_stream_full_count`]
+/// commands, containing the stream entries along with every consumer group,
+/// its consumers and their pending entries.
+///
+/// Fields that were added in later Redis versions are `None` when the server
+/// doesn't send them.
+///
+/// [`xinfo_stream_full`]: ../trait.Commands.html#method.xinfo_stream_full
+/// [`xinfo_stream_full_count`]: ../trait.Commands.html#method.xinfo_stream_full_count
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamInfoStreamFullReply {
+    /// Number of elements of the stream.
+    pub length: usize,
+    /// Number of keys in the radix tree representing the stream.
+    pub radix_tree_keys: usize,
+    /// Number of nodes in the radix tree representing the stream.
+    pub radix_tree_nodes: usize,
+    /// The last generated ID that may not be the same as the last
+    /// entry ID in case some entry was deleted.
+    pub last_generated_id: String,
+    /// The maximal entry ID that was deleted from the stream.
+    pub max_deleted_entry_id: Option<String>,
+    /// The count of all entries added to the stream during its lifetime.
+    pub entries_added: Option<usize>,
+    /// The ID of the first entry of the stream, as recorded by the server.
+    pub recorded_first_entry_id: Option<String>,
+    /// The stream entries, in ascending order.
+    pub entries: Vec<StreamId>,
+    /// All the consumer groups associated with the stream.
+    pub groups: Vec<StreamInfoFullGroup>,
+}
+
+/// A group parsed from [`xinfo_stream_full`] command.
+///
+/// [`xinfo_stream_full`]: ../trait.Commands.html#method.xinfo_stream_full
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamInfoFullGroup {
+    /// The group name.
+    pub name: String,
+    /// Last ID delivered to this group.
+    pub last_delivered_id: String,
+    /// The logical read counter of the last entry delivered to the group.
+    pub entries_read: Option<usize>,
+    /// The number of entries that are still waiting to be delivered to the group.
+    pub lag: Option<usize>,
+    /// Number of pending messages (delivered but not yet acknowledged) in the group.
+    pub pel_count: usize,
+    /// The pending messages of the group.
+    pub pending: Vec<StreamInfoFullPendingEntry>,
+    /// The consumers of the group.
+    pub consumers: Vec<StreamInfoFullConsumer>,
+}
+
+/// A consumer parsed from [`xinfo_stream_full`] command.
+///
+/// [`xinfo_stream_full`]: ../trait.Commands.html#method.xinfo_stream_full
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamInfoFullConsumer {
+    /// Name of the consumer.
+    pub name: String,
+    /// Unix timestamp in milliseconds of the last attempted interaction.
+    pub seen_time: usize,
+    /// Unix timestamp in milliseconds of the last successful interaction.
+    pub active_time: Option<usize>,
+    /// Number of pending messages for this specific consumer.
+    pub pel_count: usize,
+    /// The pending messages of the consumer.
+    pub pending: Vec<StreamInfoFullPendingEntry>,
+}
+
+/// A pending message parsed from [`xinfo_stream_full`] command.
+///
+/// [`xinfo_stream_full`]: ../trait.Commands.html#method.xinfo_stream_full
+///
+#[derive(Default, Debug, Clone)]
+pub struct StreamInfoFullPendingEntry {
+    /// The ID of the message.
+    pub id: String,
+    /// The name of the consumer the message was delivered to.
+    pub consumer: String,
+    /// Unix timestamp in milliseconds of the last delivery.
+    pub delivery_time: usize,
+    /// The number of times this message was delivered.
+    pub delivery_count: usize,
+}
+
 /// Reply type used with [`xinfo_consumer`] command, an array of every
 /// consumer in a specific consumer group.
 ///
@@ -507,6 +859,47 @@ impl FromRedisValue for StreamClaimReply {
     }
 }
 
+impl FromRedisValue for StreamAutoClaimReply {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let values = match v {
+            Value::Array(values) if values.len() >= 2 => values,
+            _ => fail!((
+                crate::types::ErrorKind::TypeError,
+                "Response type not XAUTOCLAIM compatible"
+            )),
+        };
+        let entries = match &values[1] {
+            Value::Array(entries) => entries,
+            _ => fail!((
+                crate::types::ErrorKind::TypeError,
+                "Response type not XAUTOCLAIM compatible"
+            )),
+        };
+        let mut claimed = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match entry {
+                // Redis 6.2 replies with nil for entries deleted from the stream.
+                Value::Nil => {}
+                Value::Array(_) => claimed.push(StreamId::from_array_value(entry)?),
+                // JUSTID
+                _ => claimed.push(StreamId {
+                    id: from_redis_value(entry)?,
+                    map: HashMap::default(),
+                }),
+            }
+        }
+        let deleted_ids = match values.get(2) {
+            Some(v) => from_redis_value(v)?,
+            None => Vec::new(),
+        };
+        Ok(StreamAutoClaimReply {
+            next_stream_id: from_redis_value(&values[0])?,
+            claimed,
+            deleted_ids,
+        })
+    }
+}
+
 type SPRInner = (
     usize,
     Option<String>,
@@ -623,6 +1016,123 @@ impl FromRedisValue for StreamInfoStreamReply {
     }
 }
 
+impl FromRedisValue for StreamInfoStreamFullReply {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let map: HashMap<String, Value> = from_redis_value(v)?;
+        let mut reply = StreamInfoStreamFullReply::default();
+        if let Some(v) = &map.get("length") {
+            reply.length = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("radix-tree-keys") {
+            reply.radix_tree_keys = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("radix-tree-nodes") {
+            reply.radix_tree_nodes = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("last-generated-id") {
+            reply.last_generated_id = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("max-deleted-entry-id") {
+            reply.max_deleted_entry_id = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("entries-added") {
+            reply.entries_added = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("recorded-first-entry-id") {
+            reply.recorded_first_entry_id = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("entries") {
+            let entries: Vec<Value> = from_redis_value(v)?;
+            reply.entries = entries
+                .iter()
+                .map(StreamId::from_array_value)
+                .collect::<RedisResult<_>>()?;
+        }
+        if let Some(v) = &map.get("groups") {
+            let groups: Vec<HashMap<String, Value>> = from_redis_value(v)?;
+            for map in groups {
+                reply.groups.push(StreamInfoFullGroup::from_map(map)?);
+            }
+        }
+        Ok(reply)
+    }
+}
+
+impl StreamInfoFullGroup {
+    fn from_map(map: HashMap<String, Value>) -> RedisResult<Self> {
+        let mut g = StreamInfoFullGroup::default();
+        if let Some(v) = &map.get("name") {
+            g.name = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("last-delivered-id") {
+            g.last_delivered_id = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("entries-read") {
+            g.entries_read = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("lag") {
+            g.lag = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("pel-count") {
+            g.pel_count = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("pending") {
+            let pending: Vec<(String, String, usize, usize)> = from_redis_value(v)?;
+            g.pending = pending
+                .into_iter()
+                .map(
+                    |(id, consumer, delivery_time, delivery_count)| StreamInfoFullPendingEntry {
+                        id,
+                        consumer,
+                        delivery_time,
+                        delivery_count,
+                    },
+                )
+                .collect();
+        }
+        if let Some(v) = &map.get("consumers") {
+            let consumers: Vec<HashMap<String, Value>> = from_redis_value(v)?;
+            for map in consumers {
+                g.consumers.push(StreamInfoFullConsumer::from_map(map)?);
+            }
+        }
+        Ok(g)
+    }
+}
+
+impl StreamInfoFullConsumer {
+    fn from_map(map: HashMap<String, Value>) -> RedisResult<Self> {
+        let mut c = StreamInfoFullConsumer::default();
+        if let Some(v) = &map.get("name") {
+            c.name = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("seen-time") {
+            c.seen_time = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("active-time") {
+            c.active_time = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("pel-count") {
+            c.pel_count = from_redis_value(v)?;
+        }
+        if let Some(v) = &map.get("pending") {
+            let pending: Vec<(String, usize, usize)> = from_redis_value(v)?;
+            c.pending = pending
+                .into_iter()
+                .map(
+                    |(id, delivery_time, delivery_count)| StreamInfoFullPendingEntry {
+                        id,
+                        consumer: c.name.clone(),
+                        delivery_time,
+                        delivery_count,
+                    },
+                )
+                .collect();
+        }
+        Ok(c)
+    }
+}
+
 impl FromRedisValue for StreamInfoConsumersReply {
     fn from_redis_value(v: &Value) -> RedisResult<Self> {
         let consumers: Vec<HashMap<String, Value>> = from_redis_value(v)?;
@@ -668,3 +1178,426 @@ impl FromRedisValue for StreamInfoGroupsReply {
         Ok(reply)
     }
 }
+
+/// Builder options for a [`StreamConsumer`].
+///
+/// All durations are expressed in milliseconds, like the stream command options.
+#[cfg(feature = "aio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "aio")))]
+#[derive(Debug, Clone)]
+pub struct StreamConsumerOptions {
+    /// The id the group is created at when it doesn't exist yet.
+    start_id: String,
+    /// Maximum number of entries fetched by a single read or claim.
+    count: usize,
+    /// The `BLOCK <milliseconds>` arg passed to `XREADGROUP`.
+    block: usize,
+    /// Minimum idle time of a pending entry before it is reclaimed.
+    claim_min_idle: usize,
+    /// Time between two reclaim passes.
+    claim_interval: usize,
+    /// Number of deliveries after which an entry is given up on.
+    max_deliveries: Option<usize>,
+    /// The stream that entries which were given up on are added to.
+    dead_letter_key: Option<String>,
+}
+
+#[cfg(feature = "aio")]
+impl Default for StreamConsumerOptions {
+    fn default() -> Self {
+        Self {
+            start_id: "$".to_string(),
+            count: 10,
+            block: 5_000,
+            claim_min_idle: 60_000,
+            claim_interval: 30_000,
+            max_deliveries: None,
+            dead_letter_key: None,
+        }
+    }
+}
+
+#[cfg(feature = "aio")]
+impl StreamConsumerOptions {
+    /// Sets the id the consumer group is created at if it doesn't exist yet.
+    /// Defaults to `$`, so only entries added after the group was created are delivered.
+    pub fn start_id(mut self, id: impl Into<String>) -> Self {
+        self.start_id = id.into();
+        self
+    }
+
+    /// Sets the maximum number of entries fetched by a single read or claim. Defaults to 10.
+    pub fn count(mut self, n: usize) -> Self {
+        self.count = n;
+        self
+    }
+
+    /// Sets how long a read blocks waiting for new entries. Defaults to 5 seconds.
+    pub fn block(mut self, ms: usize) -> Self {
+        self.block = ms;
+        self
+    }
+
+    /// Sets how long an entry must stay unacknowledged before it is reclaimed from
+    /// its consumer. Defaults to 60 seconds.
+    pub fn claim_min_idle(mut self, ms: usize) -> Self {
+        self.claim_min_idle = ms;
+        self
+    }
+
+    /// Sets the time between two reclaim passes. Defaults to 30 seconds.
+    pub fn claim_interval(mut self, ms: usize) -> Self {
+        self.claim_interval = ms;
+        self
+    }
+
+    /// Sets how many times an entry is delivered before it's given up on. An entry that was
+    /// delivered more often is acknowledged without being handled again, after it was added to
+    /// the [`dead_letter_key`](Self::dead_letter_key) stream if one is set. By default entries
+    /// are retried indefinitely.
+    pub fn max_deliveries(mut self, n: usize) -> Self {
+        self.max_deliveries = Some(n);
+        self
+    }
+
+    /// Sets the stream that entries which were delivered more than
+    /// [`max_deliveries`](Self::max_deliveries) times are added to, with their fields. By default
+    /// these entries are dropped.
+    pub fn dead_letter_key(mut self, key: impl Into<String>) -> Self {
+        self.dead_letter_key = Some(key.into());
+        self
+    }
+}
+
+/// A consumer group member that reads a stream and acknowledges the entries it handled.
+///
+/// [`run`](Self::run) creates the group (and the stream) if it is missing, then
+/// hands every entry to a handler and acknowledges it with `XACK` once the handler
+/// succeeded. Entries whose handler failed are logged and stay in the pending entries
+/// list, and entries left pending by this or other consumers for longer than
+/// [`claim_min_idle`](StreamConsumerOptions::claim_min_idle) are periodically taken
+/// over with `XAUTOCLAIM` and handled again, up to
+/// [`max_deliveries`](StreamConsumerOptions::max_deliveries) times. On start, the consumer
+/// first goes through the entries that were delivered to it before but never acknowledged,
+/// so an entry is handled at least once even if a worker crashes in the middle of it.
+///
+/// Reads use `XREADGROUP BLOCK`, which holds the connection until they return, so the
+/// consumer should be given a connection of its own.
+///
+/// ```rust,no_run
+/// # async fn do_something() -> redis::RedisResult<()> {
+/// use redis::streams::{StreamConsumer, StreamConsumerOptions};
+///
+/// let client = redis::Client::open("redis://127.0.0.1/")?;
+/// let con = client.get_multiplexed_async_connection().await?;
+/// let options = StreamConsumerOptions::default().count(100);
+/// let mut consumer = StreamConsumer::new(con, "events", "workers", "worker-1", options);
+/// let shutdown = async {
+///     // e.g. wait for ctrl-c
+/// };
+/// consumer
+///     .run_until(
+///         |entry| async move {
+///             println!("{}: {:?}", entry.id, entry.map);
+///             Ok::<_, std::convert::Infallible>(())
+///         },
+///         shutdown,
+///     )
+///     .await
+/// # }
+/// ```
+#[cfg(feature = "aio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "aio")))]
+pub struct StreamConsumer<C> {
+    con: C,
+    key: String,
+    group: String,
+    consumer: String,
+    options: StreamConsumerOptions,
+}
+
+#[cfg(feature = "aio")]
+impl<C> StreamConsumer<C>
+where
+    C: crate::aio::ConnectionLike + Send,
+{
+    /// Creates a consumer named `consumer` in the group `group` of the stream `key`.
+    pub fn new(
+        con: C,
+        key: impl Into<String>,
+        group: impl Into<String>,
+        consumer: impl Into<String>,
+        options: StreamConsumerOptions,
+    ) -> Self {
+        Self {
+            con,
+            key: key.into(),
+            group: group.into(),
+            consumer: consumer.into(),
+            options,
+        }
+    }
+
+    /// Returns the name of this consumer.
+    pub fn consumer(&self) -> &str {
+        &self.consumer
+    }
+
+    /// Consumes the consumer and returns its connection.
+    pub fn into_connection(self) -> C {
+        self.con
+    }
+
+    /// Handles entries until an error is returned by the server.
+    pub async fn run<F, Fut, E>(&mut self, handler: F) -> RedisResult<()>
+    where
+        F: FnMut(StreamId) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: std::fmt::Debug,
+    {
+        self.run_until(handler, futures_util::future::pending())
+            .await
+    }
+
+    /// Handles entries until `shutdown` completes or an error is returned by the server.
+    ///
+    /// Shutdown is graceful: a handler that is already running is awaited and its entry
+    /// acknowledged before returning. Entries that were fetched but not handled yet stay
+    /// pending, and are picked up again the next time this consumer starts.
+    pub async fn run_until<F, Fut, E, S>(&mut self, mut handler: F, shutdown: S) -> RedisResult<()>
+    where
+        F: FnMut(StreamId) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: std::fmt::Debug,
+        S: Future<Output = ()>,
+    {
+        futures_util::pin_mut!(shutdown);
+        self.create_group().await?;
+
+        // Entries delivered to this consumer before a restart.
+        let mut last_id = "0".to_string();
+        loop {
+            let ids = self.read_group(&last_id, None).await?;
+            let last = match ids.last() {
+                Some(id) => id.id.clone(),
+                None => break,
+            };
+            if self
+                .handle(ids, true, &mut handler, shutdown.as_mut())
+                .await?
+            {
+                return Ok(());
+            }
+            last_id = last;
+        }
+
+        let claim_interval = Duration::from_millis(self.options.claim_interval as u64);
+        let mut next_claim = Instant::now();
+        loop {
+            if next_claim <= Instant::now() {
+                if self.claim(&mut handler, shutdown.as_mut()).await? {
+                    return Ok(());
+                }
+                next_claim = Instant::now() + claim_interval;
+            }
+
+            let block = self.options.block;
+            let ids = {
+                let read = self.read_group(">", Some(block));
+                futures_util::pin_mut!(read);
+                match future::select(read, shutdown.as_mut()).await {
+                    Either::Left((ids, _)) => ids?,
+                    Either::Right(_) => return Ok(()),
+                }
+            };
+            if self
+                .handle(ids, false, &mut handler, shutdown.as_mut())
+                .await?
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn create_group(&mut self) -> RedisResult<()> {
+        let result: RedisResult<()> = self
+            .con
+            .xgroup_create_mkstream(&self.key, &self.group, &self.options.start_id)
+            .await;
+        match result {
+            Err(err) if err.code() == Some("BUSYGROUP") => Ok(()),
+            result => result,
+        }
+    }
+
+    async fn read_group(&mut self, id: &str, block: Option<usize>) -> RedisResult<Vec<StreamId>> {
+        let mut options = StreamReadOptions::default()
+            .group(&self.group, &self.consumer)
+            .count(self.options.count);
+        if let Some(ms) = block {
+            options = options.block(ms);
+        }
+        let reply: Option<StreamReadReply> = self
+            .con
+            .xread_options(&[&self.key], &[id], &options)
+            .await?;
+        Ok(reply
+            .into_iter()
+            .flat_map(|reply| reply.keys)
+            .flat_map(|key| key.ids)
+            .collect())
+    }
+
+    /// Takes over entries that stayed pending for too long. Returns whether shutdown was requested.
+    async fn claim<F, Fut, E>(
+        &mut self,
+        handler: &mut F,
+        mut shutdown: Pin<&mut impl Future<Output = ()>>,
+    ) -> RedisResult<bool>
+    where
+        F: FnMut(StreamId) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: std::fmt::Debug,
+    {
+        let mut cursor = "0-0".to_string();
+        loop {
+            let options = StreamAutoClaimOptions::default().count(self.options.count);
+            let reply: StreamAutoClaimReply = self
+                .con
+                .xautoclaim_options(
+                    &self.key,
+                    &self.group,
+                    &self.consumer,
+                    self.options.claim_min_idle,
+                    &cursor,
+                    options,
+                )
+                .await?;
+            if self
+                .handle(reply.claimed, true, handler, shutdown.as_mut())
+                .await?
+            {
+                return Ok(true);
+            }
+            if reply.next_stream_id == "0-0" {
+                return Ok(false);
+            }
+            cursor = reply.next_stream_id;
+        }
+    }
+
+    /// Runs the handler on each entry. Returns whether shutdown was requested.
+    ///
+    /// `redelivered` is set for entries that were delivered before, whose delivery counts are
+    /// checked against the maximal number of deliveries.
+    async fn handle<F, Fut, E>(
+        &mut self,
+        ids: Vec<StreamId>,
+        redelivered: bool,
+        handler: &mut F,
+        mut shutdown: Pin<&mut impl Future<Output = ()>>,
+    ) -> RedisResult<bool>
+    where
+        F: FnMut(StreamId) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: std::fmt::Debug,
+    {
+        let deliveries = match self.options.max_deliveries {
+            Some(_) if redelivered => self.deliveries(&ids).await?,
+            _ => HashMap::new(),
+        };
+        for id in ids {
+            if shutdown.as_mut().now_or_never().is_some() {
+                return Ok(true);
+            }
+            let entry_id = id.id.clone();
+            // Entries deleted from the stream while pending come back without fields
+            // and can only be acknowledged.
+            if id.is_empty() {
+                let _: () = self.con.xack(&self.key, &self.group, &[entry_id]).await?;
+                continue;
+            }
+            let times_delivered = deliveries.get(&entry_id).copied().unwrap_or(1);
+            if self
+                .options
+                .max_deliveries
+                .map_or(false, |max_deliveries| times_delivered > max_deliveries)
+            {
+                self.dead_letter(id, times_delivered).await?;
+                continue;
+            }
+            match handler(id).await {
+                Ok(()) => {
+                    let _: () = self.con.xack(&self.key, &self.group, &[entry_id]).await?;
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed to handle entry {} of stream {}: {:?}",
+                        entry_id,
+                        self.key,
+                        err
+                    );
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Returns how many times each of the entries, which are pending for this consumer,
+    /// was delivered.
+    async fn deliveries(&mut self, ids: &[StreamId]) -> RedisResult<HashMap<String, usize>> {
+        let (first, last) = match (ids.first(), ids.last()) {
+            (Some(first), Some(last)) => (&first.id, &last.id),
+            _ => return Ok(HashMap::new()),
+        };
+        let reply: StreamPendingCountReply = self
+            .con
+            .xpending_consumer_count(
+                &self.key,
+                &self.group,
+                first,
+                last,
+                ids.len(),
+                &self.consumer,
+            )
+            .await?;
+        Ok(reply
+            .ids
+            .into_iter()
+            .map(|pending| (pending.id, pending.times_delivered))
+            .collect())
+    }
+
+    /// Gives up on an entry that was delivered too many times, moving it to the dead letter
+    /// stream if there is one. The commands aren't sent in a transaction, since the dead letter
+    /// stream might be on another cluster node. The entry is acknowledged only once it was added
+    /// to the dead letter stream, so it might be added twice, but it isn't lost.
+    async fn dead_letter(&mut self, id: StreamId, times_delivered: usize) -> RedisResult<()> {
+        match &self.options.dead_letter_key {
+            Some(dead_letter_key) => {
+                tracing::warn!(
+                    "Moving entry {} of stream {} to {} after {} deliveries",
+                    id.id,
+                    self.key,
+                    dead_letter_key,
+                    times_delivered
+                );
+                let mut xadd = crate::cmd("XADD");
+                xadd.arg(dead_letter_key).arg("*");
+                for (field, value) in &id.map {
+                    xadd.arg(field).arg(from_redis_value::<Vec<u8>>(value)?);
+                }
+                let _: () = xadd.query_async(&mut self.con).await?;
+            }
+            None => {
+                tracing::warn!(
+                    "Dropping entry {} of stream {} after {} deliveries",
+                    id.id,
+                    self.key,
+                    times_delivered
+                );
+            }
+        }
+        self.con.xack(&self.key, &self.group, &[&id.id]).await
+    }
+}
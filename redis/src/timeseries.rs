@@ -0,0 +1,984 @@
+//! Defines types to use with the RedisTimeSeries (`TS.*`) commands of [`TimeSeriesCommands`].
+//!
+//! [`TimeSeriesCommands`]: ../trait.TimeSeriesCommands.html
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use redis::TimeSeriesCommands;
+//! use redis::timeseries::{TsAggregationType, TsCreateOptions, TsRangeOptions, TsSample};
+//! # fn do_something() -> redis::RedisResult<()> {
+//! let client = redis::Client::open("redis://127.0.0.1/")?;
+//! let mut con = client.get_connection()?;
+//!
+//! let options = TsCreateOptions::default()
+//!     .retention(86_400_000)
+//!     .label("sensor", "temperature");
+//! let _: () = con.ts_create("temp:1", &options)?;
+//! let _: i64 = con.ts_add("temp:1", "*", 21.5)?;
+//!
+//! let options = TsRangeOptions::default().aggregation(TsAggregationType::Avg, 60_000);
+//! let samples: Vec<TsSample> = con.ts_range("temp:1", "-", "+", &options)?;
+//! # Ok(()) }
+//! ```
+
+use crate::types::{
+    ErrorKind, FromRedisValue, HashMap, RedisResult, RedisWrite, ToRedisArgs, Value,
+};
+
+macro_rules! invalid_type_error {
+    ($v:expr, $det:expr) => {{
+        fail!((
+            ErrorKind::TypeError,
+            "Response was of incompatible type",
+            format!("{:?} (response was {:?})", $det, $v)
+        ));
+    }};
+}
+
+/// How a series handles a sample added at the timestamp of an existing sample.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TsDuplicatePolicy {
+    /// Reject the new sample with an error.
+    Block,
+    /// Keep the existing sample.
+    First,
+    /// Replace the existing sample.
+    Last,
+    /// Keep the minimal value.
+    Min,
+    /// Keep the maximal value.
+    Max,
+    /// Store the sum of both values.
+    Sum,
+}
+
+impl ToRedisArgs for TsDuplicatePolicy {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        let policy: &[u8] = match self {
+            TsDuplicatePolicy::Block => b"BLOCK",
+            TsDuplicatePolicy::First => b"FIRST",
+            TsDuplicatePolicy::Last => b"LAST",
+            TsDuplicatePolicy::Min => b"MIN",
+            TsDuplicatePolicy::Max => b"MAX",
+            TsDuplicatePolicy::Sum => b"SUM",
+        };
+        out.write_arg(policy);
+    }
+}
+
+/// How the samples of a series are stored in memory.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TsEncoding {
+    /// Gorilla compressed chunks, the server default.
+    Compressed,
+    /// Raw samples.
+    Uncompressed,
+}
+
+/// The aggregation applied to the samples of each time bucket.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TsAggregationType {
+    /// Arithmetic mean of the values
+    Avg,
+    /// Sum of the values
+    Sum,
+    /// Minimal value
+    Min,
+    /// Maximal value
+    Max,
+    /// Difference between the maximal and the minimal value
+    Range,
+    /// Number of values
+    Count,
+    /// Value with the lowest timestamp
+    First,
+    /// Value with the highest timestamp
+    Last,
+    /// Population standard deviation of the values
+    StdP,
+    /// Sample standard deviation of the values
+    StdS,
+    /// Population variance of the values
+    VarP,
+    /// Sample variance of the values
+    VarS,
+    /// Time-weighted average of the values
+    Twa,
+}
+
+impl ToRedisArgs for TsAggregationType {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        let aggregation: &[u8] = match self {
+            TsAggregationType::Avg => b"AVG",
+            TsAggregationType::Sum => b"SUM",
+            TsAggregationType::Min => b"MIN",
+            TsAggregationType::Max => b"MAX",
+            TsAggregationType::Range => b"RANGE",
+            TsAggregationType::Count => b"COUNT",
+            TsAggregationType::First => b"FIRST",
+            TsAggregationType::Last => b"LAST",
+            TsAggregationType::StdP => b"STD.P",
+            TsAggregationType::StdS => b"STD.S",
+            TsAggregationType::VarP => b"VAR.P",
+            TsAggregationType::VarS => b"VAR.S",
+            TsAggregationType::Twa => b"TWA",
+        };
+        out.write_arg(aggregation);
+    }
+}
+
+/// Writes the series settings shared by `TS.CREATE` and `TS.ADD`.
+#[derive(Default, Debug, Clone)]
+struct SeriesSettings {
+    retention: Option<u64>,
+    encoding: Option<TsEncoding>,
+    chunk_size: Option<usize>,
+    labels: Vec<(String, String)>,
+}
+
+impl SeriesSettings {
+    fn write_head<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(retention) = self.retention {
+            out.write_arg(b"RETENTION");
+            out.write_arg_fmt(retention);
+        }
+        match self.encoding {
+            Some(TsEncoding::Compressed) => {
+                out.write_arg(b"ENCODING");
+                out.write_arg(b"COMPRESSED");
+            }
+            Some(TsEncoding::Uncompressed) => {
+                out.write_arg(b"ENCODING");
+                out.write_arg(b"UNCOMPRESSED");
+            }
+            None => {}
+        }
+        if let Some(size) = self.chunk_size {
+            out.write_arg(b"CHUNK_SIZE");
+            out.write_arg_fmt(size);
+        }
+    }
+
+    fn write_labels<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if !self.labels.is_empty() {
+            out.write_arg(b"LABELS");
+            for (name, value) in &self.labels {
+                out.write_arg(name.as_bytes());
+                out.write_arg(value.as_bytes());
+            }
+        }
+    }
+}
+
+/// Builder options for [`ts_create`] command.
+///
+/// [`ts_create`]: ../trait.TimeSeriesCommands.html#method.ts_create
+#[derive(Default, Debug, Clone)]
+pub struct TsCreateOptions {
+    settings: SeriesSettings,
+    duplicate_policy: Option<TsDuplicatePolicy>,
+}
+
+impl TsCreateOptions {
+    /// Set `RETENTION <milliseconds>` cmd arg, the maximal age of the samples.
+    pub fn retention(mut self, ms: u64) -> Self {
+        self.settings.retention = Some(ms);
+        self
+    }
+
+    /// Set `ENCODING <COMPRESSED|UNCOMPRESSED>` cmd arg.
+    pub fn encoding(mut self, encoding: TsEncoding) -> Self {
+        self.settings.encoding = Some(encoding);
+        self
+    }
+
+    /// Set `CHUNK_SIZE <bytes>` cmd arg.
+    pub fn chunk_size(mut self, bytes: usize) -> Self {
+        self.settings.chunk_size = Some(bytes);
+        self
+    }
+
+    /// Set `DUPLICATE_POLICY <policy>` cmd arg.
+    pub fn duplicate_policy(mut self, policy: TsDuplicatePolicy) -> Self {
+        self.duplicate_policy = Some(policy);
+        self
+    }
+
+    /// Add a label to the `LABELS` cmd arg, used to select the series in [`ts_mrange`].
+    ///
+    /// [`ts_mrange`]: ../trait.TimeSeriesCommands.html#method.ts_mrange
+    pub fn label(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.settings.labels.push((name.into(), value.into()));
+        self
+    }
+}
+
+impl ToRedisArgs for TsCreateOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        self.settings.write_head(out);
+        if let Some(policy) = self.duplicate_policy {
+            out.write_arg(b"DUPLICATE_POLICY");
+            policy.write_redis_args(out);
+        }
+        self.settings.write_labels(out);
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// Builder options for [`ts_add_options`] command.
+///
+/// Except for `ON_DUPLICATE`, the options only apply when the series is created by the command.
+///
+/// [`ts_add_options`]: ../trait.TimeSeriesCommands.html#method.ts_add_options
+#[derive(Default, Debug, Clone)]
+pub struct TsAddOptions {
+    settings: SeriesSettings,
+    on_duplicate: Option<TsDuplicatePolicy>,
+}
+
+impl TsAddOptions {
+    /// Set `RETENTION <milliseconds>` cmd arg, the maximal age of the samples.
+    pub fn retention(mut self, ms: u64) -> Self {
+        self.settings.retention = Some(ms);
+        self
+    }
+
+    /// Set `ENCODING <COMPRESSED|UNCOMPRESSED>` cmd arg.
+    pub fn encoding(mut self, encoding: TsEncoding) -> Self {
+        self.settings.encoding = Some(encoding);
+        self
+    }
+
+    /// Set `CHUNK_SIZE <bytes>` cmd arg.
+    pub fn chunk_size(mut self, bytes: usize) -> Self {
+        self.settings.chunk_size = Some(bytes);
+        self
+    }
+
+    /// Set `ON_DUPLICATE <policy>` cmd arg, overriding the policy of the series for this sample.
+    pub fn on_duplicate(mut self, policy: TsDuplicatePolicy) -> Self {
+        self.on_duplicate = Some(policy);
+        self
+    }
+
+    /// Add a label to the `LABELS` cmd arg.
+    pub fn label(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.settings.labels.push((name.into(), value.into()));
+        self
+    }
+}
+
+impl ToRedisArgs for TsAddOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        self.settings.write_head(out);
+        if let Some(policy) = self.on_duplicate {
+            out.write_arg(b"ON_DUPLICATE");
+            policy.write_redis_args(out);
+        }
+        self.settings.write_labels(out);
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// The reference timestamp of the aggregation buckets, used by [`TsRangeOptions::align`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TsAlign {
+    /// Align the buckets to the start of the queried range (`-`).
+    Start,
+    /// Align the buckets to the end of the queried range (`+`).
+    End,
+    /// Align the buckets to the given timestamp.
+    Timestamp(i64),
+}
+
+/// The timestamp reported for each aggregation bucket, used by [`TsRangeOptions::bucket_timestamp`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TsBucketTimestamp {
+    /// The start of the bucket, the server default.
+    Start,
+    /// The middle of the bucket.
+    Mid,
+    /// The end of the bucket.
+    End,
+}
+
+/// Builder options for [`ts_range`] and [`ts_revrange`] commands.
+///
+/// [`ts_range`]: ../trait.TimeSeriesCommands.html#method.ts_range
+/// [`ts_revrange`]: ../trait.TimeSeriesCommands.html#method.ts_revrange
+#[derive(Default, Debug, Clone)]
+pub struct TsRangeOptions {
+    latest: bool,
+    filter_by_ts: Vec<i64>,
+    filter_by_value: Option<(f64, f64)>,
+    count: Option<usize>,
+    align: Option<TsAlign>,
+    aggregation: Option<(TsAggregationType, u64)>,
+    bucket_timestamp: Option<TsBucketTimestamp>,
+    empty: bool,
+}
+
+impl TsRangeOptions {
+    /// Set `LATEST` cmd arg, including the latest, still open, bucket of compacted series.
+    pub fn latest(mut self) -> Self {
+        self.latest = true;
+        self
+    }
+
+    /// Set `FILTER_BY_TS <timestamp> ...` cmd arg, only returning samples at the given timestamps.
+    pub fn filter_by_ts(mut self, timestamps: impl IntoIterator<Item = i64>) -> Self {
+        self.filter_by_ts = timestamps.into_iter().collect();
+        self
+    }
+
+    /// Set `FILTER_BY_VALUE <min> <max>` cmd arg.
+    pub fn filter_by_value(mut self, min: f64, max: f64) -> Self {
+        self.filter_by_value = Some((min, max));
+        self
+    }
+
+    /// Set `COUNT <count>` cmd arg, the maximal number of samples (or buckets) returned.
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Set `ALIGN <align>` cmd arg of the aggregation.
+    pub fn align(mut self, align: TsAlign) -> Self {
+        self.align = Some(align);
+        self
+    }
+
+    /// Set `AGGREGATION <aggregator> <bucket duration>` cmd arg, aggregating the
+    /// samples in buckets of `bucket_duration` milliseconds.
+    pub fn aggregation(mut self, aggregation: TsAggregationType, bucket_duration: u64) -> Self {
+        self.aggregation = Some((aggregation, bucket_duration));
+        self
+    }
+
+    /// Set `BUCKETTIMESTAMP <-|~|+>` cmd arg of the aggregation.
+    pub fn bucket_timestamp(mut self, bucket_timestamp: TsBucketTimestamp) -> Self {
+        self.bucket_timestamp = Some(bucket_timestamp);
+        self
+    }
+
+    /// Set `EMPTY` cmd arg, also reporting the buckets without samples.
+    pub fn empty(mut self) -> Self {
+        self.empty = true;
+        self
+    }
+
+    /// Writes the args preceding `COUNT`, which `TS.MRANGE` separates from the rest.
+    fn write_filters<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if self.latest {
+            out.write_arg(b"LATEST");
+        }
+        if !self.filter_by_ts.is_empty() {
+            out.write_arg(b"FILTER_BY_TS");
+            for timestamp in &self.filter_by_ts {
+                out.write_arg_fmt(timestamp);
+            }
+        }
+        if let Some((min, max)) = self.filter_by_value {
+            out.write_arg(b"FILTER_BY_VALUE");
+            out.write_arg_fmt(min);
+            out.write_arg_fmt(max);
+        }
+    }
+
+    fn write_aggregation<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        if let Some(count) = self.count {
+            out.write_arg(b"COUNT");
+            out.write_arg_fmt(count);
+        }
+        if let Some((aggregation, bucket_duration)) = self.aggregation {
+            match self.align {
+                Some(TsAlign::Start) => {
+                    out.write_arg(b"ALIGN");
+                    out.write_arg(b"-");
+                }
+                Some(TsAlign::End) => {
+                    out.write_arg(b"ALIGN");
+                    out.write_arg(b"+");
+                }
+                Some(TsAlign::Timestamp(timestamp)) => {
+                    out.write_arg(b"ALIGN");
+                    out.write_arg_fmt(timestamp);
+                }
+                None => {}
+            }
+            out.write_arg(b"AGGREGATION");
+            aggregation.write_redis_args(out);
+            out.write_arg_fmt(bucket_duration);
+            match self.bucket_timestamp {
+                Some(TsBucketTimestamp::Start) => {
+                    out.write_arg(b"BUCKETTIMESTAMP");
+                    out.write_arg(b"-");
+                }
+                Some(TsBucketTimestamp::Mid) => {
+                    out.write_arg(b"BUCKETTIMESTAMP");
+                    out.write_arg(b"~");
+                }
+                Some(TsBucketTimestamp::End) => {
+                    out.write_arg(b"BUCKETTIMESTAMP");
+                    out.write_arg(b"+");
+                }
+                None => {}
+            }
+            if self.empty {
+                out.write_arg(b"EMPTY");
+            }
+        }
+    }
+}
+
+impl ToRedisArgs for TsRangeOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        self.write_filters(out);
+        self.write_aggregation(out);
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Clone)]
+enum TsLabels {
+    All,
+    Selected(Vec<String>),
+}
+
+/// Builder options for [`ts_mrange`] and [`ts_mrevrange`] commands.
+///
+/// At least one filter expression, such as `sensor=temperature`, must be added
+/// with [`filter`](Self::filter) to select the series.
+///
+/// [`ts_mrange`]: ../trait.TimeSeriesCommands.html#method.ts_mrange
+/// [`ts_mrevrange`]: ../trait.TimeSeriesCommands.html#method.ts_mrevrange
+#[derive(Default, Debug, Clone)]
+pub struct TsMRangeOptions {
+    range: TsRangeOptions,
+    labels: Option<TsLabels>,
+    filters: Vec<String>,
+    group_by: Option<(String, TsAggregationType)>,
+}
+
+impl TsMRangeOptions {
+    /// Sets the options applied to the samples of each series.
+    pub fn range(mut self, options: TsRangeOptions) -> Self {
+        self.range = options;
+        self
+    }
+
+    /// Set `WITHLABELS` cmd arg, returning all the labels of each series.
+    pub fn with_labels(mut self) -> Self {
+        self.labels = Some(TsLabels::All);
+        self
+    }
+
+    /// Set `SELECTED_LABELS <label> ...` cmd arg, returning the given labels of each series.
+    pub fn selected_labels<S: Into<String>>(mut self, labels: impl IntoIterator<Item = S>) -> Self {
+        self.labels = Some(TsLabels::Selected(
+            labels.into_iter().map(Into::into).collect(),
+        ));
+        self
+    }
+
+    /// Add a filter expression to the `FILTER` cmd arg, such as `label=value`,
+    /// `label!=value`, `label=(a,b)` or `label=`.
+    pub fn filter(mut self, expression: impl Into<String>) -> Self {
+        self.filters.push(expression.into());
+        self
+    }
+
+    /// Set `GROUPBY <label> REDUCE <reducer>` cmd args, merging the series
+    /// sharing the same value of `label`.
+    pub fn group_by(mut self, label: impl Into<String>, reducer: TsAggregationType) -> Self {
+        self.group_by = Some((label.into(), reducer));
+        self
+    }
+}
+
+impl ToRedisArgs for TsMRangeOptions {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        self.range.write_filters(out);
+        match self.labels {
+            Some(TsLabels::All) => out.write_arg(b"WITHLABELS"),
+            Some(TsLabels::Selected(ref labels)) => {
+                out.write_arg(b"SELECTED_LABELS");
+                for label in labels {
+                    out.write_arg(label.as_bytes());
+                }
+            }
+            None => {}
+        }
+        self.range.write_aggregation(out);
+        out.write_arg(b"FILTER");
+        for filter in &self.filters {
+            out.write_arg(filter.as_bytes());
+        }
+        if let Some((ref label, reducer)) = self.group_by {
+            out.write_arg(b"GROUPBY");
+            out.write_arg(label.as_bytes());
+            out.write_arg(b"REDUCE");
+            reducer.write_redis_args(out);
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        false
+    }
+}
+
+/// A sample of a time series.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct TsSample {
+    /// The timestamp of the sample, in milliseconds.
+    pub timestamp: i64,
+    /// The value of the sample.
+    pub value: f64,
+}
+
+impl FromRedisValue for TsSample {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        match v {
+            Value::Array(values) if values.len() == 2 => Ok(TsSample {
+                timestamp: i64::from_redis_value(&values[0])?,
+                value: f64::from_redis_value(&values[1])?,
+            }),
+            _ => invalid_type_error!(v, "Response type not time series sample compatible"),
+        }
+    }
+}
+
+/// A series returned by [`ts_mrange`] and [`ts_mrevrange`].
+///
+/// [`ts_mrange`]: ../trait.TimeSeriesCommands.html#method.ts_mrange
+/// [`ts_mrevrange`]: ../trait.TimeSeriesCommands.html#method.ts_mrevrange
+#[derive(Default, Debug, Clone)]
+pub struct TsSeries {
+    /// The key of the series, or `label=value` for the groups of `GROUPBY`.
+    pub key: String,
+    /// The labels requested with `WITHLABELS` or `SELECTED_LABELS`. Selected
+    /// labels that the series doesn't have are left out.
+    pub labels: HashMap<String, String>,
+    /// The samples of the series.
+    pub samples: Vec<TsSample>,
+}
+
+/// Reply type used with [`ts_mrange`] and [`ts_mrevrange`] commands.
+///
+/// [`ts_mrange`]: ../trait.TimeSeriesCommands.html#method.ts_mrange
+/// [`ts_mrevrange`]: ../trait.TimeSeriesCommands.html#method.ts_mrevrange
+#[derive(Default, Debug, Clone)]
+pub struct TsMRangeReply {
+    /// The matching series.
+    pub series: Vec<TsSeries>,
+}
+
+impl FromRedisValue for TsMRangeReply {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let mut series = Vec::new();
+        match v {
+            // RESP2 replies with `[key, labels, samples]` arrays.
+            Value::Array(values) => {
+                for value in values {
+                    match value {
+                        Value::Array(parts) if parts.len() == 3 => series.push(TsSeries {
+                            key: String::from_redis_value(&parts[0])?,
+                            labels: parse_labels(&parts[1])?,
+                            samples: FromRedisValue::from_redis_value(&parts[2])?,
+                        }),
+                        _ => invalid_type_error!(v, "Response type not TS.MRANGE compatible"),
+                    }
+                }
+            }
+            // RESP3 maps each key to `[labels, (metadata,) samples]`.
+            Value::Map(entries) => {
+                for (key, value) in entries {
+                    match value {
+                        Value::Array(parts) if parts.len() >= 2 => series.push(TsSeries {
+                            key: String::from_redis_value(key)?,
+                            labels: parse_labels(&parts[0])?,
+                            samples: FromRedisValue::from_redis_value(&parts[parts.len() - 1])?,
+                        }),
+                        _ => invalid_type_error!(v, "Response type not TS.MRANGE compatible"),
+                    }
+                }
+            }
+            _ => invalid_type_error!(v, "Response type not TS.MRANGE compatible"),
+        }
+        Ok(TsMRangeReply { series })
+    }
+}
+
+/// A compaction rule of a series, as reported by [`ts_info`].
+///
+/// [`ts_info`]: ../trait.TimeSeriesCommands.html#method.ts_info
+#[derive(Default, Debug, Clone)]
+pub struct TsRule {
+    /// The key of the destination series.
+    pub dest_key: String,
+    /// The duration of the aggregation buckets, in milliseconds.
+    pub bucket_duration: u64,
+    /// The aggregation, e.g. `avg`.
+    pub aggregation: String,
+    /// The timestamp the buckets are aligned to, reported by RedisTimeSeries 1.8+.
+    pub align_timestamp: Option<i64>,
+}
+
+/// Reply type used with [`ts_info`] command.
+///
+/// [`ts_info`]: ../trait.TimeSeriesCommands.html#method.ts_info
+#[derive(Default, Debug, Clone)]
+pub struct TsInfo {
+    /// Total number of samples in the series.
+    pub total_samples: u64,
+    /// Total number of bytes allocated for the series.
+    pub memory_usage: u64,
+    /// Timestamp of the first sample, or 0 for an empty series.
+    pub first_timestamp: i64,
+    /// Timestamp of the last sample, or 0 for an empty series.
+    pub last_timestamp: i64,
+    /// Maximal age of the samples in milliseconds, 0 if they never expire.
+    pub retention_time: u64,
+    /// Number of memory chunks used by the series.
+    pub chunk_count: u64,
+    /// Size of each memory chunk, in bytes.
+    pub chunk_size: u64,
+    /// Encoding of the memory chunks, e.g. `compressed`.
+    pub chunk_type: String,
+    /// The duplicate policy of the series, if it overrides the server default.
+    pub duplicate_policy: Option<String>,
+    /// The labels of the series.
+    pub labels: HashMap<String, String>,
+    /// The key of the series this series is a compaction of.
+    pub source_key: Option<String>,
+    /// The compaction rules using this series as a source.
+    pub rules: Vec<TsRule>,
+}
+
+impl FromRedisValue for TsInfo {
+    fn from_redis_value(v: &Value) -> RedisResult<Self> {
+        let map: HashMap<String, Value> = FromRedisValue::from_redis_value(v)?;
+        let mut info = TsInfo::default();
+        for (field, value) in &map {
+            match field.as_str() {
+                "totalSamples" => info.total_samples = u64::from_redis_value(value)?,
+                "memoryUsage" => info.memory_usage = u64::from_redis_value(value)?,
+                "firstTimestamp" => info.first_timestamp = i64::from_redis_value(value)?,
+                "lastTimestamp" => info.last_timestamp = i64::from_redis_value(value)?,
+                "retentionTime" => info.retention_time = u64::from_redis_value(value)?,
+                "chunkCount" => info.chunk_count = u64::from_redis_value(value)?,
+                "chunkSize" => info.chunk_size = u64::from_redis_value(value)?,
+                "chunkType" => info.chunk_type = String::from_redis_value(value)?,
+                "duplicatePolicy" => info.duplicate_policy = Option::from_redis_value(value)?,
+                "labels" => info.labels = parse_labels(value)?,
+                "sourceKey" => info.source_key = Option::from_redis_value(value)?,
+                "rules" => info.rules = parse_rules(value)?,
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+}
+
+/// Parses labels, sent as `[[name, value], ...]` with RESP2 and as a map with RESP3.
+fn parse_labels(v: &Value) -> RedisResult<HashMap<String, String>> {
+    let pairs: Vec<(String, Option<String>)> = match v {
+        Value::Map(entries) => entries
+            .iter()
+            .map(|(name, value)| {
+                Ok((
+                    String::from_redis_value(name)?,
+                    Option::from_redis_value(value)?,
+                ))
+            })
+            .collect::<RedisResult<_>>()?,
+        _ => FromRedisValue::from_redis_value(v)?,
+    };
+    Ok(pairs
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .collect())
+}
+
+/// Parses rules, sent as `[[dest, bucket, aggregation, (align)], ...]` with RESP2
+/// and as a map from the destination key to the rest with RESP3.
+fn parse_rules(v: &Value) -> RedisResult<Vec<TsRule>> {
+    fn rule(dest_key: String, values: &[Value], v: &Value) -> RedisResult<TsRule> {
+        if values.len() < 2 {
+            invalid_type_error!(v, "Response type not TS.INFO compatible");
+        }
+        Ok(TsRule {
+            dest_key,
+            bucket_duration: u64::from_redis_value(&values[0])?,
+            aggregation: String::from_redis_value(&values[1])?,
+            align_timestamp: match values.get(2) {
+                Some(value) => Some(i64::from_redis_value(value)?),
+                None => None,
+            },
+        })
+    }
+
+    match v {
+        Value::Map(entries) => entries
+            .iter()
+            .map(|(dest, value)| match value {
+                Value::Array(values) => rule(String::from_redis_value(dest)?, values, v),
+                _ => invalid_type_error!(v, "Response type not TS.INFO compatible"),
+            })
+            .collect(),
+        Value::Array(rules) => rules
+            .iter()
+            .map(|value| match value {
+                Value::Array(values) if !values.is_empty() => {
+                    rule(String::from_redis_value(&values[0])?, &values[1..], v)
+                }
+                _ => invalid_type_error!(v, "Response type not TS.INFO compatible"),
+            })
+            .collect(),
+        Value::Nil => Ok(Vec::new()),
+        _ => invalid_type_error!(v, "Response type not TS.INFO compatible"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str;
+
+    macro_rules! assert_args {
+        ($value:expr, $($args:expr),+) => {
+            let args = $value.to_redis_args();
+            let strings: Vec<_> = args.iter()
+                                      .map(|a| str::from_utf8(a.as_ref()).unwrap())
+                                      .collect();
+            assert_eq!(strings, vec![$($args),+]);
+        }
+    }
+
+    fn bulk(s: &str) -> Value {
+        Value::BulkString(s.as_bytes().to_vec())
+    }
+
+    fn sample(timestamp: i64, value: &str) -> Value {
+        Value::Array(vec![Value::Int(timestamp), bulk(value)])
+    }
+
+    #[test]
+    fn test_create_and_add_options_args() {
+        assert_eq!(TsCreateOptions::default().to_redis_args().len(), 0);
+
+        let options = TsCreateOptions::default()
+            .retention(1000)
+            .encoding(TsEncoding::Uncompressed)
+            .duplicate_policy(TsDuplicatePolicy::Last)
+            .label("sensor", "temp")
+            .label("room", "kitchen");
+        assert_args!(
+            &options,
+            "RETENTION",
+            "1000",
+            "ENCODING",
+            "UNCOMPRESSED",
+            "DUPLICATE_POLICY",
+            "LAST",
+            "LABELS",
+            "sensor",
+            "temp",
+            "room",
+            "kitchen"
+        );
+
+        let options = TsAddOptions::default()
+            .chunk_size(128)
+            .on_duplicate(TsDuplicatePolicy::Sum);
+        assert_args!(&options, "CHUNK_SIZE", "128", "ON_DUPLICATE", "SUM");
+    }
+
+    #[test]
+    fn test_range_options_args() {
+        assert_eq!(TsRangeOptions::default().to_redis_args().len(), 0);
+
+        let options = TsRangeOptions::default()
+            .latest()
+            .filter_by_value(0.5, 10.0)
+            .count(5)
+            .align(TsAlign::Start)
+            .aggregation(TsAggregationType::StdP, 60_000)
+            .bucket_timestamp(TsBucketTimestamp::Mid)
+            .empty();
+        assert_args!(
+            &options,
+            "LATEST",
+            "FILTER_BY_VALUE",
+            "0.5",
+            "10",
+            "COUNT",
+            "5",
+            "ALIGN",
+            "-",
+            "AGGREGATION",
+            "STD.P",
+            "60000",
+            "BUCKETTIMESTAMP",
+            "~",
+            "EMPTY"
+        );
+
+        let options = TsMRangeOptions::default()
+            .range(
+                TsRangeOptions::default()
+                    .filter_by_ts([1, 2])
+                    .aggregation(TsAggregationType::Max, 10),
+            )
+            .selected_labels(["room"])
+            .filter("sensor=temp")
+            .filter("room!=")
+            .group_by("room", TsAggregationType::Avg);
+        assert_args!(
+            &options,
+            "FILTER_BY_TS",
+            "1",
+            "2",
+            "SELECTED_LABELS",
+            "room",
+            "AGGREGATION",
+            "MAX",
+            "10",
+            "FILTER",
+            "sensor=temp",
+            "room!=",
+            "GROUPBY",
+            "room",
+            "REDUCE",
+            "AVG"
+        );
+    }
+
+    #[test]
+    fn test_mrange_reply() {
+        let reply: TsMRangeReply = FromRedisValue::from_redis_value(&Value::Array(vec![
+            Value::Array(vec![
+                bulk("temp:1"),
+                Value::Array(vec![
+                    Value::Array(vec![bulk("room"), bulk("kitchen")]),
+                    Value::Array(vec![bulk("floor"), Value::Nil]),
+                ]),
+                Value::Array(vec![sample(1, "1.5"), sample(2, "2")]),
+            ]),
+            Value::Array(vec![
+                bulk("temp:2"),
+                Value::Array(vec![]),
+                Value::Array(vec![]),
+            ]),
+        ]))
+        .unwrap();
+        assert_eq!(reply.series.len(), 2);
+        assert_eq!(reply.series[0].key, "temp:1");
+        assert_eq!(reply.series[0].labels.len(), 1);
+        assert_eq!(
+            reply.series[0].labels.get("room"),
+            Some(&"kitchen".to_string())
+        );
+        assert_eq!(
+            reply.series[0].samples,
+            vec![
+                TsSample {
+                    timestamp: 1,
+                    value: 1.5
+                },
+                TsSample {
+                    timestamp: 2,
+                    value: 2.0
+                }
+            ]
+        );
+
+        let reply: TsMRangeReply = FromRedisValue::from_redis_value(&Value::Map(vec![(
+            bulk("temp:1"),
+            Value::Array(vec![
+                Value::Map(vec![(bulk("room"), bulk("kitchen"))]),
+                Value::Map(vec![(bulk("aggregators"), Value::Array(vec![]))]),
+                Value::Array(vec![Value::Array(vec![Value::Int(1), Value::Double(1.5)])]),
+            ]),
+        )]))
+        .unwrap();
+        assert_eq!(reply.series[0].labels.len(), 1);
+        assert_eq!(reply.series[0].samples[0].value, 1.5);
+    }
+
+    #[test]
+    fn test_info_reply() {
+        let info: TsInfo = FromRedisValue::from_redis_value(&Value::Array(vec![
+            bulk("totalSamples"),
+            Value::Int(3),
+            bulk("retentionTime"),
+            Value::Int(1000),
+            bulk("chunkType"),
+            bulk("compressed"),
+            bulk("duplicatePolicy"),
+            Value::Nil,
+            bulk("labels"),
+            Value::Array(vec![Value::Array(vec![bulk("sensor"), bulk("temp")])]),
+            bulk("sourceKey"),
+            Value::Nil,
+            bulk("rules"),
+            Value::Array(vec![Value::Array(vec![
+                bulk("temp:avg"),
+                Value::Int(60000),
+                bulk("AVG"),
+                Value::Int(0),
+            ])]),
+        ]))
+        .unwrap();
+        assert_eq!(info.total_samples, 3);
+        assert_eq!(info.retention_time, 1000);
+        assert_eq!(info.chunk_type, "compressed");
+        assert_eq!(info.duplicate_policy, None);
+        assert_eq!(info.labels.get("sensor"), Some(&"temp".to_string()));
+        assert_eq!(info.rules.len(), 1);
+        assert_eq!(info.rules[0].dest_key, "temp:avg");
+        assert_eq!(info.rules[0].bucket_duration, 60000);
+        assert_eq!(info.rules[0].align_timestamp, Some(0));
+    }
+}
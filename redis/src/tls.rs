@@ -53,7 +53,10 @@ pub(crate) fn inner_build_with_tls(
         )));
     };
 
-    Ok(Client { connection_info })
+    Ok(Client {
+        connection_info,
+        instrumenter: None,
+    })
 }
 
 pub(crate) fn retrieve_tls_certificates(
@@ -110,6 +113,7 @@ pub(crate) fn retrieve_tls_certificates(
     Ok(TlsConnParams {
         client_tls_params,
         root_cert_store,
+        server_name: None,
     })
 }
 
@@ -139,4 +143,5 @@ impl Clone for ClientTlsParams {
 pub struct TlsConnParams {
     pub(crate) client_tls_params: Option<ClientTlsParams>,
     pub(crate) root_cert_store: Option<RootCertStore>,
+    pub(crate) server_name: Option<String>,
 }
@@ -136,8 +136,8 @@ pub enum ErrorKind {
     /// Used when a cluster connection cannot find a connection to a valid node.
     ClusterConnectionNotFound,
 
-    #[cfg(feature = "json")]
-    /// Error Serializing a struct to JSON form
+    #[cfg(feature = "serde")]
+    /// Error serializing a value, for example to JSON form
     Serialize,
 
     /// Redis Servers prior to v6.0.0 doesn't support RESP3.
@@ -205,7 +205,7 @@ impl From<ServerError> for RedisError {
 
 /// Internal low-level redis value enum.
 #[derive(PartialEq, Debug)]
-pub(crate) enum InternalValue {
+pub(crate) enum InternalValue<B = Vec<u8>> {
     /// A nil response from the server.
     Nil,
     /// An integer response.  Note that there are a few situations
@@ -214,25 +214,25 @@ pub(crate) enum InternalValue {
     /// the same for all numeric responses.
     Int(i64),
     /// An arbitrary binary data, usually represents a binary-safe string.
-    BulkString(Vec<u8>),
+    BulkString(B),
     /// A response containing an array with more data. This is generally used by redis
     /// to express nested structures.
-    Array(Vec<InternalValue>),
+    Array(Vec<InternalValue<B>>),
     /// A simple string response, without line breaks and not binary safe.
     SimpleString(String),
     /// A status response which represents the string "OK".
     Okay,
     /// Unordered key,value list from the server. Use `as_map_iter` function.
-    Map(Vec<(InternalValue, InternalValue)>),
+    Map(Vec<(InternalValue<B>, InternalValue<B>)>),
     /// Attribute value from the server. Client will give data instead of whole Attribute type.
     Attribute {
         /// Data that attributes belong to.
-        data: Box<InternalValue>,
+        data: Box<InternalValue<B>>,
         /// Key,Value list of attributes.
-        attributes: Vec<(InternalValue, InternalValue)>,
+        attributes: Vec<(InternalValue<B>, InternalValue<B>)>,
     },
     /// Unordered set value from the server.
-    Set(Vec<InternalValue>),
+    Set(Vec<InternalValue<B>>),
     /// A floating number response from the server.
     Double(f64),
     /// A boolean response from the server.
@@ -251,7 +251,7 @@ pub(crate) enum InternalValue {
         /// Push Kind
         kind: PushKind,
         /// Remaining data from push message
-        data: Vec<InternalValue>,
+        data: Vec<InternalValue<B>>,
     },
     ServerError(ServerError),
 }
@@ -302,6 +302,53 @@ impl InternalValue {
     }
 }
 
+#[cfg(feature = "bytes")]
+impl InternalValue<bytes::Bytes> {
+    pub(crate) fn try_into_bytes_value(self) -> RedisResult<BytesValue> {
+        match self {
+            InternalValue::Nil => Ok(BytesValue::Nil),
+            InternalValue::Int(val) => Ok(BytesValue::Int(val)),
+            InternalValue::BulkString(val) => Ok(BytesValue::BulkString(val)),
+            InternalValue::Array(val) => Ok(BytesValue::Array(Self::try_into_bytes_vec(val)?)),
+            InternalValue::SimpleString(val) => Ok(BytesValue::SimpleString(val)),
+            InternalValue::Okay => Ok(BytesValue::Okay),
+            InternalValue::Map(map) => Ok(BytesValue::Map(Self::try_into_bytes_map(map)?)),
+            InternalValue::Attribute { data, attributes } => {
+                let data = Box::new((*data).try_into_bytes_value()?);
+                let attributes = Self::try_into_bytes_map(attributes)?;
+                Ok(BytesValue::Attribute { data, attributes })
+            }
+            InternalValue::Set(set) => Ok(BytesValue::Set(Self::try_into_bytes_vec(set)?)),
+            InternalValue::Double(double) => Ok(BytesValue::Double(double)),
+            InternalValue::Boolean(boolean) => Ok(BytesValue::Boolean(boolean)),
+            InternalValue::VerbatimString { format, text } => {
+                Ok(BytesValue::VerbatimString { format, text })
+            }
+            InternalValue::BigNumber(number) => Ok(BytesValue::BigNumber(number)),
+            InternalValue::Push { kind, data } => Ok(BytesValue::Push {
+                kind,
+                data: Self::try_into_bytes_vec(data)?,
+            }),
+
+            InternalValue::ServerError(err) => Err(err.into()),
+        }
+    }
+
+    fn try_into_bytes_vec(vec: Vec<Self>) -> RedisResult<Vec<BytesValue>> {
+        vec.into_iter()
+            .map(Self::try_into_bytes_value)
+            .collect::<RedisResult<Vec<_>>>()
+    }
+
+    fn try_into_bytes_map(map: Vec<(Self, Self)>) -> RedisResult<Vec<(BytesValue, BytesValue)>> {
+        let mut vec = Vec::with_capacity(map.len());
+        for (key, value) in map.into_iter() {
+            vec.push((key.try_into_bytes_value()?, value.try_into_bytes_value()?));
+        }
+        Ok(vec)
+    }
+}
+
 /// Internal low-level redis value enum.
 #[derive(PartialEq, Clone)]
 pub enum Value {
@@ -599,6 +646,144 @@ impl fmt::Debug for Value {
     }
 }
 
+/// A redis value whose bulk strings are [`bytes::Bytes`] slices of the buffer
+/// they were read from.
+///
+/// This mirrors [`Value`], but parsing a response into it does not copy the
+/// payload of bulk strings, which makes it a better fit for responses that
+/// carry large blobs.  It is produced by [`parse_redis_bytes_value`] and, for
+/// async IO, by `MultiplexedConnection::query_bytes_value` and the
+/// `BytesValueCodec`, and can be converted into a regular value with
+/// `Value::from` or directly into a result type with [`from_bytes_value`].
+///
+/// [`parse_redis_bytes_value`]: crate::parse_redis_bytes_value
+#[cfg(feature = "bytes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+#[derive(PartialEq, Clone, Debug)]
+pub enum BytesValue {
+    /// A nil response from the server.
+    Nil,
+    /// An integer response.
+    Int(i64),
+    /// An arbitrary binary data, sharing the buffer it was read from.
+    BulkString(bytes::Bytes),
+    /// A response containing an array with more data.
+    Array(Vec<BytesValue>),
+    /// A simple string response, without line breaks and not binary safe.
+    SimpleString(String),
+    /// A status response which represents the string "OK".
+    Okay,
+    /// Unordered key,value list from the server.
+    Map(Vec<(BytesValue, BytesValue)>),
+    /// Attribute value from the server.
+    Attribute {
+        /// Data that attributes belong to.
+        data: Box<BytesValue>,
+        /// Key,Value list of attributes.
+        attributes: Vec<(BytesValue, BytesValue)>,
+    },
+    /// Unordered set value from the server.
+    Set(Vec<BytesValue>),
+    /// A floating number response from the server.
+    Double(f64),
+    /// A boolean response from the server.
+    Boolean(bool),
+    /// First String is format and other is the string
+    VerbatimString {
+        /// Text's format type
+        format: VerbatimFormat,
+        /// Remaining string check format before using!
+        text: String,
+    },
+    /// Very large number that out of the range of the signed 64 bit numbers
+    BigNumber(BigInt),
+    /// Push data from the server.
+    Push {
+        /// Push Kind
+        kind: PushKind,
+        /// Remaining data from push message
+        data: Vec<BytesValue>,
+    },
+}
+
+#[cfg(feature = "bytes")]
+impl From<BytesValue> for Value {
+    /// Converts into a regular value.  Bulk strings are only copied if their
+    /// buffer is still shared.
+    fn from(value: BytesValue) -> Value {
+        fn convert_vec(values: Vec<BytesValue>) -> Vec<Value> {
+            values.into_iter().map(Value::from).collect()
+        }
+        fn convert_map(map: Vec<(BytesValue, BytesValue)>) -> Vec<(Value, Value)> {
+            map.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect()
+        }
+
+        match value {
+            BytesValue::Nil => Value::Nil,
+            BytesValue::Int(val) => Value::Int(val),
+            BytesValue::BulkString(val) => Value::BulkString(val.into()),
+            BytesValue::Array(val) => Value::Array(convert_vec(val)),
+            BytesValue::SimpleString(val) => Value::SimpleString(val),
+            BytesValue::Okay => Value::Okay,
+            BytesValue::Map(map) => Value::Map(convert_map(map)),
+            BytesValue::Attribute { data, attributes } => Value::Attribute {
+                data: Box::new((*data).into()),
+                attributes: convert_map(attributes),
+            },
+            BytesValue::Set(set) => Value::Set(convert_vec(set)),
+            BytesValue::Double(double) => Value::Double(double),
+            BytesValue::Boolean(boolean) => Value::Boolean(boolean),
+            BytesValue::VerbatimString { format, text } => Value::VerbatimString { format, text },
+            BytesValue::BigNumber(number) => Value::BigNumber(number),
+            BytesValue::Push { kind, data } => Value::Push {
+                kind,
+                data: convert_vec(data),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl From<Value> for BytesValue {
+    /// Converts a regular value.  Bulk strings take over their vector without
+    /// copying it.
+    fn from(value: Value) -> BytesValue {
+        fn convert_vec(values: Vec<Value>) -> Vec<BytesValue> {
+            values.into_iter().map(BytesValue::from).collect()
+        }
+        fn convert_map(map: Vec<(Value, Value)>) -> Vec<(BytesValue, BytesValue)> {
+            map.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect()
+        }
+
+        match value {
+            Value::Nil => BytesValue::Nil,
+            Value::Int(val) => BytesValue::Int(val),
+            Value::BulkString(val) => BytesValue::BulkString(val.into()),
+            Value::Array(val) => BytesValue::Array(convert_vec(val)),
+            Value::SimpleString(val) => BytesValue::SimpleString(val),
+            Value::Okay => BytesValue::Okay,
+            Value::Map(map) => BytesValue::Map(convert_map(map)),
+            Value::Attribute { data, attributes } => BytesValue::Attribute {
+                data: Box::new((*data).into()),
+                attributes: convert_map(attributes),
+            },
+            Value::Set(set) => BytesValue::Set(convert_vec(set)),
+            Value::Double(double) => BytesValue::Double(double),
+            Value::Boolean(boolean) => BytesValue::Boolean(boolean),
+            Value::VerbatimString { format, text } => BytesValue::VerbatimString { format, text },
+            Value::BigNumber(number) => BytesValue::BigNumber(number),
+            Value::Push { kind, data } => BytesValue::Push {
+                kind,
+                data: convert_vec(data),
+            },
+        }
+    }
+}
+
 /// Represents a redis error.  For the most part you should be using
 /// the Error trait to interact with this rather than the actual
 /// struct.
@@ -873,7 +1058,7 @@ impl RedisError {
             ErrorKind::EmptySentinelList => "empty sentinel list",
             ErrorKind::NotBusy => "not busy",
             ErrorKind::ClusterConnectionNotFound => "connection to node in cluster not found",
-            #[cfg(feature = "json")]
+            #[cfg(feature = "serde")]
             ErrorKind::Serialize => "serializing",
             ErrorKind::RESP3NotSupported => "resp3 is not supported by server",
             ErrorKind::ParseError => "parse error",
@@ -1036,7 +1221,7 @@ impl RedisError {
             ErrorKind::ClientError => RetryMethod::NoRetry,
             ErrorKind::EmptySentinelList => RetryMethod::NoRetry,
             ErrorKind::NotBusy => RetryMethod::NoRetry,
-            #[cfg(feature = "json")]
+            #[cfg(feature = "serde")]
             ErrorKind::Serialize => RetryMethod::NoRetry,
             ErrorKind::RESP3NotSupported => RetryMethod::NoRetry,
 
@@ -1706,6 +1891,23 @@ pub trait FromRedisValue: Sized {
     fn from_owned_byte_vec(_vec: Vec<u8>) -> RedisResult<Vec<Self>> {
         Self::from_owned_redis_value(Value::BulkString(_vec)).map(|rv| vec![rv])
     }
+
+    /// Given a [`BytesValue`] this attempts to convert it into the given
+    /// destination type.  By default the value is converted into a regular
+    /// `Value` first; this only needs to be implemented by types that can
+    /// take over the shared bulk string buffers without copying them.
+    #[cfg(feature = "bytes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+    fn from_bytes_value(v: BytesValue) -> RedisResult<Self> {
+        Self::from_owned_redis_value(v.into())
+    }
+
+    /// The same as `from_owned_redis_values`, but takes a `Vec<BytesValue>`.
+    #[cfg(feature = "bytes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+    fn from_bytes_values(items: Vec<BytesValue>) -> RedisResult<Vec<Self>> {
+        Self::from_owned_redis_values(items.into_iter().map(Value::from).collect())
+    }
 }
 
 fn get_inner_value(v: &Value) -> &Value {
@@ -1720,6 +1922,19 @@ fn get_inner_value(v: &Value) -> &Value {
     }
 }
 
+#[cfg(feature = "bytes")]
+fn get_bytes_inner_value(v: BytesValue) -> BytesValue {
+    if let BytesValue::Attribute {
+        data,
+        attributes: _,
+    } = v
+    {
+        *data
+    } else {
+        v
+    }
+}
+
 fn get_owned_inner_value(v: Value) -> Value {
     if let Value::Attribute {
         data,
@@ -1996,6 +2211,15 @@ macro_rules! from_vec_from_redis_value {
                     _ => invalid_type_error!(v, "Response type not vector compatible."),
                 }
             }
+            #[cfg(feature = "bytes")]
+            fn from_bytes_value(v: BytesValue) -> RedisResult<$Type> {
+                match v {
+                    BytesValue::Array(items) | BytesValue::Set(items) => {
+                        FromRedisValue::from_bytes_values(items).map($convert)
+                    }
+                    v => FromRedisValue::from_owned_redis_value(v.into()),
+                }
+            }
         }
     };
 }
@@ -2387,6 +2611,18 @@ impl<T: FromRedisValue> FromRedisValue for Option<T> {
         }
         Ok(Some(from_owned_redis_value(v)?))
     }
+    #[cfg(feature = "bytes")]
+    fn from_bytes_value(v: BytesValue) -> RedisResult<Option<T>> {
+        let v = get_bytes_inner_value(v);
+        if v == BytesValue::Nil {
+            return Ok(None);
+        }
+        Ok(Some(from_bytes_value(v)?))
+    }
+    #[cfg(feature = "bytes")]
+    fn from_bytes_values(items: Vec<BytesValue>) -> RedisResult<Vec<Option<T>>> {
+        items.into_iter().map(Self::from_bytes_value).collect()
+    }
 }
 
 #[cfg(feature = "bytes")]
@@ -2405,6 +2641,15 @@ impl FromRedisValue for bytes::Bytes {
             _ => invalid_type_error!(v, "Not a bulk string"),
         }
     }
+    fn from_bytes_value(v: BytesValue) -> RedisResult<Self> {
+        match get_bytes_inner_value(v) {
+            BytesValue::BulkString(bytes) => Ok(bytes),
+            v => invalid_type_error!(v, "Not a bulk string"),
+        }
+    }
+    fn from_bytes_values(items: Vec<BytesValue>) -> RedisResult<Vec<Self>> {
+        items.into_iter().map(Self::from_bytes_value).collect()
+    }
 }
 
 #[cfg(feature = "uuid")]
@@ -2439,6 +2684,14 @@ pub fn from_owned_redis_value<T: FromRedisValue>(v: Value) -> RedisResult<T> {
     FromRedisValue::from_owned_redis_value(v)
 }
 
+/// A shortcut function to invoke `FromRedisValue::from_bytes_value`
+/// to make the API slightly nicer.
+#[cfg(feature = "bytes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+pub fn from_bytes_value<T: FromRedisValue>(v: BytesValue) -> RedisResult<T> {
+    FromRedisValue::from_bytes_value(v)
+}
+
 /// Enum representing the communication protocol with the server. This enum represents the types
 /// of data that the server can send to the client, and the capabilities that the client can use.
 #[derive(Clone, Eq, PartialEq, Default, Debug, Copy)]
@@ -1,6 +1,6 @@
 use redis::{
     cluster::{self, ClusterClient, ClusterClientBuilder},
-    ErrorKind, FromRedisValue, RedisError,
+    parse_redis_value, ErrorKind, FromRedisValue, RedisError,
 };
 
 use std::{
@@ -35,6 +35,9 @@ pub struct MockConnectionBehavior {
     pub connection_id_provider: AtomicUsize,
     pub returned_ip_type: ConnectionIPReturnType,
     pub return_connection_err: ShouldReturnConnectionError,
+    /// Whether the handler answers `CLUSTER SHARDS`. Otherwise, the connections reply to it with
+    /// an error, like servers older than Redis 7, so that the client falls back to `CLUSTER SLOTS`.
+    pub supports_cluster_shards: bool,
 }
 
 impl MockConnectionBehavior {
@@ -45,6 +48,7 @@ impl MockConnectionBehavior {
             connection_id_provider: AtomicUsize::new(0),
             returned_ip_type: ConnectionIPReturnType::default(),
             return_connection_err: ShouldReturnConnectionError::default(),
+            supports_cluster_shards: false,
         }
     }
 
@@ -54,8 +58,30 @@ impl MockConnectionBehavior {
         RemoveHandler(vec![id.to_string()])
     }
 
+    fn should_return_connection_err(&self) -> bool {
+        match &self.return_connection_err {
+            ShouldReturnConnectionError::No => false,
+            ShouldReturnConnectionError::Yes => true,
+            // raise an error on each odd number
+            ShouldReturnConnectionError::OnOddIdx(curr_idx) => {
+                curr_idx.fetch_add(1, Ordering::SeqCst) % 2 != 0
+            }
+        }
+    }
+
     fn get_handler(&self) -> Handler {
-        self.handler.clone()
+        if self.supports_cluster_shards {
+            return self.handler.clone();
+        }
+        let handler = self.handler.clone();
+        Arc::new(move |cmd, port| {
+            if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SHARDS") {
+                return Err(parse_redis_value(
+                    b"-ERR unknown subcommand 'SHARDS'. Try CLUSTER HELP.\r\n",
+                ));
+            }
+            handler(cmd, port)
+        })
     }
 }
 
@@ -85,6 +111,7 @@ pub fn get_mock_connection_with_port(name: &str, id: usize, port: u16) -> MockCo
         id,
         handler: get_mock_connection_handler(name),
         port,
+        asking: false,
     }
 }
 
@@ -123,6 +150,8 @@ pub struct MockConnection {
     pub id: usize,
     pub handler: Handler,
     pub port: u16,
+    // Set while the connection passes `ASKING` to the handler before every request.
+    pub asking: bool,
 }
 
 #[cfg(feature = "cluster-async")]
@@ -150,15 +179,8 @@ impl cluster_async::Connect for MockConnection {
             std::io::ErrorKind::ConnectionReset,
             "mock-io-error",
         ))));
-        match &conn_utils.return_connection_err {
-            ShouldReturnConnectionError::No => {}
-            ShouldReturnConnectionError::Yes => return conn_err,
-            ShouldReturnConnectionError::OnOddIdx(curr_idx) => {
-                if curr_idx.fetch_add(1, Ordering::SeqCst) % 2 != 0 {
-                    // raise an error on each odd number
-                    return conn_err;
-                }
-            }
+        if conn_utils.should_return_connection_err() {
+            return conn_err;
         }
 
         let ip = match &conn_utils.returned_ip_type {
@@ -177,10 +199,16 @@ impl cluster_async::Connect for MockConnection {
                     .fetch_add(1, Ordering::SeqCst),
                 handler: conn_utils.get_handler(),
                 port,
+                asking: false,
             },
             ip,
         )))
     }
+
+    /// Passes `ASKING` to the handler before every request, until it's turned off.
+    fn set_asking(&mut self, asking: bool) {
+        self.asking = asking;
+    }
 }
 
 impl cluster::Connect for MockConnection {
@@ -198,12 +226,19 @@ impl cluster::Connect for MockConnection {
         let conn_utils = binding
             .get(name)
             .unwrap_or_else(|| panic!("MockConnectionUtils for `{name}` were not installed"));
+        if conn_utils.should_return_connection_err() {
+            return Err(RedisError::from(std::io::Error::new(
+                std::io::ErrorKind::ConnectionReset,
+                "mock-io-error",
+            )));
+        }
         Ok(MockConnection {
             id: conn_utils
                 .connection_id_provider
                 .fetch_add(1, Ordering::SeqCst),
             handler: conn_utils.get_handler(),
             port,
+            asking: false,
         })
     }
 
@@ -335,22 +370,41 @@ pub fn respond_startup_with_replica_using_config(
     }
 }
 
+#[cfg(feature = "cluster-async")]
+impl MockConnection {
+    fn handle_async(&self, cmd: &[u8]) -> Result<(), RedisResult<Value>> {
+        if self.asking {
+            let asking = redis::cmd("ASKING").get_packed_command();
+            (self.handler)(&[&asking[..], cmd].concat(), self.port)
+        } else {
+            (self.handler)(cmd, self.port)
+        }
+    }
+}
+
 #[cfg(feature = "cluster-async")]
 impl aio::ConnectionLike for MockConnection {
     fn req_packed_command<'a>(&'a mut self, cmd: &'a redis::Cmd) -> RedisFuture<'a, Value> {
         Box::pin(future::ready(
-            (self.handler)(&cmd.get_packed_command(), self.port)
+            self.handle_async(&cmd.get_packed_command())
                 .expect_err("Handler did not specify a response"),
         ))
     }
 
     fn req_packed_commands<'a>(
         &'a mut self,
-        _pipeline: &'a redis::Pipeline,
-        _offset: usize,
-        _count: usize,
+        pipeline: &'a redis::Pipeline,
+        offset: usize,
+        count: usize,
     ) -> RedisFuture<'a, Vec<Value>> {
-        Box::pin(future::ok(vec![]))
+        let res = self
+            .handle_async(&pipeline.get_packed_pipeline())
+            .expect_err("Handler did not specify a response");
+        Box::pin(future::ready(match res {
+            Err(err) => Err(err),
+            Ok(Value::Array(results)) => Ok(results.into_iter().skip(offset).take(count).collect()),
+            Ok(_) => Err((ErrorKind::ResponseError, "non-array response").into()),
+        }))
     }
 
     fn get_db(&self) -> i64 {
@@ -440,6 +494,28 @@ impl MockEnv {
         client_builder: ClusterClientBuilder,
         id: &str,
         handler: impl Fn(&[u8], u16) -> Result<(), RedisResult<Value>> + Send + Sync + 'static,
+    ) -> Self {
+        Self::build(client_builder, id, handler, false)
+    }
+
+    /// Like [`MockEnv::new`], but `handler` is also sent `CLUSTER SHARDS` commands.
+    pub fn with_cluster_shards(
+        id: &str,
+        handler: impl Fn(&[u8], u16) -> Result<(), RedisResult<Value>> + Send + Sync + 'static,
+    ) -> Self {
+        Self::build(
+            ClusterClient::builder(vec![&*format!("redis://{id}")]),
+            id,
+            handler,
+            true,
+        )
+    }
+
+    fn build(
+        client_builder: ClusterClientBuilder,
+        id: &str,
+        handler: impl Fn(&[u8], u16) -> Result<(), RedisResult<Value>> + Send + Sync + 'static,
+        supports_cluster_shards: bool,
     ) -> Self {
         #[cfg(feature = "cluster-async")]
         let runtime = tokio::runtime::Builder::new_current_thread()
@@ -453,6 +529,9 @@ impl MockEnv {
             &id,
             Arc::new(move |cmd, port| handler(cmd, port)),
         );
+        modify_mock_connection_behavior(&id, |behavior| {
+            behavior.supports_cluster_shards = supports_cluster_shards;
+        });
         let client = client_builder.build().unwrap();
         let connection = client.get_generic_connection().unwrap();
         #[cfg(feature = "cluster-async")]
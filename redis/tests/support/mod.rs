@@ -139,6 +139,22 @@ enum ServerType {
 
 pub enum Module {
     Json,
+    Search,
+    TimeSeries,
+    Bloom,
+}
+
+impl Module {
+    fn path(&self) -> String {
+        let (name, var) = match self {
+            Module::Json => ("RedisJSON", "REDIS_RS_REDIS_JSON_PATH"),
+            Module::Search => ("RediSearch", "REDIS_RS_REDIS_SEARCH_PATH"),
+            Module::TimeSeries => ("RedisTimeSeries", "REDIS_RS_REDIS_TIMESERIES_PATH"),
+            Module::Bloom => ("RedisBloom", "REDIS_RS_REDIS_BLOOM_PATH"),
+        };
+        env::var(var)
+            .unwrap_or_else(|_| panic!("Unable to find path to {name} at {var}, is it set?"))
+    }
 }
 
 pub struct RedisServer {
@@ -254,15 +270,7 @@ impl RedisServer {
 
         // Load Redis Modules
         for module in modules {
-            match module {
-                Module::Json => {
-                    redis_cmd
-                        .arg("--loadmodule")
-                        .arg(env::var("REDIS_RS_REDIS_JSON_PATH").expect(
-                        "Unable to find path to RedisJSON at REDIS_RS_REDIS_JSON_PATH, is it set?",
-                    ));
-                }
-            };
+            redis_cmd.arg("--loadmodule").arg(module.path());
         }
 
         redis_cmd
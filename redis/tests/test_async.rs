@@ -375,6 +375,76 @@ mod basic_async {
         .unwrap();
     }
 
+    #[test]
+    fn test_transaction_async() {
+        let ctx = TestContext::new();
+        block_on_all(async move {
+            let mut con = ctx.multiplexed_async_connection().await?;
+            let other_con = ctx.multiplexed_async_connection().await?;
+            let _: () = con.set("counter", 1).await?;
+
+            let mut attempts = 0;
+            let (new_val,): (isize,) =
+                redis::aio::transaction_async(&mut con, &["counter"], |con, mut pipe| {
+                    attempts += 1;
+                    let first_attempt = attempts == 1;
+                    let mut other_con = other_con.clone();
+                    async move {
+                        let old_val: isize = con.get("counter").await?;
+                        if first_attempt {
+                            // modify the watched key, so that the transaction is aborted
+                            let _: () = other_con.incr("counter", 10).await?;
+                        }
+                        pipe.set("counter", old_val + 1)
+                            .ignore()
+                            .get("counter")
+                            .query_async(con)
+                            .await
+                    }
+                    .boxed()
+                })
+                .await?;
+
+            assert_eq!(new_val, 12);
+            assert_eq!(attempts, 2);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_transaction_async_with_max_attempts() {
+        let ctx = TestContext::new();
+        block_on_all(async move {
+            let mut con = ctx.multiplexed_async_connection().await?;
+            let other_con = ctx.multiplexed_async_connection().await?;
+
+            let mut attempts = 0;
+            let result: RedisResult<()> = redis::aio::transaction_async_with_max_attempts(
+                &mut con,
+                &["counter"],
+                std::num::NonZeroUsize::new(3).unwrap(),
+                |con, mut pipe| {
+                    attempts += 1;
+                    let mut other_con = other_con.clone();
+                    async move {
+                        let _: () = other_con.incr("counter", 1).await?;
+                        pipe.incr("counter", 1).ignore().query_async(con).await
+                    }
+                    .boxed()
+                },
+            )
+            .await;
+
+            assert_eq!(result.unwrap_err().kind(), ErrorKind::ExecAbortError);
+            assert_eq!(attempts, 3);
+            let counter: isize = con.get("counter").await?;
+            assert_eq!(counter, 3);
+            Ok(())
+        })
+        .unwrap();
+    }
+
     fn test_async_scanning(batch_size: usize) {
         let ctx = TestContext::new();
         block_on_all(async move {
@@ -593,6 +663,177 @@ mod basic_async {
         );
     }
 
+    #[tokio::test]
+    async fn test_query_bulk_string_stream() {
+        let ctx = TestContext::new();
+        let mut con = ctx.multiplexed_async_connection().await.unwrap();
+        let blob: Vec<u8> = (0..4 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        let _: () = con.set("blob", &blob).await.unwrap();
+
+        let mut stream = con
+            .query_bulk_string_stream(cmd("GET").arg("blob"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stream.len(), blob.len());
+        let mut read = Vec::new();
+        tokio::io::copy(&mut stream, &mut read).await.unwrap();
+        assert!(read == blob);
+
+        let missing = con
+            .query_bulk_string_stream(cmd("GET").arg("missing"))
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+        let err = con
+            .query_bulk_string_stream(cmd("LPUSH").arg("list").arg(1))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TypeError);
+        let _: () = con.set("after", 1).await.unwrap();
+    }
+
+    async fn read_request(server: &mut tokio::io::DuplexStream) -> Value {
+        use tokio::io::AsyncReadExt;
+
+        let mut request = Vec::new();
+        loop {
+            let mut buf = [0; 256];
+            let read = server.read(&mut buf).await.unwrap();
+            assert_ne!(read, 0, "connection closed");
+            request.extend_from_slice(&buf[..read]);
+            if let Ok(request) = redis::parse_redis_value(&request) {
+                return request;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_bulk_string_stream_yields_chunks_as_they_arrive() {
+        use tokio::io::AsyncWriteExt;
+
+        let (client, mut server) = tokio::io::duplex(1024);
+        let info = redis::IntoConnectionInfo::into_connection_info("redis://127.0.0.1/").unwrap();
+        let (mut con, driver) = MultiplexedConnection::new(&info, client).await.unwrap();
+        tokio::spawn(driver);
+
+        let request = tokio::spawn({
+            let mut con = con.clone();
+            async move { con.query_bulk_string_stream(cmd("GET").arg("blob")).await }
+        });
+        read_request(&mut server).await;
+        server.write_all(b"$10\r\n01234").await.unwrap();
+        let mut stream = request.await.unwrap().unwrap().unwrap();
+        assert_eq!(stream.len(), 10);
+        assert_eq!(stream.next().await.unwrap().unwrap(), &b"01234"[..]);
+
+        // Replies to other requests are only read once the bulk string was received.
+        let ping = tokio::spawn({
+            let mut con = con.clone();
+            async move { cmd("PING").query_async::<_, String>(&mut con).await }
+        });
+        read_request(&mut server).await;
+        server.write_all(b"56789\r\n+PONG\r\n").await.unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap(), &b"56789"[..]);
+        assert!(stream.next().await.is_none());
+        assert_eq!(ping.await.unwrap().unwrap(), "PONG");
+
+        // The rest of a dropped stream is discarded.
+        let request = tokio::spawn({
+            let mut con = con.clone();
+            async move { con.query_bulk_string_stream(cmd("GET").arg("blob")).await }
+        });
+        read_request(&mut server).await;
+        server.write_all(b"$6\r\nabc").await.unwrap();
+        drop(request.await.unwrap().unwrap().unwrap());
+        let ping = tokio::spawn({
+            let mut con = con.clone();
+            async move { cmd("PING").query_async::<_, String>(&mut con).await }
+        });
+        read_request(&mut server).await;
+        server.write_all(b"def\r\n+PONG\r\n").await.unwrap();
+        assert_eq!(ping.await.unwrap().unwrap(), "PONG");
+
+        // Replies that are not bulk strings are not streamed.
+        for (reply, expected) in [
+            (&b"$-1\r\n"[..], Ok(false)),
+            (b"-ERR nope\r\n", Err(ErrorKind::ResponseError)),
+            (b":1\r\n", Err(ErrorKind::TypeError)),
+        ] {
+            let request = tokio::spawn({
+                let mut con = con.clone();
+                async move { con.query_bulk_string_stream(cmd("GET").arg("blob")).await }
+            });
+            read_request(&mut server).await;
+            server.write_all(reply).await.unwrap();
+            let result = request.await.unwrap();
+            assert_eq!(
+                result
+                    .map(|stream| stream.is_some())
+                    .map_err(|err| err.kind()),
+                expected
+            );
+        }
+
+        // A stream that is cut off ends with an error.
+        let request = tokio::spawn(async move {
+            let mut stream = con
+                .query_bulk_string_stream(cmd("GET").arg("blob"))
+                .await?
+                .unwrap();
+            let mut read = Vec::new();
+            tokio::io::copy(&mut stream, &mut read).await?;
+            Ok::<_, redis::RedisError>(read)
+        });
+        read_request(&mut server).await;
+        server.write_all(b"$6\r\nabc").await.unwrap();
+        drop(server);
+        assert!(request.await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_bytes_value() {
+        use redis::BytesValue;
+        use tokio::io::AsyncWriteExt;
+
+        let (client, mut server) = tokio::io::duplex(1024);
+        let info = redis::IntoConnectionInfo::into_connection_info("redis://127.0.0.1/").unwrap();
+        let (mut con, driver) = MultiplexedConnection::new(&info, client).await.unwrap();
+        tokio::spawn(driver);
+
+        let request = tokio::spawn({
+            let mut con = con.clone();
+            async move { con.query_bytes_value(cmd("MGET").arg("a").arg("b")).await }
+        });
+        read_request(&mut server).await;
+        server.write_all(b"*2\r\n$5\r\nhel").await.unwrap();
+        let ping = tokio::spawn({
+            let mut con = con.clone();
+            async move { cmd("PING").query_async::<_, String>(&mut con).await }
+        });
+        read_request(&mut server).await;
+        server.write_all(b"lo\r\n$-1\r\n+PONG\r\n").await.unwrap();
+        assert_eq!(ping.await.unwrap().unwrap(), "PONG");
+        let value = request.await.unwrap().unwrap();
+        assert_eq!(
+            value,
+            BytesValue::Array(vec![
+                BytesValue::BulkString(bytes::Bytes::from_static(b"hello")),
+                BytesValue::Nil,
+            ])
+        );
+        let value: Vec<Option<String>> = redis::from_bytes_value(value).unwrap();
+        assert_eq!(value, vec![Some("hello".to_string()), None]);
+
+        let request = tokio::spawn(async move { con.query_bytes_value(&cmd("GET")).await });
+        read_request(&mut server).await;
+        server.write_all(b"-ERR nope\r\n").await.unwrap();
+        assert_eq!(
+            request.await.unwrap().unwrap_err().kind(),
+            ErrorKind::ResponseError
+        );
+    }
+
     // Test issue of Stream trait blocking if we try to iterate more than 10 items
     // https://github.com/mitsuhiko/redis-rs/issues/537 and https://github.com/mitsuhiko/redis-rs/issues/583
     #[tokio::test]
@@ -949,6 +1190,67 @@ mod basic_async {
         .unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "connection-manager")]
+    fn test_connection_manager_instrumentation() {
+        use redis::instrumentation::{Instrumentation, RequestEvent, RequestStartEvent};
+        use std::sync::{Arc, Mutex};
+
+        // The command name, number of commands and error kind of each request.
+        type RecordedRequest = (String, usize, Option<ErrorKind>);
+
+        #[derive(Default)]
+        struct RecordedRequests {
+            started: Mutex<Vec<String>>,
+            finished: Mutex<Vec<RecordedRequest>>,
+        }
+
+        impl Instrumentation for RecordedRequests {
+            fn on_request_start(&self, event: &RequestStartEvent<'_>) {
+                self.started.lock().unwrap().push(event.command.to_string());
+            }
+
+            fn on_request(&self, event: &RequestEvent<'_>) {
+                self.finished.lock().unwrap().push((
+                    event.command.to_string(),
+                    event.commands,
+                    event.error.map(|err| err.kind()),
+                ));
+            }
+        }
+
+        let ctx = TestContext::new();
+        let recorded = Arc::new(RecordedRequests::default());
+        let client = ctx.client.clone().with_instrumentation(recorded.clone());
+        block_on_all(async move {
+            let mut manager = redis::aio::ConnectionManager::new(client).await.unwrap();
+            let _: () = manager.set("key", "value").await.unwrap();
+            let _: (String, String) = pipe()
+                .get("key")
+                .get("key")
+                .query_async(&mut manager)
+                .await
+                .unwrap();
+            let result: RedisResult<i64> = manager.incr("key", 1).await;
+            assert!(result.is_err());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            *recorded.started.lock().unwrap(),
+            vec!["SET", "PIPELINE", "INCRBY"]
+        );
+        assert_eq!(
+            *recorded.finished.lock().unwrap(),
+            vec![
+                ("SET".to_string(), 1, None),
+                ("PIPELINE".to_string(), 2, None),
+                ("INCRBY".to_string(), 1, Some(ErrorKind::ResponseError)),
+            ]
+        );
+    }
+
     #[cfg(feature = "tls-rustls")]
     mod mtls_test {
         use super::*;
@@ -1097,4 +1399,194 @@ mod basic_async {
         })
         .unwrap();
     }
+
+    #[test]
+    fn test_cached_connection_invalidates_modified_keys() {
+        use redis::aio::{CacheConfig, CachedConnection};
+        use redis::ProtocolVersion;
+
+        let ctx = TestContext::new();
+        if ctx.protocol == ProtocolVersion::RESP2 {
+            return;
+        }
+
+        block_on_all(async move {
+            let con = ctx.multiplexed_async_connection().await?;
+            let mut con = CachedConnection::new(con, CacheConfig::default()).await?;
+            let _: () = con.set("key", 1).await?;
+
+            let value: i32 = con.get("key").await?;
+            assert_eq!(value, 1);
+            let value: i32 = con.get("key").await?;
+            assert_eq!(value, 1);
+            assert_eq!(con.statistics().hits, 1);
+            assert_eq!(con.statistics().misses, 1);
+
+            // the server sends the invalidation before the reply to the write.
+            let _: () = con.set("key", 2).await?;
+            let value: i32 = con.get("key").await?;
+            assert_eq!(value, 2);
+            assert_eq!(con.statistics().invalidations, 1);
+            assert_eq!(con.statistics().misses, 2);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_cached_connection_requires_resp3() {
+        use redis::aio::{CacheConfig, CachedConnection};
+        use redis::ProtocolVersion;
+
+        let ctx = TestContext::new();
+        if ctx.protocol != ProtocolVersion::RESP2 {
+            return;
+        }
+
+        block_on_all(async move {
+            let con = ctx.multiplexed_async_connection().await?;
+            let err = CachedConnection::new(con, CacheConfig::default())
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), ErrorKind::InvalidClientConfig);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_connection_pool_reuses_returned_connections() {
+        use redis::aio::{ConnectionPoolConfig, PoolState};
+
+        let ctx = TestContext::new();
+        block_on_all(async move {
+            let pool = ctx
+                .client
+                .get_connection_pool(ConnectionPoolConfig::default().min_idle(1).max_size(2))
+                .await?;
+            assert_eq!(
+                pool.state(),
+                PoolState {
+                    connections: 1,
+                    idle_connections: 1
+                }
+            );
+
+            let mut first = pool.get().await?;
+            let mut second = pool.get().await?;
+            let first_id: i64 = cmd("CLIENT").arg("ID").query_async(&mut first).await?;
+            let second_id: i64 = cmd("CLIENT").arg("ID").query_async(&mut second).await?;
+            assert_ne!(first_id, second_id);
+            assert_eq!(
+                pool.state(),
+                PoolState {
+                    connections: 2,
+                    idle_connections: 0
+                }
+            );
+
+            drop(second);
+            let mut third = pool.get().await?;
+            let third_id: i64 = cmd("CLIENT").arg("ID").query_async(&mut third).await?;
+            assert_eq!(third_id, second_id);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_connection_pool_closes_expired_connections_of_an_unused_pool() {
+        use redis::aio::ConnectionPoolConfig;
+
+        let ctx = TestContext::new();
+        block_on_all(async move {
+            let pool = ctx
+                .client
+                .get_connection_pool(
+                    ConnectionPoolConfig::default()
+                        .idle_timeout(std::time::Duration::from_millis(100)),
+                )
+                .await?;
+            let mut pooled = pool.get().await?;
+            let pooled_id: i64 = cmd("CLIENT").arg("ID").query_async(&mut pooled).await?;
+            drop(pooled);
+
+            // The pool isn't used, so only the background check can close the connection.
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            let mut con = ctx.async_connection().await?;
+            let clients: String = cmd("CLIENT")
+                .arg("LIST")
+                .arg("ID")
+                .arg(pooled_id)
+                .query_async(&mut con)
+                .await?;
+            assert_eq!(clients, "");
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_connection_pool_drops_connection_with_cancelled_request() {
+        use redis::aio::{ConnectionPoolConfig, PoolState};
+
+        let ctx = TestContext::new();
+        block_on_all(async move {
+            let pool = ctx
+                .client
+                .get_connection_pool(ConnectionPoolConfig::default().max_size(1))
+                .await?;
+            let mut con = pool.get().await?;
+            {
+                let mut blpop = Box::pin(con.blpop::<_, Option<(String, String)>>("queue", 1.0));
+                assert!(futures::poll!(&mut blpop).is_pending());
+            }
+            drop(con);
+            assert_eq!(
+                pool.state(),
+                PoolState {
+                    connections: 0,
+                    idle_connections: 0
+                }
+            );
+
+            let mut con = pool.get().await?;
+            let _: () = con.set("key", 1).await?;
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_connection_pool_checkout_times_out_while_connections_are_blocked() {
+        use redis::aio::ConnectionPoolConfig;
+
+        let ctx = TestContext::new();
+        block_on_all(async move {
+            let pool = ctx
+                .client
+                .get_connection_pool(
+                    ConnectionPoolConfig::default()
+                        .max_size(1)
+                        .checkout_timeout(std::time::Duration::from_millis(100)),
+                )
+                .await?;
+            let mut blocked = pool.get().await?;
+            let blpop = async move {
+                let result: Option<(String, String)> = blocked.blpop("queue", 0.5).await?;
+                RedisResult::Ok(result)
+            };
+            let checkout = pool.get();
+            let (blpop, checkout) = futures::join!(blpop, checkout);
+            assert_eq!(blpop?, None);
+            assert!(checkout.err().unwrap().is_timeout());
+
+            // the connection was returned once BLPOP timed out.
+            let mut con = pool.get().await?;
+            let _: () = con.set("key", 1).await?;
+            Ok(())
+        })
+        .unwrap();
+    }
 }
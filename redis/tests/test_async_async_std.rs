@@ -325,3 +325,26 @@ fn test_script_returning_complex_type() {
     })
     .unwrap();
 }
+
+#[test]
+fn test_connection_pool() {
+    let ctx = TestContext::new();
+    block_on_all_using_async_std(async move {
+        let pool = ctx
+            .client
+            .get_connection_pool(redis::aio::ConnectionPoolConfig::default().max_size(2))
+            .await?;
+        let mut con = pool.get().await?;
+        redis::cmd("SET")
+            .arg("key")
+            .arg(b"foo")
+            .query_async::<_, ()>(&mut con)
+            .await?;
+        drop(con);
+        let mut con = pool.get().await?;
+        let result: String = redis::cmd("GET").arg("key").query_async(&mut con).await?;
+        assert_eq!(result, "foo");
+        Ok::<_, redis::RedisError>(())
+    })
+    .unwrap();
+}
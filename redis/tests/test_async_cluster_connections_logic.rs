@@ -215,6 +215,7 @@ mod test_connect_and_check {
             id: user_conn_id,
             handler: get_mock_connection_handler(name),
             port: 6379,
+            asking: false,
         };
         let node = AsyncClusterNode::new(async { user_conn }.boxed().shared(), None, Some(ip));
 
@@ -253,6 +254,7 @@ mod test_connect_and_check {
             id: user_conn_id,
             handler: get_mock_connection_handler(name),
             port: 6379,
+            asking: false,
         };
         let prev_ip = IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1));
         let node = AsyncClusterNode::new(async { user_conn }.boxed().shared(), None, Some(prev_ip));
@@ -295,6 +297,7 @@ mod test_connect_and_check {
             id: user_conn_id,
             handler: get_mock_connection_handler(name),
             port: 6379,
+            asking: false,
         };
         let prev_ip = Some(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)));
         let node = AsyncClusterNode::new(async { user_conn }.boxed().shared(), None, prev_ip);
@@ -338,11 +341,13 @@ mod test_connect_and_check {
             id: old_user_conn_id,
             handler: get_mock_connection_handler(name),
             port: 6379,
+            asking: false,
         };
         let management_conn = MockConnection {
             id: management_conn_id,
             handler: get_mock_connection_handler(name),
             port: 6379,
+            asking: false,
         };
 
         let node = AsyncClusterNode::new(
@@ -392,11 +397,13 @@ mod test_connect_and_check {
             id: old_user_conn_id,
             handler: get_mock_connection_handler(name),
             port: 6379,
+            asking: false,
         };
         let management_conn = MockConnection {
             id: management_conn_id,
             handler: get_mock_connection_handler(name),
             port: 6379,
+            asking: false,
         };
         let prev_ip = Some(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)));
         let node = AsyncClusterNode::new(
@@ -881,6 +881,55 @@ mod basic {
         assert_eq!(hash, Ok(script.get_hash().to_string()));
     }
 
+    #[test]
+    #[cfg(feature = "script")]
+    fn test_function() {
+        let ctx = TestContext::new();
+        if ctx.get_version().0 < 7 {
+            return;
+        }
+        let mut con = ctx.connection();
+
+        let library = redis::Library::new(
+            r"#!lua name=test_function_lib
+    redis.register_function('get_with_arg', function(keys, args)
+        return {redis.call('GET', keys[1]), args[1]}
+    end)
+    redis.register_function{
+        function_name='echo_ro',
+        callback=function(keys, args) return args[1] end,
+        flags={'no-writes'}
+    }
+    ",
+        );
+
+        let _: () = redis::cmd("SET")
+            .arg("my_key")
+            .arg("foo")
+            .query(&mut con)
+            .unwrap();
+        // the library is loaded by the first invocation.
+        let response = library
+            .function("get_with_arg")
+            .key("my_key")
+            .arg(42)
+            .invoke(&mut con);
+        assert_eq!(response, Ok(("foo".to_string(), 42)));
+
+        let response: RedisResult<String> = library
+            .function("echo_ro")
+            .read_only()
+            .arg("bar")
+            .invoke(&mut con);
+        assert_eq!(response, Ok("bar".to_string()));
+
+        // reloading replaces the existing library.
+        assert_eq!(
+            library.load(&mut con, true),
+            Ok("test_function_lib".to_string())
+        );
+    }
+
     #[test]
     fn test_tuple_args() {
         let ctx = TestContext::new();
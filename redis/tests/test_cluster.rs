@@ -4,15 +4,18 @@ mod support;
 #[cfg(test)]
 mod cluster {
     use std::sync::{
-        atomic::{self, AtomicI32, Ordering},
+        atomic::{self, AtomicBool, AtomicI32, Ordering},
         Arc,
     };
 
     use crate::support::*;
     use redis::{
-        cluster::{cluster_pipe, ClusterClient},
-        cmd, parse_redis_value, Commands, ConnectionLike, ErrorKind, ProtocolVersion, RedisError,
-        Value,
+        cluster::{cluster_pipe, ClusterClient, ClusterScanCursor, NodeAddress},
+        cluster_topology::{ClusterInfo, ClusterNodes, ClusterShard},
+        cmd,
+        instrumentation::{Instrumentation, RequestEvent},
+        parse_redis_value, Commands, ConnectionLike, ErrorKind, ProtocolVersion, RedisError,
+        RedisResult, ScanOptions, Value,
     };
 
     #[test]
@@ -34,6 +37,82 @@ mod cluster {
         );
     }
 
+    #[test]
+    fn test_cluster_topology_snapshot() {
+        let cluster = TestClusterContext::new(3, 1);
+        let con = cluster.connection();
+
+        let topology = con.topology();
+        assert_eq!(topology.primaries().count(), 3);
+        assert_eq!(topology.replicas().count(), 3);
+        let slots: u32 = topology
+            .slot_ranges
+            .iter()
+            .map(|range| (range.end - range.start) as u32 + 1)
+            .sum();
+        assert_eq!(slots, 16384);
+        for primary in topology.primaries() {
+            assert_eq!(primary.replicas.len(), 1);
+            let replica = topology.node(&primary.replicas[0]).unwrap();
+            assert_eq!(replica.primary.as_deref(), Some(primary.address.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_cluster_nodes_shards_and_info_replies() {
+        let cluster = TestClusterContext::new(3, 1);
+        let mut con = cluster.connection();
+
+        let nodes: ClusterNodes = cmd("CLUSTER").arg("NODES").query(&mut con).unwrap();
+        assert_eq!(nodes.nodes.len(), 6);
+        assert!(nodes.myself().is_some());
+        let covered: u32 = nodes
+            .nodes
+            .iter()
+            .flat_map(|node| node.slots.iter())
+            .map(|(start, end)| (end - start) as u32 + 1)
+            .sum();
+        assert_eq!(covered, 16384);
+
+        let info: ClusterInfo = cmd("CLUSTER").arg("INFO").query(&mut con).unwrap();
+        assert!(info.state_ok);
+        assert_eq!(info.slots_assigned, 16384);
+        assert_eq!(info.known_nodes, 6);
+        assert_eq!(info.size, 3);
+
+        // CLUSTER SHARDS is only available from Redis 7.
+        let shards: RedisResult<Vec<ClusterShard>> = cmd("CLUSTER").arg("SHARDS").query(&mut con);
+        if let Ok(shards) = shards {
+            assert_eq!(shards.len(), 3);
+            assert!(shards
+                .iter()
+                .all(|shard| shard.primary().is_some() && shard.replicas().count() == 1));
+        }
+    }
+
+    #[test]
+    fn test_cluster_scan_iter() {
+        let cluster = TestClusterContext::new(3, 0);
+        let mut con = cluster.connection();
+
+        let mut expected = Vec::new();
+        for i in 0..100 {
+            let key = format!("key{i}");
+            let _: () = con.set(&key, i).unwrap();
+            expected.push(key);
+        }
+        let _: () = con.hset("hash", "field", "value").unwrap();
+
+        let mut keys: Vec<String> = con
+            .cluster_scan_iter(ScanOptions::default().pattern("key*").object_type("string"))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        expected.sort();
+
+        assert_eq!(keys, expected);
+    }
+
     #[test]
     fn test_cluster_with_username_and_password() {
         let cluster = TestClusterContext::new_with_cluster_client_builder(
@@ -443,6 +522,55 @@ mod cluster {
         assert_eq!(value, Ok(Some(123)));
     }
 
+    #[test]
+    fn test_cluster_instrumentation_reports_retries() {
+        // The command name, node, retries and whether the request failed.
+        type Request = (String, Option<String>, u32, bool);
+
+        struct RecordedRequests(std::sync::Mutex<Vec<Request>>);
+
+        impl Instrumentation for RecordedRequests {
+            fn on_request(&self, event: &RequestEvent<'_>) {
+                self.0.lock().unwrap().push((
+                    event.command.to_string(),
+                    event.node.map(str::to_string),
+                    event.retries,
+                    event.error.is_some(),
+                ));
+            }
+        }
+
+        let name = "instrumentation_retries";
+        let recorded = Arc::new(RecordedRequests(Default::default()));
+        let requests = atomic::AtomicUsize::new(0);
+        let MockEnv {
+            mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .retries(5)
+                .instrumentation(recorded.clone()),
+            name,
+            move |cmd: &[u8], _| {
+                respond_startup(name, cmd)?;
+
+                match requests.fetch_add(1, atomic::Ordering::SeqCst) {
+                    0..=1 => Err(parse_redis_value(b"-TRYAGAIN mock\r\n")),
+                    _ => Err(Ok(Value::BulkString(b"123".to_vec()))),
+                }
+            },
+        );
+
+        let value = cmd("GET").arg("test").query::<Option<i32>>(&mut connection);
+
+        assert_eq!(value, Ok(Some(123)));
+        assert_eq!(
+            *recorded.0.lock().unwrap(),
+            vec![("GET".to_string(), Some(format!("{name}:6379")), 2, false)]
+        );
+    }
+
     #[test]
     fn test_cluster_exhaust_retries() {
         let name = "tryagain_exhaust_retries";
@@ -579,6 +707,124 @@ mod cluster {
         assert_eq!(value, Ok(Some(123)));
     }
 
+    #[test]
+    fn test_cluster_node_address_mapper() {
+        let name = "node_address_mapper";
+        let moved = AtomicBool::new(false);
+        let MockEnv {
+            mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")]).node_address_mapper(
+                move |host, port| (host == "internal").then(|| NodeAddress::new(name, port - 1000)),
+            ),
+            name,
+            move |cmd: &[u8], port| {
+                if contains_slice(cmd, b"PING") || contains_slice(cmd, b"SETNAME") {
+                    return Err(Ok(Value::SimpleString("OK".into())));
+                }
+                if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SLOTS") {
+                    return Err(Ok(create_topology_from_config(
+                        "internal",
+                        vec![
+                            MockSlotRange {
+                                primary_port: 7379,
+                                replica_ports: vec![],
+                                slot_range: (0..8191),
+                            },
+                            MockSlotRange {
+                                primary_port: 7380,
+                                replica_ports: vec![],
+                                slot_range: (8192..16383),
+                            },
+                        ],
+                    )));
+                }
+                if !moved.swap(true, Ordering::SeqCst) {
+                    assert_eq!(port, 6379);
+                    return Err(parse_redis_value(b"-MOVED 6918 internal:7380\r\n"));
+                }
+                assert_eq!(port, 6380);
+                Err(Ok(Value::BulkString(b"123".to_vec())))
+            },
+        );
+
+        let value = cmd("GET").arg("test").query::<Option<i32>>(&mut connection);
+
+        assert_eq!(value, Ok(Some(123)));
+        // Nodes are still identified by the addresses the cluster announces.
+        let topology = connection.topology();
+        assert!(topology.node("internal:7379").is_some());
+        assert!(topology.node("internal:7380").is_some());
+    }
+
+    #[test]
+    fn test_cluster_rediscovers_replaced_cluster_through_initial_nodes() {
+        let name = "rediscovers_replaced_cluster";
+        let old_name = "rediscovers_replaced_cluster_old";
+        let replaced = Arc::new(AtomicBool::new(false));
+        let replaced_clone = replaced.clone();
+        let _old_handler = MockConnectionBehavior::register_new(
+            old_name,
+            Arc::new(move |cmd, _| {
+                if replaced_clone.load(Ordering::SeqCst) {
+                    // The nodes of the old cluster are gone.
+                    return Err(Err(RedisError::from(std::io::Error::new(
+                        std::io::ErrorKind::ConnectionReset,
+                        "mock-io-error",
+                    ))));
+                }
+                respond_startup(old_name, cmd)?;
+                Err(Ok(Value::BulkString(b"456".to_vec())))
+            }),
+        );
+        let replaced_clone = replaced.clone();
+        let MockEnv {
+            mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::new(name, move |cmd: &[u8], port| {
+            if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SLOTS") {
+                // The initial node always answers with the current cluster.
+                let host = if replaced_clone.load(Ordering::SeqCst) {
+                    name
+                } else {
+                    old_name
+                };
+                return Err(Ok(create_topology_from_config(
+                    host,
+                    vec![MockSlotRange {
+                        primary_port: 6380,
+                        replica_ports: vec![],
+                        slot_range: (0..16383),
+                    }],
+                )));
+            }
+            respond_startup(name, cmd)?;
+            assert_eq!(port, 6380);
+            Err(Ok(Value::BulkString(b"123".to_vec())))
+        });
+        assert_eq!(
+            cmd("GET").arg("test").query::<Option<i32>>(&mut connection),
+            Ok(Some(456))
+        );
+
+        // Replace the whole cluster. The old nodes can't be reached anymore.
+        replaced.store(true, Ordering::SeqCst);
+        modify_mock_connection_behavior(old_name, |behavior| {
+            behavior.return_connection_err = ShouldReturnConnectionError::Yes;
+        });
+
+        let value = cmd("GET").arg("test").query::<Option<i32>>(&mut connection);
+
+        assert_eq!(value, Ok(Some(123)));
+        assert!(connection
+            .topology()
+            .node(&format!("{name}:6380"))
+            .is_some());
+    }
+
     #[test]
     fn test_cluster_ask_error_when_new_node_is_added() {
         let name = "ask_with_extra_nodes";
@@ -1039,6 +1285,115 @@ mod cluster {
         assert!(res.is_ok());
     }
 
+    fn scan_reply(cursor: &str, keys: &[&str]) -> RedisResult<Value> {
+        Ok(Value::Array(vec![
+            Value::BulkString(cursor.as_bytes().to_vec()),
+            Value::Array(
+                keys.iter()
+                    .map(|key| Value::BulkString(key.as_bytes().to_vec()))
+                    .collect(),
+            ),
+        ]))
+    }
+
+    #[test]
+    fn test_cluster_scan_iter_covers_all_primaries() {
+        let name = "test_cluster_scan_iter_covers_all_primaries";
+        let MockEnv {
+            mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::new(name, move |received_cmd: &[u8], port| {
+            respond_startup_two_nodes(name, received_cmd)?;
+            assert!(contains_slice(received_cmd, b"SCAN"));
+            assert!(contains_slice(received_cmd, b"MATCH"));
+            let continued = contains_slice(received_cmd, b"$1\r\n5\r\n");
+            match (port, continued) {
+                (6379, false) => Err(scan_reply("5", &["a"])),
+                (6379, true) => Err(scan_reply("0", &["b"])),
+                (6380, false) => Err(scan_reply("0", &["c"])),
+                _ => panic!("unexpected SCAN on port {port}"),
+            }
+        });
+
+        let keys: Vec<String> = connection
+            .cluster_scan_iter(ScanOptions::default().pattern("*"))
+            .collect();
+
+        assert_eq!(keys, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_cluster_scan_can_resume_after_error() {
+        let name = "test_cluster_scan_can_resume_after_error";
+        let failed = Arc::new(AtomicBool::new(false));
+        let MockEnv {
+            mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")]).retries(0),
+            name,
+            {
+                let failed = failed.clone();
+                move |received_cmd: &[u8], port| {
+                    respond_startup_two_nodes(name, received_cmd)?;
+                    match port {
+                        6379 => Err(scan_reply("0", &["a"])),
+                        _ if !failed.swap(true, Ordering::SeqCst) => {
+                            Err(parse_redis_value(b"-TRYAGAIN mock\r\n"))
+                        }
+                        _ => Err(scan_reply("0", &["c"])),
+                    }
+                }
+            },
+        );
+
+        let options = ScanOptions::default();
+        let (cursor, keys) = connection
+            .cluster_scan::<String>(&ClusterScanCursor::default(), &options)
+            .unwrap();
+        assert_eq!(keys, vec!["a"]);
+        assert!(!cursor.is_finished());
+
+        let err = connection
+            .cluster_scan::<String>(&cursor, &options)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TryAgain);
+
+        let (cursor, keys) = connection
+            .cluster_scan::<String>(&cursor, &options)
+            .unwrap();
+        assert_eq!(keys, vec!["c"]);
+        assert!(cursor.is_finished());
+    }
+
+    #[test]
+    fn test_cluster_scan_iter_reports_the_error_that_ended_it() {
+        let name = "test_cluster_scan_iter_reports_the_error_that_ended_it";
+        let MockEnv {
+            mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")]).retries(0),
+            name,
+            move |received_cmd: &[u8], port| {
+                respond_startup_two_nodes(name, received_cmd)?;
+                match port {
+                    6379 => Err(scan_reply("0", &["a"])),
+                    _ => Err(parse_redis_value(b"-TRYAGAIN mock\r\n")),
+                }
+            },
+        );
+
+        let mut iter = connection.cluster_scan_iter::<String>(ScanOptions::default());
+        let keys: Vec<String> = iter.by_ref().collect();
+        assert_eq!(keys, vec!["a"]);
+        assert_eq!(iter.take_error().unwrap().kind(), ErrorKind::TryAgain);
+        assert!(iter.next().is_none());
+    }
+
     #[cfg(feature = "tls-rustls")]
     mod mtls_test {
         use super::*;
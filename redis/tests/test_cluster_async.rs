@@ -9,7 +9,7 @@ mod cluster_async {
         str::from_utf8,
         sync::{
             atomic::{self, AtomicBool, AtomicI32, AtomicU16, AtomicU32, Ordering},
-            Arc,
+            Arc, Mutex,
         },
         time::Duration,
     };
@@ -20,15 +20,17 @@ mod cluster_async {
 
     use redis::{
         aio::{ConnectionLike, MultiplexedConnection},
-        cluster::ClusterClient,
+        cluster::{ClusterClient, NodeAddress, ReadFromReplicaStrategy},
         cluster_async::{testing::MANAGEMENT_CONN_NAME, ClusterConnection, Connect},
         cluster_routing::{
             MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
         },
-        cluster_topology::DEFAULT_NUMBER_OF_REFRESH_SLOTS_RETRIES,
-        cmd, from_owned_redis_value, parse_redis_value, AsyncCommands, Cmd, ErrorKind,
-        FromRedisValue, InfoDict, IntoConnectionInfo, ProtocolVersion, RedisError, RedisFuture,
-        RedisResult, Script, Value,
+        cluster_topology::{NodeRole, TopologyEvent, DEFAULT_NUMBER_OF_REFRESH_SLOTS_RETRIES},
+        cmd, from_owned_redis_value,
+        instrumentation::{Instrumentation, RequestEvent},
+        parse_redis_value, AsyncCommands, Cmd, ErrorKind, FromRedisValue, InfoDict,
+        IntoConnectionInfo, ProtocolVersion, RedisError, RedisFuture, RedisResult, ScanOptions,
+        Script, Value,
     };
 
     use crate::support::*;
@@ -55,6 +57,24 @@ mod cluster_async {
         .unwrap();
     }
 
+    #[test]
+    fn test_async_cluster_topology_snapshot() {
+        let cluster = TestClusterContext::new(3, 1);
+
+        block_on_all(async move {
+            let connection = cluster.async_connection().await;
+            let topology = connection.topology().await?;
+            assert_eq!(topology.primaries().count(), 3);
+            assert_eq!(topology.replicas().count(), 3);
+            assert!(topology.nodes.iter().all(|node| node.connected));
+            let range = topology.slot_range_for_key(b"foo").unwrap();
+            let primary = topology.node(&range.primary).unwrap();
+            assert!(primary.slot_ranges.contains(&(range.start, range.end)));
+            Ok::<_, RedisError>(())
+        })
+        .unwrap();
+    }
+
     #[test]
     fn test_async_cluster_basic_eval() {
         let cluster = TestClusterContext::new(3, 0);
@@ -305,6 +325,33 @@ mod cluster_async {
         .unwrap()
     }
 
+    #[test]
+    fn test_async_cluster_transaction() {
+        let cluster = TestClusterContext::new(3, 0);
+
+        block_on_all(async move {
+            let mut connection = cluster.async_connection().await;
+            let _: () = connection.set("{counter}a", 1).await?;
+            let (a, b): (isize, isize) = connection
+                .transaction(&["{counter}a", "{counter}b"], |con, mut pipe| {
+                    async move {
+                        let a: isize = con.get("{counter}a").await?;
+                        pipe.incr("{counter}a", 1)
+                            .set("{counter}b", a)
+                            .ignore()
+                            .get("{counter}b")
+                            .query_async(con)
+                            .await
+                    }
+                    .boxed()
+                })
+                .await?;
+            assert_eq!((a, b), (2, 1));
+            Ok::<_, RedisError>(())
+        })
+        .unwrap()
+    }
+
     #[test]
     fn test_async_cluster_multi_shard_commands() {
         let cluster = TestClusterContext::new(3, 0);
@@ -869,6 +916,331 @@ mod cluster_async {
         assert_eq!(value, Ok(Some(123)));
     }
 
+    #[test]
+    fn test_async_cluster_node_address_mapper() {
+        let name = "async_node_address_mapper";
+        let moved = AtomicBool::new(false);
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")]).node_address_mapper(
+                move |host, port| (host == "internal").then(|| NodeAddress::new(name, port - 1000)),
+            ),
+            name,
+            move |cmd: &[u8], port| {
+                if contains_slice(cmd, b"PING") || contains_slice(cmd, b"SETNAME") {
+                    return Err(Ok(Value::SimpleString("OK".into())));
+                }
+                if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SLOTS") {
+                    return Err(Ok(create_topology_from_config(
+                        "internal",
+                        vec![
+                            MockSlotRange {
+                                primary_port: 7379,
+                                replica_ports: vec![],
+                                slot_range: (0..8191),
+                            },
+                            MockSlotRange {
+                                primary_port: 7380,
+                                replica_ports: vec![],
+                                slot_range: (8192..16383),
+                            },
+                        ],
+                    )));
+                }
+                if !moved.swap(true, Ordering::SeqCst) {
+                    assert_eq!(port, 6379);
+                    return Err(parse_redis_value(b"-MOVED 6918 internal:7380\r\n"));
+                }
+                assert_eq!(port, 6380);
+                Err(Ok(Value::BulkString(b"123".to_vec())))
+            },
+        );
+
+        let value = runtime.block_on(
+            cmd("GET")
+                .arg("test")
+                .query_async::<_, Option<i32>>(&mut connection),
+        );
+        assert_eq!(value, Ok(Some(123)));
+
+        // Nodes are still identified by the addresses the cluster announces.
+        let topology = runtime.block_on(connection.topology()).unwrap();
+        assert!(topology.node("internal:7379").is_some());
+        assert!(topology.node("internal:7380").is_some());
+    }
+
+    #[test]
+    fn test_async_cluster_rediscovers_replaced_cluster_through_initial_nodes() {
+        let name = "async_rediscovers_replaced_cluster";
+        let old_name = "async_rediscovers_replaced_cluster_old";
+        let replaced = Arc::new(AtomicBool::new(false));
+        let replaced_clone = replaced.clone();
+        let _old_handler = MockConnectionBehavior::register_new(
+            old_name,
+            Arc::new(move |cmd, _| {
+                if replaced_clone.load(Ordering::SeqCst) {
+                    // The nodes of the old cluster are gone.
+                    return Err(Err(RedisError::from(std::io::Error::new(
+                        std::io::ErrorKind::ConnectionReset,
+                        "mock-io-error",
+                    ))));
+                }
+                respond_startup(old_name, cmd)?;
+                Err(Ok(Value::BulkString(b"456".to_vec())))
+            }),
+        );
+        let replaced_clone = replaced.clone();
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::new(name, move |cmd: &[u8], port| {
+            if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SLOTS") {
+                // The initial node always answers with the current cluster.
+                let host = if replaced_clone.load(Ordering::SeqCst) {
+                    name
+                } else {
+                    old_name
+                };
+                return Err(Ok(create_topology_from_config(
+                    host,
+                    vec![MockSlotRange {
+                        primary_port: 6380,
+                        replica_ports: vec![],
+                        slot_range: (0..16383),
+                    }],
+                )));
+            }
+            respond_startup(name, cmd)?;
+            assert_eq!(port, 6380);
+            Err(Ok(Value::BulkString(b"123".to_vec())))
+        });
+        let mut get = || {
+            runtime.block_on(
+                cmd("GET")
+                    .arg("test")
+                    .query_async::<_, Option<i32>>(&mut connection),
+            )
+        };
+        assert_eq!(get(), Ok(Some(456)));
+
+        // Replace the whole cluster. The old nodes can't be reached anymore.
+        replaced.store(true, Ordering::SeqCst);
+        modify_mock_connection_behavior(old_name, |behavior| {
+            behavior.return_connection_err = ShouldReturnConnectionError::Yes;
+        });
+
+        assert_eq!(get(), Ok(Some(123)));
+    }
+
+    #[test]
+    fn test_async_cluster_topology_events_report_failed_connections() {
+        let name = "topology_events_report_failed_connections";
+        let fail_requests = Arc::new(AtomicBool::new(true));
+        let fail_pings = Arc::new(AtomicBool::new(false));
+        let fail_requests_clone = fail_requests.clone();
+        let fail_pings_clone = fail_pings.clone();
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::new(name, move |cmd: &[u8], _| {
+            let io_error = || {
+                Err(Err(RedisError::from(std::io::Error::new(
+                    std::io::ErrorKind::BrokenPipe,
+                    "mock-io-error",
+                ))))
+            };
+            if contains_slice(cmd, b"PING") && fail_pings_clone.load(Ordering::SeqCst) {
+                return io_error();
+            }
+            respond_startup(name, cmd)?;
+            if fail_requests_clone.swap(false, Ordering::SeqCst) {
+                return io_error();
+            }
+            Err(Ok(Value::BulkString(b"123".to_vec())))
+        });
+        let mut events = connection.topology_events();
+        let mut get = || {
+            runtime.block_on(
+                cmd("GET")
+                    .arg("test")
+                    .query_async::<_, Option<i32>>(&mut connection),
+            )
+        };
+        let mut received = || {
+            let mut received = Vec::new();
+            while let Some(Some(event)) = events.next().now_or_never() {
+                received.push(event);
+            }
+            received
+        };
+
+        // The connection passes its health check after the error, so it wasn't lost.
+        assert_eq!(get(), Ok(Some(123)));
+        assert_eq!(received(), vec![]);
+
+        // The connection fails its health check and can't be replaced.
+        fail_pings.store(true, Ordering::SeqCst);
+        fail_requests.store(true, Ordering::SeqCst);
+        modify_mock_connection_behavior(name, |behavior| {
+            behavior.return_connection_err = ShouldReturnConnectionError::Yes;
+        });
+        assert!(get().is_err());
+        assert_eq!(
+            received(),
+            vec![TopologyEvent::ConnectionLost {
+                address: format!("{name}:6379"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_async_cluster_topology_events_after_moved() {
+        let name = "topology_events_after_moved";
+
+        let started = atomic::AtomicBool::new(false);
+        let moved = atomic::AtomicBool::new(false);
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::new(name, move |cmd: &[u8], port| {
+            if !started.load(atomic::Ordering::SeqCst) {
+                respond_startup(name, cmd)?;
+            }
+            started.store(true, atomic::Ordering::SeqCst);
+
+            if contains_slice(cmd, b"PING") || contains_slice(cmd, b"SETNAME") {
+                return Err(Ok(Value::SimpleString("OK".into())));
+            }
+            if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SLOTS") {
+                return Err(Ok(Value::Array(vec![
+                    Value::Array(vec![
+                        Value::Int(0),
+                        Value::Int(1),
+                        Value::Array(vec![
+                            Value::BulkString(name.as_bytes().to_vec()),
+                            Value::Int(6379),
+                        ]),
+                    ]),
+                    Value::Array(vec![
+                        Value::Int(2),
+                        Value::Int(16383),
+                        Value::Array(vec![
+                            Value::BulkString(name.as_bytes().to_vec()),
+                            Value::Int(6380),
+                        ]),
+                    ]),
+                ])));
+            }
+            if !moved.swap(true, atomic::Ordering::SeqCst) {
+                return Err(parse_redis_value(
+                    format!("-MOVED 123 {name}:6380\r\n").as_bytes(),
+                ));
+            }
+            assert_eq!(port, 6380);
+            Err(Ok(Value::BulkString(b"123".to_vec())))
+        });
+
+        let mut events = connection.topology_events();
+        let value = runtime.block_on(
+            cmd("GET")
+                .arg("test")
+                .query_async::<_, Option<i32>>(&mut connection),
+        );
+        assert_eq!(value, Ok(Some(123)));
+
+        let mut received = Vec::new();
+        while let Some(Some(event)) = events.next().now_or_never() {
+            received.push(event);
+        }
+        assert_eq!(
+            received,
+            vec![
+                TopologyEvent::NodeAdded {
+                    address: format!("{name}:6380"),
+                    role: NodeRole::Primary,
+                },
+                TopologyEvent::SlotsMoved {
+                    start: 2,
+                    end: 16383,
+                    from: Some(format!("{name}:6379")),
+                    to: Some(format!("{name}:6380")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_async_cluster_discovers_topology_with_cluster_shards() {
+        let name = "discovers_topology_with_cluster_shards";
+
+        let shard = |start: i64, end: i64, port: i64, health: &str| {
+            let node = [
+                ("id", Value::BulkString(format!("node-{port}").into_bytes())),
+                ("port", Value::Int(port)),
+                ("ip", Value::BulkString(b"127.0.0.1".to_vec())),
+                ("endpoint", Value::BulkString(name.as_bytes().to_vec())),
+                ("role", Value::BulkString(b"master".to_vec())),
+                ("replication-offset", Value::Int(0)),
+                ("health", Value::BulkString(health.as_bytes().to_vec())),
+            ]
+            .into_iter()
+            .flat_map(|(field, value)| [Value::BulkString(field.as_bytes().to_vec()), value])
+            .collect();
+            Value::Array(vec![
+                Value::BulkString(b"slots".to_vec()),
+                Value::Array(vec![Value::Int(start), Value::Int(end)]),
+                Value::BulkString(b"nodes".to_vec()),
+                Value::Array(vec![Value::Array(node)]),
+            ])
+        };
+        let shards = Value::Array(vec![
+            shard(0, 8191, 6379, "online"),
+            shard(8192, 16383, 6380, "online"),
+            shard(0, 0, 6381, "loading"),
+        ]);
+
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_cluster_shards(name, move |cmd: &[u8], port| {
+            if contains_slice(cmd, b"PING") || contains_slice(cmd, b"SETNAME") {
+                return Err(Ok(Value::SimpleString("OK".into())));
+            }
+            if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SHARDS") {
+                return Err(Ok(shards.clone()));
+            }
+            assert!(
+                !contains_slice(cmd, b"SLOTS"),
+                "CLUSTER SLOTS shouldn't be used"
+            );
+            Err(Ok(Value::Int(port as i64)))
+        });
+
+        let topology = runtime.block_on(connection.topology()).unwrap();
+        assert_eq!(
+            topology
+                .nodes
+                .iter()
+                .map(|node| node.address.clone())
+                .collect::<Vec<_>>(),
+            vec![format!("{name}:6379"), format!("{name}:6380")]
+        );
+        let value = runtime.block_on(cmd("GET").arg("foo").query_async::<_, u16>(&mut connection));
+        assert_eq!(value, Ok(6380));
+    }
+
     fn test_async_cluster_refresh_topology_after_moved_assert_get_succeed_and_expected_retries(
         slots_config_vec: Vec<Vec<MockSlotRange>>,
         ports: Vec<u16>,
@@ -1332,81 +1704,818 @@ mod cluster_async {
     }
 
     #[test]
-    fn test_async_cluster_replica_read() {
-        let name = "node";
-
-        // requests should route to replica
+    fn test_async_cluster_split_multi_slot_pipeline_between_nodes() {
+        let name = "test_async_cluster_split_multi_slot_pipeline_between_nodes";
         let MockEnv {
-            runtime,
             async_connection: mut connection,
             handler: _handler,
+            runtime,
             ..
-        } = MockEnv::with_client_builder(
-            ClusterClient::builder(vec![&*format!("redis://{name}")])
-                .retries(0)
-                .read_from_replicas(),
-            name,
-            move |cmd: &[u8], port| {
-                respond_startup_with_replica(name, cmd)?;
-                match port {
-                    6380 => Err(Ok(Value::BulkString(b"123".to_vec()))),
-                    _ => panic!("Wrong node"),
+        } = MockEnv::new(name, move |received_cmd: &[u8], port| {
+            respond_startup_two_nodes(name, received_cmd)?;
+            match port {
+                6379 => {
+                    assert!(!contains_slice(received_cmd, b"foo"));
+                    Err(Ok(Value::Array(vec![
+                        Value::Okay,
+                        Value::BulkString(b"baz-value".to_vec()),
+                    ])))
                 }
-            },
-        );
+                6380 => {
+                    assert!(!contains_slice(received_cmd, b"baz"));
+                    Err(Ok(Value::Array(vec![
+                        Value::BulkString(b"foo-value".to_vec()),
+                        Value::BulkString(b"foo2-value".to_vec()),
+                    ])))
+                }
+                _ => panic!("unexpected port {port}"),
+            }
+        });
 
-        let value = runtime.block_on(
-            cmd("GET")
-                .arg("test")
-                .query_async::<_, Option<i32>>(&mut connection),
+        let mut pipe = redis::pipe();
+        pipe.get("foo") // slot 12182
+            .set("baz", "baz-value") // slot 4813
+            .ignore()
+            .get("baz")
+            .get("{foo}2");
+        let result = runtime
+            .block_on(pipe.query_async::<_, (String, String, String)>(&mut connection))
+            .unwrap();
+
+        assert_eq!(
+            result,
+            (
+                "foo-value".to_string(),
+                "baz-value".to_string(),
+                "foo2-value".to_string()
+            )
         );
-        assert_eq!(value, Ok(Some(123)));
+    }
 
-        // requests should route to primary
+    #[test]
+    fn test_async_cluster_multi_slot_pipeline_handles_moved_per_slot() {
+        let name = "test_async_cluster_multi_slot_pipeline_handles_moved_per_slot";
         let MockEnv {
-            runtime,
             async_connection: mut connection,
             handler: _handler,
+            runtime,
             ..
-        } = MockEnv::with_client_builder(
-            ClusterClient::builder(vec![&*format!("redis://{name}")])
-                .retries(0)
-                .read_from_replicas(),
-            name,
-            move |cmd: &[u8], port| {
-                respond_startup_with_replica(name, cmd)?;
-                match port {
-                    6379 => Err(Ok(Value::SimpleString("OK".into()))),
-                    _ => panic!("Wrong node"),
+        } = MockEnv::new(name, move |received_cmd: &[u8], port| {
+            respond_startup_two_nodes(name, received_cmd)?;
+            match port {
+                // The slot of `baz` was moved, but the topology wasn't updated yet.
+                6379 => Err(parse_redis_value(
+                    format!("-MOVED 4813 {name}:6380\r\n").as_bytes(),
+                )),
+                6380 => {
+                    if contains_slice(received_cmd, b"baz") {
+                        assert!(!contains_slice(received_cmd, b"foo"));
+                        Err(Ok(Value::Array(vec![Value::BulkString(
+                            b"baz-value".to_vec(),
+                        )])))
+                    } else {
+                        Err(Ok(Value::Array(vec![Value::BulkString(
+                            b"foo-value".to_vec(),
+                        )])))
+                    }
                 }
-            },
-        );
+                _ => panic!("unexpected port {port}"),
+            }
+        });
 
-        let value = runtime.block_on(
-            cmd("SET")
-                .arg("test")
-                .arg("123")
-                .query_async::<_, Option<Value>>(&mut connection),
-        );
-        assert_eq!(value, Ok(Some(Value::SimpleString("OK".to_owned()))));
+        let mut pipe = redis::pipe();
+        pipe.get("baz").get("foo");
+        let result = runtime
+            .block_on(pipe.query_async::<_, (String, String)>(&mut connection))
+            .unwrap();
+
+        assert_eq!(result, ("baz-value".to_string(), "foo-value".to_string()));
     }
 
-    fn test_async_cluster_fan_out(
-        command: &'static str,
-        expected_ports: Vec<u16>,
-        slots_config: Option<Vec<MockSlotRange>>,
-    ) {
-        let name = "node";
-        let found_ports = Arc::new(std::sync::Mutex::new(Vec::new()));
-        let ports_clone = found_ports.clone();
-        let mut cmd = Cmd::new();
-        for arg in command.split_whitespace() {
-            cmd.arg(arg);
-        }
-        let packed_cmd = cmd.get_packed_command();
-        // requests should route to replica
+    #[test]
+    fn test_async_cluster_multi_slot_pipeline_sends_a_batch_per_node() {
+        let name = "test_async_cluster_multi_slot_pipeline_sends_a_batch_per_node";
+        let sent_batches = Arc::new(AtomicU32::new(0));
         let MockEnv {
-            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::new(name, {
+            let sent_batches = sent_batches.clone();
+            move |received_cmd: &[u8], port| {
+                respond_startup_two_nodes(name, received_cmd)?;
+                match port {
+                    6379 => {
+                        sent_batches.fetch_add(1, Ordering::SeqCst);
+                        Err(Ok(Value::Array(vec![
+                            Value::BulkString(b"baz-value".to_vec()),
+                            Value::BulkString(b"bar-value".to_vec()),
+                        ])))
+                    }
+                    6380 => Err(Ok(Value::Array(vec![Value::BulkString(
+                        b"foo-value".to_vec(),
+                    )]))),
+                    _ => panic!("unexpected port {port}"),
+                }
+            }
+        });
+
+        let mut pipe = redis::pipe();
+        pipe.get("baz") // slot 4813
+            .get("foo") // slot 12182
+            .get("bar"); // slot 5061
+        let result = runtime
+            .block_on(pipe.query_async::<_, (String, String, String)>(&mut connection))
+            .unwrap();
+
+        assert_eq!(
+            result,
+            (
+                "baz-value".to_string(),
+                "foo-value".to_string(),
+                "bar-value".to_string()
+            )
+        );
+        assert_eq!(sent_batches.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_async_cluster_multi_slot_pipeline_follows_ask_with_asking() {
+        let name = "test_async_cluster_multi_slot_pipeline_follows_ask_with_asking";
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::new(name, move |received_cmd: &[u8], port| {
+            respond_startup_two_nodes(name, received_cmd)?;
+            match port {
+                // The slot of `baz` is migrating to the other node.
+                6379 => Err(parse_redis_value(
+                    format!("-ASK 4813 {name}:6380\r\n").as_bytes(),
+                )),
+                6380 => {
+                    if received_cmd == cmd("ASKING").get_packed_command() {
+                        return Err(Ok(Value::Okay));
+                    }
+                    if contains_slice(received_cmd, b"baz") {
+                        assert!(contains_slice(received_cmd, b"ASKING"));
+                        Err(Ok(Value::Array(vec![
+                            Value::Okay,
+                            Value::BulkString(b"baz-value".to_vec()),
+                        ])))
+                    } else {
+                        Err(Ok(Value::Array(vec![Value::BulkString(
+                            b"foo-value".to_vec(),
+                        )])))
+                    }
+                }
+                _ => panic!("unexpected port {port}"),
+            }
+        });
+
+        let mut pipe = redis::pipe();
+        pipe.get("baz").get("foo");
+        let result = runtime
+            .block_on(pipe.query_async::<_, (String, String)>(&mut connection))
+            .unwrap();
+
+        assert_eq!(result, ("baz-value".to_string(), "foo-value".to_string()));
+    }
+
+    #[test]
+    fn test_async_cluster_multi_slot_pipeline_reads_writes_of_a_slot_from_primary() {
+        let name = "test_async_cluster_multi_slot_pipeline_reads_writes_of_a_slot_from_primary";
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")]).read_from_replicas(),
+            name,
+            move |received_cmd: &[u8], port| {
+                respond_startup_with_replica(name, received_cmd)?;
+                match port {
+                    // The primary of `baz`.
+                    6379 => {
+                        assert!(!contains_slice(received_cmd, b"foo"));
+                        Err(Ok(Value::Array(vec![
+                            Value::Okay,
+                            Value::BulkString(b"baz-value".to_vec()),
+                        ])))
+                    }
+                    // A replica of `foo`.
+                    6382 => Err(Ok(Value::Array(vec![Value::BulkString(
+                        b"foo-value".to_vec(),
+                    )]))),
+                    _ => panic!("unexpected port {port}"),
+                }
+            },
+        );
+
+        let mut pipe = redis::pipe();
+        pipe.set("baz", "baz-value") // slot 4813
+            .ignore()
+            .get("baz")
+            .get("foo"); // slot 12182
+        let result = runtime
+            .block_on(pipe.query_async::<_, (String, String)>(&mut connection))
+            .unwrap();
+
+        assert_eq!(result, ("baz-value".to_string(), "foo-value".to_string()));
+    }
+
+    #[test]
+    fn test_async_cluster_multi_slot_pipeline_retries_batch_after_connection_error() {
+        let name = "test_async_cluster_multi_slot_pipeline_retries_batch_after_connection_error";
+        let sent_batches = Arc::new(AtomicU32::new(0));
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::new(name, {
+            let sent_batches = sent_batches.clone();
+            move |received_cmd: &[u8], port| {
+                respond_startup_two_nodes(name, received_cmd)?;
+                match port {
+                    // The connection to the node of `baz` is lost on the first attempt.
+                    6379 => {
+                        if sent_batches.fetch_add(1, Ordering::SeqCst) == 0 {
+                            Err(Err(RedisError::from(std::io::Error::new(
+                                std::io::ErrorKind::ConnectionReset,
+                                "mock-io-error",
+                            ))))
+                        } else {
+                            Err(Ok(Value::Array(vec![Value::Int(1)])))
+                        }
+                    }
+                    6380 => Err(Ok(Value::Array(vec![Value::Int(2)]))),
+                    _ => panic!("unexpected port {port}"),
+                }
+            }
+        });
+
+        let mut pipe = redis::pipe();
+        pipe.incr("baz", 1).incr("foo", 1);
+        let result = runtime.block_on(pipe.query_async::<_, (i64, i64)>(&mut connection));
+
+        assert_eq!(result, Ok((1, 2)));
+        assert_eq!(sent_batches.load(Ordering::SeqCst), 2);
+    }
+
+    // The command name, number of commands, node, retries and redirects of each request.
+    type RecordedRequest = (String, usize, Option<String>, u32, u32);
+
+    #[test]
+    fn test_async_cluster_transaction_follows_moved_and_retries_aborted_exec() {
+        let name = "test_async_cluster_transaction_follows_moved_and_retries_aborted_exec";
+        let watches = Arc::new(AtomicU32::new(0));
+        let execs = AtomicU32::new(0);
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::new(name, {
+            let watches = watches.clone();
+            move |received_cmd: &[u8], port| {
+                respond_startup_two_nodes(name, received_cmd)?;
+                let ok = Value::SimpleString("OK".into());
+                let queued = Value::SimpleString("QUEUED".into());
+                if port == 6380 {
+                    // The slot of `foo` moved, but the topology wasn't updated yet.
+                    return Err(parse_redis_value(
+                        format!("-MOVED 12182 {name}:6379\r\n").as_bytes(),
+                    ));
+                }
+                if contains_slice(received_cmd, b"UNWATCH") {
+                    Err(Ok(ok))
+                } else if contains_slice(received_cmd, b"WATCH") {
+                    watches.fetch_add(1, Ordering::SeqCst);
+                    Err(Ok(ok))
+                } else if contains_slice(received_cmd, b"EXEC") {
+                    let result = match execs.fetch_add(1, Ordering::SeqCst) {
+                        0 => Value::Nil,
+                        _ => Value::Array(vec![ok.clone(), Value::BulkString(b"42".to_vec())]),
+                    };
+                    Err(Ok(Value::Array(vec![ok, queued.clone(), queued, result])))
+                } else {
+                    Err(Ok(Value::BulkString(b"41".to_vec())))
+                }
+            }
+        });
+
+        let result = runtime.block_on(connection.transaction(&["foo"], |con, mut pipe| {
+            async move {
+                let old_value: isize = con.get("foo").await?;
+                pipe.set("foo", old_value + 1)
+                    .ignore()
+                    .get("foo")
+                    .query_async::<_, Option<(isize,)>>(con)
+                    .await
+            }
+            .boxed()
+        }));
+
+        assert_eq!(result, Ok((42,)));
+        assert_eq!(watches.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_async_cluster_transaction_follows_ask_with_asking() {
+        let name = "test_async_cluster_transaction_follows_ask_with_asking";
+        let asking_watches = Arc::new(AtomicU32::new(0));
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::new(name, {
+            let asking_watches = asking_watches.clone();
+            move |received_cmd: &[u8], port| {
+                respond_startup_two_nodes(name, received_cmd)?;
+                let ok = Value::SimpleString("OK".into());
+                let queued = Value::SimpleString("QUEUED".into());
+                if port == 6380 {
+                    // The slot of `foo` is being migrated to the other node.
+                    return if contains_slice(received_cmd, b"UNWATCH") {
+                        Err(Ok(ok))
+                    } else {
+                        Err(parse_redis_value(
+                            format!("-ASK 12182 {name}:6379\r\n").as_bytes(),
+                        ))
+                    };
+                }
+                assert!(received_cmd.starts_with(&redis::cmd("ASKING").get_packed_command()));
+                if contains_slice(received_cmd, b"UNWATCH") {
+                    Err(Ok(ok))
+                } else if contains_slice(received_cmd, b"WATCH") {
+                    asking_watches.fetch_add(1, Ordering::SeqCst);
+                    Err(Ok(ok))
+                } else if contains_slice(received_cmd, b"EXEC") {
+                    let result = Value::Array(vec![ok.clone(), Value::BulkString(b"42".to_vec())]);
+                    Err(Ok(Value::Array(vec![ok, queued.clone(), queued, result])))
+                } else {
+                    Err(Ok(Value::BulkString(b"41".to_vec())))
+                }
+            }
+        });
+
+        let result = runtime.block_on(connection.transaction(&["foo"], |con, mut pipe| {
+            async move {
+                let old_value: isize = con.get("foo").await?;
+                pipe.set("foo", old_value + 1)
+                    .ignore()
+                    .get("foo")
+                    .query_async::<_, Option<(isize,)>>(con)
+                    .await
+            }
+            .boxed()
+        }));
+
+        assert_eq!(result, Ok((42,)));
+        assert_eq!(asking_watches.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_async_cluster_transaction_requires_keys_of_the_same_slot() {
+        let name = "test_async_cluster_transaction_requires_keys_of_the_same_slot";
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::new(name, move |received_cmd: &[u8], _| {
+            respond_startup_two_nodes(name, received_cmd)?;
+            panic!("unexpected command");
+        });
+
+        let result = runtime.block_on(connection.transaction(&["foo", "bar"], |_con, _pipe| {
+            async move { Ok(Some(())) }.boxed()
+        }));
+
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::CrossSlot);
+    }
+
+    #[derive(Default)]
+    struct RecordedRequests(std::sync::Mutex<Vec<RecordedRequest>>);
+
+    impl Instrumentation for RecordedRequests {
+        fn on_request(&self, event: &RequestEvent<'_>) {
+            assert!(event.error.is_none());
+            self.0.lock().unwrap().push((
+                event.command.to_string(),
+                event.commands,
+                event.node.map(str::to_string),
+                event.retries,
+                event.redirects,
+            ));
+        }
+    }
+
+    #[test]
+    fn test_async_cluster_instrumentation_reports_redirects() {
+        let name = "test_async_cluster_instrumentation_reports_redirects";
+        let recorded = Arc::new(RecordedRequests::default());
+        let moved = AtomicBool::new(false);
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .instrumentation(recorded.clone()),
+            name,
+            move |received_cmd: &[u8], port| {
+                respond_startup_two_nodes(name, received_cmd)?;
+                match port {
+                    6380 if !moved.swap(true, Ordering::SeqCst) => Err(parse_redis_value(
+                        format!("-MOVED 12182 {name}:6379\r\n").as_bytes(),
+                    )),
+                    6380 => Err(Ok(Value::Array(vec![Value::BulkString(
+                        b"bar-value".to_vec(),
+                    )]))),
+                    _ => Err(Ok(Value::BulkString(b"foo-value".to_vec()))),
+                }
+            },
+        );
+
+        runtime.block_on(async move {
+            let value: String = connection.get("foo").await.unwrap();
+            assert_eq!(value, "foo-value");
+            let (value,): (String,) = redis::pipe()
+                .get("foo")
+                .query_async(&mut connection)
+                .await
+                .unwrap();
+            assert_eq!(value, "bar-value");
+        });
+
+        assert_eq!(
+            *recorded.0.lock().unwrap(),
+            vec![
+                ("GET".to_string(), 1, Some(format!("{name}:6379")), 1, 1),
+                (
+                    "PIPELINE".to_string(),
+                    1,
+                    Some(format!("{name}:6380")),
+                    0,
+                    0
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_async_cluster_atomic_multi_slot_pipeline_returns_cross_slot_error() {
+        let name = "test_async_cluster_atomic_multi_slot_pipeline_returns_cross_slot_error";
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::new(name, move |received_cmd: &[u8], _port| {
+            respond_startup_two_nodes(name, received_cmd)?;
+            panic!("Atomic multi-slot pipelines shouldn't be sent");
+        });
+
+        let mut pipe = redis::pipe();
+        pipe.atomic().get("baz").get("foo");
+        let result = runtime.block_on(pipe.query_async::<_, (String, String)>(&mut connection));
+
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::CrossSlot);
+    }
+
+    #[test]
+    fn test_async_cluster_scan_iter_covers_all_primaries() {
+        let name = "test_async_cluster_scan_iter_covers_all_primaries";
+        let scan_reply = |cursor: &str, keys: &[&str]| {
+            Err(Ok(Value::Array(vec![
+                Value::BulkString(cursor.as_bytes().to_vec()),
+                Value::Array(
+                    keys.iter()
+                        .map(|key| Value::BulkString(key.as_bytes().to_vec()))
+                        .collect(),
+                ),
+            ])))
+        };
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::new(name, move |received_cmd: &[u8], port| {
+            respond_startup_two_nodes(name, received_cmd)?;
+            assert!(contains_slice(received_cmd, b"SCAN"));
+            assert!(contains_slice(received_cmd, b"TYPE"));
+            let continued = contains_slice(received_cmd, b"$1\r\n5\r\n");
+            match (port, continued) {
+                (6379, false) => scan_reply("5", &[]),
+                (6379, true) => scan_reply("0", &["a", "b"]),
+                (6380, false) => scan_reply("0", &["c"]),
+                _ => panic!("unexpected SCAN on port {port}"),
+            }
+        });
+
+        let keys: Vec<String> = runtime.block_on(
+            connection
+                .cluster_scan_iter(ScanOptions::default().object_type("string"))
+                .collect(),
+        );
+
+        assert_eq!(keys, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_async_cluster_function_loads_library_to_all_primaries() {
+        let name = "test_async_cluster_function_loads_library_to_all_primaries";
+        let loaded_ports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let loaded_ports_clone = loaded_ports.clone();
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::new(name, move |received_cmd: &[u8], port| {
+            respond_startup_two_nodes(name, received_cmd)?;
+            if contains_slice(received_cmd, b"FUNCTION") {
+                assert!(contains_slice(received_cmd, b"REPLACE"));
+                loaded_ports_clone.lock().unwrap().push(port);
+                return Err(Ok(Value::BulkString(b"mylib".to_vec())));
+            }
+            assert!(contains_slice(received_cmd, b"FCALL"));
+            // "foo" belongs to the second node.
+            assert_eq!(port, 6380);
+            if loaded_ports_clone.lock().unwrap().is_empty() {
+                Err(parse_redis_value(b"-ERR Function not found\r\n"))
+            } else {
+                Err(Ok(Value::Int(1)))
+            }
+        });
+        let library = redis::Library::new("#!lua name=mylib\nredis.register_function('f', f)");
+
+        let result: i64 = runtime
+            .block_on(
+                library
+                    .function("f")
+                    .key("foo")
+                    .invoke_async(&mut connection),
+            )
+            .unwrap();
+
+        assert_eq!(result, 1);
+        loaded_ports.lock().unwrap().sort();
+        assert_eq!(*loaded_ports.lock().unwrap(), vec![6379, 6380]);
+    }
+
+    #[test]
+    fn test_async_cluster_ssubscribe_routes_by_channel_slot() {
+        let name = "test_async_cluster_ssubscribe_routes_by_channel_slot";
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .use_protocol(ProtocolVersion::RESP3),
+            name,
+            move |received_cmd: &[u8], port| {
+                respond_startup_two_nodes(name, received_cmd)?;
+                assert!(contains_slice(received_cmd, b"SSUBSCRIBE"));
+                // `foo` is in slot 12182
+                assert_eq!(port, 6380);
+                Err(Ok(Value::Nil))
+            },
+        );
+
+        runtime
+            .block_on(connection.ssubscribe("foo".to_string()))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_async_cluster_ssubscribe_requires_resp3() {
+        let name = "test_async_cluster_ssubscribe_requires_resp3";
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::new(name, move |received_cmd: &[u8], _port| {
+            respond_startup_two_nodes(name, received_cmd)?;
+            panic!("SSUBSCRIBE shouldn't be sent over RESP2");
+        });
+
+        let result = runtime.block_on(connection.ssubscribe("foo".to_string()));
+
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidClientConfig);
+    }
+
+    #[test]
+    fn test_async_cluster_resubscribe_shard_channels_after_slot_migration() {
+        let name = "test_async_cluster_resubscribe_shard_channels_after_slot_migration";
+        let slot_moved = Arc::new(AtomicBool::new(false));
+        let resubscribed = Arc::new(AtomicBool::new(false));
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .use_protocol(ProtocolVersion::RESP3),
+            name,
+            {
+                let slot_moved = slot_moved.clone();
+                let resubscribed = resubscribed.clone();
+                move |received_cmd: &[u8], port| {
+                    let slots_config = if slot_moved.load(Ordering::SeqCst) {
+                        vec![MockSlotRange {
+                            primary_port: 6379,
+                            replica_ports: vec![],
+                            slot_range: (0..16383),
+                        }]
+                    } else {
+                        vec![
+                            MockSlotRange {
+                                primary_port: 6379,
+                                replica_ports: vec![],
+                                slot_range: (0..8191),
+                            },
+                            MockSlotRange {
+                                primary_port: 6380,
+                                replica_ports: vec![],
+                                slot_range: (8192..16383),
+                            },
+                        ]
+                    };
+                    respond_startup_with_replica_using_config(
+                        name,
+                        received_cmd,
+                        Some(slots_config),
+                    )?;
+                    if contains_slice(received_cmd, b"SSUBSCRIBE") {
+                        if port == 6379 {
+                            resubscribed.store(true, Ordering::SeqCst);
+                        }
+                        return Err(Ok(Value::Nil));
+                    }
+                    match port {
+                        6380 => {
+                            slot_moved.store(true, Ordering::SeqCst);
+                            Err(parse_redis_value(
+                                format!("-MOVED 12182 {name}:6379\r\n").as_bytes(),
+                            ))
+                        }
+                        _ => Err(Ok(Value::BulkString(b"foo-value".to_vec()))),
+                    }
+                }
+            },
+        );
+
+        runtime.block_on(async move {
+            connection.ssubscribe("foo".to_string()).await.unwrap();
+            assert!(!resubscribed.load(Ordering::SeqCst));
+
+            let value: String = connection.get("foo").await.unwrap();
+            assert_eq!(value, "foo-value");
+
+            for _ in 0..100 {
+                if resubscribed.load(Ordering::SeqCst) {
+                    break;
+                }
+                sleep(Duration::from_millis(10).into()).await;
+            }
+            assert!(resubscribed.load(Ordering::SeqCst));
+        });
+    }
+
+    #[test]
+    fn test_async_cluster_dont_resubscribe_shard_channels_whose_slots_didnt_move() {
+        let name = "test_async_cluster_dont_resubscribe_shard_channels_whose_slots_didnt_move";
+        let subscriptions = Arc::new(Mutex::new(Vec::new()));
+        let moved = AtomicBool::new(false);
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .use_protocol(ProtocolVersion::RESP3),
+            name,
+            {
+                let subscriptions = subscriptions.clone();
+                move |received_cmd: &[u8], port| {
+                    respond_startup_two_nodes(name, received_cmd)?;
+                    if contains_slice(received_cmd, b"SSUBSCRIBE") {
+                        subscriptions.lock().unwrap().push(port);
+                        return Err(Ok(Value::Nil));
+                    }
+                    // A stale redirect, which refreshes the slots without changing them.
+                    if !moved.swap(true, Ordering::SeqCst) {
+                        return Err(parse_redis_value(
+                            format!("-MOVED 4813 {name}:6379\r\n").as_bytes(),
+                        ));
+                    }
+                    Err(Ok(Value::BulkString(b"baz-value".to_vec())))
+                }
+            },
+        );
+
+        runtime.block_on(async move {
+            connection.ssubscribe(&["foo", "baz"]).await.unwrap();
+            let mut ports = subscriptions.lock().unwrap().clone();
+            ports.sort_unstable();
+            assert_eq!(ports, vec![6379, 6380]);
+
+            let value: String = connection.get("baz").await.unwrap();
+            assert_eq!(value, "baz-value");
+            assert_eq!(subscriptions.lock().unwrap().len(), 2);
+        });
+    }
+
+    #[test]
+    fn test_async_cluster_replica_read() {
+        let name = "node";
+
+        // requests should route to replica
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .retries(0)
+                .read_from_replicas(),
+            name,
+            move |cmd: &[u8], port| {
+                respond_startup_with_replica(name, cmd)?;
+                match port {
+                    6380 => Err(Ok(Value::BulkString(b"123".to_vec()))),
+                    _ => panic!("Wrong node"),
+                }
+            },
+        );
+
+        let value = runtime.block_on(
+            cmd("GET")
+                .arg("test")
+                .query_async::<_, Option<i32>>(&mut connection),
+        );
+        assert_eq!(value, Ok(Some(123)));
+
+        // requests should route to primary
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .retries(0)
+                .read_from_replicas(),
+            name,
+            move |cmd: &[u8], port| {
+                respond_startup_with_replica(name, cmd)?;
+                match port {
+                    6379 => Err(Ok(Value::SimpleString("OK".into()))),
+                    _ => panic!("Wrong node"),
+                }
+            },
+        );
+
+        let value = runtime.block_on(
+            cmd("SET")
+                .arg("test")
+                .arg("123")
+                .query_async::<_, Option<Value>>(&mut connection),
+        );
+        assert_eq!(value, Ok(Some(Value::SimpleString("OK".to_owned()))));
+    }
+
+    fn test_async_cluster_fan_out(
+        command: &'static str,
+        expected_ports: Vec<u16>,
+        slots_config: Option<Vec<MockSlotRange>>,
+    ) {
+        let name = "node";
+        let found_ports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let ports_clone = found_ports.clone();
+        let mut cmd = Cmd::new();
+        for arg in command.split_whitespace() {
+            cmd.arg(arg);
+        }
+        let packed_cmd = cmd.get_packed_command();
+        // requests should route to replica
+        let MockEnv {
+            runtime,
             async_connection: mut connection,
             handler: _handler,
             ..
@@ -2242,6 +3351,144 @@ mod cluster_async {
         assert_eq!(*found_ports.lock().unwrap(), vec![6380, 6381, 6383, 6384]);
     }
 
+    #[test]
+    fn test_async_cluster_read_from_replicas_in_availability_zone() {
+        let name = "node";
+        let found_ports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let ports_clone = found_ports.clone();
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")]).read_from_replica_strategy(
+                ReadFromReplicaStrategy::AZAffinity("az-a".to_string()),
+            ),
+            name,
+            move |received_cmd: &[u8], port| {
+                if contains_slice(received_cmd, b"INFO") {
+                    let availability_zone = match port {
+                        6381 | 6383 => "az-a",
+                        _ => "az-b",
+                    };
+                    return Err(Ok(Value::BulkString(
+                        format!("# Server\r\navailability_zone:{availability_zone}\r\n")
+                            .into_bytes(),
+                    )));
+                }
+                respond_startup_with_replica_using_config(
+                    name,
+                    received_cmd,
+                    Some(vec![
+                        MockSlotRange {
+                            primary_port: 6379,
+                            replica_ports: vec![6380, 6381],
+                            slot_range: (0..8191),
+                        },
+                        MockSlotRange {
+                            primary_port: 6382,
+                            replica_ports: vec![6383, 6384],
+                            slot_range: (8192..16383),
+                        },
+                    ]),
+                )?;
+                ports_clone.lock().unwrap().push(port);
+                Err(Ok(Value::Nil))
+            },
+        );
+
+        runtime.block_on(async {
+            for key in ["foo", "bar", "foo", "bar"] {
+                cmd("GET")
+                    .arg(key)
+                    .query_async::<_, ()>(&mut connection)
+                    .await
+                    .unwrap();
+            }
+        });
+
+        found_ports.lock().unwrap().sort();
+        assert_eq!(*found_ports.lock().unwrap(), vec![6381, 6381, 6383, 6383]);
+    }
+
+    #[test]
+    fn test_async_cluster_read_from_lowest_latency_replica_follows_latency_changes() {
+        let name = "node";
+        let slow_port = Arc::new(AtomicU16::new(6380));
+        let moved = Arc::new(AtomicBool::new(false));
+        let found_ports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (slow_port_clone, moved_clone, ports_clone) =
+            (slow_port.clone(), moved.clone(), found_ports.clone());
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .read_from_replica_strategy(ReadFromReplicaStrategy::LowestLatency),
+            name,
+            move |received_cmd: &[u8], port| {
+                if contains_slice(received_cmd, b"PING")
+                    && port == slow_port_clone.load(Ordering::SeqCst)
+                {
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                respond_startup_with_replica_using_config(
+                    name,
+                    received_cmd,
+                    Some(vec![
+                        MockSlotRange {
+                            primary_port: 6379,
+                            replica_ports: vec![6380, 6381],
+                            slot_range: (0..8191),
+                        },
+                        MockSlotRange {
+                            primary_port: 6382,
+                            replica_ports: vec![6383],
+                            slot_range: (8192..16383),
+                        },
+                    ]),
+                )?;
+                if port == 6381 && moved_clone.swap(false, Ordering::SeqCst) {
+                    // Triggers a refresh of the slots.
+                    return Err(parse_redis_value(
+                        format!("-MOVED 6918 {name}:6379\r\n").as_bytes(),
+                    ));
+                }
+                ports_clone.lock().unwrap().push(port);
+                Err(Ok(Value::Nil))
+            },
+        );
+
+        runtime.block_on(async {
+            cmd("GET")
+                .arg("test")
+                .query_async::<_, ()>(&mut connection)
+                .await
+                .unwrap();
+            assert_eq!(*found_ports.lock().unwrap(), vec![6381]);
+
+            slow_port.store(6381, Ordering::SeqCst);
+            moved.store(true, Ordering::SeqCst);
+            cmd("GET")
+                .arg("test")
+                .query_async::<_, ()>(&mut connection)
+                .await
+                .unwrap();
+            // Let the refresh triggered by the redirect finish.
+            sleep(Duration::from_millis(500).into()).await;
+
+            found_ports.lock().unwrap().clear();
+            cmd("GET")
+                .arg("test")
+                .query_async::<_, ()>(&mut connection)
+                .await
+                .unwrap();
+        });
+
+        assert_eq!(*found_ports.lock().unwrap(), vec![6380]);
+    }
+
     fn get_queried_node_id_if_master(cluster_nodes_output: Value) -> Option<String> {
         // Returns the node ID of the connection that was queried for CLUSTER NODES (using the 'myself' flag), if it's a master.
         // Otherwise, returns None.
@@ -2,7 +2,10 @@
 
 use assert_approx_eq::assert_approx_eq;
 
-use redis::geo::{Coord, RadiusOptions, RadiusOrder, RadiusSearchResult, Unit};
+use redis::geo::{
+    Coord, GeoSearchOptions, GeoSearchOrigin, GeoSearchResult, GeoSearchShape, RadiusOptions,
+    RadiusOrder, RadiusSearchResult, Unit,
+};
 use redis::{Commands, RedisResult};
 
 mod support;
@@ -195,3 +198,92 @@ fn test_georadius_by_member() {
 
     assert_eq!(names, vec!["Agrigento", "Palermo"]);
 }
+
+#[test]
+fn test_geosearch() {
+    let ctx = TestContext::new();
+    if ctx.get_version() < (6, 2, 0) {
+        return;
+    }
+    let mut con = ctx.connection();
+
+    assert_eq!(con.geo_add("my_gis", &[PALERMO, CATANIA, AGRIGENTO]), Ok(3));
+
+    let mut geo_search = |origin: GeoSearchOrigin,
+                          shape: GeoSearchShape,
+                          opts: GeoSearchOptions| {
+        let result: Vec<GeoSearchResult> = con.geo_search("my_gis", origin, shape, opts).unwrap();
+        result
+    };
+
+    // Simple request, without extra data
+    let result = geo_search(
+        GeoSearchOrigin::member(AGRIGENTO.2),
+        GeoSearchShape::Radius(100.0, Unit::Kilometers),
+        GeoSearchOptions::default().order(RadiusOrder::Asc),
+    );
+    let names: Vec<_> = result.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["Agrigento", "Palermo"]);
+    assert_eq!(result[0].dist, None);
+    assert_eq!(result[0].hash, None);
+
+    // Get data with every field
+    let result = geo_search(
+        GeoSearchOrigin::lon_lat(15.0, 37.0),
+        GeoSearchShape::Box(400.0, 400.0, Unit::Kilometers),
+        GeoSearchOptions::default()
+            .with_coord()
+            .with_dist()
+            .with_hash()
+            .order(RadiusOrder::Desc)
+            .limit(1),
+    );
+
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].name.as_str(), "Palermo");
+    assert_approx_eq!(result[0].dist.unwrap(), 190.4424, 0.001);
+    assert!(result[0].hash.is_some());
+    assert_approx_eq!(result[0].coord.as_ref().unwrap().longitude, 13.361_389);
+    assert_approx_eq!(result[0].coord.as_ref().unwrap().latitude, 38.115_556);
+
+    // Any matching items, not necessarily the nearest ones
+    let result = geo_search(
+        GeoSearchOrigin::lon_lat(15.0, 37.0),
+        GeoSearchShape::Radius(200.0, Unit::Kilometers),
+        GeoSearchOptions::default().limit(2).any(),
+    );
+    assert_eq!(result.len(), 2);
+}
+
+#[test]
+fn test_geosearchstore() {
+    let ctx = TestContext::new();
+    if ctx.get_version() < (6, 2, 0) {
+        return;
+    }
+    let mut con = ctx.connection();
+
+    assert_eq!(con.geo_add("my_gis", &[PALERMO, CATANIA, AGRIGENTO]), Ok(3));
+
+    let stored: RedisResult<usize> = con.geo_search_store(
+        "near_agrigento",
+        "my_gis",
+        GeoSearchOrigin::member(AGRIGENTO.2),
+        GeoSearchShape::Radius(100.0, Unit::Kilometers),
+        GeoSearchOptions::default(),
+    );
+    assert_eq!(stored, Ok(2));
+    let positions: Vec<Coord<f64>> = con.geo_pos("near_agrigento", PALERMO.2).unwrap();
+    assert_approx_eq!(positions[0].longitude, 13.36138, 0.0001);
+
+    let stored: RedisResult<usize> = con.geo_search_store(
+        "dist_from_catania",
+        "my_gis",
+        GeoSearchOrigin::member(CATANIA.2),
+        GeoSearchShape::Radius(200.0, Unit::Kilometers),
+        GeoSearchOptions::default().store_dist(),
+    );
+    assert_eq!(stored, Ok(3));
+    let dist: f64 = con.zscore("dist_from_catania", PALERMO.2).unwrap();
+    assert_approx_eq!(dist, 166.2742, 0.001);
+}
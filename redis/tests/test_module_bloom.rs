@@ -0,0 +1,137 @@
+#![cfg(feature = "bloom")]
+
+use redis::bloom::{
+    BfInfo, BfInsertOptions, BfReserveOptions, CfInfo, CfInsertOptions, CfReserveOptions, CmsInfo,
+    TDigestInfo, TDigestMergeOptions, TopKInfo, TopKReserveOptions,
+};
+use redis::{BloomCommands, RedisResult};
+
+use crate::support::*;
+mod support;
+
+const MTLS_NOT_ENABLED: bool = false;
+
+#[test]
+fn test_module_bloom_bf() {
+    let ctx = TestContext::with_modules(&[Module::Bloom], MTLS_NOT_ENABLED);
+    let mut con = ctx.connection();
+
+    let options = BfReserveOptions::default().expansion(2);
+    let _: () = con.bf_reserve("bf", 0.01, 100, &options).unwrap();
+    assert_eq!(con.bf_add("bf", "a"), Ok(true));
+    assert_eq!(con.bf_add("bf", "a"), Ok(false));
+    assert_eq!(con.bf_madd("bf", &["b", "c"]), Ok(vec![true, true]));
+    assert_eq!(con.bf_exists("bf", "b"), Ok(true));
+    assert_eq!(con.bf_mexists("bf", &["c", "d"]), Ok(vec![true, false]));
+    assert_eq!(con.bf_card("bf"), Ok(3));
+
+    let info: BfInfo = con.bf_info("bf").unwrap();
+    assert_eq!(info.capacity, 100);
+    assert_eq!(info.items_inserted, 3);
+    assert_eq!(info.expansion_rate, Some(2));
+
+    let missing: RedisResult<Vec<bool>> =
+        con.bf_insert("missing", &["a"], &BfInsertOptions::default().no_create());
+    assert!(missing.is_err());
+    let options = BfInsertOptions::default().capacity(10).non_scaling();
+    assert_eq!(
+        con.bf_insert("bf2", &["a", "b"], &options),
+        Ok(vec![true, true])
+    );
+    let info: BfInfo = con.bf_info("bf2").unwrap();
+    assert_eq!(info.expansion_rate, None);
+}
+
+#[test]
+fn test_module_bloom_cf() {
+    let ctx = TestContext::with_modules(&[Module::Bloom], MTLS_NOT_ENABLED);
+    let mut con = ctx.connection();
+
+    let options = CfReserveOptions::default().bucket_size(4);
+    let _: () = con.cf_reserve("cf", 1000, &options).unwrap();
+    assert_eq!(con.cf_add("cf", "a"), Ok(true));
+    assert_eq!(con.cf_addnx("cf", "a"), Ok(false));
+    assert_eq!(
+        con.cf_insertnx("cf", &["a", "b"], &CfInsertOptions::default()),
+        Ok(vec![0, 1])
+    );
+    assert_eq!(
+        con.cf_insert("cf", &["a"], &CfInsertOptions::default()),
+        Ok(vec![true])
+    );
+    assert_eq!(con.cf_count("cf", "a"), Ok(2));
+    assert_eq!(con.cf_del("cf", "a"), Ok(true));
+    assert_eq!(
+        con.cf_mexists("cf", &["a", "b", "c"]),
+        Ok(vec![true, true, false])
+    );
+
+    let info: CfInfo = con.cf_info("cf").unwrap();
+    assert_eq!(info.bucket_size, 4);
+    assert_eq!(info.items_inserted, 2);
+    assert_eq!(info.items_deleted, 1);
+}
+
+#[test]
+fn test_module_bloom_cms_and_topk() {
+    let ctx = TestContext::with_modules(&[Module::Bloom], MTLS_NOT_ENABLED);
+    let mut con = ctx.connection();
+
+    let _: () = con.cms_initbydim("{cms}a", 100, 5).unwrap();
+    let _: () = con.cms_initbydim("{cms}b", 100, 5).unwrap();
+    let _: () = con.cms_initbydim("{cms}c", 100, 5).unwrap();
+    assert_eq!(
+        con.cms_incrby("{cms}a", &[("x", 2), ("y", 1)]),
+        Ok(vec![2, 1])
+    );
+    assert_eq!(con.cms_incrby("{cms}b", &[("x", 1)]), Ok(vec![1]));
+    let _: () = con
+        .cms_merge_weighted("{cms}c", &[("{cms}a", 1), ("{cms}b", 10)])
+        .unwrap();
+    assert_eq!(con.cms_query("{cms}c", &["x", "y"]), Ok(vec![12, 1]));
+    let info: CmsInfo = con.cms_info("{cms}c").unwrap();
+    assert_eq!(info.width, 100);
+    assert_eq!(info.count, 13);
+
+    let options = TopKReserveOptions::default().params(50, 4, 0.9);
+    let _: () = con.topk_reserve("topk", 2, &options).unwrap();
+    let _: Vec<Option<String>> = con.topk_add("topk", &["a", "b", "a"]).unwrap();
+    let _: Vec<Option<String>> = con.topk_incrby("topk", &[("c", 10)]).unwrap();
+    assert_eq!(con.topk_query("topk", &["c", "b"]), Ok(vec![true, false]));
+    let top: Vec<(String, u64)> = con.topk_list_with_count("topk").unwrap();
+    assert_eq!(top[0], ("c".to_string(), 10));
+    let info: TopKInfo = con.topk_info("topk").unwrap();
+    assert_eq!(info.k, 2);
+    assert_eq!(info.width, 50);
+}
+
+#[test]
+fn test_module_bloom_tdigest() {
+    let ctx = TestContext::with_modules(&[Module::Bloom], MTLS_NOT_ENABLED);
+    let mut con = ctx.connection();
+
+    let _: () = con.tdigest_create_compression("{td}a", 100).unwrap();
+    let _: () = con.tdigest_create("{td}b").unwrap();
+    let _: () = con.tdigest_add("{td}a", &[1.0, 2.0, 3.0]).unwrap();
+    let _: () = con.tdigest_add("{td}b", &[4.0, 5.0]).unwrap();
+    let options = TDigestMergeOptions::default().compression(100);
+    let _: () = con
+        .tdigest_merge("{td}c", &["{td}a", "{td}b"], &options)
+        .unwrap();
+
+    assert_eq!(con.tdigest_min("{td}c"), Ok(1.0));
+    assert_eq!(con.tdigest_max("{td}c"), Ok(5.0));
+    assert_eq!(con.tdigest_byrank("{td}c", &[0]), Ok(vec![1.0]));
+    assert_eq!(con.tdigest_rank("{td}c", &[0.0]), Ok(vec![-1]));
+    let quantiles: Vec<f64> = con.tdigest_quantile("{td}c", &[0.0, 1.0]).unwrap();
+    assert_eq!(quantiles, vec![1.0, 5.0]);
+    let _: f64 = con.tdigest_trimmed_mean("{td}c", 0.1, 0.9).unwrap();
+
+    let info: TDigestInfo = con.tdigest_info("{td}c").unwrap();
+    assert_eq!(info.compression, 100);
+    assert_eq!(info.observations, 5);
+
+    let _: () = con.tdigest_reset("{td}c").unwrap();
+    let info: TDigestInfo = con.tdigest_info("{td}c").unwrap();
+    assert_eq!(info.observations, 0);
+}
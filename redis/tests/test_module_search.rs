@@ -0,0 +1,126 @@
+#![cfg(feature = "search")]
+
+use redis::search::{
+    AggregateOptions, AggregateReply, IndexOptions, IndexType, Reducer, Schema, SchemaField,
+    SearchOptions, SearchReply, SortOrder,
+};
+use redis::{Commands, Connection, SearchCommands, Value};
+
+use crate::support::*;
+mod support;
+
+const MTLS_NOT_ENABLED: bool = false;
+
+fn create_products_index(con: &mut Connection) {
+    let options = IndexOptions::default()
+        .on(IndexType::Hash)
+        .prefix("product:");
+    let schema = Schema::default()
+        .field(SchemaField::text("title").weight(2.0))
+        .field(SchemaField::tag("brand"))
+        .field(SchemaField::numeric("price").sortable());
+    let _: () = con.ft_create("products", &options, &schema).unwrap();
+
+    for (id, title, brand, price) in [
+        (1, "red shoe", "acme", 30),
+        (2, "blue shoe", "acme", 50),
+        (3, "red hat", "other", 20),
+    ] {
+        let _: () = con
+            .hset_multiple(
+                format!("product:{id}"),
+                &[
+                    ("title", title),
+                    ("brand", brand),
+                    ("price", &price.to_string()),
+                ],
+            )
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_module_search_create_and_search() {
+    let ctx = TestContext::with_modules(&[Module::Search], MTLS_NOT_ENABLED);
+    let mut con = ctx.connection();
+    create_products_index(&mut con);
+
+    let indexes: Vec<String> = con.ft_list().unwrap();
+    assert_eq!(indexes, vec!["products".to_string()]);
+
+    let options = SearchOptions::default()
+        .filter("price", 25, "+inf")
+        .sort_by("price", SortOrder::Desc);
+    let reply: SearchReply = con.ft_search("products", "shoe", &options).unwrap();
+    assert_eq!(reply.total, 2);
+    assert_eq!(reply.documents[0].id, "product:2");
+    assert_eq!(
+        reply.documents[0].get::<String>("title"),
+        Some("blue shoe".to_string())
+    );
+    assert_eq!(reply.documents[1].get::<u32>("price"), Some(30));
+
+    let options = SearchOptions::default().no_content().with_scores();
+    let value: Value = con
+        .ft_search("products", "@brand:{acme}", &options)
+        .unwrap();
+    let reply = SearchReply::parse(&value, &options).unwrap();
+    assert_eq!(reply.total, 2);
+    assert!(reply.documents.iter().all(|doc| doc.score.is_some()));
+    assert!(reply.documents.iter().all(|doc| doc.fields.is_empty()));
+
+    let _: () = con.ft_dropindex_dd("products").unwrap();
+    let exists: bool = con.exists("product:1").unwrap();
+    assert!(!exists);
+}
+
+#[test]
+fn test_module_search_aggregate_with_cursor() {
+    let ctx = TestContext::with_modules(&[Module::Search], MTLS_NOT_ENABLED);
+    let mut con = ctx.connection();
+    create_products_index(&mut con);
+
+    let options = AggregateOptions::default()
+        .group_by(["@brand"], [Reducer::count().alias("count")])
+        .sort_by("@count", SortOrder::Desc);
+    let reply: AggregateReply = con.ft_aggregate("products", "*", &options).unwrap();
+    assert_eq!(reply.rows.len(), 2);
+    assert_eq!(
+        reply.rows[0].get("brand"),
+        Some(&Value::BulkString(b"acme".to_vec()))
+    );
+    assert_eq!(reply.cursor, None);
+
+    let options = AggregateOptions::default().load("@title").cursor_count(2);
+    let mut reply: AggregateReply = con.ft_aggregate("products", "*", &options).unwrap();
+    let mut rows = reply.rows.len();
+    while let Some(cursor) = reply.cursor {
+        reply = con.ft_cursor_read("products", cursor).unwrap();
+        rows += reply.rows.len();
+    }
+    assert_eq!(rows, 3);
+}
+
+#[test]
+fn test_module_search_alias_and_alter() {
+    let ctx = TestContext::with_modules(&[Module::Search], MTLS_NOT_ENABLED);
+    let mut con = ctx.connection();
+    create_products_index(&mut con);
+
+    let _: () = con.ft_alias_add("catalog", "products").unwrap();
+    let _: () = con
+        .ft_alter("products", &SchemaField::tag("color"))
+        .unwrap();
+    let _: () = con.hset("product:4", "color", "green").unwrap();
+
+    let reply: SearchReply = con
+        .ft_search("catalog", "@color:{green}", &SearchOptions::default())
+        .unwrap();
+    assert_eq!(reply.total, 1);
+    assert_eq!(reply.documents[0].id, "product:4");
+
+    let tags: Vec<String> = con.ft_tagvals("products", "brand").unwrap();
+    assert_eq!(tags.len(), 2);
+
+    let _: () = con.ft_alias_del("catalog").unwrap();
+}
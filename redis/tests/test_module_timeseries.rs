@@ -0,0 +1,140 @@
+#![cfg(feature = "timeseries")]
+
+use redis::timeseries::{
+    TsAddOptions, TsAggregationType, TsCreateOptions, TsDuplicatePolicy, TsInfo, TsMRangeOptions,
+    TsMRangeReply, TsRangeOptions, TsSample,
+};
+use redis::{RedisResult, TimeSeriesCommands};
+
+use crate::support::*;
+mod support;
+
+const MTLS_NOT_ENABLED: bool = false;
+
+#[test]
+fn test_module_timeseries_add_and_range() {
+    let ctx = TestContext::with_modules(&[Module::TimeSeries], MTLS_NOT_ENABLED);
+    let mut con = ctx.connection();
+
+    let options = TsCreateOptions::default()
+        .retention(0)
+        .duplicate_policy(TsDuplicatePolicy::Block)
+        .label("sensor", "temp");
+    let _: () = con.ts_create("temp:1", &options).unwrap();
+
+    for (timestamp, value) in [(1000, 1.0), (2000, 2.0), (3000, 4.0)] {
+        let added: i64 = con.ts_add("temp:1", timestamp, value).unwrap();
+        assert_eq!(added, timestamp);
+    }
+    let duplicate: RedisResult<i64> = con.ts_add("temp:1", 3000, 5.0);
+    assert!(duplicate.is_err());
+    let options = TsAddOptions::default().on_duplicate(TsDuplicatePolicy::Sum);
+    let _: i64 = con.ts_add_options("temp:1", 3000, 1.0, &options).unwrap();
+
+    let latest: TsSample = con.ts_get("temp:1").unwrap();
+    assert_eq!(
+        latest,
+        TsSample {
+            timestamp: 3000,
+            value: 5.0
+        }
+    );
+
+    let samples: Vec<TsSample> = con
+        .ts_range("temp:1", "-", "+", &TsRangeOptions::default())
+        .unwrap();
+    assert_eq!(samples.len(), 3);
+
+    let options = TsRangeOptions::default().aggregation(TsAggregationType::Sum, 10_000);
+    let samples: Vec<TsSample> = con.ts_range("temp:1", 0, 10_000, &options).unwrap();
+    assert_eq!(
+        samples,
+        vec![TsSample {
+            timestamp: 0,
+            value: 8.0
+        }]
+    );
+
+    let samples: Vec<TsSample> = con
+        .ts_revrange("temp:1", "-", "+", &TsRangeOptions::default().count(1))
+        .unwrap();
+    assert_eq!(samples[0].timestamp, 3000);
+}
+
+#[test]
+fn test_module_timeseries_madd_and_mrange() {
+    let ctx = TestContext::with_modules(&[Module::TimeSeries], MTLS_NOT_ENABLED);
+    let mut con = ctx.connection();
+
+    for (key, room) in [
+        ("temp:1", "kitchen"),
+        ("temp:2", "kitchen"),
+        ("temp:3", "hall"),
+    ] {
+        let options = TsCreateOptions::default()
+            .label("sensor", "temp")
+            .label("room", room);
+        let _: () = con.ts_create(key, &options).unwrap();
+    }
+    let _: Vec<i64> = con
+        .ts_madd(&[
+            ("temp:1", 1000, 1.0),
+            ("temp:2", 1000, 3.0),
+            ("temp:3", 1000, 10.0),
+        ])
+        .unwrap();
+
+    let options = TsMRangeOptions::default()
+        .with_labels()
+        .filter("room=kitchen");
+    let reply: TsMRangeReply = con.ts_mrange("-", "+", &options).unwrap();
+    assert_eq!(reply.series.len(), 2);
+    assert!(reply
+        .series
+        .iter()
+        .all(|series| series.labels.get("room") == Some(&"kitchen".to_string())));
+
+    let options = TsMRangeOptions::default()
+        .filter("sensor=temp")
+        .group_by("room", TsAggregationType::Max);
+    let reply: TsMRangeReply = con.ts_mrevrange("-", "+", &options).unwrap();
+    assert_eq!(reply.series.len(), 2);
+    let kitchen = reply
+        .series
+        .iter()
+        .find(|series| series.key == "room=kitchen")
+        .unwrap();
+    assert_eq!(kitchen.samples[0].value, 3.0);
+}
+
+#[test]
+fn test_module_timeseries_rules_and_info() {
+    let ctx = TestContext::with_modules(&[Module::TimeSeries], MTLS_NOT_ENABLED);
+    let mut con = ctx.connection();
+
+    let options = TsCreateOptions::default().label("sensor", "temp");
+    let _: () = con.ts_create("temp:1", &options).unwrap();
+    let _: () = con
+        .ts_create("temp:1:avg", &TsCreateOptions::default())
+        .unwrap();
+    let _: () = con
+        .ts_createrule("temp:1", "temp:1:avg", TsAggregationType::Avg, 60_000)
+        .unwrap();
+
+    let info: TsInfo = con.ts_info("temp:1").unwrap();
+    assert_eq!(info.labels.get("sensor"), Some(&"temp".to_string()));
+    assert_eq!(info.rules.len(), 1);
+    assert_eq!(info.rules[0].dest_key, "temp:1:avg");
+    assert_eq!(info.rules[0].bucket_duration, 60_000);
+
+    let info: TsInfo = con.ts_info("temp:1:avg").unwrap();
+    assert_eq!(info.source_key, Some("temp:1".to_string()));
+
+    let _: () = con.ts_deleterule("temp:1", "temp:1:avg").unwrap();
+    let info: TsInfo = con.ts_info("temp:1").unwrap();
+    assert!(info.rules.is_empty());
+
+    let _: i64 = con.ts_add("temp:1", 1000, 1.0).unwrap();
+    let deleted: usize = con.ts_del("temp:1", 0, 2000).unwrap();
+    assert_eq!(deleted, 1);
+}
@@ -486,4 +486,158 @@ pub mod async_tests {
         })
         .unwrap();
     }
+
+    #[test]
+    #[cfg(feature = "connection-manager")]
+    fn test_sentinel_connection_manager_follows_failover() {
+        use redis::{sentinel::SentinelConnectionManager, AsyncCommands, RedisResult};
+
+        async fn port(con: &mut SentinelConnectionManager) -> RedisResult<u16> {
+            let (_, port): (String, u16) = redis::cmd("CONFIG")
+                .arg("GET")
+                .arg("port")
+                .query_async(con)
+                .await?;
+            Ok(port)
+        }
+
+        let master_name = "master1";
+        let context = TestSentinelContext::new(2, 3, 3);
+        let master_client = SentinelClient::build(
+            context.sentinels_connection_info().clone(),
+            String::from(master_name),
+            Some(context.sentinel_node_connection_info()),
+            redis::sentinel::SentinelServerType::Master,
+        )
+        .unwrap();
+        let sentinel_client = Client::open(context.sentinels_connection_info()[0].clone()).unwrap();
+
+        block_on_all(async move {
+            let mut con = SentinelConnectionManager::new(master_client).await?;
+            let _: () = con.set("foo", "bar").await?;
+            let old_port = port(&mut con).await?;
+
+            let mut sentinel_con = sentinel_client.get_multiplexed_async_connection().await?;
+            let _: () = redis::cmd("SENTINEL")
+                .arg("FAILOVER")
+                .arg(master_name)
+                .query_async(&mut sentinel_con)
+                .await?;
+
+            // Requests may fail while the failover is in progress.
+            let mut new_port = old_port;
+            for _ in 0..300 {
+                if let Ok(port) = port(&mut con).await {
+                    new_port = port;
+                    if new_port != old_port {
+                        break;
+                    }
+                }
+                futures_time::task::sleep(futures_time::time::Duration::from_millis(100)).await;
+            }
+            assert_ne!(new_port, old_port);
+
+            let info: String = redis::cmd("INFO")
+                .arg("REPLICATION")
+                .query_async(&mut con)
+                .await?;
+            assert_is_master_role(info);
+            let value: String = con.get("foo").await?;
+            assert_eq!(value, "bar");
+
+            Ok::<(), RedisError>(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "connection-manager")]
+    fn test_sentinel_connection_manager_retries_a_failed_reconnection() {
+        use redis::{sentinel::SentinelConnectionManager, AsyncCommands, ErrorKind, RedisResult};
+
+        let master_name = "master1";
+        let context = TestSentinelContext::new(2, 3, 3);
+        let master_client = SentinelClient::build(
+            context.sentinels_connection_info().clone(),
+            String::from(master_name),
+            Some(context.sentinel_node_connection_info()),
+            redis::sentinel::SentinelServerType::Master,
+        )
+        .unwrap();
+        let sentinel_clients: Vec<Client> = context
+            .sentinels_connection_info()
+            .iter()
+            .map(|info| Client::open(info.clone()).unwrap())
+            .collect();
+
+        block_on_all(async move {
+            let mut con =
+                SentinelConnectionManager::new_with_backoff(master_client, 2, 10, 1).await?;
+            let _: () = con.set("foo", "bar").await?;
+
+            // Make the sentinels forget the master, and drop the connection of the manager, so
+            // that reconnecting fails.
+            let mut sentinel_cons = Vec::new();
+            for client in &sentinel_clients {
+                sentinel_cons.push(client.get_multiplexed_async_connection().await?);
+            }
+            let (host, port): (String, u16) = redis::cmd("SENTINEL")
+                .arg("GET-MASTER-ADDR-BY-NAME")
+                .arg(master_name)
+                .query_async(&mut sentinel_cons[0])
+                .await?;
+            for sentinel_con in &mut sentinel_cons {
+                let _: () = redis::cmd("SENTINEL")
+                    .arg("REMOVE")
+                    .arg(master_name)
+                    .query_async(sentinel_con)
+                    .await?;
+            }
+            let result: RedisResult<()> = redis::cmd("CLIENT")
+                .arg("KILL")
+                .arg("TYPE")
+                .arg("normal")
+                .arg("SKIPME")
+                .arg("no")
+                .query_async(&mut con)
+                .await;
+            assert!(result.is_err());
+
+            let mut reconnection_failed = false;
+            for _ in 0..100 {
+                if let Err(err) = con.get::<_, String>("foo").await {
+                    if err.kind() == ErrorKind::MasterNameNotFoundBySentinel {
+                        reconnection_failed = true;
+                        break;
+                    }
+                }
+                futures_time::task::sleep(futures_time::time::Duration::from_millis(50)).await;
+            }
+            assert!(reconnection_failed);
+
+            // The failed reconnection is retried once the sentinels know the master again.
+            for sentinel_con in &mut sentinel_cons {
+                let _: () = redis::cmd("SENTINEL")
+                    .arg("MONITOR")
+                    .arg(master_name)
+                    .arg(&host)
+                    .arg(port)
+                    .arg(1)
+                    .query_async(sentinel_con)
+                    .await?;
+            }
+            let mut value = None;
+            for _ in 0..100 {
+                if let Ok(result) = con.get::<_, String>("foo").await {
+                    value = Some(result);
+                    break;
+                }
+                futures_time::task::sleep(futures_time::time::Duration::from_millis(50)).await;
+            }
+            assert_eq!(value.as_deref(), Some("bar"));
+
+            Ok::<(), RedisError>(())
+        })
+        .unwrap();
+    }
 }
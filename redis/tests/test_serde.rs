@@ -0,0 +1,140 @@
+#![cfg(feature = "serde")]
+
+use std::collections::HashMap;
+
+use redis::serde::{from_redis_value, to_redis_args, Serde, SerdeArgs};
+use redis::{Commands, ErrorKind, RedisResult, ToRedisArgs, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::support::*;
+mod support;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Role {
+    Admin,
+    Member,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct User {
+    name: String,
+    age: u32,
+    active: bool,
+    role: Role,
+    email: Option<String>,
+}
+
+fn bulk(s: &str) -> Value {
+    Value::BulkString(s.as_bytes().to_vec())
+}
+
+fn alice() -> User {
+    User {
+        name: "Alice".to_string(),
+        age: 42,
+        active: true,
+        role: Role::Admin,
+        email: None,
+    }
+}
+
+#[test]
+fn test_serialize_struct_as_field_value_pairs() {
+    let args = SerdeArgs::new(&alice()).unwrap().to_redis_args();
+    assert_eq!(
+        args,
+        vec![
+            b"name".to_vec(),
+            b"Alice".to_vec(),
+            b"age".to_vec(),
+            b"42".to_vec(),
+            b"active".to_vec(),
+            b"1".to_vec(),
+            b"role".to_vec(),
+            b"admin".to_vec(),
+        ]
+    );
+
+    let mut args = Vec::new();
+    to_redis_args(&(1, "two", 3.5), &mut args).unwrap();
+    assert_eq!(args, vec![b"1".to_vec(), b"two".to_vec(), b"3.5".to_vec()]);
+}
+
+#[test]
+fn test_serialize_nested_values_fails() {
+    #[derive(Serialize)]
+    struct Nested {
+        user: User,
+    }
+
+    let mut args = Vec::new();
+    let err = to_redis_args(&Nested { user: alice() }, &mut args).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Serialize);
+    let err = SerdeArgs::new(&Nested { user: alice() }).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Serialize);
+}
+
+#[test]
+fn test_deserialize_struct_from_flat_array_and_map() {
+    let flat = Value::Array(vec![
+        bulk("name"),
+        bulk("Alice"),
+        bulk("age"),
+        bulk("42"),
+        bulk("active"),
+        bulk("1"),
+        bulk("role"),
+        bulk("admin"),
+    ]);
+    assert_eq!(from_redis_value::<User>(&flat).unwrap(), alice());
+
+    let map = Value::Map(vec![
+        (bulk("name"), bulk("Alice")),
+        (bulk("age"), Value::Int(42)),
+        (bulk("active"), Value::Boolean(true)),
+        (bulk("role"), Value::SimpleString("admin".to_string())),
+        (bulk("email"), Value::Nil),
+    ]);
+    assert_eq!(from_redis_value::<User>(&map).unwrap(), alice());
+
+    let pairs = Value::Array(vec![bulk("a"), bulk("b")]);
+    let borrowed: HashMap<&str, &str> = from_redis_value(&pairs).unwrap();
+    assert_eq!(borrowed, HashMap::from([("a", "b")]));
+}
+
+#[test]
+fn test_deserialize_invalid_values_fails() {
+    let missing_field = Value::Array(vec![bulk("name"), bulk("Alice")]);
+    let result: RedisResult<Serde<User>> = redis::from_redis_value(&missing_field);
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::TypeError);
+
+    let invalid_number = Value::Array(vec![bulk("age"), bulk("old")]);
+    let result: RedisResult<HashMap<String, u32>> = from_redis_value(&invalid_number);
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::TypeError);
+
+    let odd_array = Value::Array(vec![bulk("name")]);
+    let result: RedisResult<HashMap<String, String>> = from_redis_value(&odd_array);
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::TypeError);
+}
+
+#[test]
+fn test_hash_round_trip() {
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    let user = User {
+        email: Some("alice@example.com".to_string()),
+        ..alice()
+    };
+    let _: () = redis::cmd("HSET")
+        .arg("user:1")
+        .arg(SerdeArgs::new(&user).unwrap())
+        .query(&mut con)
+        .unwrap();
+
+    let Serde(stored): Serde<User> = con.hgetall("user:1").unwrap();
+    assert_eq!(stored, user);
+    let age: u32 = con.hget("user:1", "age").unwrap();
+    assert_eq!(age, 42);
+}
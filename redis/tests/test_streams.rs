@@ -1,7 +1,7 @@
 #![cfg(feature = "streams")]
 
 use redis::streams::*;
-use redis::{Commands, Connection, RedisResult, ToRedisArgs};
+use redis::{from_redis_value, Commands, Connection, RedisResult, ToRedisArgs, Value};
 
 mod support;
 use crate::support::*;
@@ -88,6 +88,65 @@ fn test_cmd_options() {
     let opts = StreamReadOptions::default().noack().block(100).count(200);
 
     assert_args!(&opts, "BLOCK", "100", "COUNT", "200");
+
+    // test add and trim options
+
+    let empty = StreamAddOptions::default();
+    assert_eq!(ToRedisArgs::to_redis_args(&empty).len(), 0);
+
+    let opts = StreamAddOptions::default()
+        .nomkstream()
+        .trim(StreamTrimOptions::maxlen(StreamTrimmingMode::Exact, 10));
+    assert_args!(&opts, "NOMKSTREAM", "MAXLEN", "=", "10");
+
+    let opts = StreamTrimOptions::minid(StreamTrimmingMode::Approx, "1000-0").limit(50);
+    assert_args!(&opts, "MINID", "~", "1000-0", "LIMIT", "50");
+
+    // test autoclaim and setid options
+
+    let opts = StreamAutoClaimOptions::default().count(20).with_justid();
+    assert_args!(&opts, "COUNT", "20", "JUSTID");
+
+    let opts = StreamSetIdOptions::default()
+        .entries_added(5)
+        .max_deleted_id("10-0");
+    assert_args!(&opts, "ENTRIESADDED", "5", "MAXDELETEDID", "10-0");
+}
+
+#[test]
+fn test_autoclaim_reply() {
+    fn bulk(s: &str) -> Value {
+        Value::BulkString(s.as_bytes().to_vec())
+    }
+
+    let reply: StreamAutoClaimReply = from_redis_value(&Value::Array(vec![
+        bulk("1000-2"),
+        Value::Array(vec![
+            Value::Array(vec![
+                bulk("1000-0"),
+                Value::Array(vec![bulk("hello"), bulk("world")]),
+            ]),
+            Value::Nil,
+        ]),
+        Value::Array(vec![bulk("1000-1")]),
+    ]))
+    .unwrap();
+    assert_eq!(reply.next_stream_id, "1000-2");
+    assert_eq!(reply.claimed.len(), 1);
+    assert_eq!(reply.claimed[0].id, "1000-0");
+    assert_eq!(reply.claimed[0].get("hello"), Some("world".to_string()));
+    assert_eq!(reply.deleted_ids, vec!["1000-1".to_string()]);
+
+    // JUSTID, as replied by Redis 6.2
+    let reply: StreamAutoClaimReply = from_redis_value(&Value::Array(vec![
+        bulk("0-0"),
+        Value::Array(vec![bulk("1000-0"), bulk("1000-1")]),
+    ]))
+    .unwrap();
+    assert_eq!(reply.next_stream_id, "0-0");
+    let ids: Vec<&str> = reply.claimed.iter().map(|id| id.id.as_str()).collect();
+    assert_eq!(ids, vec!["1000-0", "1000-1"]);
+    assert!(reply.deleted_ids.is_empty());
 }
 
 #[test]
@@ -574,6 +633,165 @@ fn test_xgroup() {
     assert_eq!(result, Ok(1));
 }
 
+#[test]
+fn test_xautoclaim() {
+    // Tests the following commands....
+    // xautoclaim
+    // xautoclaim_options
+    let ctx = TestContext::new();
+    if ctx.get_version() < (6, 2, 0) {
+        return;
+    }
+    let mut con = ctx.connection();
+
+    let _: () = con.xgroup_create_mkstream("k1", "g1", "$").unwrap();
+    xadd_keyrange(&mut con, "k1", 0, 5);
+    let _: StreamReadReply = con
+        .xread_options(
+            &["k1"],
+            &[">"],
+            &StreamReadOptions::default().group("g1", "c1"),
+        )
+        .unwrap();
+
+    sleep(Duration::from_millis(5));
+
+    // claim two entries, and continue from the returned cursor
+    let opts = StreamAutoClaimOptions::default().count(2);
+    let reply: StreamAutoClaimReply = con
+        .xautoclaim_options("k1", "g1", "c2", 4, "0-0", opts)
+        .unwrap();
+    assert_eq!(reply.claimed.len(), 2);
+    assert_eq!(reply.claimed[0].get("h"), Some("w".to_string()));
+    assert_ne!(reply.next_stream_id, "0-0");
+
+    let opts = StreamAutoClaimOptions::default().with_justid();
+    let reply: StreamAutoClaimReply = con
+        .xautoclaim_options("k1", "g1", "c2", 4, &reply.next_stream_id, opts)
+        .unwrap();
+    assert_eq!(reply.claimed.len(), 3);
+    assert!(reply.claimed[0].is_empty());
+    assert_eq!(reply.next_stream_id, "0-0");
+
+    // c2 now owns everything, so nothing is idle for long enough
+    let reply: StreamAutoClaimReply = con.xautoclaim("k1", "g1", "c3", 60_000, "0-0").unwrap();
+    assert!(reply.claimed.is_empty());
+}
+
+#[test]
+fn test_xadd_options_and_xtrim_options() {
+    // Tests the following commands....
+    // xadd_options
+    // xtrim_options
+    let ctx = TestContext::new();
+    if ctx.get_version() < (6, 2, 0) {
+        return;
+    }
+    let mut con = ctx.connection();
+
+    // NOMKSTREAM doesn't create the stream
+    let opts = StreamAddOptions::default().nomkstream();
+    let id: Option<String> = con.xadd_options("k1", "*", &[("h", "w")], &opts).unwrap();
+    assert_eq!(id, None);
+    let exists: bool = con.exists("k1").unwrap();
+    assert!(!exists);
+
+    for i in 1..=10 {
+        let opts = StreamAddOptions::default()
+            .trim(StreamTrimOptions::maxlen(StreamTrimmingMode::Exact, 5));
+        let _: String = con
+            .xadd_options("k1", format!("{i}-0"), &[("h", "w")], &opts)
+            .unwrap();
+    }
+    let len: usize = con.xlen("k1").unwrap();
+    assert_eq!(len, 5);
+
+    // evicts 6-0 and 7-0
+    let opts = StreamTrimOptions::minid(StreamTrimmingMode::Exact, "8-0");
+    let trimmed: usize = con.xtrim_options("k1", &opts).unwrap();
+    assert_eq!(trimmed, 2);
+
+    let opts = StreamTrimOptions::maxlen(StreamTrimmingMode::Approx, 0).limit(10);
+    let _: usize = con.xtrim_options("k1", &opts).unwrap();
+}
+
+#[test]
+fn test_xgroup_createconsumer_and_xsetid() {
+    // Tests the following commands....
+    // xgroup_createconsumer
+    // xsetid
+    // xsetid_options
+    let ctx = TestContext::new();
+    if ctx.get_version() < (7, 0, 0) {
+        return;
+    }
+    let mut con = ctx.connection();
+
+    xadd(&mut con);
+    let _: () = con.xgroup_create("k1", "g1", "0").unwrap();
+    let created: i32 = con.xgroup_createconsumer("k1", "g1", "c1").unwrap();
+    assert_eq!(created, 1);
+    let created: i32 = con.xgroup_createconsumer("k1", "g1", "c1").unwrap();
+    assert_eq!(created, 0);
+    let reply: StreamInfoConsumersReply = con.xinfo_consumers("k1", "g1").unwrap();
+    assert_eq!(reply.consumers[0].name, "c1");
+
+    let _: () = con.xsetid("k1", "2000-0").unwrap();
+    let reply: StreamInfoStreamReply = con.xinfo_stream("k1").unwrap();
+    assert_eq!(reply.last_generated_id, "2000-0");
+
+    let opts = StreamSetIdOptions::default()
+        .entries_added(10)
+        .max_deleted_id("1500-0");
+    let _: () = con.xsetid_options("k1", "3000-0", &opts).unwrap();
+    let reply: StreamInfoStreamFullReply = con.xinfo_stream_full("k1").unwrap();
+    assert_eq!(reply.last_generated_id, "3000-0");
+    assert_eq!(reply.entries_added, Some(10));
+    assert_eq!(reply.max_deleted_entry_id, Some("1500-0".to_string()));
+}
+
+#[test]
+fn test_xinfo_stream_full() {
+    // Tests the following commands....
+    // xinfo_stream_full
+    // xinfo_stream_full_count
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+
+    xadd(&mut con);
+    let _: () = con.xgroup_create("k1", "g1", "0").unwrap();
+    let _: StreamReadReply = con
+        .xread_options(
+            &["k1"],
+            &[">"],
+            &StreamReadOptions::default().group("g1", "c1").count(1),
+        )
+        .unwrap();
+
+    let reply: StreamInfoStreamFullReply = con.xinfo_stream_full("k1").unwrap();
+    assert_eq!(reply.length, 2);
+    assert_eq!(reply.last_generated_id, "1000-1");
+    assert_eq!(reply.entries.len(), 2);
+    assert_eq!(reply.entries[0].id, "1000-0");
+    assert_eq!(reply.entries[0].get("redis"), Some("streams".to_string()));
+
+    assert_eq!(reply.groups.len(), 1);
+    let group = &reply.groups[0];
+    assert_eq!(group.name, "g1");
+    assert_eq!(group.last_delivered_id, "1000-0");
+    assert_eq!(group.pel_count, 1);
+    assert_eq!(group.pending[0].id, "1000-0");
+    assert_eq!(group.pending[0].consumer, "c1");
+    assert_eq!(group.pending[0].delivery_count, 1);
+    assert_eq!(group.consumers[0].name, "c1");
+    assert_eq!(group.consumers[0].pel_count, 1);
+    assert_eq!(group.consumers[0].pending[0].id, "1000-0");
+    assert_eq!(group.consumers[0].pending[0].consumer, "c1");
+
+    let reply: StreamInfoStreamFullReply = con.xinfo_stream_full_count("k1", 1).unwrap();
+    assert_eq!(reply.entries.len(), 1);
+}
+
 #[test]
 fn test_xrange() {
     // Tests the following commands....
@@ -625,3 +843,157 @@ fn test_xrevrange() {
     let reply: StreamRangeReply = con.xrevrange_count("k1", "+", "-", 1).unwrap();
     assert_eq!(reply.ids.len(), 1);
 }
+
+#[test]
+#[cfg(feature = "tokio-comp")]
+fn test_stream_consumer_moves_entries_to_the_dead_letter_stream() {
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+    xadd_keyrange(&mut con, "events", 0, 2);
+
+    let attempts = std::sync::Mutex::new(Vec::new());
+    block_on_all(async {
+        let options = StreamConsumerOptions::default()
+            .start_id("0")
+            .block(100)
+            .claim_min_idle(0)
+            .claim_interval(0)
+            .max_deliveries(2)
+            .dead_letter_key("dead");
+        let mut consumer =
+            StreamConsumer::new(ctx.async_connection().await?, "events", "g1", "c1", options);
+        let mut dead_con = ctx.async_connection().await?;
+        let dead_lettered = async move {
+            loop {
+                let len: usize = redis::cmd("XLEN")
+                    .arg("dead")
+                    .query_async(&mut dead_con)
+                    .await
+                    .unwrap();
+                if len == 2 {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        consumer
+            .run_until(
+                |entry| {
+                    attempts.lock().unwrap().push(entry.id);
+                    async { Err::<(), _>("failed") }
+                },
+                dead_lettered,
+            )
+            .await
+    })
+    .unwrap();
+
+    // Every entry was handled twice before it was given up on.
+    assert_eq!(attempts.into_inner().unwrap().len(), 4);
+    let reply: StreamRangeReply = con.xrange_all("dead").unwrap();
+    assert_eq!(reply.ids.len(), 2);
+    for id in reply.ids {
+        assert_eq!(id.get::<String>("h"), Some("w".to_string()));
+    }
+    let pending: StreamPendingReply = con.xpending("events", "g1").unwrap();
+    assert_eq!(pending.count(), 0);
+}
+
+#[cfg(feature = "tokio-comp")]
+async fn wait_for_handled(handled: &std::sync::Mutex<Vec<String>>, count: usize) {
+    while handled.lock().unwrap().len() < count {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[test]
+#[cfg(feature = "tokio-comp")]
+fn test_stream_consumer_retries_failed_entries() {
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+    xadd_keyrange(&mut con, "events", 0, 3);
+
+    let handled = std::sync::Mutex::new(Vec::new());
+    let mut failed = false;
+    block_on_all(async {
+        let options = StreamConsumerOptions::default()
+            .start_id("0")
+            .block(100)
+            .claim_min_idle(0)
+            .claim_interval(0);
+        let mut consumer =
+            StreamConsumer::new(ctx.async_connection().await?, "events", "g1", "c1", options);
+        consumer
+            .run_until(
+                |entry| {
+                    let result = if !failed {
+                        failed = true;
+                        Err(())
+                    } else {
+                        handled.lock().unwrap().push(entry.id);
+                        Ok(())
+                    };
+                    async move { result }
+                },
+                wait_for_handled(&handled, 3),
+            )
+            .await
+    })
+    .unwrap();
+
+    let handled = handled.into_inner().unwrap();
+    assert_eq!(handled.len(), 3);
+    let reply: StreamRangeReply = con.xrange_all("events").unwrap();
+    for id in reply.ids {
+        assert!(handled.contains(&id.id));
+    }
+    let pending: StreamPendingReply = con.xpending("events", "g1").unwrap();
+    assert_eq!(pending.count(), 0);
+}
+
+#[test]
+#[cfg(feature = "tokio-comp")]
+fn test_stream_consumer_claims_entries_of_dead_consumers() {
+    let ctx = TestContext::new();
+    let mut con = ctx.connection();
+    let _: () = con.xgroup_create_mkstream("events", "g1", "$").unwrap();
+    xadd_keyrange(&mut con, "events", 0, 2);
+    let reply: StreamReadReply = con
+        .xread_options(
+            &["events"],
+            &[">"],
+            &StreamReadOptions::default().group("g1", "dead"),
+        )
+        .unwrap();
+    assert_eq!(reply.keys[0].ids.len(), 2);
+
+    let handled = std::sync::Mutex::new(Vec::new());
+    block_on_all(async {
+        let options = StreamConsumerOptions::default()
+            .block(100)
+            .claim_min_idle(0);
+        let mut consumer = StreamConsumer::new(
+            ctx.async_connection().await?,
+            "events",
+            "g1",
+            "alive",
+            options,
+        );
+        consumer
+            .run_until(
+                |entry| {
+                    handled.lock().unwrap().push(entry.id);
+                    async { Ok::<_, ()>(()) }
+                },
+                wait_for_handled(&handled, 2),
+            )
+            .await
+    })
+    .unwrap();
+
+    let handled = handled.into_inner().unwrap();
+    let claimed: Vec<String> = reply.keys[0].ids.iter().map(|id| id.id.clone()).collect();
+    assert_eq!(handled, claimed);
+    let pending: StreamPendingReply = con.xpending("events", "g1").unwrap();
+    assert_eq!(pending.count(), 0);
+}
@@ -367,6 +367,55 @@ mod types {
         }
     }
 
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_from_bytes_value() {
+        use bytes::Bytes;
+        use redis::{
+            from_bytes_value, parse_redis_bytes_value, BytesValue, ErrorKind, RedisResult,
+        };
+
+        let buffer = Bytes::from_static(b"*4\r\n$3\r\none\r\n$3\r\ntwo\r\n$1\r\n3\r\n$-1\r\n");
+        let response = parse_redis_bytes_value(&buffer).unwrap();
+        let in_buffer = |bytes: &Bytes| buffer.as_ptr_range().contains(&bytes.as_ptr());
+
+        let v: Vec<Option<Bytes>> = from_bytes_value(response.clone()).unwrap();
+        assert_eq!(
+            v,
+            vec![
+                Some(Bytes::from_static(b"one")),
+                Some(Bytes::from_static(b"two")),
+                Some(Bytes::from_static(b"3")),
+                None
+            ]
+        );
+        assert!(v.iter().flatten().all(in_buffer));
+
+        let items = match response.clone() {
+            BytesValue::Array(items) => items,
+            _ => panic!("Expected BytesValue::Array"),
+        };
+        let v: Bytes = from_bytes_value(items[0].clone()).unwrap();
+        assert!(in_buffer(&v));
+        let v: Vec<Bytes> = from_bytes_value(BytesValue::Array(items[..3].to_vec())).unwrap();
+        assert!(v.iter().all(in_buffer));
+
+        // Other types go through a regular `Value`.
+        let v: (String, String, u8, Option<String>) = from_bytes_value(response.clone()).unwrap();
+        assert_eq!(v, ("one".into(), "two".into(), 3, None));
+        let v: Vec<(Bytes, Bytes)> =
+            from_bytes_value(BytesValue::Array(items[..2].to_vec())).unwrap();
+        assert_eq!(
+            v,
+            vec![(Bytes::from_static(b"one"), Bytes::from_static(b"two"))]
+        );
+
+        let v: RedisResult<Bytes> = from_bytes_value(BytesValue::Okay);
+        assert_eq!(v.unwrap_err().kind(), ErrorKind::TypeError);
+        let v: RedisResult<Bytes> = from_bytes_value(response);
+        assert_eq!(v.unwrap_err().kind(), ErrorKind::TypeError);
+    }
+
     #[cfg(feature = "uuid")]
     #[test]
     fn test_uuid() {